@@ -0,0 +1,47 @@
+CREATE TYPE channel_kind AS ENUM ('text', 'voice', 'announcement', 'category');
+
+CREATE TYPE overwrite_target AS ENUM ('role', 'member');
+
+CREATE TABLE server_roles(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    server_id uuid NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    permissions BIGINT NOT NULL DEFAULT 0,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE TABLE server_member_roles(
+    role_id uuid NOT NULL REFERENCES server_roles(id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id),
+    PRIMARY KEY(role_id, user_id),
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE TABLE channels(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    server_id uuid NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    parent_id uuid REFERENCES channels(id) ON DELETE SET NULL,
+    kind channel_kind NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    topic VARCHAR(1024),
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    deleted_at timestamptz,
+    CHECK (parent_id IS NULL OR kind <> 'category')
+);
+
+CREATE INDEX channels_server_id_position_idx ON channels(server_id, position);
+
+CREATE TABLE channel_permission_overwrites(
+    channel_id uuid NOT NULL REFERENCES channels(id) ON DELETE CASCADE,
+    target_id uuid NOT NULL,
+    PRIMARY KEY(channel_id, target_id),
+    target_type overwrite_target NOT NULL,
+    allow BIGINT NOT NULL DEFAULT 0,
+    deny BIGINT NOT NULL DEFAULT 0
+);
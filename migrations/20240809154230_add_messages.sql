@@ -0,0 +1,24 @@
+CREATE TABLE messages(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    channel_id uuid NOT NULL REFERENCES channels(id) ON DELETE CASCADE,
+    author_id uuid NOT NULL REFERENCES users(id),
+    content VARCHAR(2000) NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    edited_at timestamptz,
+    deleted_at timestamptz
+);
+
+CREATE INDEX messages_channel_id_created_at_idx ON messages(channel_id, created_at, id);
+
+CREATE TABLE message_revisions(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    message_id uuid NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    editor_id uuid NOT NULL REFERENCES users(id),
+    content VARCHAR(2000) NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX message_revisions_message_id_idx ON message_revisions(message_id, created_at);
@@ -0,0 +1,52 @@
+CREATE TYPE dm_thread_kind AS ENUM ('direct', 'group');
+
+CREATE TABLE dm_threads(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    kind dm_thread_kind NOT NULL,
+    owner_id uuid REFERENCES users(id),
+    name VARCHAR(100),
+    first_user_id uuid REFERENCES users(id),
+    second_user_id uuid REFERENCES users(id),
+    last_message_at timestamptz,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    deleted_at timestamptz,
+    CHECK (
+        (kind = 'direct' AND owner_id IS NULL AND first_user_id < second_user_id)
+        OR (kind = 'group' AND owner_id IS NOT NULL AND first_user_id IS NULL AND second_user_id IS NULL)
+    )
+);
+
+CREATE UNIQUE INDEX dm_threads_direct_users_idx ON dm_threads(first_user_id, second_user_id) WHERE kind = 'direct';
+
+CREATE TABLE dm_participants(
+    thread_id uuid NOT NULL REFERENCES dm_threads(id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id),
+    PRIMARY KEY(thread_id, user_id),
+    joined_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX dm_participants_user_id_idx ON dm_participants(user_id);
+
+CREATE TABLE direct_messages(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    thread_id uuid NOT NULL REFERENCES dm_threads(id) ON DELETE CASCADE,
+    author_id uuid NOT NULL REFERENCES users(id),
+    content VARCHAR(2000) NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    edited_at timestamptz,
+    deleted_at timestamptz
+);
+
+CREATE INDEX direct_messages_thread_id_created_at_idx ON direct_messages(thread_id, created_at, id);
+
+CREATE TABLE user_blocks(
+    blocker_id uuid NOT NULL REFERENCES users(id),
+    blocked_id uuid NOT NULL REFERENCES users(id),
+    PRIMARY KEY(blocker_id, blocked_id),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK (blocker_id <> blocked_id)
+);
@@ -0,0 +1,15 @@
+-- Replaces the per-message server_message_reads table with one marker per
+-- user per channel or DM thread.
+CREATE TABLE read_states(
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    channel_id uuid REFERENCES channels(id) ON DELETE CASCADE,
+    thread_id uuid REFERENCES dm_threads(id) ON DELETE CASCADE,
+    last_read_message_id uuid,
+    mention_count INT NOT NULL DEFAULT 0,
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    CHECK ((channel_id IS NULL) <> (thread_id IS NULL)),
+    CHECK (mention_count >= 0)
+);
+
+CREATE UNIQUE INDEX read_states_user_id_channel_id_idx ON read_states(user_id, channel_id) WHERE channel_id IS NOT NULL;
+CREATE UNIQUE INDEX read_states_user_id_thread_id_idx ON read_states(user_id, thread_id) WHERE thread_id IS NOT NULL;
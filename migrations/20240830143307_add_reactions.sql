@@ -0,0 +1,38 @@
+CREATE TABLE server_emojis(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    server_id uuid NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    name VARCHAR(32) NOT NULL,
+    creator_id uuid NOT NULL REFERENCES users(id),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    deleted_at timestamptz
+);
+
+CREATE UNIQUE INDEX server_emojis_server_id_name_idx ON server_emojis(server_id, lower(name)) WHERE deleted_at IS NULL;
+
+-- Replaces the single VARCHAR(4) reaction per user per message. A user can
+-- react to a message with any number of distinct emoji, each either a
+-- Unicode emoji or a custom server emoji.
+CREATE TABLE message_reactions(
+    message_id uuid NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    emoji VARCHAR(64),
+    custom_emoji_id uuid REFERENCES server_emojis(id) ON DELETE CASCADE,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK ((emoji IS NULL) <> (custom_emoji_id IS NULL))
+);
+
+CREATE UNIQUE INDEX message_reactions_unicode_idx ON message_reactions(message_id, emoji, user_id) WHERE emoji IS NOT NULL;
+CREATE UNIQUE INDEX message_reactions_custom_idx ON message_reactions(message_id, custom_emoji_id, user_id) WHERE custom_emoji_id IS NOT NULL;
+
+CREATE TABLE direct_message_reactions(
+    message_id uuid NOT NULL REFERENCES direct_messages(id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    emoji VARCHAR(64),
+    custom_emoji_id uuid REFERENCES server_emojis(id) ON DELETE CASCADE,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK ((emoji IS NULL) <> (custom_emoji_id IS NULL))
+);
+
+CREATE UNIQUE INDEX direct_message_reactions_unicode_idx ON direct_message_reactions(message_id, emoji, user_id) WHERE emoji IS NOT NULL;
+CREATE UNIQUE INDEX direct_message_reactions_custom_idx ON direct_message_reactions(message_id, custom_emoji_id, user_id) WHERE custom_emoji_id IS NOT NULL;
@@ -0,0 +1,23 @@
+-- Resolved mentions are stored with each message, and every mentioned user
+-- gets a notification that also bumps their mention count in the channel.
+ALTER TABLE messages
+    ADD COLUMN mentions uuid[] NOT NULL DEFAULT '{}',
+    ADD COLUMN mention_roles uuid[] NOT NULL DEFAULT '{}',
+    ADD COLUMN mention_channels uuid[] NOT NULL DEFAULT '{}',
+    ADD COLUMN mention_everyone BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TYPE mention_kind AS ENUM ('user', 'role', 'everyone', 'here');
+
+CREATE TABLE notifications(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    message_id uuid NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    channel_id uuid NOT NULL REFERENCES channels(id) ON DELETE CASCADE,
+    author_id uuid NOT NULL REFERENCES users(id),
+    kind mention_kind NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    UNIQUE(user_id, message_id)
+);
+
+CREATE INDEX notifications_user_id_created_at_idx ON notifications(user_id, created_at DESC, id DESC);
@@ -0,0 +1,29 @@
+-- Replies point at another message in the same channel. Threads are
+-- channels of kind 'thread' whose parent is the channel they were started
+-- in, with their own metadata and member list.
+ALTER TABLE messages ADD COLUMN reply_to_id uuid REFERENCES messages(id) ON DELETE SET NULL;
+
+ALTER TYPE channel_kind ADD VALUE 'thread';
+
+CREATE TABLE threads(
+    channel_id uuid NOT NULL REFERENCES channels(id) ON DELETE CASCADE,
+    PRIMARY KEY(channel_id),
+    starter_message_id uuid UNIQUE REFERENCES messages(id) ON DELETE SET NULL,
+    owner_id uuid NOT NULL REFERENCES users(id),
+    auto_archive_minutes INTEGER NOT NULL DEFAULT 1440,
+    last_message_at timestamptz,
+    archived_at timestamptz,
+    locked BOOLEAN NOT NULL DEFAULT false,
+    CHECK (auto_archive_minutes IN (60, 1440, 4320, 10080))
+);
+
+CREATE INDEX threads_archived_at_idx ON threads(archived_at) WHERE archived_at IS NULL;
+
+CREATE TABLE thread_members(
+    thread_id uuid NOT NULL REFERENCES threads(channel_id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY(thread_id, user_id),
+    joined_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX thread_members_user_id_idx ON thread_members(user_id);
@@ -0,0 +1,19 @@
+-- The 'simple' configuration skips stemming and stop words, so prefix
+-- queries behave the same for every language and for handles and code.
+ALTER TABLE messages ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', content)) STORED;
+
+CREATE INDEX messages_search_vector_idx ON messages USING GIN(search_vector);
+
+ALTER TABLE direct_messages ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', content)) STORED;
+
+CREATE INDEX direct_messages_search_vector_idx ON direct_messages USING GIN(search_vector);
+
+ALTER TABLE servers ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', name), 'A')
+        || setweight(to_tsvector('simple', coalesce(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX servers_search_vector_idx ON servers USING GIN(search_vector);
@@ -0,0 +1,16 @@
+CREATE TYPE message_kind AS ENUM ('default', 'message_pinned', 'message_unpinned');
+
+ALTER TABLE messages ADD COLUMN kind message_kind NOT NULL DEFAULT 'default';
+
+ALTER TABLE channels ADD COLUMN max_pins INTEGER NOT NULL DEFAULT 50
+    CHECK (max_pins BETWEEN 1 AND 250);
+
+CREATE TABLE pins(
+    message_id uuid NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    PRIMARY KEY(message_id),
+    channel_id uuid NOT NULL REFERENCES channels(id) ON DELETE CASCADE,
+    pinned_by uuid NOT NULL REFERENCES users(id),
+    pinned_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX pins_channel_id_pinned_at_idx ON pins(channel_id, pinned_at);
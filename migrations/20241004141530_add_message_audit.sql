@@ -0,0 +1,8 @@
+-- Who soft deleted a message, and when its content was purged. Purged
+-- messages stay behind as tombstones so that cursors and reply references
+-- keep their place in the channel.
+ALTER TABLE messages ADD COLUMN deleted_by uuid REFERENCES users(id);
+ALTER TABLE messages ADD COLUMN purged_at timestamptz;
+
+CREATE INDEX messages_deleted_at_idx ON messages(deleted_at) WHERE deleted_at IS NOT NULL AND purged_at IS NULL;
+CREATE INDEX message_revisions_created_at_idx ON message_revisions(created_at);
@@ -0,0 +1,21 @@
+-- Disappearing DMs. Once expired, the scheduler wipes their content and
+-- soft deletes them, leaving a tombstone so read markers stay valid.
+ALTER TABLE direct_messages ADD COLUMN expires_at timestamptz;
+
+CREATE INDEX direct_messages_expires_at_idx ON direct_messages(expires_at)
+    WHERE expires_at IS NOT NULL AND deleted_at IS NULL;
+
+CREATE TABLE scheduled_messages(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    author_id uuid NOT NULL REFERENCES users(id),
+    channel_id uuid REFERENCES channels(id) ON DELETE CASCADE,
+    dm_thread_id uuid REFERENCES dm_threads(id) ON DELETE CASCADE,
+    content VARCHAR(2000) NOT NULL,
+    send_at timestamptz NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK ((channel_id IS NULL) <> (dm_thread_id IS NULL))
+);
+
+CREATE INDEX scheduled_messages_send_at_idx ON scheduled_messages(send_at);
+CREATE INDEX scheduled_messages_author_id_send_at_idx ON scheduled_messages(author_id, send_at, id);
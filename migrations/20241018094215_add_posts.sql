@@ -0,0 +1,16 @@
+-- Posts were created before anything used them. A post need not quote
+-- another, and the author column is named like the one on messages.
+ALTER TABLE posts ALTER COLUMN quoted_post_id DROP NOT NULL;
+ALTER TABLE posts DROP CONSTRAINT posts_quoted_post_id_fkey;
+ALTER TABLE posts ADD CONSTRAINT posts_quoted_post_id_fkey
+    FOREIGN KEY (quoted_post_id) REFERENCES posts(id) ON DELETE SET NULL;
+ALTER TABLE posts DROP CONSTRAINT posts_server_id_fkey;
+ALTER TABLE posts ADD CONSTRAINT posts_server_id_fkey
+    FOREIGN KEY (server_id) REFERENCES servers(id) ON DELETE CASCADE;
+ALTER TABLE posts RENAME COLUMN user_id TO author_id;
+ALTER TABLE posts ALTER COLUMN content TYPE VARCHAR(4000);
+ALTER TABLE posts ADD COLUMN edited_at timestamptz;
+
+CREATE INDEX posts_server_id_created_at_idx ON posts(server_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL;
+CREATE INDEX posts_quoted_post_id_idx ON posts(quoted_post_id);
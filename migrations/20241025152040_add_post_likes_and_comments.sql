@@ -0,0 +1,25 @@
+-- Likes and comments go with their post, and comments can reply to
+-- one another.
+ALTER TABLE post_likes DROP CONSTRAINT post_likes_post_id_fkey;
+ALTER TABLE post_likes ADD CONSTRAINT post_likes_post_id_fkey
+    FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE;
+
+ALTER TABLE comments DROP CONSTRAINT comments_post_id_fkey;
+ALTER TABLE comments ADD CONSTRAINT comments_post_id_fkey
+    FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE;
+ALTER TABLE comments RENAME COLUMN user_id TO author_id;
+ALTER TABLE comments RENAME COLUMN comment TO content;
+ALTER TABLE comments ALTER COLUMN content TYPE VARCHAR(2000);
+ALTER TABLE comments ALTER COLUMN content SET NOT NULL;
+ALTER TABLE comments ADD COLUMN parent_id uuid REFERENCES comments(id) ON DELETE CASCADE;
+ALTER TABLE comments ADD COLUMN edited_at timestamptz;
+
+ALTER TABLE comment_likes DROP CONSTRAINT comment_likes_comment_id_fkey;
+ALTER TABLE comment_likes ADD CONSTRAINT comment_likes_comment_id_fkey
+    FOREIGN KEY (comment_id) REFERENCES comments(id) ON DELETE CASCADE;
+
+CREATE INDEX post_likes_user_id_idx ON post_likes(user_id);
+CREATE INDEX comments_post_id_parent_id_idx ON comments(post_id, parent_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL;
+CREATE INDEX comments_parent_id_idx ON comments(parent_id);
+CREATE INDEX comment_likes_user_id_idx ON comment_likes(user_id);
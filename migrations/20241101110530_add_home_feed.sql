@@ -0,0 +1,23 @@
+-- Who each user follows and which servers they have muted. Both shape the
+-- home feed: followed users' posts are pulled in from servers the user has
+-- not joined, and muted servers are left out.
+CREATE TABLE user_follows(
+    follower_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    followee_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY(follower_id, followee_id),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK (follower_id <> followee_id)
+);
+
+CREATE INDEX user_follows_followee_id_idx ON user_follows(followee_id, created_at DESC);
+
+CREATE TABLE server_mutes(
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    server_id uuid NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    PRIMARY KEY(user_id, server_id),
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX posts_author_id_created_at_idx ON posts(author_id, created_at DESC, id DESC)
+    WHERE deleted_at IS NULL;
+CREATE INDEX post_likes_post_id_created_at_idx ON post_likes(post_id, created_at);
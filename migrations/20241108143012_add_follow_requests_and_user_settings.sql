@@ -0,0 +1,19 @@
+-- Settings a user controls about their account. Users without a row have the
+-- defaults.
+CREATE TABLE user_settings(
+    user_id uuid PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    is_private BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+-- Follows of private accounts wait here until the target accepts them.
+CREATE TABLE follow_requests(
+    requester_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY(requester_id, target_id),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK (requester_id <> target_id)
+);
+
+CREATE INDEX follow_requests_target_id_idx ON follow_requests(target_id, created_at DESC);
+CREATE INDEX user_follows_follower_id_created_at_idx ON user_follows(follower_id, created_at DESC);
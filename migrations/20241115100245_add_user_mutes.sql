@@ -0,0 +1,21 @@
+-- Muting a user hides their posts and mentions from the muter without
+-- telling them, unlike a block.
+CREATE TABLE user_mutes(
+    muter_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    muted_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY(muter_id, muted_id),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK (muter_id <> muted_id)
+);
+
+ALTER TABLE user_blocks DROP CONSTRAINT user_blocks_blocker_id_fkey;
+ALTER TABLE user_blocks ADD CONSTRAINT user_blocks_blocker_id_fkey
+    FOREIGN KEY (blocker_id) REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE user_blocks DROP CONSTRAINT user_blocks_blocked_id_fkey;
+ALTER TABLE user_blocks ADD CONSTRAINT user_blocks_blocked_id_fkey
+    FOREIGN KEY (blocked_id) REFERENCES users(id) ON DELETE CASCADE;
+
+CREATE INDEX user_blocks_blocked_id_idx ON user_blocks(blocked_id);
+CREATE INDEX user_blocks_blocker_id_created_at_idx ON user_blocks(blocker_id, created_at DESC);
+CREATE INDEX user_mutes_muter_id_created_at_idx ON user_mutes(muter_id, created_at DESC);
+CREATE INDEX server_mutes_user_id_created_at_idx ON server_mutes(user_id, created_at DESC);
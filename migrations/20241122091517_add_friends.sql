@@ -0,0 +1,26 @@
+-- Friendships are mutual, so each is stored once per side to keep lookups
+-- from either user simple.
+CREATE TABLE friendships(
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    friend_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY(user_id, friend_id),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK (user_id <> friend_id)
+);
+
+CREATE INDEX friendships_user_id_created_at_idx ON friendships(user_id, created_at DESC);
+
+CREATE TABLE friend_requests(
+    sender_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    recipient_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY(sender_id, recipient_id),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK (sender_id <> recipient_id)
+);
+
+CREATE INDEX friend_requests_recipient_id_idx ON friend_requests(recipient_id, created_at DESC);
+CREATE INDEX friend_requests_sender_id_idx ON friend_requests(sender_id, created_at DESC);
+
+CREATE TYPE dm_privacy AS ENUM ('everyone', 'friends');
+
+ALTER TABLE user_settings ADD COLUMN dm_privacy dm_privacy NOT NULL DEFAULT 'everyone';
@@ -0,0 +1,7 @@
+CREATE TYPE profile_visibility AS ENUM ('everyone', 'followers', 'friends', 'nobody');
+
+ALTER TABLE user_settings
+    ADD COLUMN bio_visibility profile_visibility NOT NULL DEFAULT 'everyone',
+    ADD COLUMN servers_visibility profile_visibility NOT NULL DEFAULT 'everyone',
+    ADD COLUMN followers_visibility profile_visibility NOT NULL DEFAULT 'everyone',
+    ADD COLUMN presence_visibility profile_visibility NOT NULL DEFAULT 'everyone';
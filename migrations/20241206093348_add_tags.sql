@@ -0,0 +1,33 @@
+CREATE TABLE tags(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    name VARCHAR(64) NOT NULL UNIQUE,
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+-- The post's server and creation time are copied here so trending can be
+-- counted from this table alone.
+CREATE TABLE post_tags(
+    post_id uuid NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    tag_id uuid NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    server_id uuid NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    created_at timestamptz NOT NULL,
+    PRIMARY KEY(post_id, tag_id)
+);
+
+CREATE INDEX post_tags_tag_id_idx ON post_tags(tag_id, created_at DESC, post_id DESC);
+CREATE INDEX post_tags_created_at_idx ON post_tags(created_at);
+
+-- Trending tags as last computed, for one server or, with no server, for
+-- every server.
+CREATE TABLE trending_tags(
+    server_id uuid REFERENCES servers(id) ON DELETE CASCADE,
+    tag_id uuid NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    score double precision NOT NULL,
+    use_count bigint NOT NULL,
+    computed_at timestamptz NOT NULL
+);
+
+CREATE UNIQUE INDEX trending_tags_scope_idx ON trending_tags(
+    COALESCE(server_id, '00000000-0000-0000-0000-000000000000'), tag_id
+);
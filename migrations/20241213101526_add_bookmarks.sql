@@ -0,0 +1,30 @@
+-- Named folders a user sorts their bookmarks into. Bookmarks outside any
+-- collection are simply unsorted.
+CREATE TABLE bookmark_collections(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    UNIQUE(user_id, name)
+);
+
+-- A post or channel message the user saved for later. Soft deleted targets
+-- keep their bookmark so it can be shown as a tombstone.
+CREATE TABLE bookmarks(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    post_id uuid REFERENCES posts(id) ON DELETE CASCADE,
+    message_id uuid REFERENCES messages(id) ON DELETE CASCADE,
+    collection_id uuid REFERENCES bookmark_collections(id) ON DELETE SET NULL,
+    note VARCHAR(1000),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz NOT NULL DEFAULT now(),
+    CHECK ((post_id IS NULL) <> (message_id IS NULL))
+);
+
+CREATE UNIQUE INDEX bookmarks_user_id_post_id_idx ON bookmarks(user_id, post_id) WHERE post_id IS NOT NULL;
+CREATE UNIQUE INDEX bookmarks_user_id_message_id_idx ON bookmarks(user_id, message_id) WHERE message_id IS NOT NULL;
+CREATE INDEX bookmarks_user_id_created_at_idx ON bookmarks(user_id, created_at DESC, id DESC);
+CREATE INDEX bookmarks_collection_id_idx ON bookmarks(collection_id) WHERE collection_id IS NOT NULL;
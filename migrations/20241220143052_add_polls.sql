@@ -0,0 +1,35 @@
+-- A poll attached to exactly one post or channel message.
+CREATE TABLE polls(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    post_id uuid UNIQUE REFERENCES posts(id) ON DELETE CASCADE,
+    message_id uuid UNIQUE REFERENCES messages(id) ON DELETE CASCADE,
+    author_id uuid NOT NULL REFERENCES users(id),
+    question VARCHAR(300) NOT NULL,
+    multiple_choice boolean NOT NULL DEFAULT false,
+    anonymous boolean NOT NULL DEFAULT false,
+    hide_results boolean NOT NULL DEFAULT false,
+    closes_at timestamptz,
+    closed_at timestamptz,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    CHECK ((post_id IS NULL) <> (message_id IS NULL))
+);
+
+CREATE TABLE poll_options(
+    id uuid NOT NULL,
+    PRIMARY KEY(id),
+    poll_id uuid NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+    position integer NOT NULL,
+    text VARCHAR(100) NOT NULL,
+    UNIQUE(poll_id, position)
+);
+
+CREATE TABLE poll_votes(
+    poll_id uuid NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+    option_id uuid NOT NULL REFERENCES poll_options(id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY(option_id, user_id),
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX poll_votes_poll_id_user_id_idx ON poll_votes(poll_id, user_id);
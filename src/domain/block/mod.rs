@@ -0,0 +1,149 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, PartialEq)]
+pub enum BlockValidationErr {
+    CannotBlockSelf,
+    CannotMuteSelf,
+}
+
+impl BlockValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::CannotBlockSelf => String::from("Users cannot block themselves"),
+            Self::CannotMuteSelf => String::from("Users cannot mute themselves"),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// A user blocking another. Blocks work both ways: neither user can DM,
+/// follow or mention the other, and the blocked user cannot see the
+/// blocker's profile.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct UserBlock {
+    blocker_id: Uuid,
+    blocked_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for UserBlock {
+    fn eq(&self, other: &Self) -> bool {
+        self.blocker_id == other.blocker_id && self.blocked_id == other.blocked_id
+    }
+}
+
+impl UserBlock {
+    pub fn new(
+        blocker_id: Uuid,
+        blocked_id: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, BlockValidationErr> {
+        if blocker_id == blocked_id {
+            return Err(BlockValidationErr::CannotBlockSelf);
+        }
+        Ok(UserBlock {
+            blocker_id,
+            blocked_id,
+            created_at,
+        })
+    }
+
+    pub fn blocker_id(&self) -> Uuid {
+        self.blocker_id
+    }
+
+    pub fn blocked_id(&self) -> Uuid {
+        self.blocked_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// A user muting another. Mutes only hide the muted user's posts and
+/// mentions from the muter, who can still interact with them.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct UserMute {
+    muter_id: Uuid,
+    muted_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for UserMute {
+    fn eq(&self, other: &Self) -> bool {
+        self.muter_id == other.muter_id && self.muted_id == other.muted_id
+    }
+}
+
+impl UserMute {
+    pub fn new(
+        muter_id: Uuid,
+        muted_id: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, BlockValidationErr> {
+        if muter_id == muted_id {
+            return Err(BlockValidationErr::CannotMuteSelf);
+        }
+        Ok(UserMute {
+            muter_id,
+            muted_id,
+            created_at,
+        })
+    }
+
+    pub fn muter_id(&self) -> Uuid {
+        self.muter_id
+    }
+
+    pub fn muted_id(&self) -> Uuid {
+        self.muted_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// A server muted by a user, whose posts are left out of their home feed.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct ServerMute {
+    user_id: Uuid,
+    server_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for ServerMute {
+    fn eq(&self, other: &Self) -> bool {
+        self.user_id == other.user_id && self.server_id == other.server_id
+    }
+}
+
+impl ServerMute {
+    pub fn new(user_id: Uuid, server_id: Uuid, created_at: DateTime<Utc>) -> Self {
+        ServerMute {
+            user_id,
+            server_id,
+            created_at,
+        }
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::block::{BlockValidationErr, UserBlock, UserMute};
+
+    #[test]
+    fn users_cannot_block_or_mute_themselves() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            Err(BlockValidationErr::CannotBlockSelf),
+            UserBlock::new(id, id, Utc::now())
+        );
+        assert_eq!(
+            Err(BlockValidationErr::CannotMuteSelf),
+            UserMute::new(id, id, Utc::now())
+        );
+    }
+}
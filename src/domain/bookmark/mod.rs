@@ -0,0 +1,279 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::{message::Message, post::PostView};
+
+pub const MAX_BOOKMARK_NOTE_LENGTH: usize = 1000;
+pub const MAX_COLLECTION_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, PartialEq)]
+pub enum BookmarkValidationErr {
+    TargetRequired,
+    NoteTooLong,
+    CollectionNameEmpty,
+    CollectionNameTooLong,
+}
+
+impl BookmarkValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::TargetRequired => {
+                String::from("Exactly one of post_id or message_id must be provided")
+            }
+            Self::NoteTooLong => format!(
+                "Bookmark notes must be at most {} characters",
+                MAX_BOOKMARK_NOTE_LENGTH
+            ),
+            Self::CollectionNameEmpty => String::from("Collection names must not be empty"),
+            Self::CollectionNameTooLong => format!(
+                "Collection names must be at most {} characters",
+                MAX_COLLECTION_NAME_LENGTH
+            ),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// What a bookmark saves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BookmarkTarget {
+    Post(Uuid),
+    Message(Uuid),
+}
+
+impl BookmarkTarget {
+    pub fn from_ids(
+        post_id: Option<Uuid>,
+        message_id: Option<Uuid>,
+    ) -> Result<Self, BookmarkValidationErr> {
+        match (post_id, message_id) {
+            (Some(post_id), None) => Ok(Self::Post(post_id)),
+            (None, Some(message_id)) => Ok(Self::Message(message_id)),
+            _ => Err(BookmarkValidationErr::TargetRequired),
+        }
+    }
+}
+
+/// A post or channel message saved to the user's private list, optionally
+/// filed into one of their collections with a note of their own.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Bookmark {
+    id: Uuid,
+    user_id: Uuid,
+    post_id: Option<Uuid>,
+    message_id: Option<Uuid>,
+    collection_id: Option<Uuid>,
+    note: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl PartialEq for Bookmark {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.user_id == other.user_id
+            && self.post_id == other.post_id
+            && self.message_id == other.message_id
+            && self.collection_id == other.collection_id
+            && self.note == other.note
+    }
+}
+
+impl std::fmt::Display for Bookmark {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Bookmark {
+    pub fn new(
+        id: Uuid,
+        user_id: Uuid,
+        target: BookmarkTarget,
+        collection_id: Option<Uuid>,
+        note: Option<String>,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, BookmarkValidationErr> {
+        let (post_id, message_id) = match target {
+            BookmarkTarget::Post(post_id) => (Some(post_id), None),
+            BookmarkTarget::Message(message_id) => (None, Some(message_id)),
+        };
+        Ok(Bookmark {
+            id,
+            user_id,
+            post_id,
+            message_id,
+            collection_id,
+            note: Self::validate_note(note)?,
+            created_at,
+            updated_at: created_at,
+        })
+    }
+
+    /// Trims the note, treating a blank one as no note at all.
+    pub fn validate_note(note: Option<String>) -> Result<Option<String>, BookmarkValidationErr> {
+        let note = note
+            .map(|note| note.trim().to_string())
+            .filter(|note| !note.is_empty());
+        match note {
+            Some(note) if note.chars().count() > MAX_BOOKMARK_NOTE_LENGTH => {
+                Err(BookmarkValidationErr::NoteTooLong)
+            }
+            note => Ok(note),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn target(&self) -> BookmarkTarget {
+        match (self.post_id, self.message_id) {
+            (Some(post_id), _) => BookmarkTarget::Post(post_id),
+            (None, Some(message_id)) => BookmarkTarget::Message(message_id),
+            (None, None) => unreachable!("bookmarks always have a target"),
+        }
+    }
+
+    pub fn collection_id(&self) -> Option<Uuid> {
+        self.collection_id
+    }
+
+    pub fn set_collection_id(&mut self, collection_id: Option<Uuid>, at: DateTime<Utc>) {
+        self.collection_id = collection_id;
+        self.updated_at = at;
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    pub fn set_note(
+        &mut self,
+        note: Option<String>,
+        at: DateTime<Utc>,
+    ) -> Result<(), BookmarkValidationErr> {
+        self.note = Self::validate_note(note)?;
+        self.updated_at = at;
+        Ok(())
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+/// A named folder of bookmarks. Deleting one leaves its bookmarks unsorted.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct BookmarkCollection {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for BookmarkCollection {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.user_id == other.user_id && self.name == other.name
+    }
+}
+
+impl BookmarkCollection {
+    pub fn new(
+        id: Uuid,
+        user_id: Uuid,
+        name: String,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, BookmarkValidationErr> {
+        Ok(BookmarkCollection {
+            id,
+            user_id,
+            name: Self::validate_name(name)?,
+            created_at,
+        })
+    }
+
+    pub fn validate_name(name: String) -> Result<String, BookmarkValidationErr> {
+        let name = name.trim();
+        if name.is_empty() {
+            Err(BookmarkValidationErr::CollectionNameEmpty)
+        } else if name.chars().count() > MAX_COLLECTION_NAME_LENGTH {
+            Err(BookmarkValidationErr::CollectionNameTooLong)
+        } else {
+            Ok(name.to_string())
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn rename(&mut self, name: String) -> Result<(), BookmarkValidationErr> {
+        self.name = Self::validate_name(name)?;
+        Ok(())
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// Whether the user can currently see what a bookmark points at.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkStatus {
+    Available,
+    /// The post or message was deleted. The bookmark stays as a tombstone
+    /// until the user removes it.
+    Deleted,
+    /// The user lost access to the server or channel it was posted in.
+    Unavailable,
+}
+
+/// A bookmark as listed to its owner. The saved post or message is only
+/// included while it is available.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BookmarkView {
+    #[serde(flatten)]
+    pub bookmark: Bookmark,
+    pub status: BookmarkStatus,
+    pub post: Option<PostView>,
+    pub message: Option<Message>,
+}
+
+impl BookmarkView {
+    pub fn new(bookmark: Bookmark, status: BookmarkStatus) -> Self {
+        BookmarkView {
+            bookmark,
+            status,
+            post: None,
+            message: None,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.bookmark.id()
+    }
+}
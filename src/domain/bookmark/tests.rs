@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::bookmark::{
+        Bookmark, BookmarkCollection, BookmarkTarget, BookmarkValidationErr,
+        MAX_BOOKMARK_NOTE_LENGTH, MAX_COLLECTION_NAME_LENGTH,
+    };
+    use claim::{assert_err, assert_ok};
+    use uuid::Uuid;
+
+    #[test]
+    fn bookmarks_target_exactly_one_item() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            Ok(BookmarkTarget::Post(id)),
+            BookmarkTarget::from_ids(Some(id), None)
+        );
+        assert_eq!(
+            Ok(BookmarkTarget::Message(id)),
+            BookmarkTarget::from_ids(None, Some(id))
+        );
+        assert_err!(BookmarkTarget::from_ids(None, None));
+        assert_err!(BookmarkTarget::from_ids(Some(id), Some(id)));
+    }
+
+    #[test]
+    fn notes_are_trimmed_and_length_checked() {
+        assert_eq!(
+            Ok(Some(String::from("read later"))),
+            Bookmark::validate_note(Some(String::from("  read later "))),
+        );
+        assert_eq!(Ok(None), Bookmark::validate_note(Some(String::from("   "))));
+        assert_ok!(Bookmark::validate_note(Some(
+            "a".repeat(MAX_BOOKMARK_NOTE_LENGTH)
+        )));
+        assert_eq!(
+            Err(BookmarkValidationErr::NoteTooLong),
+            Bookmark::validate_note(Some("a".repeat(MAX_BOOKMARK_NOTE_LENGTH + 1))),
+        );
+    }
+
+    #[test]
+    fn collection_names_must_be_non_empty_and_short() {
+        assert_eq!(
+            Ok(String::from("recipes")),
+            BookmarkCollection::validate_name(String::from(" recipes ")),
+        );
+        assert_eq!(
+            Err(BookmarkValidationErr::CollectionNameEmpty),
+            BookmarkCollection::validate_name(String::from("  ")),
+        );
+        assert_eq!(
+            Err(BookmarkValidationErr::CollectionNameTooLong),
+            BookmarkCollection::validate_name("a".repeat(MAX_COLLECTION_NAME_LENGTH + 1)),
+        );
+    }
+}
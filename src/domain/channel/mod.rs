@@ -0,0 +1,300 @@
+mod overwrite;
+#[allow(clippy::module_inception)]
+mod tests;
+
+pub use overwrite::{OverwriteTarget, PermissionOverwrite};
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const MAX_CHANNEL_NAME_LENGTH: usize = 100;
+pub const MAX_CHANNEL_TOPIC_LENGTH: usize = 1024;
+/// How many messages a channel may have pinned unless it is configured
+/// otherwise, and the most it can be configured to allow.
+pub const DEFAULT_MAX_PINS: i32 = 50;
+pub const MAX_PINS_LIMIT: i32 = 250;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelKind {
+    Text,
+    Voice,
+    Announcement,
+    Category,
+    Thread,
+}
+
+impl ChannelKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Voice => "voice",
+            Self::Announcement => "announcement",
+            Self::Category => "category",
+            Self::Thread => "thread",
+        }
+    }
+
+    /// Whether messages can be sent to channels of this kind.
+    pub fn is_messageable(&self) -> bool {
+        matches!(self, Self::Text | Self::Announcement | Self::Thread)
+    }
+
+    /// Whether threads can be started from messages in channels of this kind.
+    pub fn can_have_threads(&self) -> bool {
+        matches!(self, Self::Text | Self::Announcement)
+    }
+}
+
+impl TryFrom<&str> for ChannelKind {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "text" => Ok(Self::Text),
+            "voice" => Ok(Self::Voice),
+            "announcement" => Ok(Self::Announcement),
+            "category" => Ok(Self::Category),
+            "thread" => Ok(Self::Thread),
+            other => Err(format!("{} is not a valid channel kind", other)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ChannelValidationErr {
+    NameEmpty,
+    NameTooLong,
+    TopicTooLong,
+    CategoryCannotHaveParent,
+    ParentNotCategory,
+    ParentInOtherServer,
+    ThreadNotCreatable,
+    InvalidMaxPins,
+}
+
+impl ChannelValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::NameEmpty => String::from("Channel name is empty"),
+            Self::NameTooLong => format!(
+                "Channel name is too long, must be no more than {} characters",
+                MAX_CHANNEL_NAME_LENGTH
+            ),
+            Self::TopicTooLong => format!(
+                "Channel topic is too long, must be no more than {} characters",
+                MAX_CHANNEL_TOPIC_LENGTH
+            ),
+            Self::CategoryCannotHaveParent => {
+                String::from("Category channels may not have a parent category")
+            }
+            Self::ParentNotCategory => String::from("Parent channel must be a category"),
+            Self::ParentInOtherServer => {
+                String::from("Parent category must belong to the same server")
+            }
+            Self::ThreadNotCreatable => String::from("Threads can only be started from a message"),
+            Self::InvalidMaxPins => {
+                format!("Channel pin limit must be between 1 and {}", MAX_PINS_LIMIT)
+            }
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Channel {
+    id: Uuid,
+    server_id: Uuid,
+    parent_id: Option<Uuid>,
+    kind: ChannelKind,
+    name: String,
+    topic: Option<String>,
+    position: i32,
+    max_pins: i32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl PartialEq for Channel {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.server_id == other.server_id
+            && self.parent_id == other.parent_id
+            && self.kind == other.kind
+            && self.name == other.name
+            && self.topic == other.topic
+            && self.position == other.position
+            && self.max_pins == other.max_pins
+            && self.deleted_at == other.deleted_at
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Channel {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        server_id: Uuid,
+        parent_id: Option<Uuid>,
+        kind: ChannelKind,
+        name: String,
+        topic: Option<String>,
+        position: i32,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Channel {
+            id,
+            server_id,
+            parent_id,
+            kind,
+            name,
+            topic,
+            position,
+            max_pins: DEFAULT_MAX_PINS,
+            created_at,
+            updated_at,
+            deleted_at,
+        }
+    }
+
+    pub fn validate_name(name: &str) -> Result<(), ChannelValidationErr> {
+        if name.trim().is_empty() {
+            Err(ChannelValidationErr::NameEmpty)
+        } else if name.chars().count() > MAX_CHANNEL_NAME_LENGTH {
+            Err(ChannelValidationErr::NameTooLong)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn validate_topic(topic: Option<&str>) -> Result<(), ChannelValidationErr> {
+        match topic {
+            Some(topic) if topic.chars().count() > MAX_CHANNEL_TOPIC_LENGTH => {
+                Err(ChannelValidationErr::TopicTooLong)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn validate_max_pins(max_pins: i32) -> Result<(), ChannelValidationErr> {
+        if (1..=MAX_PINS_LIMIT).contains(&max_pins) {
+            Ok(())
+        } else {
+            Err(ChannelValidationErr::InvalidMaxPins)
+        }
+    }
+
+    /// Checks that `parent` can contain a channel of `kind` in `server_id`.
+    pub fn validate_parent(
+        server_id: Uuid,
+        kind: ChannelKind,
+        parent: Option<&Channel>,
+    ) -> Result<(), ChannelValidationErr> {
+        match parent {
+            _ if kind == ChannelKind::Thread => Err(ChannelValidationErr::ThreadNotCreatable),
+            None => Ok(()),
+            Some(_) if kind == ChannelKind::Category => {
+                Err(ChannelValidationErr::CategoryCannotHaveParent)
+            }
+            Some(parent) if parent.server_id != server_id => {
+                Err(ChannelValidationErr::ParentInOtherServer)
+            }
+            Some(parent) if parent.kind != ChannelKind::Category => {
+                Err(ChannelValidationErr::ParentNotCategory)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    pub fn parent_id(&self) -> Option<Uuid> {
+        self.parent_id
+    }
+
+    /// The channel whose permission overwrites apply here. Threads have none
+    /// of their own and inherit their parent's.
+    pub fn overwrites_channel_id(&self) -> Uuid {
+        match (self.kind, self.parent_id) {
+            (ChannelKind::Thread, Some(parent_id)) => parent_id,
+            _ => self.id,
+        }
+    }
+
+    pub fn kind(&self) -> ChannelKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn topic(&self) -> Option<String> {
+        self.topic.clone()
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn max_pins(&self) -> i32 {
+        self.max_pins
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn set_parent_id(&mut self, parent_id: Option<Uuid>) {
+        self.parent_id = parent_id;
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_topic(&mut self, topic: Option<String>) {
+        self.topic = topic;
+    }
+
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+    }
+
+    pub fn set_max_pins(&mut self, max_pins: i32) {
+        self.max_pins = max_pins;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: DateTime<Utc>) {
+        self.updated_at = updated_at;
+    }
+
+    pub fn set_deleted_at(&mut self, deleted_at: Option<DateTime<Utc>>) {
+        self.deleted_at = deleted_at;
+    }
+}
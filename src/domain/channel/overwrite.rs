@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::permission::Permissions;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwriteTarget {
+    Role,
+    Member,
+}
+
+impl OverwriteTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Role => "role",
+            Self::Member => "member",
+        }
+    }
+}
+
+impl TryFrom<&str> for OverwriteTarget {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "role" => Ok(Self::Role),
+            "member" => Ok(Self::Member),
+            other => Err(format!("{} is not a valid overwrite target", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct PermissionOverwrite {
+    channel_id: Uuid,
+    target_id: Uuid,
+    target_type: OverwriteTarget,
+    allow: Permissions,
+    deny: Permissions,
+}
+
+impl PermissionOverwrite {
+    pub fn new(
+        channel_id: Uuid,
+        target_id: Uuid,
+        target_type: OverwriteTarget,
+        allow: Permissions,
+        deny: Permissions,
+    ) -> Self {
+        PermissionOverwrite {
+            channel_id,
+            target_id,
+            target_type,
+            allow,
+            deny,
+        }
+    }
+
+    pub fn channel_id(&self) -> Uuid {
+        self.channel_id
+    }
+
+    pub fn target_id(&self) -> Uuid {
+        self.target_id
+    }
+
+    pub fn target_type(&self) -> OverwriteTarget {
+        self.target_type
+    }
+
+    pub fn allow(&self) -> Permissions {
+        self.allow
+    }
+
+    pub fn deny(&self) -> Permissions {
+        self.deny
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::channel::{
+        Channel, ChannelKind, ChannelValidationErr, MAX_CHANNEL_NAME_LENGTH,
+        MAX_CHANNEL_TOPIC_LENGTH, MAX_PINS_LIMIT,
+    };
+    use chrono::Utc;
+    use claim::{assert_err, assert_ok};
+    use uuid::Uuid;
+
+    fn channel(server_id: Uuid, kind: ChannelKind) -> Channel {
+        let now = Utc::now();
+        Channel::new(
+            Uuid::new_v4(),
+            server_id,
+            None,
+            kind,
+            String::from("general"),
+            None,
+            0,
+            now,
+            now,
+            None,
+        )
+    }
+
+    #[test]
+    fn channel_name_length_is_validated() {
+        assert_ok!(Channel::validate_name(&"a".repeat(MAX_CHANNEL_NAME_LENGTH)));
+        assert_err!(Channel::validate_name(
+            &"a".repeat(MAX_CHANNEL_NAME_LENGTH + 1)
+        ));
+        assert_eq!(
+            Err(ChannelValidationErr::NameEmpty),
+            Channel::validate_name("   ")
+        );
+    }
+
+    #[test]
+    fn channel_topic_length_is_validated() {
+        assert_ok!(Channel::validate_topic(None));
+        assert_ok!(Channel::validate_topic(Some(
+            &"a".repeat(MAX_CHANNEL_TOPIC_LENGTH)
+        )));
+        assert_err!(Channel::validate_topic(Some(
+            &"a".repeat(MAX_CHANNEL_TOPIC_LENGTH + 1)
+        )));
+    }
+
+    #[test]
+    fn channel_pin_limit_is_validated() {
+        assert_ok!(Channel::validate_max_pins(1));
+        assert_ok!(Channel::validate_max_pins(MAX_PINS_LIMIT));
+        assert_eq!(
+            Err(ChannelValidationErr::InvalidMaxPins),
+            Channel::validate_max_pins(0)
+        );
+        assert_err!(Channel::validate_max_pins(MAX_PINS_LIMIT + 1));
+    }
+
+    #[test]
+    fn channel_parent_must_be_category_in_same_server() {
+        let server_id = Uuid::new_v4();
+        let category = channel(server_id, ChannelKind::Category);
+        let text = channel(server_id, ChannelKind::Text);
+        let foreign_category = channel(Uuid::new_v4(), ChannelKind::Category);
+
+        assert_ok!(Channel::validate_parent(
+            server_id,
+            ChannelKind::Text,
+            Some(&category)
+        ));
+        assert_eq!(
+            Err(ChannelValidationErr::ParentNotCategory),
+            Channel::validate_parent(server_id, ChannelKind::Voice, Some(&text))
+        );
+        assert_eq!(
+            Err(ChannelValidationErr::ParentInOtherServer),
+            Channel::validate_parent(server_id, ChannelKind::Text, Some(&foreign_category))
+        );
+        assert_eq!(
+            Err(ChannelValidationErr::CategoryCannotHaveParent),
+            Channel::validate_parent(server_id, ChannelKind::Category, Some(&category))
+        );
+        assert_eq!(
+            Err(ChannelValidationErr::ThreadNotCreatable),
+            Channel::validate_parent(server_id, ChannelKind::Thread, None)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::dm::{DmThreadValidationErr, MAX_DM_TTL_SECONDS, MIN_DM_TTL_SECONDS};
+use crate::domain::message::MessageContent;
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct DirectMessage {
+    id: Uuid,
+    thread_id: Uuid,
+    author_id: Uuid,
+    content: MessageContent,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl PartialEq for DirectMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.thread_id == other.thread_id
+            && self.author_id == other.author_id
+            && self.content == other.content
+            && self.deleted_at == other.deleted_at
+            && self.expires_at == other.expires_at
+    }
+}
+
+impl std::fmt::Display for DirectMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl DirectMessage {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        thread_id: Uuid,
+        author_id: Uuid,
+        content: MessageContent,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        edited_at: Option<DateTime<Utc>>,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        DirectMessage {
+            id,
+            thread_id,
+            author_id,
+            content,
+            created_at,
+            updated_at,
+            edited_at,
+            deleted_at,
+            expires_at: None,
+        }
+    }
+
+    /// Turns a requested time to live into the moment the message disappears.
+    pub fn expiry(
+        sent_at: DateTime<Utc>,
+        ttl_seconds: i64,
+    ) -> Result<DateTime<Utc>, DmThreadValidationErr> {
+        if !(MIN_DM_TTL_SECONDS..=MAX_DM_TTL_SECONDS).contains(&ttl_seconds) {
+            return Err(DmThreadValidationErr::InvalidTtl);
+        }
+        Ok(sent_at + Duration::seconds(ttl_seconds))
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn thread_id(&self) -> Uuid {
+        self.thread_id
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn content(&self) -> MessageContent {
+        self.content.clone()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn edited_at(&self) -> Option<DateTime<Utc>> {
+        self.edited_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn set_content(&mut self, content: MessageContent) {
+        self.content = content;
+    }
+
+    pub fn set_edited_at(&mut self, edited_at: Option<DateTime<Utc>>) {
+        self.edited_at = edited_at;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: DateTime<Utc>) {
+        self.updated_at = updated_at;
+    }
+
+    pub fn set_deleted_at(&mut self, deleted_at: Option<DateTime<Utc>>) {
+        self.deleted_at = deleted_at;
+    }
+
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.expires_at = expires_at;
+    }
+}
@@ -0,0 +1,194 @@
+mod message;
+#[allow(clippy::module_inception)]
+mod tests;
+
+pub use message::DirectMessage;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const MAX_GROUP_DM_NAME_LENGTH: usize = 100;
+/// The most people a group DM may hold, owner included.
+pub const MAX_GROUP_DM_PARTICIPANTS: usize = 10;
+/// Bounds on how long a disappearing DM may live, in seconds.
+pub const MIN_DM_TTL_SECONDS: i64 = 5;
+pub const MAX_DM_TTL_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DmThreadKind {
+    Direct,
+    Group,
+}
+
+impl DmThreadKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Direct => "direct",
+            Self::Group => "group",
+        }
+    }
+}
+
+impl TryFrom<&str> for DmThreadKind {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "direct" => Ok(Self::Direct),
+            "group" => Ok(Self::Group),
+            other => Err(format!("{} is not a valid DM thread kind", other)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DmThreadValidationErr {
+    NameEmpty,
+    NameTooLong,
+    TooManyParticipants,
+    CannotMessageSelf,
+    InvalidTtl,
+}
+
+impl DmThreadValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::NameEmpty => String::from("Group DM name is empty"),
+            Self::NameTooLong => format!(
+                "Group DM name is too long, must be no more than {} characters",
+                MAX_GROUP_DM_NAME_LENGTH
+            ),
+            Self::TooManyParticipants => format!(
+                "Group DMs may have no more than {} participants",
+                MAX_GROUP_DM_PARTICIPANTS
+            ),
+            Self::CannotMessageSelf => String::from("Cannot open a DM thread with yourself"),
+            Self::InvalidTtl => format!(
+                "Message TTL must be between {} and {} seconds",
+                MIN_DM_TTL_SECONDS, MAX_DM_TTL_SECONDS
+            ),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct DmThread {
+    id: Uuid,
+    kind: DmThreadKind,
+    owner_id: Option<Uuid>,
+    name: Option<String>,
+    last_message_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl PartialEq for DmThread {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.kind == other.kind
+            && self.owner_id == other.owner_id
+            && self.name == other.name
+            && self.deleted_at == other.deleted_at
+    }
+}
+
+impl std::fmt::Display for DmThread {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl DmThread {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        kind: DmThreadKind,
+        owner_id: Option<Uuid>,
+        name: Option<String>,
+        last_message_at: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        DmThread {
+            id,
+            kind,
+            owner_id,
+            name,
+            last_message_at,
+            created_at,
+            updated_at,
+            deleted_at,
+        }
+    }
+
+    pub fn validate_name(name: &str) -> Result<(), DmThreadValidationErr> {
+        if name.trim().is_empty() {
+            Err(DmThreadValidationErr::NameEmpty)
+        } else if name.chars().count() > MAX_GROUP_DM_NAME_LENGTH {
+            Err(DmThreadValidationErr::NameTooLong)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the size of a group DM, counting the owner among `participants`.
+    pub fn validate_participant_count(participants: usize) -> Result<(), DmThreadValidationErr> {
+        if participants > MAX_GROUP_DM_PARTICIPANTS {
+            Err(DmThreadValidationErr::TooManyParticipants)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Orders a pair of users the way direct threads are keyed in storage.
+    pub fn direct_pair(a: Uuid, b: Uuid) -> Result<(Uuid, Uuid), DmThreadValidationErr> {
+        match a.cmp(&b) {
+            std::cmp::Ordering::Less => Ok((a, b)),
+            std::cmp::Ordering::Greater => Ok((b, a)),
+            std::cmp::Ordering::Equal => Err(DmThreadValidationErr::CannotMessageSelf),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn kind(&self) -> DmThreadKind {
+        self.kind
+    }
+
+    pub fn owner_id(&self) -> Option<Uuid> {
+        self.owner_id
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn last_message_at(&self) -> Option<DateTime<Utc>> {
+        self.last_message_at
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn set_owner_id(&mut self, owner_id: Option<Uuid>) {
+        self.owner_id = owner_id;
+    }
+}
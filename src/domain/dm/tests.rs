@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::dm::{
+        DirectMessage, DmThread, DmThreadValidationErr, MAX_DM_TTL_SECONDS,
+        MAX_GROUP_DM_NAME_LENGTH, MAX_GROUP_DM_PARTICIPANTS, MIN_DM_TTL_SECONDS,
+    };
+    use chrono::{Duration, Utc};
+    use claim::{assert_err, assert_ok};
+    use uuid::Uuid;
+
+    #[test]
+    fn a_group_name_of_max_length_is_valid() {
+        assert_ok!(DmThread::validate_name(
+            &"a".repeat(MAX_GROUP_DM_NAME_LENGTH)
+        ));
+    }
+
+    #[test]
+    fn an_invalid_group_name_is_rejected() {
+        let test_cases = [
+            ("", DmThreadValidationErr::NameEmpty, "is empty"),
+            ("   ", DmThreadValidationErr::NameEmpty, "is whitespace"),
+            (
+                &"a".repeat(MAX_GROUP_DM_NAME_LENGTH + 1),
+                DmThreadValidationErr::NameTooLong,
+                "is too long",
+            ),
+        ];
+
+        for (name, expected, case) in test_cases {
+            assert_eq!(
+                Err(expected),
+                DmThread::validate_name(name),
+                "A name that {} was not rejected",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn group_size_is_capped() {
+        assert_ok!(DmThread::validate_participant_count(
+            MAX_GROUP_DM_PARTICIPANTS
+        ));
+        assert_err!(DmThread::validate_participant_count(
+            MAX_GROUP_DM_PARTICIPANTS + 1
+        ));
+    }
+
+    #[test]
+    fn direct_pairs_are_ordered_regardless_of_argument_order() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(
+            DmThread::direct_pair(a, b),
+            DmThread::direct_pair(b, a),
+            "The pair was not ordered consistently"
+        );
+        let (first, second) = DmThread::direct_pair(a, b).unwrap();
+        assert!(first < second, "The pair was not in ascending order");
+    }
+
+    #[test]
+    fn a_direct_pair_with_oneself_is_rejected() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            Err(DmThreadValidationErr::CannotMessageSelf),
+            DmThread::direct_pair(id, id)
+        );
+    }
+
+    #[test]
+    fn a_ttl_outside_the_bounds_is_rejected() {
+        let now = Utc::now();
+        assert_eq!(
+            Ok(now + Duration::seconds(MIN_DM_TTL_SECONDS)),
+            DirectMessage::expiry(now, MIN_DM_TTL_SECONDS)
+        );
+        assert_ok!(DirectMessage::expiry(now, MAX_DM_TTL_SECONDS));
+        for ttl in [MIN_DM_TTL_SECONDS - 1, MAX_DM_TTL_SECONDS + 1, -60] {
+            assert_eq!(
+                Err(DmThreadValidationErr::InvalidTtl),
+                DirectMessage::expiry(now, ttl),
+                "A TTL of {} seconds was not rejected",
+                ttl
+            );
+        }
+    }
+}
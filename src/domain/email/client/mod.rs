@@ -1,4 +1,5 @@
 mod confirmation_email;
+#[allow(clippy::module_inception)]
 mod tests;
 
 use super::ServerEmail;
@@ -0,0 +1,142 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, PartialEq)]
+pub enum FollowValidationErr {
+    CannotFollowSelf,
+}
+
+impl FollowValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::CannotFollowSelf => String::from("Users cannot follow themselves"),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// One user following another.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Follow {
+    follower_id: Uuid,
+    followee_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for Follow {
+    fn eq(&self, other: &Self) -> bool {
+        self.follower_id == other.follower_id && self.followee_id == other.followee_id
+    }
+}
+
+impl std::fmt::Display for Follow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Follow {
+    pub fn new(
+        follower_id: Uuid,
+        followee_id: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, FollowValidationErr> {
+        if follower_id == followee_id {
+            return Err(FollowValidationErr::CannotFollowSelf);
+        }
+        Ok(Follow {
+            follower_id,
+            followee_id,
+            created_at,
+        })
+    }
+
+    pub fn follower_id(&self) -> Uuid {
+        self.follower_id
+    }
+
+    pub fn followee_id(&self) -> Uuid {
+        self.followee_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// A follow of a private account, waiting for the target to accept it.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct FollowRequest {
+    requester_id: Uuid,
+    target_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for FollowRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.requester_id == other.requester_id && self.target_id == other.target_id
+    }
+}
+
+impl std::fmt::Display for FollowRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FollowRequest {
+    pub fn new(requester_id: Uuid, target_id: Uuid, created_at: DateTime<Utc>) -> Self {
+        FollowRequest {
+            requester_id,
+            target_id,
+            created_at,
+        }
+    }
+
+    pub fn requester_id(&self) -> Uuid {
+        self.requester_id
+    }
+
+    pub fn target_id(&self) -> Uuid {
+        self.target_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// The follow this request becomes once accepted.
+    pub fn accept(&self, accepted_at: DateTime<Utc>) -> Follow {
+        Follow {
+            follower_id: self.requester_id,
+            followee_id: self.target_id,
+            created_at: accepted_at,
+        }
+    }
+}
+
+/// Where the viewer stands with a user they asked to follow.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FollowStatus {
+    Following,
+    Requested,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FollowResponse {
+    pub status: FollowStatus,
+}
+
+/// How many users follow a user and how many they follow.
+#[derive(Serialize, Deserialize, FromRow, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FollowCounts {
+    pub follower_count: i64,
+    pub following_count: i64,
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::follow::{Follow, FollowRequest, FollowValidationErr};
+
+    #[test]
+    fn users_cannot_follow_themselves() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            Err(FollowValidationErr::CannotFollowSelf),
+            Follow::new(id, id, Utc::now())
+        );
+    }
+
+    #[test]
+    fn an_accepted_request_becomes_a_follow() {
+        let (requester_id, target_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let request = FollowRequest::new(requester_id, target_id, Utc::now());
+        assert_eq!(
+            Follow::new(requester_id, target_id, Utc::now()).unwrap(),
+            request.accept(Utc::now())
+        );
+    }
+}
@@ -0,0 +1,145 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, PartialEq)]
+pub enum FriendValidationErr {
+    CannotFriendSelf,
+}
+
+impl FriendValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::CannotFriendSelf => String::from("Users cannot befriend themselves"),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// One side of a friendship, as seen by `user_id`. Friendships are mutual,
+/// so the other side always exists too.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Friendship {
+    user_id: Uuid,
+    friend_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for Friendship {
+    fn eq(&self, other: &Self) -> bool {
+        self.user_id == other.user_id && self.friend_id == other.friend_id
+    }
+}
+
+impl Friendship {
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn friend_id(&self) -> Uuid {
+        self.friend_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// A request to become friends, waiting on the recipient.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct FriendRequest {
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for FriendRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.sender_id == other.sender_id && self.recipient_id == other.recipient_id
+    }
+}
+
+impl std::fmt::Display for FriendRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FriendRequest {
+    pub fn new(
+        sender_id: Uuid,
+        recipient_id: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, FriendValidationErr> {
+        if sender_id == recipient_id {
+            return Err(FriendValidationErr::CannotFriendSelf);
+        }
+        Ok(FriendRequest {
+            sender_id,
+            recipient_id,
+            created_at,
+        })
+    }
+
+    pub fn sender_id(&self) -> Uuid {
+        self.sender_id
+    }
+
+    pub fn recipient_id(&self) -> Uuid {
+        self.recipient_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Both sides of the friendship this request becomes once accepted.
+    pub fn accept(&self, accepted_at: DateTime<Utc>) -> [Friendship; 2] {
+        [
+            Friendship {
+                user_id: self.sender_id,
+                friend_id: self.recipient_id,
+                created_at: accepted_at,
+            },
+            Friendship {
+                user_id: self.recipient_id,
+                friend_id: self.sender_id,
+                created_at: accepted_at,
+            },
+        ]
+    }
+}
+
+/// Which of the user's pending friend requests to list.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FriendRequestDirection {
+    #[default]
+    Incoming,
+    Outgoing,
+}
+
+/// Where the viewer stands with a user they sent a friend request to.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FriendStatus {
+    Friends,
+    Requested,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriendRequestResponse {
+    pub status: FriendStatus,
+}
+
+/// The friends and servers the viewer shares with another user.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mutuals {
+    pub friend_ids: Vec<Uuid>,
+    pub server_ids: Vec<Uuid>,
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::domain::friend::{FriendRequest, FriendRequestDirection, FriendValidationErr};
+
+    #[test]
+    fn users_cannot_befriend_themselves() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            Err(FriendValidationErr::CannotFriendSelf),
+            FriendRequest::new(id, id, Utc::now())
+        );
+    }
+
+    #[test]
+    fn an_accepted_request_befriends_both_users() {
+        let (sender_id, recipient_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let [sent, received] = FriendRequest::new(sender_id, recipient_id, Utc::now())
+            .unwrap()
+            .accept(Utc::now());
+        assert_eq!(
+            (sender_id, recipient_id),
+            (sent.user_id(), sent.friend_id())
+        );
+        assert_eq!(
+            (recipient_id, sender_id),
+            (received.user_id(), received.friend_id())
+        );
+    }
+
+    #[test]
+    fn incoming_requests_are_listed_by_default() {
+        assert_eq!(
+            FriendRequestDirection::Incoming,
+            FriendRequestDirection::default()
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use actix_web::HttpResponse;
+use serde::{
+    de::{Unexpected, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+pub const MAX_MESSAGE_CONTENT_LENGTH: usize = 2000;
+
+#[derive(Debug, PartialEq)]
+pub enum MessageContentValidationErr {
+    ContentEmpty,
+    ContentTooLong,
+}
+
+impl MessageContentValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::ContentEmpty => String::from("Message content is empty"),
+            Self::ContentTooLong => format!(
+                "Message content is too long, must be no more than {} characters",
+                MAX_MESSAGE_CONTENT_LENGTH
+            ),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageContent(String);
+
+impl std::fmt::Display for MessageContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for MessageContent {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for MessageContent {
+    type Error = MessageContentValidationErr;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.trim().is_empty() {
+            Err(MessageContentValidationErr::ContentEmpty)
+        } else if value.chars().count() > MAX_MESSAGE_CONTENT_LENGTH {
+            Err(MessageContentValidationErr::ContentTooLong)
+        } else {
+            Ok(MessageContent(value))
+        }
+    }
+}
+
+impl TryFrom<&str> for MessageContent {
+    type Error = MessageContentValidationErr;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_from(value.to_string())
+    }
+}
+
+impl Serialize for MessageContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_string(MessageContentVisitor)
+    }
+}
+
+struct MessageContentVisitor;
+
+impl<'de> Visitor<'de> for MessageContentVisitor {
+    type Value = MessageContent;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "non-empty message content no longer than {} characters",
+            MAX_MESSAGE_CONTENT_LENGTH
+        )
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        MessageContent::try_from(v.as_str())
+            .map_err(|_| E::invalid_value(Unexpected::Str(&v), &self))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_string(v.to_string())
+    }
+}
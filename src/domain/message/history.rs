@@ -0,0 +1,85 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::MessageRevision;
+
+/// How long deleted content and superseded revisions are kept for
+/// moderators before they are purged.
+pub const MESSAGE_RETENTION_DAYS: i64 = 30;
+
+/// Anything deleted or superseded before this is past retention.
+pub fn retention_cutoff(now: DateTime<Utc>) -> DateTime<Utc> {
+    now - Duration::days(MESSAGE_RETENTION_DAYS)
+}
+
+/// The audit trail of a message as moderators see it: every revision still
+/// retained, oldest first, and who deleted the message if it was deleted.
+/// The last revision holds the latest content, including deleted content.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MessageHistory {
+    message_id: Uuid,
+    channel_id: Uuid,
+    author_id: Uuid,
+    created_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+    deleted_by: Option<Uuid>,
+    revisions: Vec<MessageRevision>,
+}
+
+impl MessageHistory {
+    pub fn new(
+        message_id: Uuid,
+        channel_id: Uuid,
+        author_id: Uuid,
+        created_at: DateTime<Utc>,
+        deleted_at: Option<DateTime<Utc>>,
+        deleted_by: Option<Uuid>,
+        revisions: Vec<MessageRevision>,
+    ) -> Self {
+        MessageHistory {
+            message_id,
+            channel_id,
+            author_id,
+            created_at,
+            deleted_at,
+            deleted_by,
+            revisions,
+        }
+    }
+
+    /// Whether the message was deleted long enough ago that its content is
+    /// due to be purged, even if the purge job has not run yet.
+    pub fn is_past_retention(&self, now: DateTime<Utc>) -> bool {
+        self.deleted_at
+            .is_some_and(|deleted_at| deleted_at < retention_cutoff(now))
+    }
+
+    pub fn message_id(&self) -> Uuid {
+        self.message_id
+    }
+
+    pub fn channel_id(&self) -> Uuid {
+        self.channel_id
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn deleted_by(&self) -> Option<Uuid> {
+        self.deleted_by
+    }
+
+    pub fn revisions(&self) -> &[MessageRevision] {
+        &self.revisions
+    }
+}
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::user::ALLOWED_HANDLE_CHARS;
+
+/// The most distinct user, role and channel mentions each resolved from one
+/// message. Anything past the cap is left as plain text.
+pub const MAX_MENTIONS_PER_MESSAGE: usize = 50;
+
+/// Punctuation that may follow a mention as part of the sentence rather than
+/// the mention itself, e.g. the comma in "thanks @ana, see #general.".
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ':', ';', '!', '?'];
+
+/// Mentions found in message content, before they are resolved against the
+/// server. Users are written `@handle`, channels `#name` and roles
+/// `<@&role_id>`; `@everyone` and `@here` are mass mentions. User and
+/// channel mentions only count at the start of the content or after
+/// whitespace, so email addresses and URL fragments are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedMentions {
+    handles: Vec<String>,
+    channel_names: Vec<String>,
+    role_ids: Vec<Uuid>,
+    everyone: bool,
+    here: bool,
+}
+
+impl ParsedMentions {
+    pub fn parse(content: &str) -> Self {
+        let mut mentions = ParsedMentions::default();
+        let mut rest = content;
+        let mut at_boundary = true;
+
+        while let Some(c) = rest.chars().next() {
+            if let Some(role_id) = rest.strip_prefix("<@&").and_then(parse_role_id) {
+                push_unique(&mut mentions.role_ids, role_id);
+                rest = &rest["<@&>".len() + role_id.to_string().len()..];
+                at_boundary = false;
+                continue;
+            }
+
+            if at_boundary && (c == '@' || c == '#') {
+                let token = &rest[1..];
+                let token = if c == '@' {
+                    &token[..token
+                        .find(|c: char| !ALLOWED_HANDLE_CHARS.contains(&c))
+                        .unwrap_or(token.len())]
+                } else {
+                    &token[..token.find(char::is_whitespace).unwrap_or(token.len())]
+                };
+                let trimmed = token.trim_end_matches(TRAILING_PUNCTUATION);
+                match (c, trimmed) {
+                    (_, "") => {}
+                    ('@', "everyone") => mentions.everyone = true,
+                    ('@', "here") => mentions.here = true,
+                    ('@', _) => push_unique(&mut mentions.handles, token.to_string()),
+                    _ => push_unique(&mut mentions.channel_names, trimmed.to_lowercase()),
+                }
+                rest = &rest[1 + token.len()..];
+                at_boundary = false;
+                continue;
+            }
+
+            at_boundary = c.is_whitespace();
+            rest = &rest[c.len_utf8()..];
+        }
+
+        mentions.handles.truncate(MAX_MENTIONS_PER_MESSAGE);
+        mentions.channel_names.truncate(MAX_MENTIONS_PER_MESSAGE);
+        mentions.role_ids.truncate(MAX_MENTIONS_PER_MESSAGE);
+        mentions
+    }
+
+    /// Every handle that might have been meant, to look up in one query. A
+    /// mention ending in punctuation could be a handle that really ends that
+    /// way, so both forms are candidates.
+    pub fn handle_candidates(&self) -> Vec<String> {
+        let mut candidates = Vec::new();
+        for handle in &self.handles {
+            push_unique(&mut candidates, handle.clone());
+            let trimmed = handle.trim_end_matches(TRAILING_PUNCTUATION);
+            if !trimmed.is_empty() {
+                push_unique(&mut candidates, trimmed.to_string());
+            }
+        }
+        candidates
+    }
+
+    /// Picks, for each `@handle` mention, the candidate that belongs to a
+    /// real user, preferring the untrimmed form.
+    pub fn matched_handles(&self, existing: &[String]) -> Vec<String> {
+        let mut matched = Vec::new();
+        for handle in &self.handles {
+            let trimmed = handle.trim_end_matches(TRAILING_PUNCTUATION);
+            if existing.contains(handle) {
+                push_unique(&mut matched, handle.clone());
+            } else if existing.iter().any(|e| e == trimmed) {
+                push_unique(&mut matched, trimmed.to_string());
+            }
+        }
+        matched
+    }
+
+    pub fn handles(&self) -> &[String] {
+        &self.handles
+    }
+
+    /// Lowercased, since channels are matched by name case-insensitively.
+    pub fn channel_names(&self) -> &[String] {
+        &self.channel_names
+    }
+
+    pub fn role_ids(&self) -> &[Uuid] {
+        &self.role_ids
+    }
+
+    pub fn everyone(&self) -> bool {
+        self.everyone
+    }
+
+    pub fn here(&self) -> bool {
+        self.here
+    }
+
+    pub fn is_mass(&self) -> bool {
+        self.everyone || self.here || !self.role_ids.is_empty()
+    }
+}
+
+fn parse_role_id(rest: &str) -> Option<Uuid> {
+    let end = rest.find('>')?;
+    Uuid::parse_str(&rest[..end])
+        .ok()
+        .filter(|id| id.to_string() == rest[..end].to_lowercase())
+}
+
+fn push_unique<T: PartialEq>(items: &mut Vec<T>, item: T) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
+
+/// The resolved mentions stored alongside a message. `mention_everyone` is
+/// set for both `@everyone` and `@here`, and only when the author was
+/// allowed to use them.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, Default, PartialEq)]
+pub struct MessageMentions {
+    mentions: Vec<Uuid>,
+    mention_roles: Vec<Uuid>,
+    mention_channels: Vec<Uuid>,
+    mention_everyone: bool,
+}
+
+impl MessageMentions {
+    pub fn new(
+        mentions: Vec<Uuid>,
+        mention_roles: Vec<Uuid>,
+        mention_channels: Vec<Uuid>,
+        mention_everyone: bool,
+    ) -> Self {
+        MessageMentions {
+            mentions,
+            mention_roles,
+            mention_channels,
+            mention_everyone,
+        }
+    }
+
+    pub fn mentions(&self) -> &[Uuid] {
+        &self.mentions
+    }
+
+    pub fn mention_roles(&self) -> &[Uuid] {
+        &self.mention_roles
+    }
+
+    pub fn mention_channels(&self) -> &[Uuid] {
+        &self.mention_channels
+    }
+
+    pub fn mention_everyone(&self) -> bool {
+        self.mention_everyone
+    }
+}
@@ -0,0 +1,205 @@
+mod content;
+mod history;
+mod mention;
+mod pin;
+mod revision;
+#[allow(clippy::module_inception)]
+mod tests;
+
+pub use content::{MessageContent, MessageContentValidationErr, MAX_MESSAGE_CONTENT_LENGTH};
+pub use history::{retention_cutoff, MessageHistory, MESSAGE_RETENTION_DAYS};
+pub use mention::{MessageMentions, ParsedMentions, MAX_MENTIONS_PER_MESSAGE};
+pub use pin::PinnedMessage;
+pub use revision::MessageRevision;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Where a page of channel messages is anchored. Pages are always returned
+/// newest first regardless of the direction they were fetched in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessageCursor {
+    Latest,
+    Before(Uuid),
+    After(Uuid),
+    Around(Uuid),
+}
+
+/// Regular messages are written by users. The others are system messages,
+/// sent on the user's behalf to record something that happened in the
+/// channel; they cannot be edited.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    #[default]
+    Default,
+    MessagePinned,
+    MessageUnpinned,
+}
+
+impl MessageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::MessagePinned => "message_pinned",
+            Self::MessageUnpinned => "message_unpinned",
+        }
+    }
+
+    pub fn is_system(&self) -> bool {
+        *self != Self::Default
+    }
+}
+
+impl TryFrom<&str> for MessageKind {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "default" => Ok(Self::Default),
+            "message_pinned" => Ok(Self::MessagePinned),
+            "message_unpinned" => Ok(Self::MessageUnpinned),
+            other => Err(format!("{} is not a valid message kind", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Message {
+    id: Uuid,
+    channel_id: Uuid,
+    author_id: Uuid,
+    content: MessageContent,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+    kind: MessageKind,
+    /// The message replied to or, for pin system messages, the message that
+    /// was pinned or unpinned.
+    reply_to_id: Option<Uuid>,
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    mentions: MessageMentions,
+}
+
+impl PartialEq for Message {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.channel_id == other.channel_id
+            && self.author_id == other.author_id
+            && self.content == other.content
+            && self.deleted_at == other.deleted_at
+            && self.kind == other.kind
+            && self.reply_to_id == other.reply_to_id
+            && self.mentions == other.mentions
+    }
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Message {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        channel_id: Uuid,
+        author_id: Uuid,
+        content: MessageContent,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        edited_at: Option<DateTime<Utc>>,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Message {
+            id,
+            channel_id,
+            author_id,
+            content,
+            created_at,
+            updated_at,
+            edited_at,
+            deleted_at,
+            kind: MessageKind::Default,
+            reply_to_id: None,
+            mentions: MessageMentions::default(),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn channel_id(&self) -> Uuid {
+        self.channel_id
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn content(&self) -> MessageContent {
+        self.content.clone()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn edited_at(&self) -> Option<DateTime<Utc>> {
+        self.edited_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn kind(&self) -> MessageKind {
+        self.kind
+    }
+
+    pub fn reply_to_id(&self) -> Option<Uuid> {
+        self.reply_to_id
+    }
+
+    pub fn mentions(&self) -> &MessageMentions {
+        &self.mentions
+    }
+
+    pub fn set_content(&mut self, content: MessageContent) {
+        self.content = content;
+    }
+
+    pub fn set_edited_at(&mut self, edited_at: Option<DateTime<Utc>>) {
+        self.edited_at = edited_at;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: DateTime<Utc>) {
+        self.updated_at = updated_at;
+    }
+
+    pub fn set_deleted_at(&mut self, deleted_at: Option<DateTime<Utc>>) {
+        self.deleted_at = deleted_at;
+    }
+
+    pub fn set_kind(&mut self, kind: MessageKind) {
+        self.kind = kind;
+    }
+
+    pub fn set_reply_to_id(&mut self, reply_to_id: Option<Uuid>) {
+        self.reply_to_id = reply_to_id;
+    }
+
+    pub fn set_mentions(&mut self, mentions: MessageMentions) {
+        self.mentions = mentions;
+    }
+}
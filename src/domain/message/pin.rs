@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::Message;
+
+/// A message pinned to its channel, with who pinned it and when.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct PinnedMessage {
+    #[sqlx(flatten)]
+    message: Message,
+    pinned_by: Uuid,
+    pinned_at: DateTime<Utc>,
+}
+
+impl PinnedMessage {
+    pub fn new(message: Message, pinned_by: Uuid, pinned_at: DateTime<Utc>) -> Self {
+        PinnedMessage {
+            message,
+            pinned_by,
+            pinned_at,
+        }
+    }
+
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    pub fn pinned_by(&self) -> Uuid {
+        self.pinned_by
+    }
+
+    pub fn pinned_at(&self) -> DateTime<Utc> {
+        self.pinned_at
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::MessageContent;
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct MessageRevision {
+    id: Uuid,
+    message_id: Uuid,
+    editor_id: Uuid,
+    content: MessageContent,
+    created_at: DateTime<Utc>,
+}
+
+impl MessageRevision {
+    pub fn new(
+        id: Uuid,
+        message_id: Uuid,
+        editor_id: Uuid,
+        content: MessageContent,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        MessageRevision {
+            id,
+            message_id,
+            editor_id,
+            content,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn message_id(&self) -> Uuid {
+        self.message_id
+    }
+
+    pub fn editor_id(&self) -> Uuid {
+        self.editor_id
+    }
+
+    pub fn content(&self) -> MessageContent {
+        self.content.clone()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
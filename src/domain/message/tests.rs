@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::message::{
+        MessageContent, MessageContentValidationErr, MessageHistory, ParsedMentions,
+        MAX_MENTIONS_PER_MESSAGE, MAX_MESSAGE_CONTENT_LENGTH, MESSAGE_RETENTION_DAYS,
+    };
+    use chrono::{Duration, Utc};
+    use claim::{assert_err, assert_ok};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug)]
+    struct ValidContentFixture(pub String);
+
+    impl quickcheck::Arbitrary for ValidContentFixture {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            let content: String = String::arbitrary(g)
+                .chars()
+                .take(MAX_MESSAGE_CONTENT_LENGTH - 1)
+                .collect();
+            ValidContentFixture(format!("a{}", content))
+        }
+    }
+
+    #[test]
+    fn a_2000_character_message_is_valid() {
+        assert_ok!(MessageContent::try_from(
+            "a".repeat(MAX_MESSAGE_CONTENT_LENGTH)
+        ));
+    }
+
+    #[test]
+    fn length_is_measured_in_characters_not_bytes() {
+        assert_ok!(MessageContent::try_from(
+            "é".repeat(MAX_MESSAGE_CONTENT_LENGTH)
+        ));
+    }
+
+    #[test]
+    fn a_message_longer_than_2000_characters_is_rejected() {
+        assert_eq!(
+            Err(MessageContentValidationErr::ContentTooLong),
+            MessageContent::try_from("a".repeat(MAX_MESSAGE_CONTENT_LENGTH + 1))
+        );
+    }
+
+    #[test]
+    fn whitespace_only_messages_are_rejected() {
+        for content in ["", " ", "\n\t"] {
+            assert_err!(MessageContent::try_from(content));
+        }
+    }
+
+    #[quickcheck_macros::quickcheck]
+    fn valid_content_parsed_successfully(content: ValidContentFixture) -> bool {
+        MessageContent::try_from(content.0).is_ok()
+    }
+
+    #[test]
+    fn mentions_are_parsed_from_content() {
+        let role_id = Uuid::new_v4();
+        let mentions = ParsedMentions::parse(&format!(
+            "hey @ana, @bob.smith and <@&{}> see #General. @everyone @here",
+            role_id
+        ));
+        assert_eq!(&["ana,", "bob.smith"], mentions.handles());
+        assert_eq!(&["general"], mentions.channel_names());
+        assert_eq!(&[role_id], mentions.role_ids());
+        assert!(mentions.everyone());
+        assert!(mentions.here());
+        assert!(mentions.is_mass());
+    }
+
+    #[test]
+    fn mentions_must_start_a_word() {
+        let mentions = ParsedMentions::parse("mail me@example.com or see a#b, @ alone");
+        assert_eq!(ParsedMentions::default(), mentions);
+    }
+
+    #[test]
+    fn repeated_mentions_are_deduplicated() {
+        let mentions = ParsedMentions::parse("@ana @ana #x #X");
+        assert_eq!(&["ana"], mentions.handles());
+        assert_eq!(&["x"], mentions.channel_names());
+        assert!(!mentions.is_mass());
+    }
+
+    #[test]
+    fn handles_prefer_the_untrimmed_form_when_it_exists() {
+        let mentions = ParsedMentions::parse("thanks @ana. and @bob!");
+        assert_eq!(
+            vec!["ana.", "ana", "bob!", "bob"],
+            mentions.handle_candidates()
+        );
+        let existing = vec![
+            String::from("ana"),
+            String::from("bob!"),
+            String::from("bob"),
+        ];
+        assert_eq!(vec!["ana", "bob!"], mentions.matched_handles(&existing));
+    }
+
+    #[test]
+    fn mentions_are_capped() {
+        let content: Vec<String> = (0..MAX_MENTIONS_PER_MESSAGE + 5)
+            .map(|i| format!("@user{}", i))
+            .collect();
+        let mentions = ParsedMentions::parse(&content.join(" "));
+        assert_eq!(MAX_MENTIONS_PER_MESSAGE, mentions.handles().len());
+    }
+
+    #[test]
+    fn deleted_history_expires_after_retention() {
+        let now = Utc::now();
+        let history = |deleted_at| {
+            MessageHistory::new(
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                now - Duration::days(60),
+                deleted_at,
+                None,
+                vec![],
+            )
+        };
+        assert!(!history(None).is_past_retention(now));
+        assert!(
+            !history(Some(now - Duration::days(MESSAGE_RETENTION_DAYS - 1))).is_past_retention(now)
+        );
+        assert!(
+            history(Some(now - Duration::days(MESSAGE_RETENTION_DAYS + 1))).is_past_retention(now)
+        );
+    }
+}
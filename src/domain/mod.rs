@@ -1,4 +1,24 @@
+pub mod block;
+pub mod bookmark;
+pub mod channel;
 pub mod confirmation_token;
+pub mod dm;
 pub mod email;
+pub mod follow;
+pub mod friend;
+pub mod message;
+pub mod notification;
+pub mod pagination;
+pub mod permission;
+pub mod poll;
+pub mod post;
+pub mod presence;
+pub mod reaction;
+pub mod read_state;
+pub mod role;
+pub mod schedule;
+pub mod search;
 pub mod server;
+pub mod tag;
+pub mod thread;
 pub mod user;
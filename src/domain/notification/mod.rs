@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Why a user was notified about a message. When a user is mentioned more
+/// than one way, the most direct kind wins.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum MentionKind {
+    User,
+    Role,
+    Everyone,
+    Here,
+}
+
+impl MentionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Role => "role",
+            Self::Everyone => "everyone",
+            Self::Here => "here",
+        }
+    }
+}
+
+impl TryFrom<&str> for MentionKind {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "user" => Ok(Self::User),
+            "role" => Ok(Self::Role),
+            "everyone" => Ok(Self::Everyone),
+            "here" => Ok(Self::Here),
+            other => Err(format!("{} is not a valid mention kind", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Notification {
+    id: Uuid,
+    user_id: Uuid,
+    message_id: Uuid,
+    channel_id: Uuid,
+    author_id: Uuid,
+    kind: MentionKind,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for Notification {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.user_id == other.user_id
+            && self.message_id == other.message_id
+            && self.channel_id == other.channel_id
+            && self.author_id == other.author_id
+            && self.kind == other.kind
+    }
+}
+
+impl std::fmt::Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Notification {
+    pub fn new(
+        id: Uuid,
+        user_id: Uuid,
+        message_id: Uuid,
+        channel_id: Uuid,
+        author_id: Uuid,
+        kind: MentionKind,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Notification {
+            id,
+            user_id,
+            message_id,
+            channel_id,
+            author_id,
+            kind,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn message_id(&self) -> Uuid {
+        self.message_id
+    }
+
+    pub fn channel_id(&self) -> Uuid {
+        self.channel_id
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn kind(&self) -> MentionKind {
+        self.kind
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
@@ -0,0 +1,121 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+#[derive(Debug)]
+pub enum PageErr {
+    /// The cursor points at an item that is no longer in the list, so there
+    /// is nothing to page on from.
+    CursorNotFound,
+    Query(sqlx::Error),
+}
+
+impl From<sqlx::Error> for PageErr {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Query(e)
+    }
+}
+
+impl PageErr {
+    /// Paged handlers send this for any failure to read a page, so a stale
+    /// cursor is reported the same way on every list.
+    pub fn handle_http(&self) -> HttpResponse {
+        match self {
+            Self::CursorNotFound => {
+                tracing::error!("400 - page cursor not found");
+                HttpResponse::BadRequest().body(
+                    "The cursor does not match any item in this list, start over from the first page",
+                )
+            }
+            Self::Query(e) => {
+                tracing::error!("failed to get page: {:?}", e);
+                HttpResponse::InternalServerError().finish()
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PageParams {
+    pub cursor: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+impl PageParams {
+    pub fn new(cursor: Option<Uuid>, limit: Option<i64>) -> Self {
+        PageParams { cursor, limit }
+    }
+
+    pub fn cursor(&self) -> Option<Uuid> {
+        self.cursor
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+
+    /// Storage queries fetch one row past the page so that `Page::from_rows`
+    /// can tell whether another page exists without a second COUNT query.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit() + 1
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Page<T> {
+    items: Vec<T>,
+    next_cursor: Option<Uuid>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, next_cursor: Option<Uuid>) -> Self {
+        Page { items, next_cursor }
+    }
+
+    pub fn from_rows<F>(mut rows: Vec<T>, params: &PageParams, cursor_of: F) -> Self
+    where
+        F: Fn(&T) -> Uuid,
+    {
+        let limit = params.limit() as usize;
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(cursor_of)
+        } else {
+            None
+        };
+        Page {
+            items: rows,
+            next_cursor,
+        }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    pub fn next_cursor(&self) -> Option<Uuid> {
+        self.next_cursor
+    }
+
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::pagination::{Page, PageParams, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+    use uuid::Uuid;
+
+    #[test]
+    fn limit_defaults_when_not_provided() {
+        assert_eq!(DEFAULT_PAGE_LIMIT, PageParams::default().limit());
+    }
+
+    #[test]
+    fn limit_is_clamped_to_bounds() {
+        assert_eq!(1, PageParams::new(None, Some(0)).limit());
+        assert_eq!(1, PageParams::new(None, Some(-20)).limit());
+        assert_eq!(
+            MAX_PAGE_LIMIT,
+            PageParams::new(None, Some(MAX_PAGE_LIMIT + 1)).limit()
+        );
+    }
+
+    #[test]
+    fn next_cursor_set_only_when_extra_row_fetched() {
+        let params = PageParams::new(None, Some(2));
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+
+        let page = Page::from_rows(ids.clone(), &params, |id| *id);
+        assert_eq!(&ids[..2], page.items());
+        assert_eq!(Some(ids[1]), page.next_cursor());
+
+        let page = Page::from_rows(ids[..2].to_vec(), &params, |id| *id);
+        assert_eq!(2, page.items().len());
+        assert_eq!(None, page.next_cursor());
+    }
+}
@@ -0,0 +1,157 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::channel::{OverwriteTarget, PermissionOverwrite};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Permissions(i64);
+
+impl Permissions {
+    pub const VIEW_CHANNEL: Self = Self(1 << 0);
+    pub const SEND_MESSAGES: Self = Self(1 << 1);
+    pub const READ_MESSAGE_HISTORY: Self = Self(1 << 2);
+    pub const ADD_REACTIONS: Self = Self(1 << 3);
+    pub const CONNECT: Self = Self(1 << 4);
+    pub const SPEAK: Self = Self(1 << 5);
+    pub const MANAGE_MESSAGES: Self = Self(1 << 6);
+    pub const MANAGE_CHANNELS: Self = Self(1 << 7);
+    pub const MANAGE_ROLES: Self = Self(1 << 8);
+    pub const MENTION_EVERYONE: Self = Self(1 << 9);
+    pub const MANAGE_EMOJIS: Self = Self(1 << 10);
+    pub const ADMINISTRATOR: Self = Self(1 << 30);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self(
+            Self::VIEW_CHANNEL.0
+                | Self::SEND_MESSAGES.0
+                | Self::READ_MESSAGE_HISTORY.0
+                | Self::ADD_REACTIONS.0
+                | Self::CONNECT.0
+                | Self::SPEAK.0
+                | Self::MANAGE_MESSAGES.0
+                | Self::MANAGE_CHANNELS.0
+                | Self::MANAGE_ROLES.0
+                | Self::MENTION_EVERYONE.0
+                | Self::MANAGE_EMOJIS.0
+                | Self::ADMINISTRATOR.0,
+        )
+    }
+
+    /// Permissions every server member has before roles and overwrites are
+    /// applied.
+    pub const fn member_default() -> Self {
+        Self(
+            Self::VIEW_CHANNEL.0
+                | Self::SEND_MESSAGES.0
+                | Self::READ_MESSAGE_HISTORY.0
+                | Self::ADD_REACTIONS.0
+                | Self::CONNECT.0
+                | Self::SPEAK.0,
+        )
+    }
+
+    pub const fn from_bits(bits: i64) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(&self) -> i64 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for Permissions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+/// Computes a member's server-wide permissions from the roles they hold.
+/// Owners and admins always receive every permission.
+pub fn compute_base_permissions(
+    is_owner: bool,
+    is_admin: bool,
+    role_permissions: &[Permissions],
+) -> Permissions {
+    if is_owner || is_admin {
+        return Permissions::all();
+    }
+    let permissions = role_permissions
+        .iter()
+        .fold(Permissions::member_default(), |acc, p| acc | *p);
+    if permissions.contains(Permissions::ADMINISTRATOR) {
+        Permissions::all()
+    } else {
+        permissions
+    }
+}
+
+/// Layers channel overwrites on top of base permissions. The `@everyone`
+/// overwrite (a role overwrite targeting the server ID) is applied first,
+/// then the combined overwrites of the member's roles, then the overwrite
+/// for the member themself, with deny applied before allow at each step.
+pub fn apply_overwrites(
+    base: Permissions,
+    server_id: Uuid,
+    user_id: Uuid,
+    role_ids: &[Uuid],
+    overwrites: &[PermissionOverwrite],
+) -> Permissions {
+    if base.contains(Permissions::ADMINISTRATOR) {
+        return Permissions::all();
+    }
+
+    let mut permissions = base;
+
+    if let Some(everyone) = overwrites
+        .iter()
+        .find(|o| o.target_type() == OverwriteTarget::Role && o.target_id() == server_id)
+    {
+        permissions = permissions
+            .difference(everyone.deny())
+            .union(everyone.allow());
+    }
+
+    let (role_allow, role_deny) = overwrites
+        .iter()
+        .filter(|o| o.target_type() == OverwriteTarget::Role && role_ids.contains(&o.target_id()))
+        .fold(
+            (Permissions::empty(), Permissions::empty()),
+            |(allow, deny), o| (allow | o.allow(), deny | o.deny()),
+        );
+    permissions = permissions.difference(role_deny).union(role_allow);
+
+    if let Some(member) = overwrites
+        .iter()
+        .find(|o| o.target_type() == OverwriteTarget::Member && o.target_id() == user_id)
+    {
+        permissions = permissions.difference(member.deny()).union(member.allow());
+    }
+
+    permissions
+}
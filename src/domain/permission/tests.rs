@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::{
+        channel::{OverwriteTarget, PermissionOverwrite},
+        permission::{apply_overwrites, compute_base_permissions, Permissions},
+    };
+    use uuid::Uuid;
+
+    fn overwrite(
+        target_id: Uuid,
+        target_type: OverwriteTarget,
+        allow: Permissions,
+        deny: Permissions,
+    ) -> PermissionOverwrite {
+        PermissionOverwrite::new(Uuid::new_v4(), target_id, target_type, allow, deny)
+    }
+
+    #[test]
+    fn owners_and_admins_get_all_permissions() {
+        assert_eq!(
+            Permissions::all(),
+            compute_base_permissions(true, false, &[])
+        );
+        assert_eq!(
+            Permissions::all(),
+            compute_base_permissions(false, true, &[])
+        );
+        assert_eq!(
+            Permissions::all(),
+            compute_base_permissions(false, false, &[Permissions::ADMINISTRATOR])
+        );
+    }
+
+    #[test]
+    fn role_permissions_are_added_to_member_defaults() {
+        let permissions = compute_base_permissions(false, false, &[Permissions::MANAGE_CHANNELS]);
+        assert!(permissions.contains(Permissions::VIEW_CHANNEL));
+        assert!(permissions.contains(Permissions::MANAGE_CHANNELS));
+        assert!(!permissions.contains(Permissions::MANAGE_ROLES));
+    }
+
+    #[test]
+    fn overwrites_apply_everyone_then_roles_then_member() {
+        let server_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let role_id = Uuid::new_v4();
+        let base = Permissions::member_default();
+
+        let everyone = overwrite(
+            server_id,
+            OverwriteTarget::Role,
+            Permissions::empty(),
+            Permissions::VIEW_CHANNEL,
+        );
+        let role = overwrite(
+            role_id,
+            OverwriteTarget::Role,
+            Permissions::VIEW_CHANNEL,
+            Permissions::SEND_MESSAGES,
+        );
+        let member = overwrite(
+            user_id,
+            OverwriteTarget::Member,
+            Permissions::SEND_MESSAGES,
+            Permissions::empty(),
+        );
+
+        let hidden = apply_overwrites(
+            base,
+            server_id,
+            user_id,
+            &[],
+            std::slice::from_ref(&everyone),
+        );
+        assert!(!hidden.contains(Permissions::VIEW_CHANNEL));
+
+        let with_role = apply_overwrites(
+            base,
+            server_id,
+            user_id,
+            &[role_id],
+            &[everyone.clone(), role.clone()],
+        );
+        assert!(with_role.contains(Permissions::VIEW_CHANNEL));
+        assert!(!with_role.contains(Permissions::SEND_MESSAGES));
+
+        let with_member = apply_overwrites(
+            base,
+            server_id,
+            user_id,
+            &[role_id],
+            &[everyone, role, member],
+        );
+        assert!(with_member.contains(Permissions::VIEW_CHANNEL));
+        assert!(with_member.contains(Permissions::SEND_MESSAGES));
+    }
+
+    #[test]
+    fn administrators_ignore_overwrites() {
+        let server_id = Uuid::new_v4();
+        let everyone = overwrite(
+            server_id,
+            OverwriteTarget::Role,
+            Permissions::empty(),
+            Permissions::all(),
+        );
+        assert_eq!(
+            Permissions::all(),
+            apply_overwrites(
+                Permissions::all(),
+                server_id,
+                Uuid::new_v4(),
+                &[],
+                &[everyone]
+            )
+        );
+    }
+}
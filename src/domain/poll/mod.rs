@@ -0,0 +1,458 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use std::collections::{HashMap, HashSet};
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 10;
+pub const MAX_POLL_QUESTION_LENGTH: usize = 300;
+pub const MAX_POLL_OPTION_LENGTH: usize = 100;
+/// How long after creation a poll may be set to close.
+pub const MAX_POLL_DURATION_DAYS: i64 = 30;
+
+#[derive(Debug, PartialEq)]
+pub enum PollValidationErr {
+    QuestionEmpty,
+    QuestionTooLong,
+    TooFewOptions,
+    TooManyOptions,
+    OptionEmpty,
+    OptionTooLong,
+    DuplicateOption,
+    ClosesInPast,
+    ClosesTooFar,
+    NoOptionChosen,
+    SingleChoice,
+    UnknownOption,
+    Closed,
+}
+
+impl PollValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::QuestionEmpty => String::from("Poll questions must not be empty"),
+            Self::QuestionTooLong => format!(
+                "Poll questions must be at most {} characters",
+                MAX_POLL_QUESTION_LENGTH
+            ),
+            Self::TooFewOptions | Self::TooManyOptions => format!(
+                "Polls must have between {} and {} options",
+                MIN_POLL_OPTIONS, MAX_POLL_OPTIONS
+            ),
+            Self::OptionEmpty => String::from("Poll options must not be empty"),
+            Self::OptionTooLong => format!(
+                "Poll options must be at most {} characters",
+                MAX_POLL_OPTION_LENGTH
+            ),
+            Self::DuplicateOption => String::from("Poll options must be unique"),
+            Self::ClosesInPast => String::from("Polls must close in the future"),
+            Self::ClosesTooFar => format!(
+                "Polls can stay open for at most {} days",
+                MAX_POLL_DURATION_DAYS
+            ),
+            Self::NoOptionChosen => String::from("At least one option must be chosen"),
+            Self::SingleChoice => String::from("This poll only allows one option to be chosen"),
+            Self::UnknownOption => String::from("Chosen options must belong to the poll"),
+            Self::Closed => String::from("This poll is closed"),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// What a poll is attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollTarget {
+    Post(Uuid),
+    Message(Uuid),
+}
+
+/// A poll as requested alongside a new post or message.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NewPoll {
+    pub question: String,
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub multiple_choice: bool,
+    /// Keeps who voted for what private, showing only the counts.
+    #[serde(default)]
+    pub anonymous: bool,
+    /// Hides the counts from each user until they vote or the poll closes.
+    #[serde(default)]
+    pub hide_results: bool,
+    #[serde(default)]
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Poll {
+    id: Uuid,
+    post_id: Option<Uuid>,
+    message_id: Option<Uuid>,
+    author_id: Uuid,
+    question: String,
+    multiple_choice: bool,
+    anonymous: bool,
+    hide_results: bool,
+    closes_at: Option<DateTime<Utc>>,
+    closed_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for Poll {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.post_id == other.post_id
+            && self.message_id == other.message_id
+            && self.author_id == other.author_id
+            && self.question == other.question
+            && self.multiple_choice == other.multiple_choice
+            && self.anonymous == other.anonymous
+            && self.hide_results == other.hide_results
+    }
+}
+
+impl std::fmt::Display for Poll {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Poll {
+    /// Validates a requested poll and builds it along with its options, in
+    /// the order they were given.
+    pub fn new(
+        id: Uuid,
+        author_id: Uuid,
+        target: PollTarget,
+        draft: NewPoll,
+        created_at: DateTime<Utc>,
+    ) -> Result<(Self, Vec<PollOption>), PollValidationErr> {
+        let question = draft.question.trim();
+        if question.is_empty() {
+            return Err(PollValidationErr::QuestionEmpty);
+        }
+        if question.chars().count() > MAX_POLL_QUESTION_LENGTH {
+            return Err(PollValidationErr::QuestionTooLong);
+        }
+        if draft.options.len() < MIN_POLL_OPTIONS {
+            return Err(PollValidationErr::TooFewOptions);
+        }
+        if draft.options.len() > MAX_POLL_OPTIONS {
+            return Err(PollValidationErr::TooManyOptions);
+        }
+        if let Some(closes_at) = draft.closes_at {
+            if closes_at <= created_at {
+                return Err(PollValidationErr::ClosesInPast);
+            }
+            if closes_at > created_at + Duration::days(MAX_POLL_DURATION_DAYS) {
+                return Err(PollValidationErr::ClosesTooFar);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut options = Vec::with_capacity(draft.options.len());
+        for (position, text) in draft.options.iter().enumerate() {
+            let text = text.trim();
+            if text.is_empty() {
+                return Err(PollValidationErr::OptionEmpty);
+            }
+            if text.chars().count() > MAX_POLL_OPTION_LENGTH {
+                return Err(PollValidationErr::OptionTooLong);
+            }
+            if !seen.insert(text.to_lowercase()) {
+                return Err(PollValidationErr::DuplicateOption);
+            }
+            options.push(PollOption {
+                id: Uuid::new_v4(),
+                poll_id: id,
+                position: position as i32,
+                text: text.to_string(),
+            });
+        }
+
+        let (post_id, message_id) = match target {
+            PollTarget::Post(post_id) => (Some(post_id), None),
+            PollTarget::Message(message_id) => (None, Some(message_id)),
+        };
+        let poll = Poll {
+            id,
+            post_id,
+            message_id,
+            author_id,
+            question: question.to_string(),
+            multiple_choice: draft.multiple_choice,
+            anonymous: draft.anonymous,
+            hide_results: draft.hide_results,
+            closes_at: draft.closes_at,
+            closed_at: None,
+            created_at,
+        };
+        Ok((poll, options))
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn target(&self) -> PollTarget {
+        match (self.post_id, self.message_id) {
+            (Some(post_id), _) => PollTarget::Post(post_id),
+            (None, Some(message_id)) => PollTarget::Message(message_id),
+            (None, None) => unreachable!("polls always have a target"),
+        }
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn question(&self) -> &str {
+        &self.question
+    }
+
+    pub fn multiple_choice(&self) -> bool {
+        self.multiple_choice
+    }
+
+    pub fn anonymous(&self) -> bool {
+        self.anonymous
+    }
+
+    pub fn hide_results(&self) -> bool {
+        self.hide_results
+    }
+
+    pub fn closes_at(&self) -> Option<DateTime<Utc>> {
+        self.closes_at
+    }
+
+    pub fn closed_at(&self) -> Option<DateTime<Utc>> {
+        self.closed_at
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Whether the poll was closed early or has reached its close time.
+    pub fn is_closed(&self, now: DateTime<Utc>) -> bool {
+        self.closed_at.is_some() || self.closes_at.is_some_and(|closes_at| closes_at <= now)
+    }
+
+    /// Closes the poll early. Fails if it is already closed.
+    pub fn close(&mut self, now: DateTime<Utc>) -> Result<(), PollValidationErr> {
+        if self.is_closed(now) {
+            return Err(PollValidationErr::Closed);
+        }
+        self.closed_at = Some(now);
+        Ok(())
+    }
+
+    /// Checks a vote against the poll's options, returning the chosen
+    /// option ids without duplicates.
+    pub fn validate_choice(
+        &self,
+        options: &[PollOption],
+        chosen: &[Uuid],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, PollValidationErr> {
+        if self.is_closed(now) {
+            return Err(PollValidationErr::Closed);
+        }
+        let mut option_ids = vec![];
+        for &option_id in chosen {
+            if !options
+                .iter()
+                .any(|o| o.id == option_id && o.poll_id == self.id)
+            {
+                return Err(PollValidationErr::UnknownOption);
+            }
+            if !option_ids.contains(&option_id) {
+                option_ids.push(option_id);
+            }
+        }
+        match option_ids.len() {
+            0 => Err(PollValidationErr::NoOptionChosen),
+            1 => Ok(option_ids),
+            _ if self.multiple_choice => Ok(option_ids),
+            _ => Err(PollValidationErr::SingleChoice),
+        }
+    }
+
+    /// Whether the counts may be shown to a user who has or has not voted.
+    pub fn shows_results(&self, has_voted: bool, now: DateTime<Utc>) -> bool {
+        !self.hide_results || has_voted || self.is_closed(now)
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq, Eq)]
+pub struct PollOption {
+    id: Uuid,
+    poll_id: Uuid,
+    position: i32,
+    text: String,
+}
+
+impl PollOption {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn poll_id(&self) -> Uuid {
+        self.poll_id
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// One user's choice of one option. Users choosing several options in a
+/// multiple choice poll have one vote per option.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq, Eq)]
+pub struct PollVote {
+    pub poll_id: Uuid,
+    pub option_id: Uuid,
+    pub user_id: Uuid,
+}
+
+/// Counts votes per option and the number of distinct voters.
+fn count_votes(votes: &[PollVote]) -> (HashMap<Uuid, i64>, i64) {
+    let mut counts: HashMap<Uuid, i64> = HashMap::new();
+    let mut voters = HashSet::new();
+    for vote in votes {
+        *counts.entry(vote.option_id).or_default() += 1;
+        voters.insert(vote.user_id);
+    }
+    (counts, voters.len() as i64)
+}
+
+/// An option with its results, as far as the viewer may see them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PollOptionResult {
+    #[serde(flatten)]
+    pub option: PollOption,
+    pub vote_count: Option<i64>,
+    /// Only listed for polls with public voters, once results are shown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voter_ids: Option<Vec<Uuid>>,
+}
+
+/// A poll as seen by one user: the counts, including how many users voted,
+/// are left out while its results are hidden from them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PollView {
+    #[serde(flatten)]
+    pub poll: Poll,
+    pub closed: bool,
+    pub results_visible: bool,
+    pub voter_count: Option<i64>,
+    pub options: Vec<PollOptionResult>,
+    pub own_votes: Vec<Uuid>,
+}
+
+impl PollView {
+    /// Builds the view of `poll` for `viewer_id` from its options and every
+    /// vote cast in it.
+    pub fn new(
+        poll: Poll,
+        options: Vec<PollOption>,
+        votes: &[PollVote],
+        viewer_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let own_votes: Vec<Uuid> = votes
+            .iter()
+            .filter(|v| v.user_id == viewer_id)
+            .map(|v| v.option_id)
+            .collect();
+        let results_visible = poll.shows_results(!own_votes.is_empty(), now);
+        let (counts, voter_count) = count_votes(votes);
+        let options = options
+            .into_iter()
+            .map(|option| {
+                let vote_count =
+                    results_visible.then(|| counts.get(&option.id).copied().unwrap_or_default());
+                let voter_ids = (results_visible && !poll.anonymous).then(|| {
+                    votes
+                        .iter()
+                        .filter(|v| v.option_id == option.id)
+                        .map(|v| v.user_id)
+                        .collect()
+                });
+                PollOptionResult {
+                    option,
+                    vote_count,
+                    voter_ids,
+                }
+            })
+            .collect();
+        PollView {
+            closed: poll.is_closed(now),
+            poll,
+            results_visible,
+            voter_count: results_visible.then_some(voter_count),
+            options,
+            own_votes,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.poll.id()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PollOptionTally {
+    pub option_id: Uuid,
+    pub vote_count: i64,
+}
+
+/// Live results pushed to everyone following the poll whenever a vote
+/// changes or it closes. The counts are left out while the poll hides its
+/// results, since the same event reaches users who have not voted; the
+/// tally sent when it closes reveals them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PollTally {
+    pub poll_id: Uuid,
+    pub closed: bool,
+    pub voter_count: Option<i64>,
+    pub options: Option<Vec<PollOptionTally>>,
+}
+
+impl PollTally {
+    pub fn new(
+        poll: &Poll,
+        options: &[PollOption],
+        votes: &[PollVote],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let (counts, voter_count) = count_votes(votes);
+        let shows_results = poll.shows_results(false, now);
+        let options = shows_results.then(|| {
+            options
+                .iter()
+                .map(|option| PollOptionTally {
+                    option_id: option.id,
+                    vote_count: counts.get(&option.id).copied().unwrap_or_default(),
+                })
+                .collect()
+        });
+        PollTally {
+            poll_id: poll.id,
+            closed: poll.is_closed(now),
+            voter_count: shows_results.then_some(voter_count),
+            options,
+        }
+    }
+}
@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::poll::{
+        NewPoll, Poll, PollOption, PollTally, PollTarget, PollValidationErr, PollView, PollVote,
+        MAX_POLL_DURATION_DAYS, MAX_POLL_OPTIONS,
+    };
+    use chrono::{Duration, Utc};
+    use claim::assert_ok;
+    use uuid::Uuid;
+
+    fn draft(options: &[&str]) -> NewPoll {
+        NewPoll {
+            question: String::from("Lunch?"),
+            options: options.iter().map(|o| o.to_string()).collect(),
+            multiple_choice: false,
+            anonymous: false,
+            hide_results: false,
+            closes_at: None,
+        }
+    }
+
+    fn new_poll(draft: NewPoll) -> Result<(Poll, Vec<PollOption>), PollValidationErr> {
+        Poll::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            PollTarget::Post(Uuid::new_v4()),
+            draft,
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn polls_are_validated() {
+        assert_ok!(new_poll(draft(&["pizza", "sushi"])));
+
+        let now = Utc::now();
+        let too_many: Vec<String> = (0..=MAX_POLL_OPTIONS).map(|i| i.to_string()).collect();
+        let too_many: Vec<&str> = too_many.iter().map(String::as_str).collect();
+        let mut blank_question = draft(&["pizza", "sushi"]);
+        blank_question.question = String::from("  ");
+        let mut closes_in_past = draft(&["pizza", "sushi"]);
+        closes_in_past.closes_at = Some(now - Duration::minutes(1));
+        let mut closes_too_far = draft(&["pizza", "sushi"]);
+        closes_too_far.closes_at = Some(now + Duration::days(MAX_POLL_DURATION_DAYS + 1));
+
+        let test_cases = [
+            (
+                blank_question,
+                PollValidationErr::QuestionEmpty,
+                "a blank question",
+            ),
+            (
+                draft(&["pizza"]),
+                PollValidationErr::TooFewOptions,
+                "one option",
+            ),
+            (
+                draft(&too_many),
+                PollValidationErr::TooManyOptions,
+                "too many options",
+            ),
+            (
+                draft(&["pizza", " "]),
+                PollValidationErr::OptionEmpty,
+                "a blank option",
+            ),
+            (
+                draft(&["pizza", "Pizza "]),
+                PollValidationErr::DuplicateOption,
+                "a repeated option",
+            ),
+            (
+                closes_in_past,
+                PollValidationErr::ClosesInPast,
+                "a past close time",
+            ),
+            (
+                closes_too_far,
+                PollValidationErr::ClosesTooFar,
+                "a distant close time",
+            ),
+        ];
+        for (draft, expected, case) in test_cases {
+            assert_eq!(
+                Some(expected),
+                new_poll(draft).err(),
+                "A poll with {} was not rejected",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn choices_respect_the_poll_mode() {
+        let now = Utc::now();
+        let (single, single_options) = new_poll(draft(&["a", "b", "c"])).unwrap();
+        let ids: Vec<Uuid> = single_options.iter().map(|o| o.id()).collect();
+        assert_eq!(
+            Ok(vec![ids[0]]),
+            single.validate_choice(&single_options, &[ids[0], ids[0]], now)
+        );
+        assert_eq!(
+            Err(PollValidationErr::SingleChoice),
+            single.validate_choice(&single_options, &ids[..2], now)
+        );
+        assert_eq!(
+            Err(PollValidationErr::NoOptionChosen),
+            single.validate_choice(&single_options, &[], now)
+        );
+        assert_eq!(
+            Err(PollValidationErr::UnknownOption),
+            single.validate_choice(&single_options, &[Uuid::new_v4()], now)
+        );
+
+        let mut multiple = draft(&["a", "b", "c"]);
+        multiple.multiple_choice = true;
+        let (mut multiple, options) = new_poll(multiple).unwrap();
+        let ids: Vec<Uuid> = options.iter().map(|o| o.id()).collect();
+        assert_eq!(
+            Ok(ids[..2].to_vec()),
+            multiple.validate_choice(&options, &ids[..2], now)
+        );
+
+        assert_ok!(multiple.close(now));
+        assert_eq!(Err(PollValidationErr::Closed), multiple.close(now));
+        assert_eq!(
+            Err(PollValidationErr::Closed),
+            multiple.validate_choice(&options, &ids[..1], now)
+        );
+    }
+
+    #[test]
+    fn hidden_results_show_once_voted_or_closed() {
+        let now = Utc::now();
+        let mut hidden = draft(&["a", "b"]);
+        hidden.hide_results = true;
+        hidden.anonymous = true;
+        hidden.closes_at = Some(now + Duration::hours(1));
+        let (poll, options) = new_poll(hidden).unwrap();
+        let voter = Uuid::new_v4();
+        let votes = vec![PollVote {
+            poll_id: poll.id(),
+            option_id: options[0].id(),
+            user_id: voter,
+        }];
+
+        let onlooker = PollView::new(poll.clone(), options.clone(), &votes, Uuid::new_v4(), now);
+        assert!(!onlooker.results_visible);
+        assert_eq!(None, onlooker.voter_count, "The voter count was shown");
+        assert!(onlooker.options.iter().all(|o| o.vote_count.is_none()));
+        let tally = PollTally::new(&poll, &options, &votes, now);
+        assert_eq!((None, None), (tally.voter_count, tally.options));
+
+        let own = PollView::new(poll.clone(), options.clone(), &votes, voter, now);
+        assert!(own.results_visible);
+        assert_eq!(Some(1), own.voter_count);
+        assert_eq!(vec![options[0].id()], own.own_votes);
+        assert_eq!(Some(1), own.options[0].vote_count);
+        assert!(
+            own.options.iter().all(|o| o.voter_ids.is_none()),
+            "An anonymous poll listed its voters"
+        );
+
+        let after_close = now + Duration::hours(2);
+        let closed = PollView::new(
+            poll.clone(),
+            options.clone(),
+            &votes,
+            Uuid::new_v4(),
+            after_close,
+        );
+        assert!(closed.closed && closed.results_visible);
+        assert_eq!(Some(1), closed.voter_count);
+        let tally = PollTally::new(&poll, &options, &votes, after_close);
+        assert_eq!(Some(1), tally.voter_count);
+        assert!(tally.options.is_some());
+    }
+}
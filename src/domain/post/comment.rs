@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::{message::MessageContent, post::LikeSummary};
+
+/// The order comments are listed in. `Top` puts the most liked first.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentSort {
+    #[default]
+    Newest,
+    Top,
+}
+
+/// A comment on a post. Replies point at the comment they answer through
+/// `parent_id`; top level comments have none.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Comment {
+    id: Uuid,
+    post_id: Uuid,
+    parent_id: Option<Uuid>,
+    author_id: Uuid,
+    content: MessageContent,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl PartialEq for Comment {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.post_id == other.post_id
+            && self.parent_id == other.parent_id
+            && self.author_id == other.author_id
+            && self.content == other.content
+            && self.deleted_at == other.deleted_at
+    }
+}
+
+impl std::fmt::Display for Comment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Comment {
+    pub fn new(
+        id: Uuid,
+        post_id: Uuid,
+        parent_id: Option<Uuid>,
+        author_id: Uuid,
+        content: MessageContent,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Comment {
+            id,
+            post_id,
+            parent_id,
+            author_id,
+            content,
+            created_at,
+            updated_at: created_at,
+            edited_at: None,
+            deleted_at: None,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn post_id(&self) -> Uuid {
+        self.post_id
+    }
+
+    pub fn parent_id(&self) -> Option<Uuid> {
+        self.parent_id
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn content(&self) -> MessageContent {
+        self.content.clone()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn edited_at(&self) -> Option<DateTime<Utc>> {
+        self.edited_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn set_content(&mut self, content: MessageContent) {
+        self.content = content;
+    }
+
+    pub fn set_edited_at(&mut self, edited_at: Option<DateTime<Utc>>) {
+        self.edited_at = edited_at;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: DateTime<Utc>) {
+        self.updated_at = updated_at;
+    }
+}
+
+/// A comment with its like and reply counts as seen by the viewer.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct CommentView {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub comment: Comment,
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub likes: LikeSummary,
+    pub reply_count: i64,
+}
+
+impl CommentView {
+    pub fn new(comment: Comment) -> Self {
+        CommentView {
+            comment,
+            likes: LikeSummary::default(),
+            reply_count: 0,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.comment.id()
+    }
+}
@@ -0,0 +1,109 @@
+use actix_web::HttpResponse;
+use serde::{
+    de::{Unexpected, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+pub const MAX_POST_CONTENT_LENGTH: usize = 4000;
+
+#[derive(Debug, PartialEq)]
+pub enum PostContentValidationErr {
+    ContentEmpty,
+    ContentTooLong,
+}
+
+impl PostContentValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::ContentEmpty => String::from("Post content is empty"),
+            Self::ContentTooLong => format!(
+                "Post content is too long, must be no more than {} characters",
+                MAX_POST_CONTENT_LENGTH
+            ),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostContent(String);
+
+impl std::fmt::Display for PostContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for PostContent {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for PostContent {
+    type Error = PostContentValidationErr;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.trim().is_empty() {
+            Err(PostContentValidationErr::ContentEmpty)
+        } else if value.chars().count() > MAX_POST_CONTENT_LENGTH {
+            Err(PostContentValidationErr::ContentTooLong)
+        } else {
+            Ok(PostContent(value))
+        }
+    }
+}
+
+impl TryFrom<&str> for PostContent {
+    type Error = PostContentValidationErr;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_from(value.to_string())
+    }
+}
+
+impl Serialize for PostContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for PostContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_string(PostContentVisitor)
+    }
+}
+
+struct PostContentVisitor;
+
+impl<'de> Visitor<'de> for PostContentVisitor {
+    type Value = PostContent;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "non-empty post content no longer than {} characters",
+            MAX_POST_CONTENT_LENGTH
+        )
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        PostContent::try_from(v.as_str()).map_err(|_| E::invalid_value(Unexpected::Str(&v), &self))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_string(v.to_string())
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{pagination::Page, post::PostView};
+
+/// Ranking only looks at posts this recent, so old viral posts do not sit
+/// at the top of the feed forever.
+pub const ENGAGEMENT_WINDOW_DAYS: i64 = 7;
+pub const LIKE_WEIGHT: f64 = 1.0;
+/// A comment takes more effort than a like, so it counts for more.
+pub const COMMENT_WEIGHT: f64 = 2.0;
+/// How quickly a post's score decays with its age in hours.
+pub const SCORE_GRAVITY: f64 = 1.5;
+
+/// The order of the home feed. `Engagement` ranks posts by their likes and
+/// comments, decayed by age.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedSort {
+    #[default]
+    Recent,
+    Engagement,
+}
+
+/// A page of the home feed. `as_of` is the moment the feed was first read;
+/// passing it back with the cursor leaves out posts, likes and comments
+/// made since, so later pages neither repeat nor skip posts.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HomeFeed {
+    #[serde(flatten)]
+    pub page: Page<PostView>,
+    pub as_of: DateTime<Utc>,
+}
+
+impl HomeFeed {
+    pub fn new(page: Page<PostView>, as_of: DateTime<Utc>) -> Self {
+        HomeFeed { page, as_of }
+    }
+}
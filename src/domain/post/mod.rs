@@ -0,0 +1,159 @@
+mod comment;
+mod content;
+mod feed;
+#[allow(clippy::module_inception)]
+mod tests;
+
+pub use comment::{Comment, CommentSort, CommentView};
+pub use content::{PostContent, PostContentValidationErr, MAX_POST_CONTENT_LENGTH};
+pub use feed::{
+    FeedSort, HomeFeed, COMMENT_WEIGHT, ENGAGEMENT_WINDOW_DAYS, LIKE_WEIGHT, SCORE_GRAVITY,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::poll::PollView;
+
+/// A post in a server's feed. Posts belong to the server rather than to a
+/// channel, so any member may read them.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Post {
+    id: Uuid,
+    server_id: Uuid,
+    author_id: Uuid,
+    content: PostContent,
+    quoted_post_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl PartialEq for Post {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.server_id == other.server_id
+            && self.author_id == other.author_id
+            && self.content == other.content
+            && self.quoted_post_id == other.quoted_post_id
+            && self.deleted_at == other.deleted_at
+    }
+}
+
+impl std::fmt::Display for Post {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Post {
+    pub fn new(
+        id: Uuid,
+        server_id: Uuid,
+        author_id: Uuid,
+        content: PostContent,
+        quoted_post_id: Option<Uuid>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Post {
+            id,
+            server_id,
+            author_id,
+            content,
+            quoted_post_id,
+            created_at,
+            updated_at: created_at,
+            edited_at: None,
+            deleted_at: None,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn content(&self) -> PostContent {
+        self.content.clone()
+    }
+
+    pub fn quoted_post_id(&self) -> Option<Uuid> {
+        self.quoted_post_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn edited_at(&self) -> Option<DateTime<Utc>> {
+        self.edited_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+
+    pub fn set_content(&mut self, content: PostContent) {
+        self.content = content;
+    }
+
+    pub fn set_edited_at(&mut self, edited_at: Option<DateTime<Utc>>) {
+        self.edited_at = edited_at;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: DateTime<Utc>) {
+        self.updated_at = updated_at;
+    }
+}
+
+/// How many people like something, and whether the viewer is one of them.
+#[derive(Serialize, Deserialize, FromRow, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LikeSummary {
+    pub like_count: i64,
+    pub liked: bool,
+}
+
+/// A post as shown in a feed, with the post it quotes inlined and its
+/// engagement counts and poll as seen by the viewer. The quote is missing
+/// when the quoted post has since been deleted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PostView {
+    #[serde(flatten)]
+    pub post: Post,
+    pub quoted_post: Option<Post>,
+    #[serde(flatten)]
+    pub likes: LikeSummary,
+    pub comment_count: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll: Option<PollView>,
+}
+
+impl PostView {
+    pub fn new(post: Post, quoted_post: Option<Post>) -> Self {
+        PostView {
+            post,
+            quoted_post,
+            likes: LikeSummary::default(),
+            comment_count: 0,
+            poll: None,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.post.id()
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::post::{
+        CommentSort, FeedSort, PostContent, PostContentValidationErr, MAX_POST_CONTENT_LENGTH,
+    };
+    use claim::{assert_err, assert_ok};
+
+    #[test]
+    fn a_post_of_max_length_is_valid() {
+        assert_ok!(PostContent::try_from("é".repeat(MAX_POST_CONTENT_LENGTH)));
+    }
+
+    #[test]
+    fn a_post_longer_than_the_max_is_rejected() {
+        assert_eq!(
+            Err(PostContentValidationErr::ContentTooLong),
+            PostContent::try_from("a".repeat(MAX_POST_CONTENT_LENGTH + 1))
+        );
+    }
+
+    #[test]
+    fn whitespace_only_posts_are_rejected() {
+        for content in ["", " ", "\n\t"] {
+            assert_err!(PostContent::try_from(content));
+        }
+    }
+
+    #[test]
+    fn comments_sort_newest_first_by_default() {
+        assert_eq!(CommentSort::Newest, CommentSort::default());
+        assert_eq!(
+            CommentSort::Top,
+            serde_json::from_str::<CommentSort>("\"top\"").unwrap()
+        );
+    }
+
+    #[test]
+    fn the_home_feed_is_newest_first_by_default() {
+        assert_eq!(FeedSort::Recent, FeedSort::default());
+        assert_eq!(
+            FeedSort::Engagement,
+            serde_json::from_str::<FeedSort>("\"engagement\"").unwrap()
+        );
+    }
+}
@@ -0,0 +1,170 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use std::collections::HashSet;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::user::ProfileVisibility;
+
+pub const MAX_CUSTOM_STATUS_LENGTH: usize = 128;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PresenceStatus {
+    #[default]
+    Online,
+    Idle,
+    Dnd,
+    Invisible,
+    Offline,
+}
+
+impl PresenceStatus {
+    /// Whether a user may pick this status for themself. Offline is only
+    /// ever derived from having no open connections.
+    pub fn is_settable(&self) -> bool {
+        !matches!(self, Self::Offline)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PresenceValidationErr {
+    StatusNotSettable,
+    CustomStatusEmpty,
+    CustomStatusTooLong,
+    CustomStatusExpired,
+}
+
+impl PresenceValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::StatusNotSettable => String::from("Offline cannot be set as a status"),
+            Self::CustomStatusEmpty => String::from("Custom status is empty"),
+            Self::CustomStatusTooLong => format!(
+                "Custom status is too long, must be no more than {} characters",
+                MAX_CUSTOM_STATUS_LENGTH
+            ),
+            Self::CustomStatusExpired => String::from("Custom status expiry is in the past"),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CustomStatus {
+    text: String,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl CustomStatus {
+    pub fn new(text: String, expires_at: Option<DateTime<Utc>>) -> Self {
+        CustomStatus { text, expires_at }
+    }
+
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), PresenceValidationErr> {
+        if self.text.trim().is_empty() {
+            Err(PresenceValidationErr::CustomStatusEmpty)
+        } else if self.text.chars().count() > MAX_CUSTOM_STATUS_LENGTH {
+            Err(PresenceValidationErr::CustomStatusTooLong)
+        } else if self.is_expired(now) {
+            Err(PresenceValidationErr::CustomStatusExpired)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.map(|at| at <= now).unwrap_or(false)
+    }
+
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Presence {
+    user_id: Uuid,
+    status: PresenceStatus,
+    custom_status: Option<CustomStatus>,
+}
+
+impl Presence {
+    pub fn new(user_id: Uuid, status: PresenceStatus, custom_status: Option<CustomStatus>) -> Self {
+        Presence {
+            user_id,
+            status,
+            custom_status,
+        }
+    }
+
+    pub fn offline(user_id: Uuid) -> Self {
+        Presence::new(user_id, PresenceStatus::Offline, None)
+    }
+
+    /// How this presence appears to other users: invisible users and users
+    /// without connections look offline and show no custom status.
+    pub fn as_seen_by_others(&self) -> Self {
+        match self.status {
+            PresenceStatus::Invisible | PresenceStatus::Offline => Presence::offline(self.user_id),
+            _ => self.clone(),
+        }
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn status(&self) -> PresenceStatus {
+        self.status
+    }
+
+    pub fn custom_status(&self) -> Option<CustomStatus> {
+        self.custom_status.clone()
+    }
+}
+
+/// Who may see a user's presence, resolved from their presence visibility
+/// setting and relationships so the gateway can check viewers without a
+/// database round trip.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PresenceAudience {
+    visibility: ProfileVisibility,
+    /// The followers and friends `visibility` lets in. Empty when it lets in
+    /// everyone or nobody.
+    allowed: HashSet<Uuid>,
+    /// Users on either side of a block, who never see the user online.
+    blocked: HashSet<Uuid>,
+}
+
+impl PresenceAudience {
+    pub fn new(visibility: ProfileVisibility, allowed: Vec<Uuid>, blocked: Vec<Uuid>) -> Self {
+        PresenceAudience {
+            visibility,
+            allowed: allowed.into_iter().collect(),
+            blocked: blocked.into_iter().collect(),
+        }
+    }
+
+    /// Whether another user may see the user's presence.
+    pub fn allows(&self, viewer_id: Uuid) -> bool {
+        if self.blocked.contains(&viewer_id) {
+            return false;
+        }
+        match self.visibility {
+            ProfileVisibility::Everyone => true,
+            ProfileVisibility::Followers | ProfileVisibility::Friends => {
+                self.allowed.contains(&viewer_id)
+            }
+            ProfileVisibility::Nobody => false,
+        }
+    }
+}
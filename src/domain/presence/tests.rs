@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::{
+        presence::{
+            CustomStatus, Presence, PresenceAudience, PresenceStatus, PresenceValidationErr,
+            MAX_CUSTOM_STATUS_LENGTH,
+        },
+        user::ProfileVisibility,
+    };
+    use chrono::{Duration, Utc};
+    use claim::assert_ok;
+    use uuid::Uuid;
+
+    #[test]
+    fn a_valid_custom_status_is_accepted() {
+        let now = Utc::now();
+        assert_ok!(CustomStatus::new("a".repeat(MAX_CUSTOM_STATUS_LENGTH), None).validate(now));
+        assert_ok!(
+            CustomStatus::new(String::from("brb"), Some(now + Duration::hours(1))).validate(now)
+        );
+    }
+
+    #[test]
+    fn an_invalid_custom_status_is_rejected() {
+        let now = Utc::now();
+        let test_cases = [
+            (
+                CustomStatus::new(String::from("  "), None),
+                PresenceValidationErr::CustomStatusEmpty,
+                "is blank",
+            ),
+            (
+                CustomStatus::new("a".repeat(MAX_CUSTOM_STATUS_LENGTH + 1), None),
+                PresenceValidationErr::CustomStatusTooLong,
+                "is too long",
+            ),
+            (
+                CustomStatus::new(String::from("brb"), Some(now - Duration::seconds(1))),
+                PresenceValidationErr::CustomStatusExpired,
+                "has already expired",
+            ),
+        ];
+
+        for (status, expected, case) in test_cases {
+            assert_eq!(
+                Err(expected),
+                status.validate(now),
+                "A custom status that {} was not rejected",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn offline_is_not_settable() {
+        assert!(!PresenceStatus::Offline.is_settable());
+        for status in [
+            PresenceStatus::Online,
+            PresenceStatus::Idle,
+            PresenceStatus::Dnd,
+            PresenceStatus::Invisible,
+        ] {
+            assert!(status.is_settable(), "{:?} was not settable", status);
+        }
+    }
+
+    #[test]
+    fn invisible_users_appear_offline_to_others() {
+        let user_id = Uuid::new_v4();
+        let presence = Presence::new(
+            user_id,
+            PresenceStatus::Invisible,
+            Some(CustomStatus::new(String::from("hiding"), None)),
+        );
+        assert_eq!(Presence::offline(user_id), presence.as_seen_by_others());
+
+        let dnd = Presence::new(user_id, PresenceStatus::Dnd, None);
+        assert_eq!(dnd, dnd.as_seen_by_others());
+    }
+
+    #[test]
+    fn presence_audience_follows_visibility_and_blocks() {
+        let friend = Uuid::new_v4();
+        let blocked = Uuid::new_v4();
+        let stranger = Uuid::new_v4();
+
+        let everyone = PresenceAudience::new(ProfileVisibility::Everyone, vec![], vec![blocked]);
+        assert!(everyone.allows(stranger));
+        assert!(!everyone.allows(blocked));
+
+        let friends = PresenceAudience::new(
+            ProfileVisibility::Friends,
+            vec![friend, blocked],
+            vec![blocked],
+        );
+        assert!(friends.allows(friend));
+        assert!(!friends.allows(stranger));
+        assert!(!friends.allows(blocked), "a block overrides friendship");
+
+        let nobody = PresenceAudience::new(ProfileVisibility::Nobody, vec![friend], vec![]);
+        assert!(!nobody.allows(friend));
+    }
+}
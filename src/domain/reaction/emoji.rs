@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::ReactionValidationErr;
+
+pub const MIN_EMOJI_NAME_LENGTH: usize = 2;
+pub const MAX_EMOJI_NAME_LENGTH: usize = 32;
+
+/// A custom emoji uploaded to a server. Names are unique per server, ignoring
+/// case.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct ServerEmoji {
+    id: Uuid,
+    server_id: Uuid,
+    name: String,
+    creator_id: Uuid,
+    created_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+impl PartialEq for ServerEmoji {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.server_id == other.server_id
+            && self.name == other.name
+            && self.creator_id == other.creator_id
+            && self.deleted_at == other.deleted_at
+    }
+}
+
+impl std::fmt::Display for ServerEmoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl ServerEmoji {
+    pub fn new(
+        id: Uuid,
+        server_id: Uuid,
+        name: String,
+        creator_id: Uuid,
+        created_at: DateTime<Utc>,
+        deleted_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        ServerEmoji {
+            id,
+            server_id,
+            name,
+            creator_id,
+            created_at,
+            deleted_at,
+        }
+    }
+
+    pub fn validate_name(name: &str) -> Result<(), ReactionValidationErr> {
+        let length = name.chars().count();
+        if length < MIN_EMOJI_NAME_LENGTH {
+            Err(ReactionValidationErr::EmojiNameTooShort)
+        } else if length > MAX_EMOJI_NAME_LENGTH {
+            Err(ReactionValidationErr::EmojiNameTooLong)
+        } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            Err(ReactionValidationErr::EmojiNameInvalidChars)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn creator_id(&self) -> Uuid {
+        self.creator_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
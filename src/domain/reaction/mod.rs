@@ -0,0 +1,129 @@
+mod emoji;
+#[allow(clippy::module_inception)]
+mod tests;
+
+pub use emoji::{ServerEmoji, MAX_EMOJI_NAME_LENGTH, MIN_EMOJI_NAME_LENGTH};
+
+use actix_web::HttpResponse;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How many distinct emoji a single message can collect.
+pub const MAX_REACTIONS_PER_MESSAGE: i64 = 20;
+
+#[derive(Debug, PartialEq)]
+pub enum ReactionValidationErr {
+    InvalidEmoji,
+    EmojiNameTooShort,
+    EmojiNameTooLong,
+    EmojiNameInvalidChars,
+    TooManyReactions,
+    CustomEmojiNotAllowed,
+}
+
+impl ReactionValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::InvalidEmoji => {
+                String::from("Reaction must be a Unicode emoji or a custom emoji id")
+            }
+            Self::EmojiNameTooShort => format!(
+                "Emoji name is too short, must be at least {} characters",
+                MIN_EMOJI_NAME_LENGTH
+            ),
+            Self::EmojiNameTooLong => format!(
+                "Emoji name is too long, must be no more than {} characters",
+                MAX_EMOJI_NAME_LENGTH
+            ),
+            Self::EmojiNameInvalidChars => {
+                String::from("Emoji name may only contain letters, numbers and underscores")
+            }
+            Self::TooManyReactions => format!(
+                "Message already has the maximum of {} different reactions",
+                MAX_REACTIONS_PER_MESSAGE
+            ),
+            Self::CustomEmojiNotAllowed => {
+                String::from("Custom emoji can only be used in their own server")
+            }
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// The emoji of a reaction: either a Unicode emoji, stored fully qualified,
+/// or a reference to a custom server emoji.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionEmoji {
+    Unicode(String),
+    Custom(Uuid),
+}
+
+impl TryFrom<&str> for ReactionEmoji {
+    type Error = ReactionValidationErr;
+
+    /// Parses an emoji path segment. Custom emoji are referenced by id; any
+    /// other value must be a single Unicode emoji, including ZWJ sequences,
+    /// flags and skin tone variants.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Ok(id) = Uuid::parse_str(value) {
+            return Ok(Self::Custom(id));
+        }
+        match emojis::get(value) {
+            Some(emoji) => Ok(Self::Unicode(emoji.as_str().to_string())),
+            None => Err(ReactionValidationErr::InvalidEmoji),
+        }
+    }
+}
+
+impl ReactionEmoji {
+    /// Splits the emoji into the `emoji` and `custom_emoji_id` columns.
+    pub fn columns(&self) -> (Option<String>, Option<Uuid>) {
+        match self {
+            Self::Unicode(emoji) => (Some(emoji.clone()), None),
+            Self::Custom(id) => (None, Some(*id)),
+        }
+    }
+
+    pub fn from_columns(emoji: Option<String>, custom_emoji_id: Option<Uuid>) -> Option<Self> {
+        match (emoji, custom_emoji_id) {
+            (Some(emoji), None) => Some(Self::Unicode(emoji)),
+            (None, Some(id)) => Some(Self::Custom(id)),
+            _ => None,
+        }
+    }
+
+    pub fn custom_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Unicode(_) => None,
+            Self::Custom(id) => Some(*id),
+        }
+    }
+}
+
+/// How many users reacted to a message with an emoji, and whether the
+/// viewing user is one of them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReactionCount {
+    emoji: ReactionEmoji,
+    count: i64,
+    me: bool,
+}
+
+impl ReactionCount {
+    pub fn new(emoji: ReactionEmoji, count: i64, me: bool) -> Self {
+        ReactionCount { emoji, count, me }
+    }
+
+    pub fn emoji(&self) -> ReactionEmoji {
+        self.emoji.clone()
+    }
+
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+
+    pub fn me(&self) -> bool {
+        self.me
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::reaction::{
+        ReactionEmoji, ReactionValidationErr, ServerEmoji, MAX_EMOJI_NAME_LENGTH,
+    };
+    use uuid::Uuid;
+
+    #[test]
+    fn unicode_emoji_are_accepted() {
+        let test_cases = [
+            ("👍", "a simple emoji"),
+            ("👍🏽", "a skin tone variant"),
+            ("👩‍👩‍👧‍👦", "a ZWJ sequence"),
+            ("🇯🇵", "a flag"),
+            ("🏳️‍🌈", "a ZWJ flag"),
+        ];
+
+        for (emoji, case) in test_cases {
+            assert_eq!(
+                Ok(ReactionEmoji::Unicode(emoji.to_string())),
+                ReactionEmoji::try_from(emoji),
+                "{} was not accepted",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn unqualified_emoji_are_normalized() {
+        assert_eq!(
+            Ok(ReactionEmoji::Unicode(String::from("❤️"))),
+            ReactionEmoji::try_from("❤"),
+            "An unqualified heart was not normalized to its fully qualified form"
+        );
+    }
+
+    #[test]
+    fn custom_emoji_ids_are_accepted() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            Ok(ReactionEmoji::Custom(id)),
+            ReactionEmoji::try_from(id.to_string().as_str())
+        );
+    }
+
+    #[test]
+    fn non_emoji_are_rejected() {
+        for value in ["", "a", ":thumbsup:", "👍👍", "👍 ", "1234"] {
+            assert_eq!(
+                Err(ReactionValidationErr::InvalidEmoji),
+                ReactionEmoji::try_from(value),
+                "{:?} was not rejected",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn emoji_columns_round_trip() {
+        for emoji in [
+            ReactionEmoji::Unicode(String::from("🚀")),
+            ReactionEmoji::Custom(Uuid::new_v4()),
+        ] {
+            let (unicode, custom_id) = emoji.columns();
+            assert_eq!(Some(emoji), ReactionEmoji::from_columns(unicode, custom_id));
+        }
+        assert_eq!(None, ReactionEmoji::from_columns(None, None));
+    }
+
+    #[test]
+    fn emoji_names_are_validated() {
+        assert!(ServerEmoji::validate_name("party_parrot2").is_ok());
+        let test_cases = [
+            ("a", ReactionValidationErr::EmojiNameTooShort),
+            (
+                &"a".repeat(MAX_EMOJI_NAME_LENGTH + 1),
+                ReactionValidationErr::EmojiNameTooLong,
+            ),
+            ("party parrot", ReactionValidationErr::EmojiNameInvalidChars),
+            ("party-parrot", ReactionValidationErr::EmojiNameInvalidChars),
+        ];
+
+        for (name, expected) in test_cases {
+            assert_eq!(
+                Err(expected),
+                ServerEmoji::validate_name(name),
+                "{:?} was not rejected",
+                name
+            );
+        }
+    }
+}
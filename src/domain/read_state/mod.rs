@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Unread and mention counts for one channel or DM thread, as returned by
+/// `GET /users/@me/unread`. Messages the user wrote never count as unread.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct UnreadState {
+    channel_id: Option<Uuid>,
+    thread_id: Option<Uuid>,
+    last_read_message_id: Option<Uuid>,
+    unread_count: i64,
+    mention_count: i32,
+}
+
+impl UnreadState {
+    pub fn channel_id(&self) -> Option<Uuid> {
+        self.channel_id
+    }
+
+    pub fn thread_id(&self) -> Option<Uuid> {
+        self.thread_id
+    }
+
+    pub fn last_read_message_id(&self) -> Option<Uuid> {
+        self.last_read_message_id
+    }
+
+    pub fn unread_count(&self) -> i64 {
+        self.unread_count
+    }
+
+    pub fn mention_count(&self) -> i32 {
+        self.mention_count
+    }
+}
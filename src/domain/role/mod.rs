@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::permission::Permissions;
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Role {
+    id: Uuid,
+    server_id: Uuid,
+    name: String,
+    permissions: Permissions,
+    position: i32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl PartialEq for Role {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.server_id == other.server_id
+            && self.name == other.name
+            && self.permissions == other.permissions
+            && self.position == other.position
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Role {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        server_id: Uuid,
+        name: String,
+        permissions: Permissions,
+        position: i32,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Self {
+        Role {
+            id,
+            server_id,
+            name,
+            permissions,
+            position,
+            created_at,
+            updated_at,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
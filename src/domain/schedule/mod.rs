@@ -0,0 +1,166 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::message::MessageContent;
+
+/// How far ahead a message may be scheduled.
+pub const MAX_SCHEDULE_AHEAD_DAYS: i64 = 30;
+/// The most messages a user may have waiting to be sent at once.
+pub const MAX_PENDING_SCHEDULED_MESSAGES: i64 = 100;
+
+#[derive(Debug, PartialEq)]
+pub enum ScheduleValidationErr {
+    TargetRequired,
+    SendAtInPast,
+    SendAtTooFar,
+    TooManyPending,
+}
+
+impl ScheduleValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::TargetRequired => {
+                String::from("Exactly one of channel_id or dm_thread_id must be provided")
+            }
+            Self::SendAtInPast => String::from("Scheduled messages must be sent in the future"),
+            Self::SendAtTooFar => format!(
+                "Messages can be scheduled at most {} days ahead",
+                MAX_SCHEDULE_AHEAD_DAYS
+            ),
+            Self::TooManyPending => format!(
+                "No more than {} messages may be scheduled at once",
+                MAX_PENDING_SCHEDULED_MESSAGES
+            ),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// Where a scheduled message will be delivered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleTarget {
+    Channel(Uuid),
+    DmThread(Uuid),
+}
+
+impl ScheduleTarget {
+    pub fn from_ids(
+        channel_id: Option<Uuid>,
+        dm_thread_id: Option<Uuid>,
+    ) -> Result<Self, ScheduleValidationErr> {
+        match (channel_id, dm_thread_id) {
+            (Some(channel_id), None) => Ok(Self::Channel(channel_id)),
+            (None, Some(thread_id)) => Ok(Self::DmThread(thread_id)),
+            _ => Err(ScheduleValidationErr::TargetRequired),
+        }
+    }
+}
+
+/// A message waiting to be sent by the message scheduler. Once delivered it
+/// is removed and lives on as an ordinary message.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct ScheduledMessage {
+    id: Uuid,
+    author_id: Uuid,
+    channel_id: Option<Uuid>,
+    dm_thread_id: Option<Uuid>,
+    content: MessageContent,
+    send_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for ScheduledMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.author_id == other.author_id
+            && self.channel_id == other.channel_id
+            && self.dm_thread_id == other.dm_thread_id
+            && self.content == other.content
+    }
+}
+
+impl std::fmt::Display for ScheduledMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl ScheduledMessage {
+    pub fn new(
+        id: Uuid,
+        author_id: Uuid,
+        target: ScheduleTarget,
+        content: MessageContent,
+        send_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        let (channel_id, dm_thread_id) = match target {
+            ScheduleTarget::Channel(channel_id) => (Some(channel_id), None),
+            ScheduleTarget::DmThread(thread_id) => (None, Some(thread_id)),
+        };
+        ScheduledMessage {
+            id,
+            author_id,
+            channel_id,
+            dm_thread_id,
+            content,
+            send_at,
+            created_at,
+        }
+    }
+
+    pub fn validate_send_at(
+        now: DateTime<Utc>,
+        send_at: DateTime<Utc>,
+    ) -> Result<(), ScheduleValidationErr> {
+        if send_at <= now {
+            Err(ScheduleValidationErr::SendAtInPast)
+        } else if send_at > now + Duration::days(MAX_SCHEDULE_AHEAD_DAYS) {
+            Err(ScheduleValidationErr::SendAtTooFar)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn validate_pending_count(pending: i64) -> Result<(), ScheduleValidationErr> {
+        if pending >= MAX_PENDING_SCHEDULED_MESSAGES {
+            Err(ScheduleValidationErr::TooManyPending)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn author_id(&self) -> Uuid {
+        self.author_id
+    }
+
+    pub fn target(&self) -> ScheduleTarget {
+        match (self.channel_id, self.dm_thread_id) {
+            (Some(channel_id), _) => ScheduleTarget::Channel(channel_id),
+            (None, Some(thread_id)) => ScheduleTarget::DmThread(thread_id),
+            (None, None) => unreachable!("scheduled messages always have a target"),
+        }
+    }
+
+    pub fn content(&self) -> MessageContent {
+        self.content.clone()
+    }
+
+    pub fn send_at(&self) -> DateTime<Utc> {
+        self.send_at
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
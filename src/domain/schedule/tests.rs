@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::schedule::{
+        ScheduleTarget, ScheduleValidationErr, ScheduledMessage, MAX_PENDING_SCHEDULED_MESSAGES,
+        MAX_SCHEDULE_AHEAD_DAYS,
+    };
+    use chrono::{Duration, Utc};
+    use claim::assert_ok;
+    use uuid::Uuid;
+
+    #[test]
+    fn send_at_must_be_in_the_schedulable_window() {
+        let now = Utc::now();
+        assert_ok!(ScheduledMessage::validate_send_at(
+            now,
+            now + Duration::minutes(1)
+        ));
+        assert_ok!(ScheduledMessage::validate_send_at(
+            now,
+            now + Duration::days(MAX_SCHEDULE_AHEAD_DAYS)
+        ));
+
+        let test_cases = [
+            (now, ScheduleValidationErr::SendAtInPast, "is now"),
+            (
+                now - Duration::minutes(1),
+                ScheduleValidationErr::SendAtInPast,
+                "is in the past",
+            ),
+            (
+                now + Duration::days(MAX_SCHEDULE_AHEAD_DAYS) + Duration::seconds(1),
+                ScheduleValidationErr::SendAtTooFar,
+                "is too far ahead",
+            ),
+        ];
+        for (send_at, expected, case) in test_cases {
+            assert_eq!(
+                Err(expected),
+                ScheduledMessage::validate_send_at(now, send_at),
+                "A send time that {} was not rejected",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn exactly_one_target_is_required() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            Ok(ScheduleTarget::Channel(id)),
+            ScheduleTarget::from_ids(Some(id), None)
+        );
+        assert_eq!(
+            Ok(ScheduleTarget::DmThread(id)),
+            ScheduleTarget::from_ids(None, Some(id))
+        );
+        assert_eq!(
+            Err(ScheduleValidationErr::TargetRequired),
+            ScheduleTarget::from_ids(None, None)
+        );
+        assert_eq!(
+            Err(ScheduleValidationErr::TargetRequired),
+            ScheduleTarget::from_ids(Some(id), Some(Uuid::new_v4()))
+        );
+    }
+
+    #[test]
+    fn pending_messages_are_capped() {
+        assert_ok!(ScheduledMessage::validate_pending_count(
+            MAX_PENDING_SCHEDULED_MESSAGES - 1
+        ));
+        assert_eq!(
+            Err(ScheduleValidationErr::TooManyPending),
+            ScheduledMessage::validate_pending_count(MAX_PENDING_SCHEDULED_MESSAGES)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const MAX_SEARCH_QUERY_LENGTH: usize = 200;
+/// Terms past this are ignored, to keep a pasted paragraph from turning into
+/// an unbounded tsquery.
+pub const MAX_SEARCH_TERMS: usize = 16;
+
+#[derive(Debug, PartialEq)]
+pub enum SearchValidationErr {
+    QueryTooLong,
+    NothingToSearch,
+    InvalidDateRange,
+    AttachmentsUnsupported,
+}
+
+impl SearchValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::QueryTooLong => format!(
+                "Search query is too long, must be no more than {} characters",
+                MAX_SEARCH_QUERY_LENGTH
+            ),
+            Self::NothingToSearch => String::from("Search needs a query or at least one filter"),
+            Self::InvalidDateRange => String::from("Search 'after' must be earlier than 'before'"),
+            Self::AttachmentsUnsupported => String::from(
+                "Messages cannot carry attachments yet, so 'has=attachment' is not supported",
+            ),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// Turns free text into a prefix tsquery for the `simple` configuration,
+/// e.g. "Rust fan" becomes `rust:* & fan:*`, so results match as the user
+/// types. Punctuation only separates terms, which also keeps tsquery
+/// operators out of the query. Returns `None` when no terms are left.
+pub fn to_prefix_tsquery(q: &str) -> Option<String> {
+    let terms: Vec<String> = q
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" & "))
+    }
+}
+
+/// What a message must contain, from `has=...`. Messages have no
+/// attachments to filter on yet, so the filter is parsed but rejected until
+/// they do.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HasFilter {
+    Attachment,
+}
+
+/// Query string of `GET /servers/{id}/messages/search`.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct MessageSearchParams {
+    #[serde(default)]
+    pub q: String,
+    pub author_id: Option<Uuid>,
+    pub channel_id: Option<Uuid>,
+    pub has: Option<HasFilter>,
+    /// Only messages that mention this user.
+    pub mentions: Option<Uuid>,
+    pub before: Option<DateTime<Utc>>,
+    pub after: Option<DateTime<Utc>>,
+}
+
+impl MessageSearchParams {
+    pub fn validate(&self) -> Result<(), SearchValidationErr> {
+        if self.has == Some(HasFilter::Attachment) {
+            return Err(SearchValidationErr::AttachmentsUnsupported);
+        }
+        validate_search(
+            &self.q,
+            self.author_id.is_some()
+                || self.channel_id.is_some()
+                || self.has.is_some()
+                || self.mentions.is_some()
+                || self.before.is_some()
+                || self.after.is_some(),
+            self.before,
+            self.after,
+        )
+    }
+
+    pub fn tsquery(&self) -> Option<String> {
+        to_prefix_tsquery(&self.q)
+    }
+}
+
+/// Query string of `GET /search`, which searches the user's DMs.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct DmSearchParams {
+    #[serde(default)]
+    pub q: String,
+    pub author_id: Option<Uuid>,
+    pub thread_id: Option<Uuid>,
+    pub before: Option<DateTime<Utc>>,
+    pub after: Option<DateTime<Utc>>,
+}
+
+impl DmSearchParams {
+    pub fn validate(&self) -> Result<(), SearchValidationErr> {
+        validate_search(
+            &self.q,
+            self.author_id.is_some()
+                || self.thread_id.is_some()
+                || self.before.is_some()
+                || self.after.is_some(),
+            self.before,
+            self.after,
+        )
+    }
+
+    pub fn tsquery(&self) -> Option<String> {
+        to_prefix_tsquery(&self.q)
+    }
+}
+
+fn validate_search(
+    q: &str,
+    has_filters: bool,
+    before: Option<DateTime<Utc>>,
+    after: Option<DateTime<Utc>>,
+) -> Result<(), SearchValidationErr> {
+    if q.chars().count() > MAX_SEARCH_QUERY_LENGTH {
+        return Err(SearchValidationErr::QueryTooLong);
+    }
+    if to_prefix_tsquery(q).is_none() && !has_filters {
+        return Err(SearchValidationErr::NothingToSearch);
+    }
+    if let (Some(before), Some(after)) = (before, after) {
+        if after >= before {
+            return Err(SearchValidationErr::InvalidDateRange);
+        }
+    }
+    Ok(())
+}
+
+/// A matching message with an excerpt of its content in which the matched
+/// terms are wrapped in `<mark>` tags. The rest of the excerpt is HTML
+/// escaped, so it is safe to render as HTML.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct SearchResult<T> {
+    #[sqlx(flatten)]
+    message: T,
+    snippet: String,
+}
+
+impl<T> SearchResult<T> {
+    pub fn new(message: T, snippet: String) -> Self {
+        SearchResult { message, snippet }
+    }
+
+    pub fn message(&self) -> &T {
+        &self.message
+    }
+
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+}
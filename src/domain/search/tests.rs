@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::search::{
+        to_prefix_tsquery, DmSearchParams, MessageSearchParams, SearchValidationErr,
+        MAX_SEARCH_QUERY_LENGTH, MAX_SEARCH_TERMS,
+    };
+    use chrono::{Duration, Utc};
+    use claim::{assert_none, assert_ok};
+    use uuid::Uuid;
+
+    #[test]
+    fn terms_become_prefix_matches() {
+        assert_eq!(
+            Some(String::from("rust:* & fan:*")),
+            to_prefix_tsquery("Rust fan")
+        );
+        assert_eq!(
+            Some(String::from("don:* & t:* & panic:*")),
+            to_prefix_tsquery("  don't   PANIC! ")
+        );
+        assert_eq!(Some(String::from("café:*")), to_prefix_tsquery("Café"));
+    }
+
+    #[test]
+    fn tsquery_operators_are_stripped() {
+        assert_eq!(
+            Some(String::from("a:* & b:* & c:*")),
+            to_prefix_tsquery("a & !b | (c:*)")
+        );
+        assert_none!(to_prefix_tsquery("&|!():*<->"));
+        assert_none!(to_prefix_tsquery(""));
+    }
+
+    #[test]
+    fn terms_are_capped() {
+        let q = vec!["word"; MAX_SEARCH_TERMS + 5].join(" ");
+        let tsquery = to_prefix_tsquery(&q).unwrap();
+        assert_eq!(MAX_SEARCH_TERMS, tsquery.split(" & ").count());
+    }
+
+    #[test]
+    fn search_needs_a_query_or_filter() {
+        let params = MessageSearchParams {
+            q: String::from("  ?? "),
+            ..Default::default()
+        };
+        assert_eq!(Err(SearchValidationErr::NothingToSearch), params.validate());
+
+        let params = MessageSearchParams {
+            author_id: Some(Uuid::new_v4()),
+            ..params
+        };
+        assert_ok!(params.validate());
+
+        let params = DmSearchParams {
+            q: String::from("hello"),
+            ..Default::default()
+        };
+        assert_ok!(params.validate());
+    }
+
+    #[test]
+    fn invalid_searches_are_rejected() {
+        let params = MessageSearchParams {
+            q: "a".repeat(MAX_SEARCH_QUERY_LENGTH + 1),
+            ..Default::default()
+        };
+        assert_eq!(Err(SearchValidationErr::QueryTooLong), params.validate());
+
+        let now = Utc::now();
+        let params = DmSearchParams {
+            q: String::from("hello"),
+            before: Some(now),
+            after: Some(now + Duration::days(1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(SearchValidationErr::InvalidDateRange),
+            params.validate()
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct ServerMember {
+    server_id: Uuid,
+    user_id: Uuid,
+    is_admin: bool,
+    is_banned: bool,
+    joined_at: DateTime<Utc>,
+}
+
+impl ServerMember {
+    pub fn new(
+        server_id: Uuid,
+        user_id: Uuid,
+        is_admin: bool,
+        is_banned: bool,
+        joined_at: DateTime<Utc>,
+    ) -> Self {
+        ServerMember {
+            server_id,
+            user_id,
+            is_admin,
+            is_banned,
+            joined_at,
+        }
+    }
+
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.is_admin
+    }
+
+    pub fn is_banned(&self) -> bool {
+        self.is_banned
+    }
+
+    pub fn joined_at(&self) -> DateTime<Utc> {
+        self.joined_at
+    }
+}
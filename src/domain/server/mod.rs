@@ -1,3 +1,7 @@
+mod member;
+
+pub use member::ServerMember;
+
 use serde::{Deserialize, Serialize};
 
 use chrono::{DateTime, Utc};
@@ -0,0 +1,139 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+pub const MAX_TAG_LENGTH: usize = 64;
+
+/// The most distinct tags kept from one post. Anything past the cap is left
+/// as plain text.
+pub const MAX_TAGS_PER_POST: usize = 20;
+
+/// How far back uses count towards a tag trending.
+pub const TRENDING_WINDOW_HOURS: i64 = 24;
+
+/// How many trending tags are kept for each server and for everywhere.
+pub const TRENDING_TAGS_LIMIT: i64 = 20;
+
+#[derive(Debug, PartialEq)]
+pub enum TagValidationErr {
+    TagEmpty,
+    TagTooLong,
+    TagContainsForbiddenChars(char),
+    TagAllDigits,
+}
+
+impl TagValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::TagEmpty => String::from("Tag is empty"),
+            Self::TagTooLong => format!(
+                "Tag is too long, must be no more than {} characters",
+                MAX_TAG_LENGTH
+            ),
+            Self::TagContainsForbiddenChars(c) => {
+                format!("Tag contains forbidden character '{}'", c)
+            }
+            Self::TagAllDigits => String::from("Tag must contain a letter"),
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// A hashtag, normalized to lowercase without the leading `#`. Tags are
+/// letters, digits and underscores, with at least one letter so that
+/// "#1" is not a tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag(String);
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for Tag {
+    type Error = TagValidationErr;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let raw = value.strip_prefix('#').unwrap_or(value);
+        if raw.is_empty() {
+            return Err(TagValidationErr::TagEmpty);
+        } else if let Some(c) = raw.chars().find(|&c| !is_tag_char(c)) {
+            return Err(TagValidationErr::TagContainsForbiddenChars(c));
+        } else if !raw.chars().any(char::is_alphabetic) {
+            return Err(TagValidationErr::TagAllDigits);
+        }
+
+        // Some letters lowercase to a letter plus a combining mark, as 'İ'
+        // does to "i\u{307}". Marks are not tag characters, so they are
+        // dropped to keep every stored name a valid tag itself.
+        let name: String = raw
+            .chars()
+            .flat_map(char::to_lowercase)
+            .filter(|&c| is_tag_char(c))
+            .collect();
+        if name.chars().count() > MAX_TAG_LENGTH {
+            Err(TagValidationErr::TagTooLong)
+        } else {
+            Ok(Tag(name))
+        }
+    }
+}
+
+impl Tag {
+    /// Finds the distinct tags in post content, in order of first use. Like
+    /// mentions, a tag only counts at the start of the content or after
+    /// whitespace, so URL fragments are ignored; it runs until the first
+    /// character a tag cannot hold, leaving trailing punctuation out.
+    pub fn parse_all(content: &str) -> Vec<Tag> {
+        let mut tags = Vec::new();
+        let mut rest = content;
+        let mut at_boundary = true;
+
+        while let Some(c) = rest.chars().next() {
+            if at_boundary && c == '#' {
+                let token = &rest[1..];
+                let token = &token[..token.find(|c| !is_tag_char(c)).unwrap_or(token.len())];
+                if let Ok(tag) = Tag::try_from(token) {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+                rest = &rest[1 + token.len()..];
+                at_boundary = false;
+                continue;
+            }
+
+            at_boundary = c.is_whitespace();
+            rest = &rest[c.len_utf8()..];
+        }
+
+        tags.truncate(MAX_TAGS_PER_POST);
+        tags
+    }
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// A tag picking up use, as last computed by the trending job. Each use in
+/// the window counts by how recent it is, from 1 for a use just now down to
+/// 0 at the edge of the window, so a tag taking off outranks one used as
+/// often but spread out over the day.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct TrendingTag {
+    pub tag: String,
+    pub score: f64,
+    /// How many posts used the tag within the window.
+    pub use_count: i64,
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::tag::{Tag, TagValidationErr, MAX_TAGS_PER_POST, MAX_TAG_LENGTH};
+
+    fn names(tags: Vec<Tag>) -> Vec<String> {
+        tags.into_iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn tags_are_normalized() {
+        assert_eq!(Ok(Tag(String::from("rust"))), Tag::try_from("#Rust"));
+        assert_eq!(Ok(Tag(String::from("día_2"))), Tag::try_from("DÍA_2"));
+    }
+
+    #[test]
+    fn tags_are_validated_before_lowercasing() {
+        let tag = Tag::try_from("#İstanbul").expect("A dotted capital I was rejected");
+        assert_eq!(Tag(String::from("istanbul")), tag);
+        assert_eq!(
+            Ok(tag.clone()),
+            Tag::try_from(tag.as_ref()),
+            "The normalized name was not a valid tag itself"
+        );
+        assert_eq!(
+            Err(TagValidationErr::TagContainsForbiddenChars('-')),
+            Tag::try_from("İ-stanbul")
+        );
+    }
+
+    #[test]
+    fn invalid_tags_are_rejected() {
+        let test_cases = [
+            ("", TagValidationErr::TagEmpty),
+            ("#", TagValidationErr::TagEmpty),
+            ("2024", TagValidationErr::TagAllDigits),
+            (
+                "rust-lang",
+                TagValidationErr::TagContainsForbiddenChars('-'),
+            ),
+            (
+                &"a".repeat(MAX_TAG_LENGTH + 1),
+                TagValidationErr::TagTooLong,
+            ),
+        ];
+        for (tag, err) in test_cases {
+            assert_eq!(Err(err), Tag::try_from(tag), "{} was not rejected", tag);
+        }
+    }
+
+    #[test]
+    fn tags_are_parsed_from_content() {
+        assert_eq!(
+            vec!["rust", "web_dev", "día"],
+            names(Tag::parse_all(
+                "#Rust is great for #web_dev, #RUST! see https://x.io/#anchor #1 #día"
+            ))
+        );
+        assert!(Tag::parse_all("no tags here, just a # sign").is_empty());
+    }
+
+    #[test]
+    fn parsed_tags_are_capped() {
+        let content = (0..MAX_TAGS_PER_POST + 5)
+            .map(|i| format!("#tag{}", i))
+            .collect::<Vec<String>>()
+            .join(" ");
+        assert_eq!(MAX_TAGS_PER_POST, Tag::parse_all(&content).len());
+    }
+}
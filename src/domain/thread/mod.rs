@@ -0,0 +1,228 @@
+#[allow(clippy::module_inception)]
+mod tests;
+
+use actix_web::HttpResponse;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::channel::MAX_CHANNEL_NAME_LENGTH;
+
+/// How long, in minutes, a thread may go without messages before it is
+/// archived. Only these durations are offered.
+pub const AUTO_ARCHIVE_DURATIONS: &[i32] = &[60, 1440, 4320, 10080];
+pub const DEFAULT_AUTO_ARCHIVE_MINUTES: i32 = 1440;
+
+#[derive(Debug, PartialEq)]
+pub enum ThreadValidationErr {
+    NameEmpty,
+    NameTooLong,
+    InvalidAutoArchiveDuration,
+    ParentCannotHaveThreads,
+}
+
+impl ThreadValidationErr {
+    pub fn handle_http(&self) -> HttpResponse {
+        let body = match self {
+            Self::NameEmpty => String::from("Thread name is empty"),
+            Self::NameTooLong => format!(
+                "Thread name is too long, must be no more than {} characters",
+                MAX_CHANNEL_NAME_LENGTH
+            ),
+            Self::InvalidAutoArchiveDuration => format!(
+                "Thread auto archive duration must be one of {:?} minutes",
+                AUTO_ARCHIVE_DURATIONS
+            ),
+            Self::ParentCannotHaveThreads => {
+                String::from("Threads can only be started in text or announcement channels")
+            }
+        };
+        HttpResponse::BadRequest().body(body)
+    }
+}
+
+/// A thread channel spawned from a message. Its messages live in the
+/// `messages` table under the thread's own id, and its permissions are those
+/// of the parent channel.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct Thread {
+    id: Uuid,
+    server_id: Uuid,
+    parent_id: Uuid,
+    starter_message_id: Option<Uuid>,
+    owner_id: Uuid,
+    name: String,
+    auto_archive_minutes: i32,
+    last_message_at: Option<DateTime<Utc>>,
+    archived_at: Option<DateTime<Utc>>,
+    locked: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl PartialEq for Thread {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.server_id == other.server_id
+            && self.parent_id == other.parent_id
+            && self.starter_message_id == other.starter_message_id
+            && self.owner_id == other.owner_id
+            && self.name == other.name
+            && self.auto_archive_minutes == other.auto_archive_minutes
+            && self.archived_at.is_some() == other.archived_at.is_some()
+            && self.locked == other.locked
+    }
+}
+
+impl std::fmt::Display for Thread {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Thread {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: Uuid,
+        server_id: Uuid,
+        parent_id: Uuid,
+        starter_message_id: Option<Uuid>,
+        owner_id: Uuid,
+        name: String,
+        auto_archive_minutes: i32,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Thread {
+            id,
+            server_id,
+            parent_id,
+            starter_message_id,
+            owner_id,
+            name,
+            auto_archive_minutes,
+            last_message_at: None,
+            archived_at: None,
+            locked: false,
+            created_at,
+        }
+    }
+
+    pub fn validate_auto_archive_minutes(minutes: i32) -> Result<(), ThreadValidationErr> {
+        if AUTO_ARCHIVE_DURATIONS.contains(&minutes) {
+            Ok(())
+        } else {
+            Err(ThreadValidationErr::InvalidAutoArchiveDuration)
+        }
+    }
+
+    pub fn validate_name(name: &str) -> Result<(), ThreadValidationErr> {
+        if name.trim().is_empty() {
+            Err(ThreadValidationErr::NameEmpty)
+        } else if name.chars().count() > MAX_CHANNEL_NAME_LENGTH {
+            Err(ThreadValidationErr::NameTooLong)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The last time anything was said in the thread, or when it was started.
+    pub fn last_activity_at(&self) -> DateTime<Utc> {
+        self.last_message_at.unwrap_or(self.created_at)
+    }
+
+    /// Whether an open thread has been inactive for its whole auto archive
+    /// duration as of `now`.
+    pub fn is_archive_due(&self, now: DateTime<Utc>) -> bool {
+        self.archived_at.is_none()
+            && self.last_activity_at() + Duration::minutes(self.auto_archive_minutes as i64) <= now
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn server_id(&self) -> Uuid {
+        self.server_id
+    }
+
+    pub fn parent_id(&self) -> Uuid {
+        self.parent_id
+    }
+
+    pub fn starter_message_id(&self) -> Option<Uuid> {
+        self.starter_message_id
+    }
+
+    pub fn owner_id(&self) -> Uuid {
+        self.owner_id
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn auto_archive_minutes(&self) -> i32 {
+        self.auto_archive_minutes
+    }
+
+    pub fn last_message_at(&self) -> Option<DateTime<Utc>> {
+        self.last_message_at
+    }
+
+    pub fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.archived_at
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_auto_archive_minutes(&mut self, auto_archive_minutes: i32) {
+        self.auto_archive_minutes = auto_archive_minutes;
+    }
+
+    pub fn set_last_message_at(&mut self, last_message_at: Option<DateTime<Utc>>) {
+        self.last_message_at = last_message_at;
+    }
+
+    pub fn set_archived_at(&mut self, archived_at: Option<DateTime<Utc>>) {
+        self.archived_at = archived_at;
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug, PartialEq)]
+pub struct ThreadMember {
+    thread_id: Uuid,
+    user_id: Uuid,
+    joined_at: DateTime<Utc>,
+}
+
+impl ThreadMember {
+    pub fn thread_id(&self) -> Uuid {
+        self.thread_id
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn joined_at(&self) -> DateTime<Utc> {
+        self.joined_at
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::{
+        channel::MAX_CHANNEL_NAME_LENGTH,
+        thread::{Thread, ThreadValidationErr, DEFAULT_AUTO_ARCHIVE_MINUTES},
+    };
+    use chrono::{Duration, Utc};
+    use claim::assert_ok;
+    use uuid::Uuid;
+
+    fn thread(auto_archive_minutes: i32) -> Thread {
+        Thread::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Some(Uuid::new_v4()),
+            Uuid::new_v4(),
+            String::from("thread"),
+            auto_archive_minutes,
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn only_offered_auto_archive_durations_are_valid() {
+        for minutes in [60, 1440, 4320, 10080] {
+            assert_ok!(Thread::validate_auto_archive_minutes(minutes));
+        }
+        for minutes in [0, -60, 30, 2000] {
+            assert_eq!(
+                Err(ThreadValidationErr::InvalidAutoArchiveDuration),
+                Thread::validate_auto_archive_minutes(minutes)
+            );
+        }
+    }
+
+    #[test]
+    fn thread_names_are_validated() {
+        assert_ok!(Thread::validate_name(
+            "a".repeat(MAX_CHANNEL_NAME_LENGTH).as_str()
+        ));
+        assert_eq!(
+            Err(ThreadValidationErr::NameEmpty),
+            Thread::validate_name(" ")
+        );
+        assert_eq!(
+            Err(ThreadValidationErr::NameTooLong),
+            Thread::validate_name("a".repeat(MAX_CHANNEL_NAME_LENGTH + 1).as_str())
+        );
+    }
+
+    #[test]
+    fn threads_are_due_for_archive_after_inactivity() {
+        let mut thread = thread(60);
+        let now = Utc::now();
+        assert!(!thread.is_archive_due(now));
+        assert!(thread.is_archive_due(now + Duration::minutes(61)));
+
+        thread.set_last_message_at(Some(now + Duration::minutes(30)));
+        assert!(!thread.is_archive_due(now + Duration::minutes(61)));
+        assert!(thread.is_archive_due(now + Duration::minutes(91)));
+
+        thread.set_archived_at(Some(now));
+        assert!(!thread.is_archive_due(now + Duration::minutes(91)));
+    }
+
+    #[test]
+    fn default_auto_archive_duration_is_valid() {
+        assert_ok!(Thread::validate_auto_archive_minutes(
+            DEFAULT_AUTO_ARCHIVE_MINUTES
+        ));
+    }
+}
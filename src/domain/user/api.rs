@@ -1,24 +1,52 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{Email, Handle, User};
+use super::{Email, Handle, ProfileViewer, User, UserSettings};
+use crate::domain::follow::FollowCounts;
 
+/// A user's profile. Only the user's own view carries their email and
+/// settings; everyone else sees what the user's privacy settings allow.
 #[derive(Deserialize, Serialize)]
 pub struct GetUserResponse {
     id: Uuid,
-    email: Email,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email: Option<Email>,
     handle: Handle,
     name: Option<String>,
     profile_photo: Option<String>,
     bio: Option<String>,
+    #[serde(flatten, default)]
+    follow_counts: Option<FollowCounts>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    settings: Option<UserSettings>,
 }
 
 impl GetUserResponse {
+    /// The view a user gets of their own profile.
+    pub fn own(user: User, settings: UserSettings, follow_counts: FollowCounts) -> Self {
+        let mut response = Self::public(user.clone(), &settings, ProfileViewer::owner());
+        response.email = Some(user.email);
+        response.follow_counts = Some(follow_counts);
+        response.settings = Some(settings);
+        response
+    }
+
+    /// What `viewer` may see of someone's profile. Follow counts are left for
+    /// the caller to add with [`Self::set_follow_counts`], which respects the
+    /// same settings.
+    pub fn public(user: User, settings: &UserSettings, viewer: ProfileViewer) -> Self {
+        let mut response = GetUserResponse::from(user.clone());
+        if settings.bio_visibility().allows(viewer) {
+            response.bio = user.bio;
+        }
+        response
+    }
+
     pub fn id(&self) -> Uuid {
         self.id
     }
 
-    pub fn email(&self) -> Email {
+    pub fn email(&self) -> Option<Email> {
         self.email.clone()
     }
 
@@ -37,17 +65,40 @@ impl GetUserResponse {
     pub fn bio(&self) -> Option<String> {
         self.bio.clone()
     }
+
+    pub fn follow_counts(&self) -> Option<FollowCounts> {
+        self.follow_counts
+    }
+
+    pub fn settings(&self) -> Option<UserSettings> {
+        self.settings.clone()
+    }
+
+    /// Shows the follow counts if `viewer` may see the user's followers.
+    pub fn set_follow_counts(
+        &mut self,
+        follow_counts: FollowCounts,
+        settings: &UserSettings,
+        viewer: ProfileViewer,
+    ) {
+        if settings.followers_visibility().allows(viewer) {
+            self.follow_counts = Some(follow_counts);
+        }
+    }
 }
 
+/// The smallest view of a user, with only what their settings cannot hide.
 impl From<User> for GetUserResponse {
     fn from(user: User) -> Self {
         GetUserResponse {
             id: user.id,
-            email: user.email,
+            email: None,
             handle: user.handle,
             name: user.name,
             profile_photo: user.profile_photo,
-            bio: user.bio,
+            bio: None,
+            follow_counts: None,
+            settings: None,
         }
     }
 }
@@ -1,5 +1,7 @@
 mod api;
 mod credentials;
+mod settings;
+#[allow(clippy::module_inception)]
 mod tests;
 
 use actix_web::HttpResponse;
@@ -9,6 +11,7 @@ pub use credentials::{
     EmailValidationErr, Handle, HandleValidationErr, Login, Password, PasswordValidationErr,
     ALLOWED_HANDLE_CHARS, ALLOWED_PASSWORD_CHARS,
 };
+pub use settings::{DmPrivacy, ProfileViewer, ProfileVisibility, UserSettings};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Who may start direct messages with a user.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DmPrivacy {
+    #[default]
+    Everyone,
+    Friends,
+}
+
+impl DmPrivacy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Everyone => "everyone",
+            Self::Friends => "friends",
+        }
+    }
+}
+
+impl TryFrom<&str> for DmPrivacy {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "everyone" => Ok(Self::Everyone),
+            "friends" => Ok(Self::Friends),
+            other => Err(format!("{} is not a valid DM privacy setting", other)),
+        }
+    }
+}
+
+/// Who may see a part of a user's profile. The user always sees all of it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileVisibility {
+    #[default]
+    Everyone,
+    /// The user's followers. Friends count as followers.
+    Followers,
+    Friends,
+    Nobody,
+}
+
+impl ProfileVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Everyone => "everyone",
+            Self::Followers => "followers",
+            Self::Friends => "friends",
+            Self::Nobody => "nobody",
+        }
+    }
+
+    pub fn allows(&self, viewer: ProfileViewer) -> bool {
+        if viewer.is_self {
+            return true;
+        }
+        match self {
+            Self::Everyone => true,
+            Self::Followers => viewer.is_follower || viewer.is_friend,
+            Self::Friends => viewer.is_friend,
+            Self::Nobody => false,
+        }
+    }
+}
+
+impl TryFrom<&str> for ProfileVisibility {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "everyone" => Ok(Self::Everyone),
+            "followers" => Ok(Self::Followers),
+            "friends" => Ok(Self::Friends),
+            "nobody" => Ok(Self::Nobody),
+            other => Err(format!("{} is not a valid profile visibility", other)),
+        }
+    }
+}
+
+/// How the caller relates to the owner of a profile they are viewing.
+/// Anonymous callers are nothing to anyone.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProfileViewer {
+    pub is_self: bool,
+    pub is_follower: bool,
+    pub is_friend: bool,
+}
+
+impl ProfileViewer {
+    pub fn owner() -> Self {
+        ProfileViewer {
+            is_self: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Account settings a user controls. Users who never changed them have the
+/// defaults.
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct UserSettings {
+    user_id: Uuid,
+    /// Whether new followers need the user's approval.
+    is_private: bool,
+    dm_privacy: DmPrivacy,
+    bio_visibility: ProfileVisibility,
+    servers_visibility: ProfileVisibility,
+    /// Covers both who follows the user and who they follow.
+    followers_visibility: ProfileVisibility,
+    presence_visibility: ProfileVisibility,
+    updated_at: DateTime<Utc>,
+}
+
+impl PartialEq for UserSettings {
+    fn eq(&self, other: &Self) -> bool {
+        self.user_id == other.user_id
+            && self.is_private == other.is_private
+            && self.dm_privacy == other.dm_privacy
+            && self.bio_visibility == other.bio_visibility
+            && self.servers_visibility == other.servers_visibility
+            && self.followers_visibility == other.followers_visibility
+            && self.presence_visibility == other.presence_visibility
+    }
+}
+
+impl UserSettings {
+    pub fn new(user_id: Uuid, updated_at: DateTime<Utc>) -> Self {
+        UserSettings {
+            user_id,
+            is_private: false,
+            dm_privacy: DmPrivacy::default(),
+            bio_visibility: ProfileVisibility::default(),
+            servers_visibility: ProfileVisibility::default(),
+            followers_visibility: ProfileVisibility::default(),
+            presence_visibility: ProfileVisibility::default(),
+            updated_at,
+        }
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.is_private
+    }
+
+    pub fn dm_privacy(&self) -> DmPrivacy {
+        self.dm_privacy
+    }
+
+    pub fn bio_visibility(&self) -> ProfileVisibility {
+        self.bio_visibility
+    }
+
+    pub fn servers_visibility(&self) -> ProfileVisibility {
+        self.servers_visibility
+    }
+
+    pub fn followers_visibility(&self) -> ProfileVisibility {
+        self.followers_visibility
+    }
+
+    pub fn presence_visibility(&self) -> ProfileVisibility {
+        self.presence_visibility
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn set_is_private(&mut self, is_private: bool) {
+        self.is_private = is_private;
+    }
+
+    pub fn set_dm_privacy(&mut self, dm_privacy: DmPrivacy) {
+        self.dm_privacy = dm_privacy;
+    }
+
+    pub fn set_bio_visibility(&mut self, visibility: ProfileVisibility) {
+        self.bio_visibility = visibility;
+    }
+
+    pub fn set_servers_visibility(&mut self, visibility: ProfileVisibility) {
+        self.servers_visibility = visibility;
+    }
+
+    pub fn set_followers_visibility(&mut self, visibility: ProfileVisibility) {
+        self.followers_visibility = visibility;
+    }
+
+    pub fn set_presence_visibility(&mut self, visibility: ProfileVisibility) {
+        self.presence_visibility = visibility;
+    }
+
+    pub fn set_updated_at(&mut self, updated_at: DateTime<Utc>) {
+        self.updated_at = updated_at;
+    }
+}
@@ -146,4 +146,30 @@ mod tests {
     fn valid_handle_parsed_successfully(handle: ValidHandleFixture) -> bool {
         Handle::try_from(handle.0).is_ok()
     }
+
+    #[test]
+    fn profile_visibility_matches_the_viewer() {
+        use crate::domain::user::{ProfileViewer, ProfileVisibility};
+
+        let stranger = ProfileViewer::default();
+        let follower = ProfileViewer {
+            is_follower: true,
+            ..Default::default()
+        };
+        let friend = ProfileViewer {
+            is_friend: true,
+            ..Default::default()
+        };
+        let test_cases = [
+            (ProfileVisibility::Everyone, [true, true, true, true]),
+            (ProfileVisibility::Followers, [false, true, true, true]),
+            (ProfileVisibility::Friends, [false, false, true, true]),
+            (ProfileVisibility::Nobody, [false, false, false, true]),
+        ];
+        for (visibility, expected) in test_cases {
+            let allowed =
+                [stranger, follower, friend, ProfileViewer::owner()].map(|v| visibility.allows(v));
+            assert_eq!(expected, allowed, "{:?} was not applied", visibility);
+        }
+    }
 }
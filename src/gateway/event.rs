@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{
+    channel::Channel,
+    dm::{DirectMessage, DmThread},
+    follow::{Follow, FollowRequest},
+    friend::{FriendRequest, Friendship},
+    message::{Message, PinnedMessage},
+    poll::PollTally,
+    post::Post,
+    presence::{CustomStatus, Presence, PresenceStatus},
+    reaction::{ReactionEmoji, ServerEmoji},
+    server::Server,
+    thread::Thread,
+};
+
+/// What a gateway session is subscribed to. Events are published to exactly
+/// one topic and fanned out to every session subscribed to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Topic {
+    Server(Uuid),
+    Channel(Uuid),
+    DmThread(Uuid),
+    User(Uuid),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "t", content = "d", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GatewayEvent {
+    MessageCreate(Message),
+    MessageUpdate(Message),
+    MessageDelete {
+        id: Uuid,
+        channel_id: Uuid,
+    },
+    MessageAck {
+        channel_id: Uuid,
+        message_id: Uuid,
+    },
+    MessageReactionAdd {
+        channel_id: Uuid,
+        message_id: Uuid,
+        user_id: Uuid,
+        emoji: ReactionEmoji,
+    },
+    MessageReactionRemove {
+        channel_id: Uuid,
+        message_id: Uuid,
+        user_id: Uuid,
+        emoji: ReactionEmoji,
+    },
+    MessagePin(PinnedMessage),
+    MessageUnpin {
+        channel_id: Uuid,
+        message_id: Uuid,
+    },
+    DirectMessageCreate(DirectMessage),
+    DirectMessageUpdate(DirectMessage),
+    DirectMessageDelete {
+        id: Uuid,
+        thread_id: Uuid,
+    },
+    DirectMessageAck {
+        thread_id: Uuid,
+        message_id: Uuid,
+    },
+    DirectMessageReactionAdd {
+        thread_id: Uuid,
+        message_id: Uuid,
+        user_id: Uuid,
+        emoji: ReactionEmoji,
+    },
+    DirectMessageReactionRemove {
+        thread_id: Uuid,
+        message_id: Uuid,
+        user_id: Uuid,
+        emoji: ReactionEmoji,
+    },
+    DmThreadCreate {
+        thread: DmThread,
+        participant_ids: Vec<Uuid>,
+    },
+    DmParticipantAdd {
+        thread_id: Uuid,
+        user_id: Uuid,
+    },
+    DmParticipantRemove {
+        thread_id: Uuid,
+        user_id: Uuid,
+    },
+    /// Sent when the owner leaves a group DM and it passes to the longest
+    /// standing remaining participant.
+    DmOwnerUpdate {
+        thread_id: Uuid,
+        owner_id: Uuid,
+    },
+    ChannelCreate(Channel),
+    ChannelUpdate(Channel),
+    ChannelDelete {
+        id: Uuid,
+        server_id: Uuid,
+    },
+    ThreadCreate(Thread),
+    ThreadUpdate(Thread),
+    ThreadMemberAdd {
+        thread_id: Uuid,
+        user_id: Uuid,
+    },
+    ThreadMemberRemove {
+        thread_id: Uuid,
+        user_id: Uuid,
+    },
+    MemberJoin {
+        server_id: Uuid,
+        user_id: Uuid,
+    },
+    ServerUpdate(Server),
+    ServerDelete {
+        id: Uuid,
+    },
+    EmojiCreate(ServerEmoji),
+    EmojiDelete {
+        id: Uuid,
+        server_id: Uuid,
+    },
+    PostCreate(Post),
+    PostUpdate(Post),
+    PostDelete {
+        id: Uuid,
+        server_id: Uuid,
+    },
+    PollUpdate(PollTally),
+    FollowCreate(Follow),
+    FollowRequestCreate(FollowRequest),
+    FriendRequestCreate(FriendRequest),
+    FriendAdd(Friendship),
+    PresenceUpdate(Presence),
+    TypingStart {
+        channel_id: Uuid,
+        user_id: Uuid,
+    },
+    TypingStop {
+        channel_id: Uuid,
+        user_id: Uuid,
+    },
+    DmTypingStart {
+        thread_id: Uuid,
+        user_id: Uuid,
+    },
+    DmTypingStop {
+        thread_id: Uuid,
+        user_id: Uuid,
+    },
+}
+
+/// Frames sent from the gateway to clients.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ServerFrame {
+    Hello {
+        heartbeat_interval: u64,
+    },
+    HeartbeatAck,
+    Ready {
+        session_id: Uuid,
+        user_id: Uuid,
+    },
+    Resumed {
+        session_id: Uuid,
+        replayed: usize,
+    },
+    InvalidSession,
+    Dispatch {
+        s: u64,
+        #[serde(flatten)]
+        event: Box<GatewayEvent>,
+    },
+}
+
+/// Frames sent from clients to the gateway. A connection must identify or
+/// resume before anything else.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ClientFrame {
+    Identify {
+        token: String,
+    },
+    Resume {
+        token: String,
+        session_id: Uuid,
+        seq: u64,
+    },
+    Heartbeat,
+    PresenceUpdate {
+        status: PresenceStatus,
+        custom_status: Option<CustomStatus>,
+    },
+    TypingStart {
+        channel_id: Uuid,
+    },
+    DmTypingStart {
+        thread_id: Uuid,
+    },
+}
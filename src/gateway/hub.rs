@@ -0,0 +1,642 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Instant,
+};
+
+use actix::{Actor, AsyncContext, Context, Handler, Message, MessageResult, Recipient};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{
+    GatewayEvent, Topic, MAX_BUFFERED_EVENTS, RESUME_WINDOW, TYPING_COOLDOWN, TYPING_TIMEOUT,
+};
+use crate::domain::presence::{CustomStatus, Presence, PresenceAudience, PresenceStatus};
+
+/// An event delivered to a single session, stamped with that session's
+/// sequence number.
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "()")]
+pub struct Dispatch {
+    pub seq: u64,
+    pub event: GatewayEvent,
+}
+
+/// Registers a new session and returns its id. `audience` is who may see
+/// the user's presence.
+#[derive(Message)]
+#[rtype(result = "Uuid")]
+pub struct Connect {
+    pub user_id: Uuid,
+    pub connection_id: Uuid,
+    pub topics: Vec<Topic>,
+    pub audience: PresenceAudience,
+    pub recipient: Recipient<Dispatch>,
+}
+
+/// Reattaches a connection to an existing session. Returns the events the
+/// client missed after `seq`, or `None` if the session cannot be resumed.
+#[derive(Message)]
+#[rtype(result = "Option<Vec<Dispatch>>")]
+pub struct Resume {
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    pub connection_id: Uuid,
+    pub seq: u64,
+    pub recipient: Recipient<Dispatch>,
+}
+
+/// Detaches a connection from its session. The session stays resumable
+/// for `RESUME_WINDOW`.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Disconnect {
+    pub session_id: Uuid,
+    pub connection_id: Uuid,
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Publish {
+    pub topic: Topic,
+    pub event: GatewayEvent,
+}
+
+impl Publish {
+    pub fn new(topic: Topic, event: GatewayEvent) -> Self {
+        Publish { topic, event }
+    }
+}
+
+/// Adds topics to every session of a user.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Subscribe {
+    pub user_id: Uuid,
+    pub topics: Vec<Topic>,
+}
+
+/// Removes topics from every session of a user.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Unsubscribe {
+    pub user_id: Uuid,
+    pub topics: Vec<Topic>,
+}
+
+/// Sets the status a user has picked for themself. Their custom status is
+/// replaced, or cleared when `None`.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct SetPresence {
+    pub user_id: Uuid,
+    pub status: PresenceStatus,
+    pub custom_status: Option<CustomStatus>,
+}
+
+/// Replaces who may see a connected user's presence after their settings
+/// or relationships changed. Users who lost sight of them see them go
+/// offline and users who gained it see their current presence.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct SetPresenceAudience {
+    pub user_id: Uuid,
+    pub audience: PresenceAudience,
+}
+
+/// Returns a user's presence as `viewer_id` sees it.
+#[derive(Message)]
+#[rtype(result = "Presence")]
+pub struct GetPresence {
+    pub user_id: Uuid,
+    pub viewer_id: Uuid,
+}
+
+/// Filters `user_ids` down to the users `viewer_id` currently sees as
+/// online, idle or do not disturb. Invisible users and users hiding their
+/// presence from the viewer are left out.
+#[derive(Message)]
+#[rtype(result = "Vec<Uuid>")]
+pub struct GetOnlineUsers {
+    pub user_ids: Vec<Uuid>,
+    pub viewer_id: Uuid,
+}
+
+/// Marks a user as typing in a channel or DM thread. Returns whether the
+/// indicator was sent; refreshes within `TYPING_COOLDOWN` are dropped. When
+/// `session_id` is set the request came over the gateway, and that session
+/// must already be subscribed to the topic.
+#[derive(Message)]
+#[rtype(result = "bool")]
+pub struct StartTyping {
+    pub user_id: Uuid,
+    pub topic: Topic,
+    pub session_id: Option<Uuid>,
+}
+
+/// What a user has picked, plus how many connections they have open. A user
+/// with no open connections is offline whatever they picked.
+#[derive(Default)]
+struct PresenceState {
+    connections: usize,
+    status: PresenceStatus,
+    custom_status: Option<CustomStatus>,
+}
+
+impl PresenceState {
+    fn presence(&self, user_id: Uuid) -> Presence {
+        if self.connections == 0 {
+            return Presence::offline(user_id);
+        }
+        let custom_status = self
+            .custom_status
+            .clone()
+            .filter(|custom_status| !custom_status.is_expired(Utc::now()));
+        Presence::new(user_id, self.status, custom_status)
+    }
+}
+
+struct SessionState {
+    user_id: Uuid,
+    connection_id: Uuid,
+    recipient: Option<Recipient<Dispatch>>,
+    topics: HashSet<Topic>,
+    seq: u64,
+    buffer: VecDeque<Dispatch>,
+    disconnected_at: Option<Instant>,
+}
+
+/// Fans published events out to the sessions subscribed to their topic.
+/// Every session numbers its events so that a client reconnecting within
+/// `RESUME_WINDOW` can replay what it missed.
+#[derive(Default)]
+pub struct Hub {
+    sessions: HashMap<Uuid, SessionState>,
+    topics: HashMap<Topic, HashSet<Uuid>>,
+    presences: HashMap<Uuid, PresenceState>,
+    audiences: HashMap<Uuid, PresenceAudience>,
+    typing: HashMap<(Uuid, Topic), Instant>,
+}
+
+impl Hub {
+    fn dispatch(&mut self, session_id: Uuid, event: GatewayEvent) {
+        if let Some(session) = self.sessions.get_mut(&session_id) {
+            session.seq += 1;
+            let dispatch = Dispatch {
+                seq: session.seq,
+                event,
+            };
+            if session.buffer.len() == MAX_BUFFERED_EVENTS {
+                session.buffer.pop_front();
+            }
+            session.buffer.push_back(dispatch.clone());
+            if let Some(recipient) = &session.recipient {
+                recipient.do_send(dispatch);
+            }
+        }
+    }
+
+    fn presence(&self, user_id: Uuid) -> Presence {
+        self.presences
+            .get(&user_id)
+            .map(|state| state.presence(user_id))
+            .unwrap_or_else(|| Presence::offline(user_id))
+    }
+
+    /// A user's presence as another user sees it. Users hiding their
+    /// presence from the viewer look offline.
+    fn presence_seen_by(&self, user_id: Uuid, viewer_id: Uuid) -> Presence {
+        let presence = self.presence(user_id);
+        if user_id == viewer_id {
+            presence
+        } else if self.audience_allows(user_id, viewer_id) {
+            presence.as_seen_by_others()
+        } else {
+            Presence::offline(user_id)
+        }
+    }
+
+    fn audience_allows(&self, user_id: Uuid, viewer_id: Uuid) -> bool {
+        self.audiences
+            .get(&user_id)
+            .map(|audience| audience.allows(viewer_id))
+            .unwrap_or(true)
+    }
+
+    /// Sessions of other users that share a server or DM thread with the
+    /// given user's sessions, with the id of the user owning each.
+    fn shared_sessions(&self, user_id: Uuid) -> HashMap<Uuid, Uuid> {
+        let own_sessions = self.user_session_ids(user_id);
+        own_sessions
+            .iter()
+            .filter_map(|session_id| self.sessions.get(session_id))
+            .flat_map(|session| session.topics.iter())
+            .filter(|topic| matches!(topic, Topic::Server(_) | Topic::DmThread(_)))
+            .filter_map(|topic| self.topics.get(topic))
+            .flatten()
+            .filter(|session_id| !own_sessions.contains(session_id))
+            .filter_map(|&session_id| {
+                self.sessions
+                    .get(&session_id)
+                    .map(|session| (session_id, session.user_id))
+            })
+            .collect()
+    }
+
+    /// The shared sessions of users allowed to see the given user's presence.
+    fn presence_audience(&self, user_id: Uuid) -> Vec<Uuid> {
+        self.shared_sessions(user_id)
+            .into_iter()
+            .filter(|&(_, viewer_id)| self.audience_allows(user_id, viewer_id))
+            .map(|(session_id, _)| session_id)
+            .collect()
+    }
+
+    /// Applies a change to a user's presence state and dispatches a
+    /// `PresenceUpdate` to whoever sees a difference. The user's own sessions
+    /// only hear about changes made while they were online.
+    fn update_presence(&mut self, user_id: Uuid, update: impl FnOnce(&mut PresenceState)) {
+        let before = self.presence(user_id);
+        update(self.presences.entry(user_id).or_default());
+        let after = self.presence(user_id);
+
+        let was_online = before.status() != PresenceStatus::Offline;
+        if was_online && after.status() != PresenceStatus::Offline && before != after {
+            for session_id in self.user_session_ids(user_id) {
+                self.dispatch(session_id, GatewayEvent::PresenceUpdate(after.clone()));
+            }
+        }
+        let seen_after = after.as_seen_by_others();
+        if before.as_seen_by_others() != seen_after {
+            for session_id in self.presence_audience(user_id) {
+                self.dispatch(session_id, GatewayEvent::PresenceUpdate(seen_after.clone()));
+            }
+        }
+    }
+
+    fn typing_event(topic: Topic, user_id: Uuid, started: bool) -> Option<GatewayEvent> {
+        match (topic, started) {
+            (Topic::Channel(channel_id), true) => Some(GatewayEvent::TypingStart {
+                channel_id,
+                user_id,
+            }),
+            (Topic::Channel(channel_id), false) => Some(GatewayEvent::TypingStop {
+                channel_id,
+                user_id,
+            }),
+            (Topic::DmThread(thread_id), true) => {
+                Some(GatewayEvent::DmTypingStart { thread_id, user_id })
+            }
+            (Topic::DmThread(thread_id), false) => {
+                Some(GatewayEvent::DmTypingStop { thread_id, user_id })
+            }
+            _ => None,
+        }
+    }
+
+    /// Dispatches a typing event to the topic's subscribers, leaving out the
+    /// typing user's own sessions.
+    fn publish_typing(&mut self, user_id: Uuid, topic: Topic, started: bool) {
+        let event = match Self::typing_event(topic, user_id, started) {
+            Some(event) => event,
+            None => return,
+        };
+        let subscribers: Vec<Uuid> = match self.topics.get(&topic) {
+            Some(subscribers) => subscribers
+                .iter()
+                .copied()
+                .filter(|session_id| {
+                    self.sessions
+                        .get(session_id)
+                        .map(|session| session.user_id != user_id)
+                        .unwrap_or(false)
+                })
+                .collect(),
+            None => return,
+        };
+        for session_id in subscribers {
+            self.dispatch(session_id, event.clone());
+        }
+    }
+
+    fn prune_expired_typing(&mut self) {
+        let expired: Vec<(Uuid, Topic)> = self
+            .typing
+            .iter()
+            .filter(|(_, started_at)| started_at.elapsed() >= TYPING_TIMEOUT)
+            .map(|(&key, _)| key)
+            .collect();
+        for (user_id, topic) in expired {
+            self.typing.remove(&(user_id, topic));
+            self.publish_typing(user_id, topic, false);
+        }
+    }
+
+    fn prune_expired_custom_statuses(&mut self) {
+        let now = Utc::now();
+        let expired: Vec<Uuid> = self
+            .presences
+            .iter()
+            .filter(|(_, state)| {
+                state
+                    .custom_status
+                    .as_ref()
+                    .map(|custom_status| custom_status.is_expired(now))
+                    .unwrap_or(false)
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        for user_id in expired {
+            self.update_presence(user_id, |state| state.custom_status = None);
+        }
+    }
+
+    fn subscribe(&mut self, session_id: Uuid, topics: &[Topic]) {
+        if let Some(session) = self.sessions.get_mut(&session_id) {
+            for &topic in topics {
+                session.topics.insert(topic);
+                self.topics.entry(topic).or_default().insert(session_id);
+            }
+        }
+    }
+
+    fn unsubscribe(&mut self, session_id: Uuid, topics: &[Topic]) {
+        if let Some(session) = self.sessions.get_mut(&session_id) {
+            for topic in topics {
+                session.topics.remove(topic);
+                if let Some(subscribers) = self.topics.get_mut(topic) {
+                    subscribers.remove(&session_id);
+                    if subscribers.is_empty() {
+                        self.topics.remove(topic);
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove_session(&mut self, session_id: Uuid) {
+        if let Some(session) = self.sessions.get(&session_id) {
+            let user_id = session.user_id;
+            let topics: Vec<Topic> = session.topics.iter().copied().collect();
+            self.unsubscribe(session_id, &topics);
+            self.sessions.remove(&session_id);
+            if self.user_session_ids(user_id).is_empty() {
+                self.audiences.remove(&user_id);
+            }
+        }
+    }
+
+    fn user_session_ids(&self, user_id: Uuid) -> Vec<Uuid> {
+        self.sessions
+            .iter()
+            .filter(|(_, session)| session.user_id == user_id)
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    fn prune_expired(&mut self) {
+        let expired: Vec<Uuid> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| {
+                session
+                    .disconnected_at
+                    .map(|at| at.elapsed() > RESUME_WINDOW)
+                    .unwrap_or(false)
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        for session_id in expired {
+            tracing::info!("gateway session {} expired", session_id);
+            self.remove_session(session_id);
+        }
+        self.prune_expired_custom_statuses();
+    }
+}
+
+impl Actor for Hub {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(RESUME_WINDOW / 4, |hub, _| hub.prune_expired());
+        ctx.run_interval(TYPING_TIMEOUT / 8, |hub, _| hub.prune_expired_typing());
+    }
+}
+
+impl Handler<Connect> for Hub {
+    type Result = MessageResult<Connect>;
+
+    fn handle(&mut self, msg: Connect, _: &mut Self::Context) -> Self::Result {
+        let session_id = Uuid::new_v4();
+        self.sessions.insert(
+            session_id,
+            SessionState {
+                user_id: msg.user_id,
+                connection_id: msg.connection_id,
+                recipient: Some(msg.recipient),
+                topics: HashSet::new(),
+                seq: 0,
+                buffer: VecDeque::new(),
+                disconnected_at: None,
+            },
+        );
+        self.subscribe(session_id, &msg.topics);
+        self.audiences.insert(msg.user_id, msg.audience);
+        self.update_presence(msg.user_id, |state| state.connections += 1);
+        tracing::info!(
+            "gateway session {} connected for user {}",
+            session_id,
+            msg.user_id
+        );
+        MessageResult(session_id)
+    }
+}
+
+impl Handler<Resume> for Hub {
+    type Result = MessageResult<Resume>;
+
+    fn handle(&mut self, msg: Resume, _: &mut Self::Context) -> Self::Result {
+        let session = match self.sessions.get_mut(&msg.session_id) {
+            Some(session) if session.user_id == msg.user_id => session,
+            _ => return MessageResult(None),
+        };
+        if msg.seq > session.seq {
+            return MessageResult(None);
+        }
+        // Events the client missed have already been dropped from the buffer.
+        let oldest = session
+            .buffer
+            .front()
+            .map(|d| d.seq)
+            .unwrap_or(session.seq + 1);
+        if msg.seq + 1 < oldest {
+            return MessageResult(None);
+        }
+
+        let was_disconnected = session.disconnected_at.is_some();
+        session.connection_id = msg.connection_id;
+        session.recipient = Some(msg.recipient);
+        session.disconnected_at = None;
+        let missed = session
+            .buffer
+            .iter()
+            .filter(|d| d.seq > msg.seq)
+            .cloned()
+            .collect();
+        if was_disconnected {
+            self.update_presence(msg.user_id, |state| state.connections += 1);
+        }
+        MessageResult(Some(missed))
+    }
+}
+
+impl Handler<Disconnect> for Hub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Disconnect, _: &mut Self::Context) -> Self::Result {
+        if let Some(session) = self.sessions.get_mut(&msg.session_id) {
+            // A resumed session may already belong to a newer connection.
+            if session.connection_id == msg.connection_id && session.disconnected_at.is_none() {
+                session.recipient = None;
+                session.disconnected_at = Some(Instant::now());
+                let user_id = session.user_id;
+                self.update_presence(user_id, |state| {
+                    state.connections = state.connections.saturating_sub(1)
+                });
+            }
+        }
+    }
+}
+
+impl Handler<Publish> for Hub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Publish, _: &mut Self::Context) -> Self::Result {
+        // Sending a message ends its author's typing indicator; clients
+        // clear it themselves when the message arrives.
+        if let GatewayEvent::MessageCreate(message) = &msg.event {
+            self.typing.remove(&(message.author_id(), msg.topic));
+        } else if let GatewayEvent::DirectMessageCreate(message) = &msg.event {
+            self.typing.remove(&(message.author_id(), msg.topic));
+        }
+
+        let subscribers: Vec<Uuid> = match self.topics.get(&msg.topic) {
+            Some(subscribers) => subscribers.iter().copied().collect(),
+            None => return,
+        };
+        for session_id in subscribers {
+            self.dispatch(session_id, msg.event.clone());
+        }
+    }
+}
+
+impl Handler<Subscribe> for Hub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Subscribe, _: &mut Self::Context) -> Self::Result {
+        for session_id in self.user_session_ids(msg.user_id) {
+            self.subscribe(session_id, &msg.topics);
+        }
+    }
+}
+
+impl Handler<Unsubscribe> for Hub {
+    type Result = ();
+
+    fn handle(&mut self, msg: Unsubscribe, _: &mut Self::Context) -> Self::Result {
+        for session_id in self.user_session_ids(msg.user_id) {
+            self.unsubscribe(session_id, &msg.topics);
+        }
+    }
+}
+
+impl Handler<SetPresence> for Hub {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetPresence, _: &mut Self::Context) -> Self::Result {
+        self.update_presence(msg.user_id, |state| {
+            state.status = msg.status;
+            state.custom_status = msg.custom_status;
+        });
+    }
+}
+
+impl Handler<SetPresenceAudience> for Hub {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetPresenceAudience, _: &mut Self::Context) -> Self::Result {
+        let before = match self.audiences.get(&msg.user_id) {
+            Some(audience) => audience.clone(),
+            None => return,
+        };
+        self.audiences.insert(msg.user_id, msg.audience.clone());
+
+        let seen = self.presence(msg.user_id).as_seen_by_others();
+        if seen.status() == PresenceStatus::Offline {
+            return;
+        }
+        for (session_id, viewer_id) in self.shared_sessions(msg.user_id) {
+            match (before.allows(viewer_id), msg.audience.allows(viewer_id)) {
+                (false, true) => {
+                    self.dispatch(session_id, GatewayEvent::PresenceUpdate(seen.clone()))
+                }
+                (true, false) => self.dispatch(
+                    session_id,
+                    GatewayEvent::PresenceUpdate(Presence::offline(msg.user_id)),
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Handler<GetPresence> for Hub {
+    type Result = MessageResult<GetPresence>;
+
+    fn handle(&mut self, msg: GetPresence, _: &mut Self::Context) -> Self::Result {
+        MessageResult(self.presence_seen_by(msg.user_id, msg.viewer_id))
+    }
+}
+
+impl Handler<GetOnlineUsers> for Hub {
+    type Result = MessageResult<GetOnlineUsers>;
+
+    fn handle(&mut self, msg: GetOnlineUsers, _: &mut Self::Context) -> Self::Result {
+        MessageResult(
+            msg.user_ids
+                .into_iter()
+                .filter(|&user_id| {
+                    self.presence_seen_by(user_id, msg.viewer_id).status()
+                        != PresenceStatus::Offline
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Handler<StartTyping> for Hub {
+    type Result = bool;
+
+    fn handle(&mut self, msg: StartTyping, _: &mut Self::Context) -> Self::Result {
+        if let Some(session_id) = msg.session_id {
+            let subscribed = self
+                .sessions
+                .get(&session_id)
+                .map(|session| {
+                    session.user_id == msg.user_id && session.topics.contains(&msg.topic)
+                })
+                .unwrap_or(false);
+            if !subscribed {
+                return false;
+            }
+        }
+        let key = (msg.user_id, msg.topic);
+        if let Some(started_at) = self.typing.get(&key) {
+            if started_at.elapsed() < TYPING_COOLDOWN {
+                return false;
+            }
+        }
+        self.typing.insert(key, Instant::now());
+        self.publish_typing(msg.user_id, msg.topic, true);
+        true
+    }
+}
@@ -0,0 +1,26 @@
+mod event;
+mod hub;
+mod session;
+mod subscription;
+#[allow(clippy::module_inception)]
+mod tests;
+
+pub use event::*;
+pub use hub::*;
+pub use session::*;
+pub use subscription::*;
+
+use std::time::Duration;
+
+/// How often the gateway pings each connection.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// How long a connection may stay silent before it is dropped.
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(45);
+/// How long a dropped session can still be resumed.
+pub const RESUME_WINDOW: Duration = Duration::from_secs(120);
+/// How many dispatched events each session keeps for replay on resume.
+pub const MAX_BUFFERED_EVENTS: usize = 1000;
+/// How long a typing indicator lasts unless it is refreshed.
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(8);
+/// How soon a user may refresh their typing indicator in the same place.
+pub const TYPING_COOLDOWN: Duration = Duration::from_secs(3);
@@ -0,0 +1,325 @@
+use std::time::Instant;
+
+use actix::{
+    fut::wrap_future, Actor, ActorContext, ActorFutureExt, Addr, AsyncContext, Handler,
+    StreamHandler,
+};
+use actix_web_actors::ws::{self, CloseCode, CloseReason};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{
+    resolve_topics, ClientFrame, Connect, Disconnect, Dispatch, Hub, Resume, ServerFrame,
+    SetPresence, StartTyping, Topic, CLIENT_TIMEOUT, HEARTBEAT_INTERVAL,
+};
+use crate::{
+    domain::presence::{CustomStatus, PresenceStatus},
+    storage::get_presence_audience,
+    utils::jwt::get_claims_from_token,
+};
+
+/// One WebSocket connection to the gateway. A connection starts anonymous
+/// and attaches to a hub session once it identifies or resumes.
+pub struct Session {
+    connection_id: Uuid,
+    session_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    last_seen: Instant,
+    hub: Addr<Hub>,
+    db_pool: PgPool,
+}
+
+impl Session {
+    pub fn new(hub: Addr<Hub>, db_pool: PgPool) -> Self {
+        Session {
+            connection_id: Uuid::new_v4(),
+            session_id: None,
+            user_id: None,
+            last_seen: Instant::now(),
+            hub,
+            db_pool,
+        }
+    }
+
+    fn send(&self, ctx: &mut ws::WebsocketContext<Self>, frame: &ServerFrame) {
+        match serde_json::to_string(frame) {
+            Ok(text) => ctx.text(text),
+            Err(e) => tracing::error!("failed to serialize gateway frame: {:?}", e),
+        }
+    }
+
+    fn close(&self, ctx: &mut ws::WebsocketContext<Self>, code: CloseCode, reason: &str) {
+        tracing::error!("closing gateway connection: {}", reason);
+        ctx.close(Some(CloseReason {
+            code,
+            description: Some(reason.to_string()),
+        }));
+        ctx.stop();
+    }
+
+    fn authenticate(token: String) -> Option<Uuid> {
+        match get_claims_from_token(token) {
+            Ok(claims) => Uuid::parse_str(&claims.sub).ok(),
+            Err(e) => {
+                tracing::error!("failed to get claims from gateway token: {:?}", e);
+                None
+            }
+        }
+    }
+
+    fn heartbeat(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.run_interval(HEARTBEAT_INTERVAL, |session, ctx| {
+            if Instant::now().duration_since(session.last_seen) > CLIENT_TIMEOUT {
+                tracing::info!("gateway connection {} timed out", session.connection_id);
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+
+    fn identify(&mut self, token: String, ctx: &mut ws::WebsocketContext<Self>) {
+        let user_id = match Self::authenticate(token) {
+            Some(user_id) => user_id,
+            None => return self.close(ctx, CloseCode::Policy, "authentication failed"),
+        };
+
+        let db_pool = self.db_pool.clone();
+        let hub = self.hub.clone();
+        let connection_id = self.connection_id;
+        let recipient = ctx.address().recipient();
+        let connect = async move {
+            let topics = resolve_topics(&db_pool, user_id)
+                .await
+                .map_err(|e| format!("failed to resolve topics: {:?}", e))?;
+            let audience = get_presence_audience(&db_pool, user_id)
+                .await
+                .map_err(|e| format!("failed to resolve presence audience: {:?}", e))?;
+            hub.send(Connect {
+                user_id,
+                connection_id,
+                topics,
+                audience,
+                recipient,
+            })
+            .await
+            .map_err(|e| format!("failed to reach gateway hub: {:?}", e))
+        };
+
+        // `wait` holds back dispatches until READY has been sent.
+        ctx.wait(
+            wrap_future::<_, Self>(connect).map(move |res, session, ctx| match res {
+                Ok(session_id) => {
+                    session.session_id = Some(session_id);
+                    session.user_id = Some(user_id);
+                    session.send(
+                        ctx,
+                        &ServerFrame::Ready {
+                            session_id,
+                            user_id,
+                        },
+                    );
+                }
+                Err(e) => {
+                    tracing::error!("failed to identify user {}: {}", user_id, e);
+                    session.close(ctx, CloseCode::Error, "failed to identify");
+                }
+            }),
+        );
+    }
+
+    fn resume(
+        &mut self,
+        token: String,
+        session_id: Uuid,
+        seq: u64,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let user_id = match Self::authenticate(token) {
+            Some(user_id) => user_id,
+            None => return self.close(ctx, CloseCode::Policy, "authentication failed"),
+        };
+
+        let resume = self.hub.send(Resume {
+            user_id,
+            session_id,
+            connection_id: self.connection_id,
+            seq,
+            recipient: ctx.address().recipient(),
+        });
+
+        ctx.wait(
+            wrap_future::<_, Self>(resume).map(move |res, session, ctx| match res {
+                Ok(Some(missed)) => {
+                    session.session_id = Some(session_id);
+                    session.user_id = Some(user_id);
+                    let replayed = missed.len();
+                    for dispatch in missed {
+                        session.send(
+                            ctx,
+                            &ServerFrame::Dispatch {
+                                s: dispatch.seq,
+                                event: Box::new(dispatch.event),
+                            },
+                        );
+                    }
+                    session.send(
+                        ctx,
+                        &ServerFrame::Resumed {
+                            session_id,
+                            replayed,
+                        },
+                    );
+                }
+                Ok(None) => session.send(ctx, &ServerFrame::InvalidSession),
+                Err(e) => {
+                    tracing::error!("failed to resume session {}: {:?}", session_id, e);
+                    session.close(ctx, CloseCode::Error, "failed to resume");
+                }
+            }),
+        );
+    }
+
+    fn update_presence(
+        &mut self,
+        status: PresenceStatus,
+        custom_status: Option<CustomStatus>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        let user_id = match self.user_id {
+            Some(user_id) => user_id,
+            None => return self.close(ctx, CloseCode::Policy, "not identified"),
+        };
+        if !status.is_settable() {
+            return self.close(ctx, CloseCode::Invalid, "status cannot be set");
+        }
+        if let Some(custom_status) = &custom_status {
+            if let Err(e) = custom_status.validate(Utc::now()) {
+                tracing::error!("invalid custom status: {:?}", e);
+                return self.close(ctx, CloseCode::Invalid, "invalid custom status");
+            }
+        }
+        self.hub.do_send(SetPresence {
+            user_id,
+            status,
+            custom_status,
+        });
+    }
+
+    /// Typing over the gateway only needs the session to be subscribed to the
+    /// channel or thread; the hub drops anything else.
+    fn start_typing(&mut self, topic: Topic, ctx: &mut ws::WebsocketContext<Self>) {
+        let (session_id, user_id) = match (self.session_id, self.user_id) {
+            (Some(session_id), Some(user_id)) => (session_id, user_id),
+            _ => return self.close(ctx, CloseCode::Policy, "not identified"),
+        };
+        self.hub.do_send(StartTyping {
+            user_id,
+            topic,
+            session_id: Some(session_id),
+        });
+    }
+
+    fn handle_frame(&mut self, text: &str, ctx: &mut ws::WebsocketContext<Self>) {
+        let frame = match serde_json::from_str::<ClientFrame>(text) {
+            Ok(frame) => frame,
+            Err(e) => {
+                tracing::error!("failed to parse gateway frame: {:?}", e);
+                return self.close(ctx, CloseCode::Invalid, "invalid frame");
+            }
+        };
+
+        match frame {
+            ClientFrame::Heartbeat => self.send(ctx, &ServerFrame::HeartbeatAck),
+            ClientFrame::Identify { .. } | ClientFrame::Resume { .. }
+                if self.session_id.is_some() =>
+            {
+                self.close(ctx, CloseCode::Policy, "already identified")
+            }
+            ClientFrame::Identify { token } => self.identify(token, ctx),
+            ClientFrame::Resume {
+                token,
+                session_id,
+                seq,
+            } => self.resume(token, session_id, seq, ctx),
+            ClientFrame::PresenceUpdate {
+                status,
+                custom_status,
+            } => self.update_presence(status, custom_status, ctx),
+            ClientFrame::TypingStart { channel_id } => {
+                self.start_typing(Topic::Channel(channel_id), ctx)
+            }
+            ClientFrame::DmTypingStart { thread_id } => {
+                self.start_typing(Topic::DmThread(thread_id), ctx)
+            }
+        }
+    }
+}
+
+impl Actor for Session {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.heartbeat(ctx);
+        self.send(
+            ctx,
+            &ServerFrame::Hello {
+                heartbeat_interval: HEARTBEAT_INTERVAL.as_millis() as u64,
+            },
+        );
+    }
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        if let Some(session_id) = self.session_id {
+            self.hub.do_send(Disconnect {
+                session_id,
+                connection_id: self.connection_id,
+            });
+        }
+    }
+}
+
+impl Handler<Dispatch> for Session {
+    type Result = ();
+
+    fn handle(&mut self, msg: Dispatch, ctx: &mut Self::Context) -> Self::Result {
+        self.send(
+            ctx,
+            &ServerFrame::Dispatch {
+                s: msg.seq,
+                event: Box::new(msg.event),
+            },
+        );
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Session {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(e) => {
+                tracing::error!("gateway protocol error: {:?}", e);
+                ctx.stop();
+                return;
+            }
+        };
+        self.last_seen = Instant::now();
+
+        match msg {
+            ws::Message::Ping(bytes) => ctx.pong(&bytes),
+            ws::Message::Pong(_) | ws::Message::Nop => {}
+            ws::Message::Text(text) => self.handle_frame(&text, ctx),
+            ws::Message::Binary(_) => self.close(
+                ctx,
+                CloseCode::Unsupported,
+                "binary frames are not supported",
+            ),
+            ws::Message::Close(reason) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            ws::Message::Continuation(_) => ctx.stop(),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use actix::Addr;
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+use super::{Hub, Subscribe, Topic, Unsubscribe};
+use crate::{
+    domain::{channel::Channel, permission::Permissions, server::Server},
+    storage::{
+        get_all_servers_by_member_id, get_channel_permissions, get_dm_thread_ids_by_user_id,
+        get_joined_thread_ids, get_server_member_ids, get_viewable_channels,
+    },
+};
+
+/// Everything a user should hear about when they identify: their own user
+/// topic, every server they belong to, the channels they can view in them
+/// and their DM threads.
+#[tracing::instrument(name = "Resolving gateway topics", skip(user_id, db_pool), fields(user_id = %user_id))]
+pub async fn resolve_topics(db_pool: &PgPool, user_id: Uuid) -> Result<Vec<Topic>, Error> {
+    let mut topics = vec![Topic::User(user_id)];
+    for server in get_all_servers_by_member_id(db_pool, user_id).await? {
+        topics.extend(server_topics(db_pool, &server, user_id).await?);
+    }
+    topics.extend(
+        get_dm_thread_ids_by_user_id(db_pool, user_id)
+            .await?
+            .into_iter()
+            .map(Topic::DmThread),
+    );
+    Ok(topics)
+}
+
+/// The server topic plus the topics of every channel in it the user can view
+/// and of the threads they have joined in those channels.
+pub async fn server_topics(
+    db_pool: &PgPool,
+    server: &Server,
+    user_id: Uuid,
+) -> Result<Vec<Topic>, Error> {
+    let mut topics = vec![Topic::Server(server.id())];
+    let channel_ids: Vec<Uuid> = get_viewable_channels(db_pool, server, user_id)
+        .await?
+        .iter()
+        .map(|c| c.id())
+        .collect();
+    topics.extend(channel_ids.iter().copied().map(Topic::Channel));
+    topics.extend(
+        get_joined_thread_ids(db_pool, server.id(), user_id)
+            .await?
+            .into_iter()
+            .filter(|(_, parent_id)| channel_ids.contains(parent_id))
+            .map(|(thread_id, _)| Topic::Channel(thread_id)),
+    );
+    Ok(topics)
+}
+
+/// Re-evaluates who may view a channel and moves each member's sessions on
+/// or off its topic, along with the topics of the threads they have joined
+/// in it. Call after anything that changes channel visibility.
+#[tracing::instrument(
+    name = "Refreshing channel subscriptions",
+    skip(server, channel, hub, db_pool),
+    fields(channel_id = %channel.id())
+)]
+pub async fn refresh_channel_subscriptions(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    server: &Server,
+    channel: &Channel,
+) -> Result<(), Error> {
+    for user_id in get_server_member_ids(db_pool, server.id()).await? {
+        let permissions = get_channel_permissions(db_pool, server, channel, user_id).await?;
+        let mut topics = vec![Topic::Channel(channel.id())];
+        topics.extend(
+            get_joined_thread_ids(db_pool, server.id(), user_id)
+                .await?
+                .into_iter()
+                .filter(|(_, parent_id)| *parent_id == channel.id())
+                .map(|(thread_id, _)| Topic::Channel(thread_id)),
+        );
+        if permissions.contains(Permissions::VIEW_CHANNEL) {
+            hub.do_send(Subscribe { user_id, topics });
+        } else {
+            hub.do_send(Unsubscribe { user_id, topics });
+        }
+    }
+    Ok(())
+}
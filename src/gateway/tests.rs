@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use crate::gateway::{ClientFrame, GatewayEvent, ServerFrame};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn dispatch_frames_carry_op_sequence_type_and_data() {
+        let (id, server_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let frame = ServerFrame::Dispatch {
+            s: 7,
+            event: Box::new(GatewayEvent::ChannelDelete { id, server_id }),
+        };
+        let value = serde_json::to_value(&frame).expect("Failed to serialize frame");
+        assert_eq!(
+            json!({
+                "op": "dispatch",
+                "s": 7,
+                "t": "CHANNEL_DELETE",
+                "d": {"id": id, "server_id": server_id},
+            }),
+            value,
+            "The dispatch frame did not have the expected shape"
+        );
+        let decoded: ServerFrame =
+            serde_json::from_value(value).expect("Failed to deserialize frame");
+        assert_eq!(frame, decoded, "The frame did not round trip");
+    }
+
+    #[test]
+    fn client_frames_are_parsed_by_op() {
+        let session_id = Uuid::new_v4();
+        let test_cases = [
+            (json!({"op": "heartbeat"}), ClientFrame::Heartbeat),
+            (
+                json!({"op": "identify", "token": "abc"}),
+                ClientFrame::Identify {
+                    token: String::from("abc"),
+                },
+            ),
+            (
+                json!({"op": "resume", "token": "abc", "session_id": session_id, "seq": 3}),
+                ClientFrame::Resume {
+                    token: String::from("abc"),
+                    session_id,
+                    seq: 3,
+                },
+            ),
+            (
+                json!({"op": "typing_start", "channel_id": session_id}),
+                ClientFrame::TypingStart {
+                    channel_id: session_id,
+                },
+            ),
+        ];
+
+        for (value, expected) in test_cases {
+            assert_eq!(
+                expected,
+                serde_json::from_value::<ClientFrame>(value.clone())
+                    .unwrap_or_else(|_| panic!("Failed to parse {}", value)),
+                "The client frame was not parsed correctly"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_client_ops_are_rejected() {
+        assert!(serde_json::from_value::<ClientFrame>(json!({"op": "shutdown"})).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        channel::{Channel, ChannelKind},
+        permission::Permissions,
+        server::Server,
+    },
+    storage::{get_channel_by_id, get_channel_permissions, get_server_by_id},
+};
+
+/// Loads a channel that has not been soft deleted, along with its server, and
+/// checks that the user holds the `required` permissions in it. Users who
+/// cannot view the channel get a 404 so that hidden channels are not leaked.
+pub async fn authorize_channel(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+    user_id: Uuid,
+    required: Permissions,
+) -> Result<(Server, Channel, Permissions), HttpResponse> {
+    let not_found = || {
+        let err = format!("channel {} not found", channel_id);
+        tracing::error!(err);
+        HttpResponse::NotFound().body(err)
+    };
+
+    let channel = match get_channel_by_id(db_pool, channel_id).await {
+        Ok(channel) if channel.deleted_at().is_none() => channel,
+        Ok(_) | Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+        Err(e) => {
+            tracing::error!("failed to get channel {}: {:?}", channel_id, e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    // Threads go away with the channel they were started in.
+    if channel.kind() == ChannelKind::Thread {
+        let parent_id = channel.overwrites_channel_id();
+        match get_channel_by_id(db_pool, parent_id).await {
+            Ok(parent) if parent.deleted_at().is_none() => {}
+            Ok(_) | Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+            Err(e) => {
+                tracing::error!("failed to get channel {}: {:?}", parent_id, e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        }
+    }
+
+    let server = match get_server_by_id(db_pool, channel.server_id()).await {
+        Ok(server) if server.deleted_at().is_none() => server,
+        Ok(_) | Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+        Err(e) => {
+            tracing::error!("failed to get server {}: {:?}", channel.server_id(), e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    let permissions = match get_channel_permissions(db_pool, &server, &channel, user_id).await {
+        Ok(permissions) => permissions,
+        Err(e) => {
+            tracing::error!(
+                "failed to resolve permissions for user {} in channel {}: {:?}",
+                user_id,
+                channel_id,
+                e
+            );
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    if !permissions.contains(Permissions::VIEW_CHANNEL) {
+        return Err(not_found());
+    }
+    if !permissions.contains(required) {
+        let err = format!(
+            "user {} is missing permissions in channel {}",
+            user_id, channel_id
+        );
+        tracing::error!(err);
+        return Err(HttpResponse::Forbidden().body(err));
+    }
+
+    Ok((server, channel, permissions))
+}
+
+/// Same as `authorize_channel`, but also requires the channel to belong to
+/// `server_id` for routes nested under `/servers/{server_id}`.
+pub async fn authorize_server_channel(
+    db_pool: &PgPool,
+    server_id: Uuid,
+    channel_id: Uuid,
+    user_id: Uuid,
+    required: Permissions,
+) -> Result<(Server, Channel, Permissions), HttpResponse> {
+    let (server, channel, permissions) =
+        authorize_channel(db_pool, channel_id, user_id, required).await?;
+    if server.id() != server_id {
+        let err = format!("channel {} not found", channel_id);
+        tracing::error!(err);
+        return Err(HttpResponse::NotFound().body(err));
+    }
+    Ok((server, channel, permissions))
+}
@@ -0,0 +1,129 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        channel::{Channel, ChannelKind},
+        permission::Permissions,
+    },
+    gateway::{refresh_channel_subscriptions, GatewayEvent, Hub, Publish, Topic},
+    handlers::{middleware::UserID, server::authorize_server},
+    storage::{get_channel_by_id, get_next_channel_position, upsert_channel},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateChannelRequestBody {
+    pub kind: ChannelKind,
+    pub name: String,
+    pub topic: Option<String>,
+    pub parent_id: Option<Uuid>,
+    pub position: Option<i32>,
+}
+
+#[tracing::instrument(
+    name = "Creating new channel",
+    skip(server_id, body, user_id, db_pool, hub),
+    fields(
+        server_id = %server_id,
+        channel_name = %body.name,
+        channel_kind = %body.kind.as_str(),
+    )
+)]
+pub async fn create(
+    server_id: Path<Uuid>,
+    body: Json<CreateChannelRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+
+    let server =
+        match authorize_server(&db_pool, server_id, user_id, Permissions::MANAGE_CHANNELS).await {
+            Ok((server, _)) => server,
+            Err(e) => return e,
+        };
+
+    if let Err(e) = Channel::validate_name(&body.name)
+        .and_then(|_| Channel::validate_topic(body.topic.as_deref()))
+    {
+        tracing::error!("400 - invalid channel: {:?}", e);
+        return e.handle_http();
+    }
+
+    let parent = match body.parent_id {
+        Some(parent_id) => match get_channel_by_id(&db_pool, parent_id).await {
+            Ok(parent) if parent.deleted_at().is_none() => Some(parent),
+            Ok(_) | Err(sqlx::Error::RowNotFound) => {
+                let err = format!("parent channel {} not found", parent_id);
+                tracing::error!(err);
+                return HttpResponse::BadRequest().body(err);
+            }
+            Err(e) => {
+                tracing::error!("500 - Failed to get parent channel {}: {:?}", parent_id, e);
+                return HttpResponse::InternalServerError().finish();
+            }
+        },
+        None => None,
+    };
+    if let Err(e) = Channel::validate_parent(server_id, body.kind, parent.as_ref()) {
+        tracing::error!("400 - invalid channel parent: {:?}", e);
+        return e.handle_http();
+    }
+
+    let position = match body.position {
+        Some(position) => position,
+        None => match get_next_channel_position(&db_pool, server_id).await {
+            Ok(position) => position,
+            Err(e) => {
+                tracing::error!("500 - Failed to get next channel position: {:?}", e);
+                return HttpResponse::InternalServerError().finish();
+            }
+        },
+    };
+
+    let now = Utc::now();
+    let channel = Channel::new(
+        Uuid::new_v4(),
+        server_id,
+        body.parent_id,
+        body.kind,
+        body.name.trim().to_string(),
+        body.topic,
+        position,
+        now,
+        now,
+        None,
+    );
+
+    match upsert_channel(&db_pool, &channel).await {
+        Ok(_) => {
+            tracing::info!("Channel {} successfully inserted to database", channel.id());
+            if let Err(e) = refresh_channel_subscriptions(&db_pool, &hub, &server, &channel).await {
+                tracing::error!(
+                    "failed to subscribe members to channel {}: {:?}",
+                    channel.id(),
+                    e
+                );
+            }
+            hub.do_send(Publish::new(
+                Topic::Channel(channel.id()),
+                GatewayEvent::ChannelCreate(channel.clone()),
+            ));
+            HttpResponse::Ok().json(channel)
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
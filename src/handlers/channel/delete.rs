@@ -0,0 +1,64 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{channel::authorize_server_channel, middleware::UserID},
+    storage::soft_delete_channel,
+};
+
+#[tracing::instrument(
+    name = "Soft Deleting Channel",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        server_id = %path.0,
+        channel_id = %path.1,
+    )
+)]
+pub async fn soft_delete(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (server_id, channel_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_server_channel(
+        &db_pool,
+        server_id,
+        channel_id,
+        user_id,
+        Permissions::MANAGE_CHANNELS,
+    )
+    .await
+    {
+        return e;
+    }
+
+    match soft_delete_channel(&db_pool, channel_id, Utc::now()).await {
+        Ok(_) => {
+            tracing::info!("channel {} successfully soft deleted", channel_id);
+            hub.do_send(Publish::new(
+                Topic::Channel(channel_id),
+                GatewayEvent::ChannelDelete {
+                    id: channel_id,
+                    server_id,
+                },
+            ));
+            HttpResponse::Ok().finish()
+        }
+        Err(e) => {
+            let err = format!("failed to soft delete channel {}: {}", channel_id, e);
+            tracing::error!(err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    handlers::{channel::authorize_server_channel, middleware::UserID, server::authorize_server},
+    storage::get_viewable_channels,
+};
+
+#[tracing::instrument(
+    name = "Getting channel by ID",
+    skip(path, user_id, db_pool),
+    fields(
+        server_id = %path.0,
+        channel_id = %path.1,
+    )
+)]
+pub async fn get_by_id(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (server_id, channel_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match authorize_server_channel(
+        &db_pool,
+        server_id,
+        channel_id,
+        user_id,
+        Permissions::VIEW_CHANNEL,
+    )
+    .await
+    {
+        Ok((_, channel, _)) => HttpResponse::Ok().json(channel),
+        Err(e) => e,
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting channels by server ID",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn get_many_by_server(
+    server_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let server = match authorize_server(&db_pool, server_id, user_id, Permissions::empty()).await {
+        Ok((server, _)) => server,
+        Err(e) => return e,
+    };
+
+    match get_viewable_channels(&db_pool, &server, user_id).await {
+        Ok(channels) => HttpResponse::Ok().json(channels),
+        Err(e) => {
+            tracing::error!("failed to get channels for server {}: {:?}", server_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
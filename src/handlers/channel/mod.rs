@@ -0,0 +1,16 @@
+mod authorize;
+mod create;
+mod delete;
+mod get;
+mod permission;
+mod update;
+
+pub use authorize::*;
+pub use create::*;
+pub use delete::*;
+pub use get::*;
+pub use permission::*;
+pub use update::*;
+
+pub const BASE_PATH: &str = "/channels";
+pub const PERMISSIONS_PATH: &str = "/permissions";
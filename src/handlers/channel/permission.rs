@@ -0,0 +1,214 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        channel::{OverwriteTarget, PermissionOverwrite},
+        permission::Permissions,
+    },
+    gateway::{refresh_channel_subscriptions, Hub},
+    handlers::{channel::authorize_server_channel, middleware::UserID},
+    storage::{
+        delete_channel_overwrite, get_channel_overwrites, get_role_by_id, get_server_member,
+        upsert_channel_overwrite,
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct PutOverwriteRequestBody {
+    pub target_type: OverwriteTarget,
+    #[serde(default)]
+    pub allow: Permissions,
+    #[serde(default)]
+    pub deny: Permissions,
+}
+
+#[tracing::instrument(
+    name = "Getting channel permission overwrites",
+    skip(path, user_id, db_pool),
+    fields(
+        server_id = %path.0,
+        channel_id = %path.1,
+    )
+)]
+pub async fn get_overwrites(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (server_id, channel_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_server_channel(
+        &db_pool,
+        server_id,
+        channel_id,
+        user_id,
+        Permissions::VIEW_CHANNEL,
+    )
+    .await
+    {
+        return e;
+    }
+
+    match get_channel_overwrites(&db_pool, channel_id).await {
+        Ok(overwrites) => HttpResponse::Ok().json(overwrites),
+        Err(e) => {
+            tracing::error!(
+                "failed to get overwrites for channel {}: {:?}",
+                channel_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Putting channel permission overwrite",
+    skip(path, body, user_id, db_pool, hub),
+    fields(
+        server_id = %path.0,
+        channel_id = %path.1,
+        target_id = %path.2,
+    )
+)]
+pub async fn put_overwrite(
+    path: Path<(Uuid, Uuid, Uuid)>,
+    body: Json<PutOverwriteRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (server_id, channel_id, target_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (server, channel) = match authorize_server_channel(
+        &db_pool,
+        server_id,
+        channel_id,
+        user_id,
+        Permissions::MANAGE_ROLES,
+    )
+    .await
+    {
+        Ok((server, channel, _)) => (server, channel),
+        Err(e) => return e,
+    };
+
+    let target_exists = match body.target_type {
+        OverwriteTarget::Role if target_id == server_id => Ok(true),
+        OverwriteTarget::Role => match get_role_by_id(&db_pool, target_id).await {
+            Ok(role) => Ok(role.server_id() == server_id),
+            Err(sqlx::Error::RowNotFound) => Ok(false),
+            Err(e) => Err(e),
+        },
+        OverwriteTarget::Member => get_server_member(&db_pool, server_id, target_id)
+            .await
+            .map(|m| m.is_some()),
+    };
+    match target_exists {
+        Ok(true) => {}
+        Ok(false) => {
+            let err = format!(
+                "{} {} not found in server {}",
+                body.target_type.as_str(),
+                target_id,
+                server_id
+            );
+            tracing::error!(err);
+            return HttpResponse::BadRequest().body(err);
+        }
+        Err(e) => {
+            tracing::error!("failed to look up overwrite target {}: {:?}", target_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    let overwrite = PermissionOverwrite::new(
+        channel_id,
+        target_id,
+        body.target_type,
+        body.allow,
+        body.deny,
+    );
+    match upsert_channel_overwrite(&db_pool, &overwrite).await {
+        Ok(_) => {
+            if let Err(e) = refresh_channel_subscriptions(&db_pool, &hub, &server, &channel).await {
+                tracing::error!(
+                    "failed to refresh subscriptions for channel {}: {:?}",
+                    channel_id,
+                    e
+                );
+            }
+            HttpResponse::Ok().json(overwrite)
+        }
+        Err(e) => {
+            tracing::error!("failed to upsert overwrite: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Deleting channel permission overwrite",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        server_id = %path.0,
+        channel_id = %path.1,
+        target_id = %path.2,
+    )
+)]
+pub async fn delete_overwrite(
+    path: Path<(Uuid, Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (server_id, channel_id, target_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (server, channel) = match authorize_server_channel(
+        &db_pool,
+        server_id,
+        channel_id,
+        user_id,
+        Permissions::MANAGE_ROLES,
+    )
+    .await
+    {
+        Ok((server, channel, _)) => (server, channel),
+        Err(e) => return e,
+    };
+
+    match delete_channel_overwrite(&db_pool, channel_id, target_id).await {
+        Ok(result) if result.rows_affected() == 0 => {
+            let err = format!(
+                "overwrite for {} not found in channel {}",
+                target_id, channel_id
+            );
+            tracing::error!(err);
+            HttpResponse::NotFound().body(err)
+        }
+        Ok(_) => {
+            if let Err(e) = refresh_channel_subscriptions(&db_pool, &hub, &server, &channel).await {
+                tracing::error!(
+                    "failed to refresh subscriptions for channel {}: {:?}",
+                    channel_id,
+                    e
+                );
+            }
+            HttpResponse::Ok().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to delete overwrite: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
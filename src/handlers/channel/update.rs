@@ -0,0 +1,137 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{channel::Channel, permission::Permissions},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{channel::authorize_server_channel, middleware::UserID},
+    storage::{get_channel_by_id, upsert_channel},
+};
+
+/// Distinguishes a field that was explicitly set to `null` (`Some(None)`)
+/// from one that was left out of the request body (`None`).
+pub(crate) fn deserialize_nullable<'de, T, D>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PatchChannelRequestBody {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub topic: Option<Option<String>>,
+    pub position: Option<i32>,
+    pub max_pins: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub parent_id: Option<Option<Uuid>>,
+}
+
+#[tracing::instrument(
+    name = "Patching channel details",
+    skip(path, body, user_id, db_pool, hub),
+    fields(
+        server_id = %path.0,
+        channel_id = %path.1,
+    )
+)]
+pub async fn patch(
+    path: Path<(Uuid, Uuid)>,
+    body: Json<PatchChannelRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (server_id, channel_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+
+    let mut channel = match authorize_server_channel(
+        &db_pool,
+        server_id,
+        channel_id,
+        user_id,
+        Permissions::MANAGE_CHANNELS,
+    )
+    .await
+    {
+        Ok((_, channel, _)) => channel,
+        Err(e) => return e,
+    };
+
+    if let Some(name) = body.name {
+        if let Err(e) = Channel::validate_name(&name) {
+            tracing::error!("400 - invalid channel name: {:?}", e);
+            return e.handle_http();
+        }
+        channel.set_name(name.trim().to_string());
+    }
+    if let Some(topic) = body.topic {
+        if let Err(e) = Channel::validate_topic(topic.as_deref()) {
+            tracing::error!("400 - invalid channel topic: {:?}", e);
+            return e.handle_http();
+        }
+        channel.set_topic(topic);
+    }
+    if let Some(position) = body.position {
+        channel.set_position(position);
+    }
+    if let Some(max_pins) = body.max_pins {
+        if let Err(e) = Channel::validate_max_pins(max_pins) {
+            tracing::error!("400 - invalid channel pin limit: {:?}", e);
+            return e.handle_http();
+        }
+        channel.set_max_pins(max_pins);
+    }
+    if let Some(parent_id) = body.parent_id {
+        let parent = match parent_id {
+            Some(parent_id) => match get_channel_by_id(&db_pool, parent_id).await {
+                Ok(parent) if parent.deleted_at().is_none() => Some(parent),
+                Ok(_) | Err(sqlx::Error::RowNotFound) => {
+                    let err = format!("parent channel {} not found", parent_id);
+                    tracing::error!(err);
+                    return HttpResponse::BadRequest().body(err);
+                }
+                Err(e) => {
+                    tracing::error!("failed to get parent channel {}: {:?}", parent_id, e);
+                    return HttpResponse::InternalServerError().finish();
+                }
+            },
+            None => None,
+        };
+        if let Err(e) = Channel::validate_parent(server_id, channel.kind(), parent.as_ref()) {
+            tracing::error!("400 - invalid channel parent: {:?}", e);
+            return e.handle_http();
+        }
+        channel.set_parent_id(parent_id);
+    }
+
+    match upsert_channel(&db_pool, &channel).await {
+        Ok(_) => {
+            tracing::info!("Channel {} successfully patched in database", channel_id);
+            hub.do_send(Publish::new(
+                Topic::Channel(channel_id),
+                GatewayEvent::ChannelUpdate(channel.clone()),
+            ));
+            HttpResponse::Ok().json(channel)
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to patch channel {} in database: {:?}",
+                channel_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{dm::DmThread, user::DmPrivacy},
+    storage::{
+        are_friends, get_blocked_user_ids_among, get_dm_participant_ids, get_dm_thread_by_id,
+        get_user_by_id, get_user_settings, is_blocked_between,
+    },
+};
+
+/// Loads a DM thread that has not been soft deleted along with its
+/// participant ids. Users outside the thread get a 404 so that threads they
+/// are not part of are not leaked.
+pub async fn authorize_dm_thread(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    user_id: Uuid,
+) -> Result<(DmThread, Vec<Uuid>), HttpResponse> {
+    let not_found = || {
+        let err = format!("DM thread {} not found", thread_id);
+        tracing::error!(err);
+        HttpResponse::NotFound().body(err)
+    };
+
+    let thread = match get_dm_thread_by_id(db_pool, thread_id).await {
+        Ok(thread) if thread.deleted_at().is_none() => thread,
+        Ok(_) | Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+        Err(e) => {
+            tracing::error!("failed to get DM thread {}: {:?}", thread_id, e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    let participant_ids = match get_dm_participant_ids(db_pool, thread_id).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!(
+                "failed to get participants of DM thread {}: {:?}",
+                thread_id,
+                e
+            );
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+    if !participant_ids.contains(&user_id) {
+        return Err(not_found());
+    }
+
+    Ok((thread, participant_ids))
+}
+
+/// Fails with a 403 if either user has blocked the other.
+pub async fn ensure_not_blocked(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    other_user_id: Uuid,
+) -> Result<(), HttpResponse> {
+    match is_blocked_between(db_pool, user_id, other_user_id).await {
+        Ok(false) => Ok(()),
+        Ok(true) => {
+            let err = format!(
+                "user {} cannot interact with user {}",
+                user_id, other_user_id
+            );
+            tracing::error!("403 - {}", err);
+            Err(HttpResponse::Forbidden().body(err))
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to check blocks between users {} and {}: {:?}",
+                user_id,
+                other_user_id,
+                e
+            );
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Fails with a 403 if `user_id` and any of `other_user_ids` have blocked
+/// one another, so nobody ends up in a group DM with someone they blocked.
+pub async fn ensure_not_blocked_among(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    other_user_ids: &[Uuid],
+) -> Result<(), HttpResponse> {
+    match get_blocked_user_ids_among(db_pool, user_id, other_user_ids).await {
+        Ok(blocked_ids) if blocked_ids.is_empty() => Ok(()),
+        Ok(_) => {
+            let err = format!(
+                "user {} cannot interact with every member of the group",
+                user_id
+            );
+            tracing::error!("403 - {}", err);
+            Err(HttpResponse::Forbidden().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to check blocks of user {}: {:?}", user_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Fails with a 403 if the recipient only accepts DMs from friends and the
+/// sender is not one of them.
+pub async fn ensure_accepts_dms_from(
+    db_pool: &PgPool,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+) -> Result<(), HttpResponse> {
+    let settings = get_user_settings(db_pool, recipient_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to get settings of user {}: {:?}", recipient_id, e);
+            HttpResponse::InternalServerError().finish()
+        })?;
+    if settings.dm_privacy() == DmPrivacy::Everyone {
+        return Ok(());
+    }
+
+    match are_friends(db_pool, recipient_id, sender_id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            let err = format!("user {} only accepts DMs from friends", recipient_id);
+            tracing::error!("403 - {}", err);
+            Err(HttpResponse::Forbidden().body(err))
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to check friendship between users {} and {}: {:?}",
+                sender_id,
+                recipient_id,
+                e
+            );
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Fails with a 404 if the user does not exist or has been soft deleted.
+pub async fn ensure_user_exists(db_pool: &PgPool, user_id: Uuid) -> Result<(), HttpResponse> {
+    match get_user_by_id(db_pool, user_id).await {
+        Ok(user) if user.deleted_at().is_none() => Ok(()),
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("user {} not found", user_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get user {}: {:?}", user_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
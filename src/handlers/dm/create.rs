@@ -0,0 +1,187 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::dm::{DmThread, DmThreadKind},
+    gateway::{GatewayEvent, Hub, Publish, Subscribe, Topic},
+    handlers::{
+        dm::{
+            ensure_accepts_dms_from, ensure_not_blocked, ensure_not_blocked_among,
+            ensure_user_exists, DmThreadResponse,
+        },
+        middleware::UserID,
+    },
+    storage::{get_or_insert_direct_thread, insert_group_thread},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct OpenDirectThreadRequestBody {
+    pub recipient_id: Uuid,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateGroupThreadRequestBody {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub participant_ids: Vec<Uuid>,
+}
+
+/// Subscribes every participant's sessions to a new thread and tells them
+/// about it.
+fn announce_thread(hub: &Addr<Hub>, thread: &DmThread, participant_ids: &[Uuid]) {
+    for &user_id in participant_ids {
+        hub.do_send(Subscribe {
+            user_id,
+            topics: vec![Topic::DmThread(thread.id())],
+        });
+        hub.do_send(Publish::new(
+            Topic::User(user_id),
+            GatewayEvent::DmThreadCreate {
+                thread: thread.clone(),
+                participant_ids: participant_ids.to_vec(),
+            },
+        ));
+    }
+}
+
+#[tracing::instrument(
+    name = "Opening direct DM thread",
+    skip(body, user_id, db_pool, hub),
+    fields(
+        recipient_id = %body.recipient_id,
+    )
+)]
+pub async fn open_direct(
+    body: Json<OpenDirectThreadRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let recipient_id = body.recipient_id;
+
+    let (first_user_id, second_user_id) = match DmThread::direct_pair(user_id, recipient_id) {
+        Ok(pair) => pair,
+        Err(e) => {
+            tracing::error!("400 - invalid DM recipient: {:?}", e);
+            return e.handle_http();
+        }
+    };
+    if let Err(e) = ensure_user_exists(&db_pool, recipient_id).await {
+        return e;
+    }
+    if let Err(e) = ensure_not_blocked(&db_pool, user_id, recipient_id).await {
+        return e;
+    }
+    if let Err(e) = ensure_accepts_dms_from(&db_pool, user_id, recipient_id).await {
+        return e;
+    }
+
+    let now = Utc::now();
+    let thread = DmThread::new(
+        Uuid::new_v4(),
+        DmThreadKind::Direct,
+        None,
+        None,
+        None,
+        now,
+        now,
+        None,
+    );
+
+    let participant_ids = vec![first_user_id, second_user_id];
+    match get_or_insert_direct_thread(&db_pool, &thread, first_user_id, second_user_id).await {
+        Ok(existing) if existing.id() != thread.id() => {
+            HttpResponse::Ok().json(DmThreadResponse::new(existing, participant_ids))
+        }
+        Ok(thread) => {
+            tracing::info!(
+                "DM thread {} successfully inserted to database",
+                thread.id()
+            );
+            announce_thread(&hub, &thread, &participant_ids);
+            HttpResponse::Ok().json(DmThreadResponse::new(thread, participant_ids))
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Creating group DM thread",
+    skip(body, user_id, db_pool, hub),
+    fields(
+        participants = body.participant_ids.len(),
+    )
+)]
+pub async fn create_group(
+    body: Json<CreateGroupThreadRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+
+    if let Some(name) = &body.name {
+        if let Err(e) = DmThread::validate_name(name) {
+            tracing::error!("400 - invalid group DM name: {:?}", e);
+            return e.handle_http();
+        }
+    }
+
+    let mut participant_ids = vec![user_id];
+    for id in body.participant_ids {
+        if !participant_ids.contains(&id) {
+            participant_ids.push(id);
+        }
+    }
+    if let Err(e) = DmThread::validate_participant_count(participant_ids.len()) {
+        tracing::error!("400 - invalid group DM size: {:?}", e);
+        return e.handle_http();
+    }
+    for (i, &id) in participant_ids.iter().enumerate().skip(1) {
+        if let Err(e) = ensure_user_exists(&db_pool, id).await {
+            return e;
+        }
+        if let Err(e) = ensure_not_blocked_among(&db_pool, id, &participant_ids[..i]).await {
+            return e;
+        }
+        if let Err(e) = ensure_accepts_dms_from(&db_pool, user_id, id).await {
+            return e;
+        }
+    }
+
+    let now = Utc::now();
+    let thread = DmThread::new(
+        Uuid::new_v4(),
+        DmThreadKind::Group,
+        Some(user_id),
+        body.name,
+        None,
+        now,
+        now,
+        None,
+    );
+
+    match insert_group_thread(&db_pool, &thread, &participant_ids).await {
+        Ok(_) => {
+            tracing::info!("Group DM {} successfully inserted to database", thread.id());
+            announce_thread(&hub, &thread, &participant_ids);
+            HttpResponse::Ok().json(DmThreadResponse::new(thread, participant_ids))
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        dm::DmThread,
+        pagination::{Page, PageParams},
+    },
+    handlers::{dm::authorize_dm_thread, middleware::UserID},
+    storage::{get_dm_participants_by_thread_ids, get_dm_threads_by_user_id},
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DmThreadResponse {
+    #[serde(flatten)]
+    pub thread: DmThread,
+    pub participant_ids: Vec<Uuid>,
+}
+
+impl DmThreadResponse {
+    pub fn new(thread: DmThread, participant_ids: Vec<Uuid>) -> Self {
+        DmThreadResponse {
+            thread,
+            participant_ids,
+        }
+    }
+}
+
+#[tracing::instrument(name = "Getting DM threads for user", skip(params, user_id, db_pool))]
+pub async fn get_many(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let threads = match get_dm_threads_by_user_id(&db_pool, user_id, &params).await {
+        Ok(threads) => Page::from_rows(threads, &params, |t| t.id()),
+        Err(e) => return e.handle_http(),
+    };
+
+    let thread_ids = threads
+        .items()
+        .iter()
+        .map(|t| t.id())
+        .collect::<Vec<Uuid>>();
+    let participants = match get_dm_participants_by_thread_ids(&db_pool, &thread_ids).await {
+        Ok(participants) => participants,
+        Err(e) => {
+            tracing::error!(
+                "failed to get DM participants for user {}: {:?}",
+                user_id,
+                e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    HttpResponse::Ok().json(threads.map(|thread| {
+        let participant_ids = participants
+            .iter()
+            .filter(|(thread_id, _)| *thread_id == thread.id())
+            .map(|(_, user_id)| *user_id)
+            .collect();
+        DmThreadResponse::new(thread, participant_ids)
+    }))
+}
+
+#[tracing::instrument(
+    name = "Getting DM thread by ID",
+    skip(thread_id, user_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn get_by_id(
+    thread_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        Ok((thread, participant_ids)) => {
+            HttpResponse::Ok().json(DmThreadResponse::new(thread, participant_ids))
+        }
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,404 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        dm::{DirectMessage, DmThreadKind},
+        message::MessageContent,
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        dm::{authorize_dm_thread, ensure_accepts_dms_from, ensure_not_blocked},
+        message::{with_reactions, EditMessageRequestBody, GetMessagesQuery},
+        middleware::UserID,
+    },
+    storage::{
+        ack_direct_message, edit_direct_message, get_direct_message_by_id,
+        get_direct_messages_by_thread_id, insert_direct_message, soft_delete_direct_message,
+        ReactionTarget,
+    },
+};
+
+/// Loads a direct message that has not been soft deleted and belongs to
+/// `thread_id`. On failure, returns the response the handler should send.
+pub async fn find_thread_message(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    message_id: Uuid,
+) -> Result<DirectMessage, HttpResponse> {
+    match get_direct_message_by_id(db_pool, message_id).await {
+        Ok(message) if message.deleted_at().is_none() && message.thread_id() == thread_id => {
+            Ok(message)
+        }
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("message {} not found", message_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get direct message {}: {:?}", message_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateDirectMessageRequestBody {
+    pub content: String,
+    /// Makes the message disappear this many seconds after it is sent.
+    #[serde(default)]
+    pub ttl_seconds: Option<i64>,
+}
+
+#[tracing::instrument(
+    name = "Sending direct message",
+    skip(thread_id, body, user_id, db_pool, hub),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn create_message(
+    thread_id: Path<Uuid>,
+    body: Json<CreateDirectMessageRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    match send_direct_message(
+        &db_pool,
+        &hub,
+        thread_id.into_inner(),
+        Uuid::from(&user_id.into_inner()),
+        body.content,
+        body.ttl_seconds,
+    )
+    .await
+    {
+        Ok(message) => HttpResponse::Ok().json(message),
+        Err(e) => e,
+    }
+}
+
+/// Sends a direct message on behalf of `user_id`, checking membership and
+/// blocks first. Shared by the DM endpoint and the message scheduler.
+pub async fn send_direct_message(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    thread_id: Uuid,
+    user_id: Uuid,
+    content: String,
+    ttl_seconds: Option<i64>,
+) -> Result<DirectMessage, HttpResponse> {
+    let (thread, participant_ids) = authorize_dm_thread(db_pool, thread_id, user_id).await?;
+    if thread.kind() == DmThreadKind::Direct {
+        for &other_id in participant_ids.iter().filter(|&&id| id != user_id) {
+            ensure_not_blocked(db_pool, user_id, other_id).await?;
+            ensure_accepts_dms_from(db_pool, user_id, other_id).await?;
+        }
+    }
+
+    let content = match MessageContent::try_from(content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid message content: {:?}", e);
+            return Err(e.handle_http());
+        }
+    };
+
+    let now = Utc::now();
+    let expires_at = match ttl_seconds.map(|ttl| DirectMessage::expiry(now, ttl)) {
+        Some(Ok(expires_at)) => Some(expires_at),
+        Some(Err(e)) => {
+            tracing::error!("400 - invalid message TTL: {:?}", e);
+            return Err(e.handle_http());
+        }
+        None => None,
+    };
+    let mut message = DirectMessage::new(
+        Uuid::new_v4(),
+        thread_id,
+        user_id,
+        content,
+        now,
+        now,
+        None,
+        None,
+    );
+    message.set_expires_at(expires_at);
+
+    match insert_direct_message(db_pool, &message).await {
+        Ok(_) => {
+            tracing::info!(
+                "Direct message {} successfully inserted to database",
+                message.id()
+            );
+            hub.do_send(Publish::new(
+                Topic::DmThread(thread_id),
+                GatewayEvent::DirectMessageCreate(message.clone()),
+            ));
+            Ok(message)
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting direct messages by thread ID",
+    skip(thread_id, query, user_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn get_messages(
+    thread_id: Path<Uuid>,
+    query: Query<GetMessagesQuery>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let cursor = match query.cursor() {
+        Some(cursor) => cursor,
+        None => {
+            let err = "only one of before, after or around may be provided";
+            tracing::error!("400 - {}", err);
+            return HttpResponse::BadRequest().body(err);
+        }
+    };
+
+    if let Err(e) = authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        return e;
+    }
+
+    let messages =
+        match get_direct_messages_by_thread_id(&db_pool, thread_id, cursor, query.limit()).await {
+            Ok(messages) => messages,
+            Err(e) => return e.handle_http(),
+        };
+
+    match with_reactions(
+        &db_pool,
+        ReactionTarget::DirectMessage,
+        messages,
+        user_id,
+        DirectMessage::id,
+    )
+    .await
+    {
+        Ok(messages) => HttpResponse::Ok().json(messages),
+        Err(e) => e,
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting direct message by ID",
+    skip(path, user_id, db_pool),
+    fields(
+        thread_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn get_message(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (thread_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        return e;
+    }
+
+    let message = match find_thread_message(&db_pool, thread_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+
+    match with_reactions(
+        &db_pool,
+        ReactionTarget::DirectMessage,
+        vec![message],
+        user_id,
+        DirectMessage::id,
+    )
+    .await
+    {
+        Ok(mut messages) => HttpResponse::Ok().json(messages.pop()),
+        Err(e) => e,
+    }
+}
+
+#[tracing::instrument(
+    name = "Editing direct message",
+    skip(path, body, user_id, db_pool, hub),
+    fields(
+        thread_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn edit_message(
+    path: Path<(Uuid, Uuid)>,
+    body: Json<EditMessageRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (thread_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        return e;
+    }
+
+    let mut message = match find_thread_message(&db_pool, thread_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+    if message.author_id() != user_id {
+        let err = format!("message {} can only be edited by its author", message_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    let content = match MessageContent::try_from(body.into_inner().content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid message content: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    let now = Utc::now();
+    match edit_direct_message(&db_pool, message_id, &content, now).await {
+        Ok(_) => {
+            message.set_content(content);
+            message.set_edited_at(Some(now));
+            message.set_updated_at(now);
+            hub.do_send(Publish::new(
+                Topic::DmThread(thread_id),
+                GatewayEvent::DirectMessageUpdate(message.clone()),
+            ));
+            HttpResponse::Ok().json(message)
+        }
+        Err(e) => {
+            tracing::error!("Failed to edit direct message {}: {:?}", message_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Soft Deleting Direct Message",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        thread_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn delete_message(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (thread_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        return e;
+    }
+
+    let message = match find_thread_message(&db_pool, thread_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+    if message.author_id() != user_id {
+        let err = format!("message {} can only be deleted by its author", message_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    match soft_delete_direct_message(&db_pool, message_id, Utc::now()).await {
+        Ok(_) => {
+            hub.do_send(Publish::new(
+                Topic::DmThread(thread_id),
+                GatewayEvent::DirectMessageDelete {
+                    id: message_id,
+                    thread_id,
+                },
+            ));
+            HttpResponse::Ok().finish()
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to soft delete direct message {}: {:?}",
+                message_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Acknowledging direct message",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        thread_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn ack_message(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (thread_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        return e;
+    }
+
+    let message = match find_thread_message(&db_pool, thread_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+
+    match ack_direct_message(&db_pool, user_id, &message).await {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::User(user_id),
+                    GatewayEvent::DirectMessageAck {
+                        thread_id,
+                        message_id,
+                    },
+                ));
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to acknowledge direct message {}: {:?}",
+                message_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,21 @@
+mod authorize;
+mod create;
+mod get;
+mod message;
+mod participant;
+mod reaction;
+mod typing;
+
+pub use authorize::*;
+pub use create::*;
+pub use get::*;
+pub use message::*;
+pub use participant::*;
+pub use reaction::*;
+pub use typing::*;
+
+pub const BASE_PATH: &str = "/dms";
+pub const GROUPS_PATH: &str = "/groups";
+pub const PARTICIPANTS_PATH: &str = "/participants";
+pub const MESSAGES_PATH: &str = "/messages";
+pub const TYPING_PATH: &str = "/typing";
@@ -0,0 +1,172 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::dm::{DmThread, DmThreadKind},
+    gateway::{GatewayEvent, Hub, Publish, Subscribe, Topic, Unsubscribe},
+    handlers::{
+        dm::{
+            authorize_dm_thread, ensure_accepts_dms_from, ensure_not_blocked_among,
+            ensure_user_exists, DmThreadResponse,
+        },
+        middleware::UserID,
+    },
+    storage::{insert_dm_participant, remove_dm_participant},
+};
+
+fn ensure_group(thread: &DmThread) -> Result<(), HttpResponse> {
+    if thread.kind() != DmThreadKind::Group {
+        let err = format!("DM thread {} is not a group DM", thread.id());
+        tracing::error!("400 - {}", err);
+        return Err(HttpResponse::BadRequest().body(err));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "Adding group DM participant",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        thread_id = %path.0,
+        participant_id = %path.1,
+    )
+)]
+pub async fn add_participant(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (thread_id, participant_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (thread, mut participant_ids) =
+        match authorize_dm_thread(&db_pool, thread_id, user_id).await {
+            Ok(res) => res,
+            Err(e) => return e,
+        };
+    if let Err(e) = ensure_group(&thread) {
+        return e;
+    }
+    if thread.owner_id() != Some(user_id) {
+        let err = format!("only the owner may add people to group DM {}", thread_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+    if participant_ids.contains(&participant_id) {
+        return HttpResponse::Ok().json(DmThreadResponse::new(thread, participant_ids));
+    }
+    if let Err(e) = DmThread::validate_participant_count(participant_ids.len() + 1) {
+        tracing::error!("400 - invalid group DM size: {:?}", e);
+        return e.handle_http();
+    }
+    if let Err(e) = ensure_user_exists(&db_pool, participant_id).await {
+        return e;
+    }
+    if let Err(e) = ensure_not_blocked_among(&db_pool, participant_id, &participant_ids).await {
+        return e;
+    }
+    if let Err(e) = ensure_accepts_dms_from(&db_pool, user_id, participant_id).await {
+        return e;
+    }
+
+    match insert_dm_participant(&db_pool, thread_id, participant_id).await {
+        Ok(_) => {
+            participant_ids.push(participant_id);
+            hub.do_send(Subscribe {
+                user_id: participant_id,
+                topics: vec![Topic::DmThread(thread_id)],
+            });
+            hub.do_send(Publish::new(
+                Topic::DmThread(thread_id),
+                GatewayEvent::DmParticipantAdd {
+                    thread_id,
+                    user_id: participant_id,
+                },
+            ));
+            HttpResponse::Ok().json(DmThreadResponse::new(thread, participant_ids))
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Removing group DM participant",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        thread_id = %path.0,
+        participant_id = %path.1,
+    )
+)]
+pub async fn remove_participant(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (thread_id, participant_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (thread, participant_ids) = match authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        Ok(res) => res,
+        Err(e) => return e,
+    };
+    if let Err(e) = ensure_group(&thread) {
+        return e;
+    }
+    if participant_id != user_id && thread.owner_id() != Some(user_id) {
+        let err = format!(
+            "only the owner may remove people from group DM {}",
+            thread_id
+        );
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+    if !participant_ids.contains(&participant_id) {
+        let err = format!(
+            "user {} is not a participant of group DM {}",
+            participant_id, thread_id
+        );
+        tracing::error!(err);
+        return HttpResponse::NotFound().body(err);
+    }
+
+    match remove_dm_participant(&db_pool, thread_id, participant_id).await {
+        Ok(new_owner_id) => {
+            hub.do_send(Publish::new(
+                Topic::DmThread(thread_id),
+                GatewayEvent::DmParticipantRemove {
+                    thread_id,
+                    user_id: participant_id,
+                },
+            ));
+            if let Some(owner_id) = new_owner_id {
+                tracing::info!("group DM {} passed to user {}", thread_id, owner_id);
+                hub.do_send(Publish::new(
+                    Topic::DmThread(thread_id),
+                    GatewayEvent::DmOwnerUpdate {
+                        thread_id,
+                        owner_id,
+                    },
+                ));
+            }
+            hub.do_send(Unsubscribe {
+                user_id: participant_id,
+                topics: vec![Topic::DmThread(thread_id)],
+            });
+            HttpResponse::Ok().finish()
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
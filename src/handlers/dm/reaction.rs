@@ -0,0 +1,197 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{dm::DmThreadKind, pagination::PageParams},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        dm::{authorize_dm_thread, ensure_not_blocked, find_thread_message},
+        message::{
+            ensure_reaction_capacity, page_reaction_users, parse_reaction_emoji,
+            resolve_reaction_emoji,
+        },
+        middleware::UserID,
+    },
+    storage::{delete_reaction, insert_reaction, ReactionTarget},
+};
+
+#[tracing::instrument(
+    name = "Adding direct message reaction",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        thread_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn add_reaction(
+    path: Path<(Uuid, Uuid, String)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (thread_id, message_id, emoji) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (thread, participant_ids) = match authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        Ok(res) => res,
+        Err(e) => return e,
+    };
+    if thread.kind() == DmThreadKind::Direct {
+        for &other_id in participant_ids.iter().filter(|&&id| id != user_id) {
+            if let Err(e) = ensure_not_blocked(&db_pool, user_id, other_id).await {
+                return e;
+            }
+        }
+    }
+    if let Err(e) = find_thread_message(&db_pool, thread_id, message_id).await {
+        return e;
+    }
+    // Custom emoji belong to a server, so they cannot be used in DMs.
+    let emoji = match resolve_reaction_emoji(&db_pool, &emoji, None).await {
+        Ok(emoji) => emoji,
+        Err(e) => return e,
+    };
+    if let Err(e) =
+        ensure_reaction_capacity(&db_pool, ReactionTarget::DirectMessage, message_id, &emoji).await
+    {
+        return e;
+    }
+
+    match insert_reaction(
+        &db_pool,
+        ReactionTarget::DirectMessage,
+        message_id,
+        user_id,
+        &emoji,
+    )
+    .await
+    {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::DmThread(thread_id),
+                    GatewayEvent::DirectMessageReactionAdd {
+                        thread_id,
+                        message_id,
+                        user_id,
+                        emoji,
+                    },
+                ));
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to add reaction to direct message {}: {:?}",
+                message_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Removing direct message reaction",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        thread_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn remove_reaction(
+    path: Path<(Uuid, Uuid, String)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (thread_id, message_id, emoji) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        return e;
+    }
+    if let Err(e) = find_thread_message(&db_pool, thread_id, message_id).await {
+        return e;
+    }
+    let emoji = match parse_reaction_emoji(&emoji) {
+        Ok(emoji) => emoji,
+        Err(e) => return e,
+    };
+
+    match delete_reaction(
+        &db_pool,
+        ReactionTarget::DirectMessage,
+        message_id,
+        user_id,
+        &emoji,
+    )
+    .await
+    {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::DmThread(thread_id),
+                    GatewayEvent::DirectMessageReactionRemove {
+                        thread_id,
+                        message_id,
+                        user_id,
+                        emoji,
+                    },
+                ));
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to remove reaction from direct message {}: {:?}",
+                message_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting direct message reaction users",
+    skip(path, params, user_id, db_pool),
+    fields(
+        thread_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn get_reactions(
+    path: Path<(Uuid, Uuid, String)>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (thread_id, message_id, emoji) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        return e;
+    }
+    if let Err(e) = find_thread_message(&db_pool, thread_id, message_id).await {
+        return e;
+    }
+    let emoji = match parse_reaction_emoji(&emoji) {
+        Ok(emoji) => emoji,
+        Err(e) => return e,
+    };
+
+    page_reaction_users(
+        &db_pool,
+        ReactionTarget::DirectMessage,
+        message_id,
+        &emoji,
+        &params,
+    )
+    .await
+}
@@ -0,0 +1,65 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::dm::DmThreadKind,
+    gateway::{Hub, StartTyping, Topic},
+    handlers::{
+        dm::{authorize_dm_thread, ensure_not_blocked},
+        middleware::UserID,
+    },
+};
+
+#[tracing::instrument(
+    name = "Starting DM typing indicator",
+    skip(thread_id, user_id, db_pool, hub),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn start_typing(
+    thread_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (thread, participant_ids) = match authorize_dm_thread(&db_pool, thread_id, user_id).await {
+        Ok(res) => res,
+        Err(e) => return e,
+    };
+    if thread.kind() == DmThreadKind::Direct {
+        for &other_id in participant_ids.iter().filter(|&&id| id != user_id) {
+            if let Err(e) = ensure_not_blocked(&db_pool, user_id, other_id).await {
+                return e;
+            }
+        }
+    }
+
+    match hub
+        .send(StartTyping {
+            user_id,
+            topic: Topic::DmThread(thread_id),
+            session_id: None,
+        })
+        .await
+    {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => {
+            let err = format!("user {} is typing too often", user_id);
+            tracing::error!("429 - {}", err);
+            HttpResponse::TooManyRequests().body(err)
+        }
+        Err(e) => {
+            tracing::error!("failed to start typing in DM thread {}: {:?}", thread_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Payload},
+    HttpRequest, HttpResponse,
+};
+use actix_web_actors::ws;
+use sqlx::PgPool;
+
+use crate::gateway::{Hub, Session};
+
+#[tracing::instrument(name = "Opening gateway connection", skip(req, stream, hub, db_pool))]
+pub async fn connect(
+    req: HttpRequest,
+    stream: Payload,
+    hub: Data<Addr<Hub>>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let session = Session::new(hub.get_ref().clone(), db_pool.get_ref().clone());
+    match ws::start(session, &req, stream) {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!("failed to start gateway connection: {:?}", e);
+            e.error_response()
+        }
+    }
+}
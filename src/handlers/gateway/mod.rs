@@ -0,0 +1,5 @@
+mod connect;
+
+pub use connect::*;
+
+pub const BASE_PATH: &str = "/gateway";
@@ -0,0 +1,68 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{channel::authorize_channel, message::find_channel_message, middleware::UserID},
+    storage::ack_channel_message,
+};
+
+#[tracing::instrument(
+    name = "Acknowledging message",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn ack(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::READ_MESSAGE_HISTORY,
+    )
+    .await
+    {
+        return e;
+    }
+
+    let message = match find_channel_message(&db_pool, channel_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+
+    match ack_channel_message(&db_pool, user_id, &message).await {
+        Ok(result) => {
+            // Other sessions of the same user move their marker too.
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::User(user_id),
+                    GatewayEvent::MessageAck {
+                        channel_id,
+                        message_id,
+                    },
+                ));
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to acknowledge message {}: {:?}", message_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        channel::ChannelKind,
+        message::{Message, MessageContent},
+        permission::Permissions,
+        poll::{NewPoll, PollTarget, PollView},
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        channel::authorize_channel,
+        message::{find_channel_message, resolve_mentions, MessageResponse},
+        middleware::UserID,
+        poll::{build_poll, new_poll_view},
+        thread::{ensure_thread_open, find_thread, record_thread_message},
+    },
+    storage::{insert_mention_notifications, insert_message},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateMessageRequestBody {
+    pub content: String,
+    #[serde(default)]
+    pub reply_to_id: Option<Uuid>,
+    #[serde(default)]
+    pub poll: Option<NewPoll>,
+}
+
+#[tracing::instrument(
+    name = "Sending new message",
+    skip(channel_id, body, user_id, db_pool, hub),
+    fields(
+        channel_id = %channel_id,
+    )
+)]
+pub async fn create(
+    channel_id: Path<Uuid>,
+    body: Json<CreateMessageRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    match send_message(
+        &db_pool,
+        &hub,
+        channel_id.into_inner(),
+        Uuid::from(&user_id.into_inner()),
+        body.content,
+        body.reply_to_id,
+        body.poll,
+    )
+    .await
+    {
+        Ok((message, poll)) => HttpResponse::Ok().json(MessageResponse {
+            message,
+            reactions: vec![],
+            poll,
+        }),
+        Err(e) => e,
+    }
+}
+
+/// Sends a message to a channel on behalf of `user_id`, checking access,
+/// resolving mentions and notifying subscribers. This is the one path every
+/// channel message takes, whether posted directly or delivered on schedule.
+/// A poll sent along with the message is saved with it.
+pub async fn send_message(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    channel_id: Uuid,
+    user_id: Uuid,
+    content: String,
+    reply_to_id: Option<Uuid>,
+    poll: Option<NewPoll>,
+) -> Result<(Message, Option<PollView>), HttpResponse> {
+    let (server, channel, permissions) =
+        authorize_channel(db_pool, channel_id, user_id, Permissions::SEND_MESSAGES).await?;
+    if !channel.kind().is_messageable() {
+        let err = format!(
+            "messages cannot be sent to {} channels",
+            channel.kind().as_str()
+        );
+        tracing::error!("400 - {}", err);
+        return Err(HttpResponse::BadRequest().body(err));
+    }
+
+    let thread = if channel.kind() == ChannelKind::Thread {
+        Some(find_thread(db_pool, channel_id).await?)
+    } else {
+        None
+    };
+    if let Some(thread) = &thread {
+        ensure_thread_open(thread, permissions)?;
+    }
+
+    if let Some(reply_to_id) = reply_to_id {
+        find_channel_message(db_pool, channel_id, reply_to_id).await?;
+    }
+
+    let content = match MessageContent::try_from(content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid message content: {:?}", e);
+            return Err(e.handle_http());
+        }
+    };
+
+    let (mentions, recipients) = resolve_mentions(
+        db_pool,
+        hub,
+        &server,
+        &channel,
+        user_id,
+        permissions,
+        &content,
+    )
+    .await?;
+
+    let now = Utc::now();
+    let mut message = Message::new(
+        Uuid::new_v4(),
+        channel_id,
+        user_id,
+        content,
+        now,
+        now,
+        None,
+        None,
+    );
+    message.set_reply_to_id(reply_to_id);
+    message.set_mentions(mentions);
+    let poll = match poll {
+        Some(draft) => Some(build_poll(
+            draft,
+            user_id,
+            PollTarget::Message(message.id()),
+        )?),
+        None => None,
+    };
+
+    let saved_poll = poll
+        .as_ref()
+        .map(|(poll, options)| (poll, options.as_slice()));
+    match insert_message(db_pool, &message, saved_poll).await {
+        Ok(_) => {
+            tracing::info!("Message {} successfully inserted to database", message.id());
+            let poll = poll.map(|(poll, options)| new_poll_view(poll, options));
+            let recipients = match thread {
+                Some(thread) => {
+                    match record_thread_message(db_pool, hub, thread, &message, recipients).await {
+                        Ok(recipients) => recipients,
+                        Err(e) => {
+                            tracing::error!("failed to record thread message: {:?}", e);
+                            vec![]
+                        }
+                    }
+                }
+                None => recipients,
+            };
+            if !recipients.is_empty() {
+                if let Err(e) = insert_mention_notifications(db_pool, &message, &recipients).await {
+                    tracing::error!("failed to notify mentions in {}: {:?}", message.id(), e);
+                }
+            }
+            hub.do_send(Publish::new(
+                Topic::Channel(channel_id),
+                GatewayEvent::MessageCreate(message.clone()),
+            ));
+            Ok((message, poll))
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
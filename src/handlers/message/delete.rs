@@ -0,0 +1,71 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{channel::authorize_channel, message::find_channel_message, middleware::UserID},
+    storage::soft_delete_message,
+};
+
+#[tracing::instrument(
+    name = "Soft Deleting Message",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn soft_delete(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let permissions =
+        match authorize_channel(&db_pool, channel_id, user_id, Permissions::VIEW_CHANNEL).await {
+            Ok((_, _, permissions)) => permissions,
+            Err(e) => return e,
+        };
+
+    let message = match find_channel_message(&db_pool, channel_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+    if message.author_id() != user_id && !permissions.contains(Permissions::MANAGE_MESSAGES) {
+        let err = format!(
+            "user {} may not delete message {} from another user",
+            user_id, message_id
+        );
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    match soft_delete_message(&db_pool, message_id, user_id, Utc::now()).await {
+        Ok(_) => {
+            tracing::info!("message {} successfully soft deleted", message_id);
+            hub.do_send(Publish::new(
+                Topic::Channel(channel_id),
+                GatewayEvent::MessageDelete {
+                    id: message_id,
+                    channel_id,
+                },
+            ));
+            HttpResponse::Ok().finish()
+        }
+        Err(e) => {
+            let err = format!("failed to soft delete message {}: {}", message_id, e);
+            tracing::error!(err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
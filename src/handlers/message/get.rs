@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        message::{Message, MessageCursor},
+        pagination::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT},
+        permission::Permissions,
+        poll::{PollTarget, PollView},
+        reaction::ReactionCount,
+    },
+    handlers::{channel::authorize_channel, middleware::UserID, poll::build_poll_views},
+    storage::{
+        get_message_by_id, get_messages_by_channel_id, get_polls_by_targets, get_reaction_counts,
+        ReactionTarget,
+    },
+};
+
+/// A channel or direct message along with its reaction counts as seen by
+/// the requesting user, and the poll attached to a channel message.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MessageResponse<M> {
+    #[serde(flatten)]
+    pub message: M,
+    pub reactions: Vec<ReactionCount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll: Option<PollView>,
+}
+
+/// Attaches reaction counts to each message, keeping the messages in order.
+pub async fn with_reactions<M, F>(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    messages: Vec<M>,
+    viewer_id: Uuid,
+    id_of: F,
+) -> Result<Vec<MessageResponse<M>>, HttpResponse>
+where
+    F: Fn(&M) -> Uuid,
+{
+    let message_ids: Vec<Uuid> = messages.iter().map(&id_of).collect();
+    let counts = match get_reaction_counts(db_pool, target, &message_ids, viewer_id).await {
+        Ok(counts) => counts,
+        Err(e) => {
+            tracing::error!("failed to get reaction counts: {:?}", e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    let mut counts_by_message: HashMap<Uuid, Vec<ReactionCount>> = HashMap::new();
+    for (message_id, count) in counts {
+        counts_by_message.entry(message_id).or_default().push(count);
+    }
+
+    Ok(messages
+        .into_iter()
+        .map(|message| {
+            let reactions = counts_by_message
+                .remove(&id_of(&message))
+                .unwrap_or_default();
+            MessageResponse {
+                message,
+                reactions,
+                poll: None,
+            }
+        })
+        .collect())
+}
+
+/// Attaches the poll of each channel message that has one.
+pub async fn with_polls(
+    db_pool: &PgPool,
+    mut messages: Vec<MessageResponse<Message>>,
+    viewer_id: Uuid,
+) -> Result<Vec<MessageResponse<Message>>, HttpResponse> {
+    let message_ids: Vec<Uuid> = messages.iter().map(|m| m.message.id()).collect();
+    let polls = match get_polls_by_targets(db_pool, &[], &message_ids).await {
+        Ok(polls) => polls,
+        Err(e) => {
+            tracing::error!("failed to get message polls: {:?}", e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+    let mut polls_by_message: HashMap<Uuid, PollView> = build_poll_views(db_pool, polls, viewer_id)
+        .await?
+        .into_iter()
+        .filter_map(|view| match view.poll.target() {
+            PollTarget::Message(message_id) => Some((message_id, view)),
+            PollTarget::Post(_) => None,
+        })
+        .collect();
+    for message in &mut messages {
+        message.poll = polls_by_message.remove(&message.message.id());
+    }
+    Ok(messages)
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct GetMessagesQuery {
+    pub before: Option<Uuid>,
+    pub after: Option<Uuid>,
+    pub around: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+impl GetMessagesQuery {
+    pub fn cursor(&self) -> Option<MessageCursor> {
+        match (self.before, self.after, self.around) {
+            (None, None, None) => Some(MessageCursor::Latest),
+            (Some(id), None, None) => Some(MessageCursor::Before(id)),
+            (None, Some(id), None) => Some(MessageCursor::After(id)),
+            (None, None, Some(id)) => Some(MessageCursor::Around(id)),
+            _ => None,
+        }
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+}
+
+/// Loads a message that has not been soft deleted and belongs to
+/// `channel_id`. On failure, returns the response the handler should send.
+pub async fn find_channel_message(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+    message_id: Uuid,
+) -> Result<Message, HttpResponse> {
+    match get_message_by_id(db_pool, message_id).await {
+        Ok(message) if message.deleted_at().is_none() && message.channel_id() == channel_id => {
+            Ok(message)
+        }
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("message {} not found", message_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get message {}: {:?}", message_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting messages by channel ID",
+    skip(channel_id, query, user_id, db_pool),
+    fields(
+        channel_id = %channel_id,
+    )
+)]
+pub async fn get_many_by_channel(
+    channel_id: Path<Uuid>,
+    query: Query<GetMessagesQuery>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let channel_id = channel_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let cursor = match query.cursor() {
+        Some(cursor) => cursor,
+        None => {
+            let err = "only one of before, after or around may be provided";
+            tracing::error!("400 - {}", err);
+            return HttpResponse::BadRequest().body(err);
+        }
+    };
+
+    if let Err(e) = authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::READ_MESSAGE_HISTORY,
+    )
+    .await
+    {
+        return e;
+    }
+
+    let messages =
+        match get_messages_by_channel_id(&db_pool, channel_id, cursor, query.limit()).await {
+            Ok(messages) => messages,
+            Err(e) => return e.handle_http(),
+        };
+
+    let messages = match with_reactions(
+        &db_pool,
+        ReactionTarget::Message,
+        messages,
+        user_id,
+        Message::id,
+    )
+    .await
+    {
+        Ok(messages) => messages,
+        Err(e) => return e,
+    };
+    match with_polls(&db_pool, messages, user_id).await {
+        Ok(messages) => HttpResponse::Ok().json(messages),
+        Err(e) => e,
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting message by ID",
+    skip(path, user_id, db_pool),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn get_by_id(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::READ_MESSAGE_HISTORY,
+    )
+    .await
+    {
+        return e;
+    }
+
+    let message = match find_channel_message(&db_pool, channel_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+
+    let messages = match with_reactions(
+        &db_pool,
+        ReactionTarget::Message,
+        vec![message],
+        user_id,
+        Message::id,
+    )
+    .await
+    {
+        Ok(messages) => messages,
+        Err(e) => return e,
+    };
+    match with_polls(&db_pool, messages, user_id).await {
+        Ok(mut messages) => HttpResponse::Ok().json(messages.pop()),
+        Err(e) => e,
+    }
+}
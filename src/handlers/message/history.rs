@@ -0,0 +1,60 @@
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    handlers::{channel::authorize_channel, middleware::UserID},
+    storage::get_message_history,
+};
+
+/// Returns every retained revision of a message for moderators, including
+/// the content of messages deleted within the retention period.
+#[tracing::instrument(
+    name = "Getting message history",
+    skip(path, user_id, db_pool),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn get_history(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::READ_MESSAGE_HISTORY | Permissions::MANAGE_MESSAGES,
+    )
+    .await
+    {
+        return e;
+    }
+
+    match get_message_history(&db_pool, message_id).await {
+        Ok(history)
+            if history.channel_id() == channel_id && !history.is_past_retention(Utc::now()) =>
+        {
+            HttpResponse::Ok().json(history)
+        }
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("message {} not found", message_id);
+            tracing::error!(err);
+            HttpResponse::NotFound().body(err)
+        }
+        Err(e) => {
+            tracing::error!("failed to get history of message {}: {:?}", message_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
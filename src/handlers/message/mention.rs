@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use actix::Addr;
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        channel::Channel,
+        message::{MessageContent, MessageMentions, ParsedMentions},
+        notification::MentionKind,
+        permission::Permissions,
+        server::Server,
+    },
+    gateway::{GetOnlineUsers, Hub},
+    storage::{
+        get_blocked_user_ids_among, get_channel_viewers, get_muter_ids_among,
+        get_roles_by_server_id, get_users_by_handles, get_viewable_channels,
+    },
+};
+
+/// Resolves the mentions in `content` against the server, returning what to
+/// store on the message and who to notify. Users and roles only count if
+/// they can see the channel, channels only if the author can see them, and
+/// `@everyone`, `@here` and role mentions are dropped unless the author has
+/// `MENTION_EVERYONE`. The author is never notified, and neither is anyone
+/// on either side of a block with them or who has muted them. Users on
+/// either side of a block are not mentioned at all.
+pub async fn resolve_mentions(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    server: &Server,
+    channel: &Channel,
+    author_id: Uuid,
+    permissions: Permissions,
+    content: &MessageContent,
+) -> Result<(MessageMentions, Vec<(Uuid, MentionKind)>), HttpResponse> {
+    let parsed = ParsedMentions::parse(content.as_ref());
+    let allow_mass = permissions.contains(Permissions::MENTION_EVERYONE);
+
+    let viewers = if !parsed.handles().is_empty() || (allow_mass && parsed.is_mass()) {
+        match get_channel_viewers(db_pool, server, channel).await {
+            Ok(viewers) => viewers,
+            Err(e) => {
+                tracing::error!("failed to resolve viewers of {}: {:?}", channel.id(), e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        }
+    } else {
+        vec![]
+    };
+
+    let mut user_ids = vec![];
+    if !parsed.handles().is_empty() {
+        let users = match get_users_by_handles(db_pool, &parsed.handle_candidates()).await {
+            Ok(users) => users,
+            Err(e) => {
+                tracing::error!("failed to resolve mentioned handles: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        };
+        let existing: Vec<String> = users.iter().map(|u| u.handle().to_string()).collect();
+        for handle in parsed.matched_handles(&existing) {
+            if let Some(user) = users.iter().find(|u| u.handle().as_ref() == handle) {
+                if viewers.iter().any(|(id, _)| *id == user.id()) {
+                    user_ids.push(user.id());
+                }
+            }
+        }
+    }
+
+    let mut channel_ids = vec![];
+    if !parsed.channel_names().is_empty() {
+        let channels = match get_viewable_channels(db_pool, server, author_id).await {
+            Ok(channels) => channels,
+            Err(e) => {
+                tracing::error!("failed to resolve mentioned channels: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        };
+        for name in parsed.channel_names() {
+            if let Some(channel) = channels.iter().find(|c| c.name().to_lowercase() == *name) {
+                channel_ids.push(channel.id());
+            }
+        }
+    }
+
+    let mut role_ids = vec![];
+    if allow_mass && !parsed.role_ids().is_empty() {
+        let roles = match get_roles_by_server_id(db_pool, server.id()).await {
+            Ok(roles) => roles,
+            Err(e) => {
+                tracing::error!("failed to resolve mentioned roles: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        };
+        role_ids = parsed
+            .role_ids()
+            .iter()
+            .copied()
+            .filter(|id| roles.iter().any(|r| r.id() == *id))
+            .collect();
+    }
+
+    let mut recipients: BTreeMap<Uuid, MentionKind> = BTreeMap::new();
+    let mut notify = |user_id: Uuid, kind: MentionKind| {
+        let entry = recipients.entry(user_id).or_insert(kind);
+        *entry = (*entry).min(kind);
+    };
+    for &user_id in &user_ids {
+        notify(user_id, MentionKind::User);
+    }
+    for (user_id, held) in &viewers {
+        if held.iter().any(|id| role_ids.contains(id)) {
+            notify(*user_id, MentionKind::Role);
+        }
+        if allow_mass && parsed.everyone() {
+            notify(*user_id, MentionKind::Everyone);
+        }
+    }
+    if allow_mass && parsed.here() {
+        let user_ids = viewers.iter().map(|(id, _)| *id).collect();
+        match hub
+            .send(GetOnlineUsers {
+                user_ids,
+                viewer_id: author_id,
+            })
+            .await
+        {
+            Ok(online) => online
+                .into_iter()
+                .for_each(|user_id| notify(user_id, MentionKind::Here)),
+            Err(e) => tracing::error!("failed to get online users: {:?}", e),
+        }
+    }
+    recipients.remove(&author_id);
+
+    let candidates: Vec<Uuid> = recipients.keys().chain(&user_ids).copied().collect();
+    if !candidates.is_empty() {
+        let blocked = match get_blocked_user_ids_among(db_pool, author_id, &candidates).await {
+            Ok(blocked) => blocked,
+            Err(e) => {
+                tracing::error!("failed to check blocks of mentioned users: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        };
+        let muters = match get_muter_ids_among(db_pool, author_id, &candidates).await {
+            Ok(muters) => muters,
+            Err(e) => {
+                tracing::error!("failed to check mutes of mentioned users: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        };
+        user_ids.retain(|id| !blocked.contains(id));
+        recipients.retain(|id, _| !blocked.contains(id) && !muters.contains(id));
+    }
+
+    Ok((
+        MessageMentions::new(
+            user_ids,
+            role_ids,
+            channel_ids,
+            allow_mass && (parsed.everyone() || parsed.here()),
+        ),
+        recipients.into_iter().collect(),
+    ))
+}
@@ -0,0 +1,28 @@
+mod ack;
+mod create;
+mod delete;
+mod get;
+mod history;
+mod mention;
+mod pin;
+mod reaction;
+mod typing;
+mod update;
+
+pub use ack::*;
+pub use create::*;
+pub use delete::*;
+pub use get::*;
+pub use history::*;
+pub use mention::*;
+pub use pin::*;
+pub use reaction::*;
+pub use typing::*;
+pub use update::*;
+
+pub const BASE_PATH: &str = "/messages";
+pub const TYPING_PATH: &str = "/typing";
+pub const ACK_PATH: &str = "/ack";
+pub const REACTIONS_PATH: &str = "/reactions";
+pub const PINS_PATH: &str = "/pins";
+pub const HISTORY_PATH: &str = "/history";
@@ -0,0 +1,239 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        message::{Message, MessageContent, MessageKind, PinnedMessage},
+        permission::Permissions,
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{channel::authorize_channel, message::find_channel_message, middleware::UserID},
+    storage::{delete_pin, get_pins_by_channel_id, insert_message, insert_pin, is_message_pinned},
+};
+
+/// Records a pin or unpin in the channel with a system message from the
+/// moderator who did it, referring to the affected message.
+async fn send_pin_system_message(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    channel_id: Uuid,
+    user_id: Uuid,
+    message_id: Uuid,
+    kind: MessageKind,
+) {
+    let content = match kind {
+        MessageKind::MessageUnpinned => "unpinned a message from this channel.",
+        _ => "pinned a message to this channel.",
+    };
+    let now = Utc::now();
+    let mut message = Message::new(
+        Uuid::new_v4(),
+        channel_id,
+        user_id,
+        MessageContent::try_from(content).expect("system message content is valid"),
+        now,
+        now,
+        None,
+        None,
+    );
+    message.set_kind(kind);
+    message.set_reply_to_id(Some(message_id));
+
+    match insert_message(db_pool, &message, None).await {
+        Ok(_) => hub.do_send(Publish::new(
+            Topic::Channel(channel_id),
+            GatewayEvent::MessageCreate(message),
+        )),
+        Err(e) => tracing::error!(
+            "failed to send {} system message in {}: {:?}",
+            kind.as_str(),
+            channel_id,
+            e
+        ),
+    }
+}
+
+/// Returns the channel's pinned messages in the order they were pinned.
+#[tracing::instrument(
+    name = "Getting pinned messages",
+    skip(channel_id, user_id, db_pool),
+    fields(
+        channel_id = %channel_id,
+    )
+)]
+pub async fn get_pins(
+    channel_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let channel_id = channel_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY,
+    )
+    .await
+    {
+        return e;
+    }
+
+    match get_pins_by_channel_id(&db_pool, channel_id).await {
+        Ok(pins) => HttpResponse::Ok().json(pins),
+        Err(e) => {
+            tracing::error!("failed to get pins of channel {}: {:?}", channel_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Pins a message to its channel. Pinning a message that is already pinned
+/// succeeds without doing anything.
+#[tracing::instrument(
+    name = "Pinning message",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn pin(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let channel = match authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::MANAGE_MESSAGES,
+    )
+    .await
+    {
+        Ok((_, channel, _)) => channel,
+        Err(e) => return e,
+    };
+    let message = match find_channel_message(&db_pool, channel_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+    if message.kind().is_system() {
+        let err = format!("system message {} cannot be pinned", message_id);
+        tracing::error!("400 - {}", err);
+        return HttpResponse::BadRequest().body(err);
+    }
+
+    let pinned_at = Utc::now();
+    match insert_pin(&db_pool, channel_id, message_id, user_id, pinned_at).await {
+        Ok(result) if result.rows_affected() > 0 => {}
+        Ok(_) => {
+            return match is_message_pinned(&db_pool, message_id).await {
+                Ok(true) => HttpResponse::NoContent().finish(),
+                Ok(false) => {
+                    let err = format!(
+                        "channel {} already has the maximum of {} pins",
+                        channel_id,
+                        channel.max_pins()
+                    );
+                    tracing::error!("400 - {}", err);
+                    HttpResponse::BadRequest().body(err)
+                }
+                Err(e) => {
+                    tracing::error!("failed to check pin of message {}: {:?}", message_id, e);
+                    HttpResponse::InternalServerError().finish()
+                }
+            };
+        }
+        Err(e) => {
+            tracing::error!("failed to pin message {}: {:?}", message_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    tracing::info!("message {} pinned in channel {}", message_id, channel_id);
+    hub.do_send(Publish::new(
+        Topic::Channel(channel_id),
+        GatewayEvent::MessagePin(PinnedMessage::new(message, user_id, pinned_at)),
+    ));
+    send_pin_system_message(
+        &db_pool,
+        &hub,
+        channel_id,
+        user_id,
+        message_id,
+        MessageKind::MessagePinned,
+    )
+    .await;
+    HttpResponse::NoContent().finish()
+}
+
+#[tracing::instrument(
+    name = "Unpinning message",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn unpin(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) =
+        authorize_channel(&db_pool, channel_id, user_id, Permissions::MANAGE_MESSAGES).await
+    {
+        return e;
+    }
+    if let Err(e) = find_channel_message(&db_pool, channel_id, message_id).await {
+        return e;
+    }
+
+    match delete_pin(&db_pool, message_id).await {
+        Ok(result) if result.rows_affected() > 0 => {}
+        Ok(_) => {
+            let err = format!("message {} is not pinned", message_id);
+            tracing::error!("404 - {}", err);
+            return HttpResponse::NotFound().body(err);
+        }
+        Err(e) => {
+            tracing::error!("failed to unpin message {}: {:?}", message_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    tracing::info!("message {} unpinned in channel {}", message_id, channel_id);
+    hub.do_send(Publish::new(
+        Topic::Channel(channel_id),
+        GatewayEvent::MessageUnpin {
+            channel_id,
+            message_id,
+        },
+    ));
+    send_pin_system_message(
+        &db_pool,
+        &hub,
+        channel_id,
+        user_id,
+        message_id,
+        MessageKind::MessageUnpinned,
+    )
+    .await;
+    HttpResponse::NoContent().finish()
+}
@@ -0,0 +1,331 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        pagination::{Page, PageParams},
+        permission::Permissions,
+        reaction::{ReactionEmoji, ReactionValidationErr, MAX_REACTIONS_PER_MESSAGE},
+        user::GetUserResponse,
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{channel::authorize_channel, message::find_channel_message, middleware::UserID},
+    storage::{
+        delete_reaction, get_reaction_emoji_count, get_reaction_users, get_server_emoji_by_id,
+        insert_reaction, ReactionTarget,
+    },
+};
+
+/// Parses an emoji path segment without checking that a custom emoji still
+/// exists, so reactions with since-deleted emoji can still be listed and
+/// removed.
+pub fn parse_reaction_emoji(value: &str) -> Result<ReactionEmoji, HttpResponse> {
+    ReactionEmoji::try_from(value).map_err(|e| {
+        tracing::error!("400 - invalid reaction emoji {:?}: {:?}", value, e);
+        e.handle_http()
+    })
+}
+
+/// Parses an emoji path segment for a new reaction. Custom emoji must exist
+/// and belong to `server_id`; where there is no server they are rejected.
+pub async fn resolve_reaction_emoji(
+    db_pool: &PgPool,
+    value: &str,
+    server_id: Option<Uuid>,
+) -> Result<ReactionEmoji, HttpResponse> {
+    let emoji = parse_reaction_emoji(value)?;
+    let custom_id = match emoji.custom_id() {
+        Some(custom_id) => custom_id,
+        None => return Ok(emoji),
+    };
+
+    match get_server_emoji_by_id(db_pool, custom_id).await {
+        Ok(custom) if custom.deleted_at().is_none() => {
+            if Some(custom.server_id()) == server_id {
+                Ok(emoji)
+            } else {
+                let err = ReactionValidationErr::CustomEmojiNotAllowed;
+                tracing::error!("400 - {:?}", err);
+                Err(err.handle_http())
+            }
+        }
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("emoji {} not found", custom_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get emoji {}: {:?}", custom_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Fails with a 400 if adding `emoji` would take the message past
+/// `MAX_REACTIONS_PER_MESSAGE` distinct emoji.
+pub async fn ensure_reaction_capacity(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    message_id: Uuid,
+    emoji: &ReactionEmoji,
+) -> Result<(), HttpResponse> {
+    match get_reaction_emoji_count(db_pool, target, message_id, emoji).await {
+        Ok((count, exists)) if exists || count < MAX_REACTIONS_PER_MESSAGE => Ok(()),
+        Ok(_) => {
+            let err = ReactionValidationErr::TooManyReactions;
+            tracing::error!("400 - {:?}", err);
+            Err(err.handle_http())
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to count reactions of message {}: {:?}",
+                message_id,
+                e
+            );
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Pages through the users who reacted to a message with an emoji.
+pub async fn page_reaction_users(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    message_id: Uuid,
+    emoji: &ReactionEmoji,
+    params: &PageParams,
+) -> HttpResponse {
+    match get_reaction_users(db_pool, target, message_id, emoji, params).await {
+        Ok(users) => {
+            let page = Page::from_rows(users, params, |u| u.id()).map(GetUserResponse::from);
+            HttpResponse::Ok().json(page)
+        }
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Adding reaction",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn add_reaction(
+    path: Path<(Uuid, Uuid, String)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id, emoji) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let server = match authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::READ_MESSAGE_HISTORY.union(Permissions::ADD_REACTIONS),
+    )
+    .await
+    {
+        Ok((server, _, _)) => server,
+        Err(e) => return e,
+    };
+    if let Err(e) = find_channel_message(&db_pool, channel_id, message_id).await {
+        return e;
+    }
+    let emoji = match resolve_reaction_emoji(&db_pool, &emoji, Some(server.id())).await {
+        Ok(emoji) => emoji,
+        Err(e) => return e,
+    };
+    if let Err(e) =
+        ensure_reaction_capacity(&db_pool, ReactionTarget::Message, message_id, &emoji).await
+    {
+        return e;
+    }
+
+    match insert_reaction(
+        &db_pool,
+        ReactionTarget::Message,
+        message_id,
+        user_id,
+        &emoji,
+    )
+    .await
+    {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::Channel(channel_id),
+                    GatewayEvent::MessageReactionAdd {
+                        channel_id,
+                        message_id,
+                        user_id,
+                        emoji,
+                    },
+                ));
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to add reaction to message {}: {:?}", message_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+async fn remove_reaction(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    channel_id: Uuid,
+    message_id: Uuid,
+    emoji: &str,
+    actor_id: Uuid,
+    user_id: Uuid,
+) -> HttpResponse {
+    // Removing someone else's reaction is a moderation action.
+    let required = if actor_id == user_id {
+        Permissions::READ_MESSAGE_HISTORY
+    } else {
+        Permissions::READ_MESSAGE_HISTORY.union(Permissions::MANAGE_MESSAGES)
+    };
+    if let Err(e) = authorize_channel(db_pool, channel_id, actor_id, required).await {
+        return e;
+    }
+    if let Err(e) = find_channel_message(db_pool, channel_id, message_id).await {
+        return e;
+    }
+    let emoji = match parse_reaction_emoji(emoji) {
+        Ok(emoji) => emoji,
+        Err(e) => return e,
+    };
+
+    match delete_reaction(
+        db_pool,
+        ReactionTarget::Message,
+        message_id,
+        user_id,
+        &emoji,
+    )
+    .await
+    {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::Channel(channel_id),
+                    GatewayEvent::MessageReactionRemove {
+                        channel_id,
+                        message_id,
+                        user_id,
+                        emoji,
+                    },
+                ));
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to remove reaction from message {}: {:?}",
+                message_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Removing own reaction",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn remove_own_reaction(
+    path: Path<(Uuid, Uuid, String)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id, emoji) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    remove_reaction(
+        &db_pool, &hub, channel_id, message_id, &emoji, user_id, user_id,
+    )
+    .await
+}
+
+#[tracing::instrument(
+    name = "Removing user reaction",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+        user_id = %path.3,
+    )
+)]
+pub async fn remove_user_reaction(
+    path: Path<(Uuid, Uuid, String, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let actor_id = Uuid::from(&user_id.into_inner());
+    let (channel_id, message_id, emoji, user_id) = path.into_inner();
+    remove_reaction(
+        &db_pool, &hub, channel_id, message_id, &emoji, actor_id, user_id,
+    )
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting reaction users",
+    skip(path, params, user_id, db_pool),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn get_reactions(
+    path: Path<(Uuid, Uuid, String)>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (channel_id, message_id, emoji) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::READ_MESSAGE_HISTORY,
+    )
+    .await
+    {
+        return e;
+    }
+    if let Err(e) = find_channel_message(&db_pool, channel_id, message_id).await {
+        return e;
+    }
+    let emoji = match parse_reaction_emoji(&emoji) {
+        Ok(emoji) => emoji,
+        Err(e) => return e,
+    };
+
+    page_reaction_users(
+        &db_pool,
+        ReactionTarget::Message,
+        message_id,
+        &emoji,
+        &params,
+    )
+    .await
+}
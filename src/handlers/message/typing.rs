@@ -0,0 +1,64 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    gateway::{Hub, StartTyping, Topic},
+    handlers::{channel::authorize_channel, middleware::UserID},
+};
+
+#[tracing::instrument(
+    name = "Starting typing indicator",
+    skip(channel_id, user_id, db_pool, hub),
+    fields(
+        channel_id = %channel_id,
+    )
+)]
+pub async fn start_typing(
+    channel_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let channel_id = channel_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let channel =
+        match authorize_channel(&db_pool, channel_id, user_id, Permissions::SEND_MESSAGES).await {
+            Ok((_, channel, _)) => channel,
+            Err(e) => return e,
+        };
+    if !channel.kind().is_messageable() {
+        let err = format!(
+            "typing indicators cannot be sent to {} channels",
+            channel.kind().as_str()
+        );
+        tracing::error!("400 - {}", err);
+        return HttpResponse::BadRequest().body(err);
+    }
+
+    match hub
+        .send(StartTyping {
+            user_id,
+            topic: Topic::Channel(channel_id),
+            session_id: None,
+        })
+        .await
+    {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => {
+            let err = format!("user {} is typing too often", user_id);
+            tracing::error!("429 - {}", err);
+            HttpResponse::TooManyRequests().body(err)
+        }
+        Err(e) => {
+            tracing::error!("failed to start typing in channel {}: {:?}", channel_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{message::MessageContent, permission::Permissions},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        channel::authorize_channel,
+        message::{find_channel_message, resolve_mentions},
+        middleware::UserID,
+    },
+    storage::edit_message,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct EditMessageRequestBody {
+    pub content: String,
+}
+
+#[tracing::instrument(
+    name = "Editing message",
+    skip(path, body, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn edit(
+    path: Path<(Uuid, Uuid)>,
+    body: Json<EditMessageRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (server, channel, permissions) =
+        match authorize_channel(&db_pool, channel_id, user_id, Permissions::VIEW_CHANNEL).await {
+            Ok(authorized) => authorized,
+            Err(e) => return e,
+        };
+
+    let mut message = match find_channel_message(&db_pool, channel_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => return e,
+    };
+    if message.author_id() != user_id {
+        let err = format!("message {} can only be edited by its author", message_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+    if message.kind().is_system() {
+        let err = format!("system message {} cannot be edited", message_id);
+        tracing::error!("400 - {}", err);
+        return HttpResponse::BadRequest().body(err);
+    }
+
+    let content = match MessageContent::try_from(body.into_inner().content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid message content: {:?}", e);
+            return e.handle_http();
+        }
+    };
+    if content == message.content() {
+        return HttpResponse::Ok().json(message);
+    }
+
+    // Edits refresh the stored mentions but do not notify anyone again.
+    let mentions = match resolve_mentions(
+        &db_pool,
+        &hub,
+        &server,
+        &channel,
+        user_id,
+        permissions,
+        &content,
+    )
+    .await
+    {
+        Ok((mentions, _)) => mentions,
+        Err(e) => return e,
+    };
+
+    let now = Utc::now();
+    match edit_message(&db_pool, message_id, user_id, &content, &mentions, now).await {
+        Ok(_) => {
+            tracing::info!("Message {} successfully edited", message_id);
+            message.set_content(content);
+            message.set_mentions(mentions);
+            message.set_edited_at(Some(now));
+            message.set_updated_at(now);
+            hub.do_send(Publish::new(
+                Topic::Channel(channel_id),
+                GatewayEvent::MessageUpdate(message.clone()),
+            ));
+            HttpResponse::Ok().json(message)
+        }
+        Err(e) => {
+            tracing::error!("Failed to edit message {}: {:?}", message_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use actix_web::{
     body::BoxBody,
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage, HttpResponse,
+    Error, HttpMessage, HttpRequest, HttpResponse,
 };
 use futures::{
     future::{ready, Ready},
@@ -12,6 +12,7 @@ use uuid::Uuid;
 
 use crate::{consts::headers, utils::jwt::get_claims_from_token};
 
+#[derive(Clone, Copy, Debug)]
 pub struct UserID(Uuid);
 
 impl UserID {
@@ -26,6 +27,14 @@ impl From<&UserID> for Uuid {
     }
 }
 
+/// The user behind a request's token, for routes that serve anonymous
+/// callers too and so are not wrapped in `AuthMiddleware`.
+pub fn optional_user_id(req: &HttpRequest) -> Option<UserID> {
+    let token = req.headers().get(headers::AUTHORIZATION)?.to_str().ok()?;
+    let claims = get_claims_from_token(token.to_string()).ok()?;
+    Uuid::parse_str(&claims.sub).ok().map(UserID::new)
+}
+
 pub struct AuthMiddleware;
 
 impl<S> Transform<S, ServiceRequest> for AuthMiddleware
@@ -1,4 +1,13 @@
+pub mod channel;
+pub mod dm;
+pub mod gateway;
 pub mod health_check;
+pub mod message;
 pub mod middleware;
+pub mod poll;
+pub mod post;
+pub mod search;
 pub mod server;
+pub mod tag;
+pub mod thread;
 pub mod user;
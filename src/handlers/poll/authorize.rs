@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use actix_web::HttpResponse;
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        permission::Permissions,
+        poll::{NewPoll, Poll, PollOption, PollTarget, PollView, PollVote},
+    },
+    gateway::Topic,
+    handlers::{channel::authorize_channel, post::authorize_post},
+    storage::{get_message_by_id, get_poll_by_id, get_poll_options, get_poll_votes},
+};
+
+/// Loads a poll and checks that the user can see the post or message it is
+/// attached to. Also returns the topic its live results are published to.
+pub async fn authorize_poll(
+    db_pool: &PgPool,
+    poll_id: Uuid,
+    user_id: Uuid,
+) -> Result<(Poll, Topic), HttpResponse> {
+    let not_found = || {
+        let err = format!("poll {} not found", poll_id);
+        tracing::error!(err);
+        HttpResponse::NotFound().body(err)
+    };
+
+    let poll = match get_poll_by_id(db_pool, poll_id).await {
+        Ok(poll) => poll,
+        Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+        Err(e) => {
+            tracing::error!("failed to get poll {}: {:?}", poll_id, e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    let topic = match poll.target() {
+        PollTarget::Post(post_id) => {
+            let (post, _) = authorize_post(db_pool, post_id, user_id, Permissions::empty()).await?;
+            Topic::Server(post.server_id())
+        }
+        PollTarget::Message(message_id) => {
+            let message = match get_message_by_id(db_pool, message_id).await {
+                Ok(message) if message.deleted_at().is_none() => message,
+                Ok(_) | Err(sqlx::Error::RowNotFound) => return Err(not_found()),
+                Err(e) => {
+                    tracing::error!("failed to get message {}: {:?}", message_id, e);
+                    return Err(HttpResponse::InternalServerError().finish());
+                }
+            };
+            authorize_channel(
+                db_pool,
+                message.channel_id(),
+                user_id,
+                Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY,
+            )
+            .await?;
+            Topic::Channel(message.channel_id())
+        }
+    };
+
+    Ok((poll, topic))
+}
+
+/// Validates a poll requested alongside a new post or message, before the
+/// post or message itself is saved.
+pub fn build_poll(
+    draft: NewPoll,
+    author_id: Uuid,
+    target: PollTarget,
+) -> Result<(Poll, Vec<PollOption>), HttpResponse> {
+    Poll::new(Uuid::new_v4(), author_id, target, draft, Utc::now()).map_err(|e| {
+        tracing::error!("400 - invalid poll: {:?}", e);
+        e.handle_http()
+    })
+}
+
+/// How the author of a poll that was just saved first sees it.
+pub fn new_poll_view(poll: Poll, options: Vec<PollOption>) -> PollView {
+    let author_id = poll.author_id();
+    PollView::new(poll, options, &[], author_id, Utc::now())
+}
+
+/// Loads the options and every vote of a poll.
+pub async fn load_poll_votes(
+    db_pool: &PgPool,
+    poll: &Poll,
+) -> Result<(Vec<PollOption>, Vec<PollVote>), HttpResponse> {
+    let ids = [poll.id()];
+    let options = get_poll_options(db_pool, &ids).await.map_err(|e| {
+        tracing::error!("failed to get options of poll {}: {:?}", poll.id(), e);
+        HttpResponse::InternalServerError().finish()
+    })?;
+    let votes = get_poll_votes(db_pool, &ids).await.map_err(|e| {
+        tracing::error!("failed to get votes of poll {}: {:?}", poll.id(), e);
+        HttpResponse::InternalServerError().finish()
+    })?;
+    Ok((options, votes))
+}
+
+/// Turns polls into what `viewer_id` sees of them, order kept.
+pub async fn build_poll_views(
+    db_pool: &PgPool,
+    polls: Vec<Poll>,
+    viewer_id: Uuid,
+) -> Result<Vec<PollView>, HttpResponse> {
+    if polls.is_empty() {
+        return Ok(vec![]);
+    }
+    let ids: Vec<Uuid> = polls.iter().map(Poll::id).collect();
+    let mut options: HashMap<Uuid, Vec<PollOption>> = HashMap::new();
+    match get_poll_options(db_pool, &ids).await {
+        Ok(rows) => {
+            for option in rows {
+                options.entry(option.poll_id()).or_default().push(option);
+            }
+        }
+        Err(e) => {
+            tracing::error!("failed to get poll options: {:?}", e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    }
+    let mut votes: HashMap<Uuid, Vec<PollVote>> = HashMap::new();
+    match get_poll_votes(db_pool, &ids).await {
+        Ok(rows) => {
+            for vote in rows {
+                votes.entry(vote.poll_id).or_default().push(vote);
+            }
+        }
+        Err(e) => {
+            tracing::error!("failed to get poll votes: {:?}", e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    }
+
+    let now = Utc::now();
+    Ok(polls
+        .into_iter()
+        .map(|poll| {
+            let options = options.remove(&poll.id()).unwrap_or_default();
+            let votes = votes.remove(&poll.id()).unwrap_or_default();
+            PollView::new(poll, options, &votes, viewer_id, now)
+        })
+        .collect())
+}
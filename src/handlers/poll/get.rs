@@ -0,0 +1,37 @@
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::handlers::{
+    middleware::UserID,
+    poll::{authorize_poll, build_poll_views},
+};
+
+#[tracing::instrument(
+    name = "Getting poll by ID",
+    skip(poll_id, user_id, db_pool),
+    fields(
+        poll_id = %poll_id,
+    )
+)]
+pub async fn get_by_id(
+    poll_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let poll_id = poll_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let poll = match authorize_poll(&db_pool, poll_id, user_id).await {
+        Ok((poll, _)) => poll,
+        Err(e) => return e,
+    };
+
+    match build_poll_views(&db_pool, vec![poll], user_id).await {
+        Ok(mut polls) => HttpResponse::Ok().json(polls.pop()),
+        Err(e) => e,
+    }
+}
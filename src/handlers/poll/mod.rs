@@ -0,0 +1,11 @@
+mod authorize;
+mod get;
+mod vote;
+
+pub use authorize::*;
+pub use get::*;
+pub use vote::*;
+
+pub const BASE_PATH: &str = "/polls";
+pub const VOTES_PATH: &str = "/votes";
+pub const CLOSE_PATH: &str = "/close";
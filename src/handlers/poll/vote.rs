@@ -0,0 +1,185 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::poll::{Poll, PollTally, PollValidationErr, PollView},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        middleware::UserID,
+        poll::{authorize_poll, load_poll_votes},
+    },
+    storage::{close_poll, delete_poll_votes, replace_poll_votes},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct VoteRequestBody {
+    pub option_ids: Vec<Uuid>,
+}
+
+/// Pushes the poll's current tally to everyone following it and returns
+/// how `user_id` now sees the poll.
+async fn publish_tally(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    poll: Poll,
+    topic: Topic,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<PollView, HttpResponse> {
+    let (options, votes) = load_poll_votes(db_pool, &poll).await?;
+    hub.do_send(Publish::new(
+        topic,
+        GatewayEvent::PollUpdate(PollTally::new(&poll, &options, &votes, now)),
+    ));
+    Ok(PollView::new(poll, options, &votes, user_id, now))
+}
+
+/// Votes in a poll, replacing any earlier vote by the user.
+#[tracing::instrument(
+    name = "Voting in poll",
+    skip(poll_id, body, user_id, db_pool, hub),
+    fields(
+        poll_id = %poll_id,
+    )
+)]
+pub async fn vote(
+    poll_id: Path<Uuid>,
+    body: Json<VoteRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let poll_id = poll_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let now = Utc::now();
+
+    let (poll, topic) = match authorize_poll(&db_pool, poll_id, user_id).await {
+        Ok(authorized) => authorized,
+        Err(e) => return e,
+    };
+    let (options, _) = match load_poll_votes(&db_pool, &poll).await {
+        Ok(loaded) => loaded,
+        Err(e) => return e,
+    };
+    let option_ids = match poll.validate_choice(&options, &body.option_ids, now) {
+        Ok(option_ids) => option_ids,
+        Err(e) => {
+            tracing::error!("400 - invalid vote in poll {}: {:?}", poll_id, e);
+            return e.handle_http();
+        }
+    };
+
+    if let Err(e) = replace_poll_votes(&db_pool, poll_id, user_id, &option_ids, now).await {
+        tracing::error!("failed to vote in poll {}: {:?}", poll_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    match publish_tally(&db_pool, &hub, poll, topic, user_id, now).await {
+        Ok(view) => HttpResponse::Ok().json(view),
+        Err(e) => e,
+    }
+}
+
+/// Takes back the user's vote while the poll is still open.
+#[tracing::instrument(
+    name = "Retracting poll vote",
+    skip(poll_id, user_id, db_pool, hub),
+    fields(
+        poll_id = %poll_id,
+    )
+)]
+pub async fn retract_vote(
+    poll_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let poll_id = poll_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let now = Utc::now();
+
+    let (poll, topic) = match authorize_poll(&db_pool, poll_id, user_id).await {
+        Ok(authorized) => authorized,
+        Err(e) => return e,
+    };
+    if poll.is_closed(now) {
+        tracing::error!("400 - poll {} is closed", poll_id);
+        return PollValidationErr::Closed.handle_http();
+    }
+
+    match delete_poll_votes(&db_pool, poll_id, user_id).await {
+        Ok(result) if result.rows_affected() > 0 => {}
+        Ok(_) => {
+            let err = format!("user {} has not voted in poll {}", user_id, poll_id);
+            tracing::error!("404 - {}", err);
+            return HttpResponse::NotFound().body(err);
+        }
+        Err(e) => {
+            tracing::error!("failed to retract vote in poll {}: {:?}", poll_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    match publish_tally(&db_pool, &hub, poll, topic, user_id, now).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => e,
+    }
+}
+
+/// Closes a poll before its close time. Only its author may do so.
+#[tracing::instrument(
+    name = "Closing poll",
+    skip(poll_id, user_id, db_pool, hub),
+    fields(
+        poll_id = %poll_id,
+    )
+)]
+pub async fn close(
+    poll_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let poll_id = poll_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let now = Utc::now();
+
+    let (mut poll, topic) = match authorize_poll(&db_pool, poll_id, user_id).await {
+        Ok(authorized) => authorized,
+        Err(e) => return e,
+    };
+    if poll.author_id() != user_id {
+        let err = format!("user {} did not create poll {}", user_id, poll_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+    if let Err(e) = poll.close(now) {
+        tracing::error!("400 - poll {} is closed", poll_id);
+        return e.handle_http();
+    }
+
+    match close_poll(&db_pool, poll_id, now).await {
+        Ok(result) if result.rows_affected() > 0 => {}
+        Ok(_) => {
+            tracing::error!("400 - poll {} was closed concurrently", poll_id);
+            return PollValidationErr::Closed.handle_http();
+        }
+        Err(e) => {
+            tracing::error!("failed to close poll {}: {:?}", poll_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    tracing::info!("poll {} closed early", poll_id);
+    match publish_tally(&db_pool, &hub, poll, topic, user_id, now).await {
+        Ok(view) => HttpResponse::Ok().json(view),
+        Err(e) => e,
+    }
+}
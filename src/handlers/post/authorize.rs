@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        permission::Permissions,
+        poll::{PollTarget, PollView},
+        post::{LikeSummary, Post, PostView},
+    },
+    handlers::{poll::build_poll_views, server::authorize_server},
+    storage::{get_polls_by_targets, get_post_by_id, get_post_stats, get_posts_by_ids},
+};
+
+/// Loads a post that has not been soft deleted. On failure, returns the
+/// response the handler should send.
+pub async fn find_post(db_pool: &PgPool, post_id: Uuid) -> Result<Post, HttpResponse> {
+    match get_post_by_id(db_pool, post_id).await {
+        Ok(post) if post.deleted_at().is_none() => Ok(post),
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("post {} not found", post_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get post {}: {:?}", post_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Loads a post and checks that the user holds the `required` permissions
+/// in its server.
+pub async fn authorize_post(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    user_id: Uuid,
+    required: Permissions,
+) -> Result<(Post, Permissions), HttpResponse> {
+    let post = find_post(db_pool, post_id).await?;
+    let (_, permissions) = authorize_server(db_pool, post.server_id(), user_id, required).await?;
+    Ok((post, permissions))
+}
+
+/// Turns posts into what `viewer_id` sees: quotes inlined, like and comment
+/// counts and polls filled in, order kept.
+pub async fn build_post_views(
+    db_pool: &PgPool,
+    posts: Vec<Post>,
+    viewer_id: Uuid,
+) -> Result<Vec<PostView>, HttpResponse> {
+    if posts.is_empty() {
+        return Ok(vec![]);
+    }
+    let quoted_ids: Vec<Uuid> = posts.iter().filter_map(Post::quoted_post_id).collect();
+    let quoted: HashMap<Uuid, Post> = if quoted_ids.is_empty() {
+        HashMap::new()
+    } else {
+        match get_posts_by_ids(db_pool, &quoted_ids).await {
+            Ok(quoted) => quoted.into_iter().map(|p| (p.id(), p)).collect(),
+            Err(e) => {
+                tracing::error!("failed to get quoted posts: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        }
+    };
+    let ids: Vec<Uuid> = posts.iter().map(Post::id).collect();
+    let stats: HashMap<Uuid, (LikeSummary, i64)> =
+        match get_post_stats(db_pool, &ids, viewer_id).await {
+            Ok(stats) => stats
+                .into_iter()
+                .map(|(id, like_count, liked, comment_count)| {
+                    (id, (LikeSummary { like_count, liked }, comment_count))
+                })
+                .collect(),
+            Err(e) => {
+                tracing::error!("failed to get post stats: {:?}", e);
+                return Err(HttpResponse::InternalServerError().finish());
+            }
+        };
+    let polls = match get_polls_by_targets(db_pool, &ids, &[]).await {
+        Ok(polls) => polls,
+        Err(e) => {
+            tracing::error!("failed to get post polls: {:?}", e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+    let mut polls: HashMap<Uuid, PollView> = build_poll_views(db_pool, polls, viewer_id)
+        .await?
+        .into_iter()
+        .filter_map(|view| match view.poll.target() {
+            PollTarget::Post(post_id) => Some((post_id, view)),
+            PollTarget::Message(_) => None,
+        })
+        .collect();
+    Ok(posts
+        .into_iter()
+        .map(|post| {
+            let quoted_post = post
+                .quoted_post_id()
+                .and_then(|id| quoted.get(&id).cloned());
+            let (likes, comment_count) = stats.get(&post.id()).copied().unwrap_or_default();
+            let mut view = PostView::new(post, quoted_post);
+            view.likes = likes;
+            view.comment_count = comment_count;
+            view.poll = polls.remove(&view.id());
+            view
+        })
+        .collect())
+}
@@ -0,0 +1,271 @@
+use actix_web::{
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        message::MessageContent,
+        pagination::{Page, PageParams},
+        permission::Permissions,
+        post::{Comment, CommentSort, CommentView},
+    },
+    handlers::{middleware::UserID, post::authorize_post},
+    storage::{
+        edit_comment, get_comment_by_id, get_comment_view_by_id, get_comments_by_post_id,
+        insert_comment, soft_delete_comment,
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateCommentRequestBody {
+    pub content: String,
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EditCommentRequestBody {
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct GetCommentsQuery {
+    #[serde(default)]
+    pub sort: CommentSort,
+    /// Lists the replies to this comment instead of the top level comments.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+/// Loads a comment that has not been soft deleted and belongs to `post_id`.
+/// On failure, returns the response the handler should send.
+pub async fn find_post_comment(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    comment_id: Uuid,
+) -> Result<Comment, HttpResponse> {
+    match get_comment_by_id(db_pool, comment_id).await {
+        Ok(comment) if comment.deleted_at().is_none() && comment.post_id() == post_id => {
+            Ok(comment)
+        }
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("comment {} not found", comment_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get comment {}: {:?}", comment_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+async fn comment_view(db_pool: &PgPool, comment_id: Uuid, user_id: Uuid) -> HttpResponse {
+    match get_comment_view_by_id(db_pool, comment_id, user_id).await {
+        Ok(view) => HttpResponse::Ok().json(view),
+        Err(e) => {
+            tracing::error!("failed to get comment {}: {:?}", comment_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Creating comment",
+    skip(post_id, body, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn create_comment(
+    post_id: Path<Uuid>,
+    body: Json<CreateCommentRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let post_id = post_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_post(&db_pool, post_id, user_id, Permissions::SEND_MESSAGES).await {
+        return e;
+    }
+
+    let body = body.into_inner();
+    if let Some(parent_id) = body.parent_id {
+        if let Err(e) = find_post_comment(&db_pool, post_id, parent_id).await {
+            return e;
+        }
+    }
+
+    let content = match MessageContent::try_from(body.content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid comment content: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    let comment = Comment::new(
+        Uuid::new_v4(),
+        post_id,
+        body.parent_id,
+        user_id,
+        content,
+        Utc::now(),
+    );
+    match insert_comment(&db_pool, &comment).await {
+        Ok(_) => {
+            tracing::info!("Comment {} successfully inserted to database", comment.id());
+            HttpResponse::Ok().json(CommentView::new(comment))
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Lists the comments on a post, newest or most liked first.
+#[tracing::instrument(
+    name = "Getting comments by post ID",
+    skip(post_id, query, params, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn get_comments(
+    post_id: Path<Uuid>,
+    query: Query<GetCommentsQuery>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let post_id = post_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let query = query.into_inner();
+    let params = params.into_inner();
+
+    if let Err(e) = authorize_post(&db_pool, post_id, user_id, Permissions::empty()).await {
+        return e;
+    }
+    if let Some(parent_id) = query.parent_id {
+        if let Err(e) = find_post_comment(&db_pool, post_id, parent_id).await {
+            return e;
+        }
+    }
+
+    match get_comments_by_post_id(
+        &db_pool,
+        post_id,
+        query.parent_id,
+        user_id,
+        query.sort,
+        &params,
+    )
+    .await
+    {
+        Ok(comments) => HttpResponse::Ok().json(Page::from_rows(comments, &params, |c| c.id())),
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Editing comment",
+    skip(path, body, user_id, db_pool),
+    fields(
+        post_id = %path.0,
+        comment_id = %path.1,
+    )
+)]
+pub async fn update_comment(
+    path: Path<(Uuid, Uuid)>,
+    body: Json<EditCommentRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (post_id, comment_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_post(&db_pool, post_id, user_id, Permissions::empty()).await {
+        return e;
+    }
+    let comment = match find_post_comment(&db_pool, post_id, comment_id).await {
+        Ok(comment) => comment,
+        Err(e) => return e,
+    };
+    if comment.author_id() != user_id {
+        let err = format!("comment {} can only be edited by its author", comment_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    let content = match MessageContent::try_from(body.into_inner().content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid comment content: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    if let Err(e) = edit_comment(&db_pool, comment_id, &content, Utc::now()).await {
+        tracing::error!("failed to edit comment {}: {:?}", comment_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    comment_view(&db_pool, comment_id, user_id).await
+}
+
+/// Comments can be deleted by their author, by the author of the post they
+/// are on, and by anyone who can manage messages in the server.
+#[tracing::instrument(
+    name = "Soft deleting comment",
+    skip(path, user_id, db_pool),
+    fields(
+        post_id = %path.0,
+        comment_id = %path.1,
+    )
+)]
+pub async fn delete_comment(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (post_id, comment_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (post, permissions) =
+        match authorize_post(&db_pool, post_id, user_id, Permissions::empty()).await {
+            Ok(authorized) => authorized,
+            Err(e) => return e,
+        };
+    let comment = match find_post_comment(&db_pool, post_id, comment_id).await {
+        Ok(comment) => comment,
+        Err(e) => return e,
+    };
+    if comment.author_id() != user_id
+        && post.author_id() != user_id
+        && !permissions.contains(Permissions::MANAGE_MESSAGES)
+    {
+        let err = format!(
+            "user {} may not delete comment {} from another user",
+            user_id, comment_id
+        );
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    match soft_delete_comment(&db_pool, comment_id, Utc::now()).await {
+        Ok(_) => {
+            tracing::info!("comment {} successfully soft deleted", comment_id);
+            HttpResponse::Ok().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to soft delete comment {}: {:?}", comment_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
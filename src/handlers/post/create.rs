@@ -0,0 +1,116 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        permission::Permissions,
+        poll::{NewPoll, PollTarget},
+        post::{Post, PostContent, PostView},
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        middleware::UserID,
+        poll::{build_poll, new_poll_view},
+        post::find_post,
+        server::authorize_server,
+    },
+    storage::insert_post,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreatePostRequestBody {
+    pub content: String,
+    #[serde(default)]
+    pub quoted_post_id: Option<Uuid>,
+    #[serde(default)]
+    pub poll: Option<NewPoll>,
+}
+
+#[tracing::instrument(
+    name = "Creating post",
+    skip(server_id, body, user_id, db_pool, hub),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn create(
+    server_id: Path<Uuid>,
+    body: Json<CreatePostRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_server(&db_pool, server_id, user_id, Permissions::SEND_MESSAGES).await
+    {
+        return e;
+    }
+
+    let body = body.into_inner();
+    let quoted_post = match body.quoted_post_id {
+        Some(quoted_post_id) => match find_post(&db_pool, quoted_post_id).await {
+            Ok(quoted) if quoted.server_id() == server_id => Some(quoted),
+            Ok(_) => {
+                let err = "posts can only quote posts from the same server";
+                tracing::error!("400 - {}", err);
+                return HttpResponse::BadRequest().body(err);
+            }
+            Err(e) => return e,
+        },
+        None => None,
+    };
+
+    let content = match PostContent::try_from(body.content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid post content: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    let post = Post::new(
+        Uuid::new_v4(),
+        server_id,
+        user_id,
+        content,
+        body.quoted_post_id,
+        Utc::now(),
+    );
+    let poll = match body.poll {
+        Some(draft) => match build_poll(draft, user_id, PollTarget::Post(post.id())) {
+            Ok(poll) => Some(poll),
+            Err(e) => return e,
+        },
+        None => None,
+    };
+
+    let saved_poll = poll
+        .as_ref()
+        .map(|(poll, options)| (poll, options.as_slice()));
+    match insert_post(&db_pool, &post, saved_poll).await {
+        Ok(_) => {
+            tracing::info!("Post {} successfully inserted to database", post.id());
+            let poll = poll.map(|(poll, options)| new_poll_view(poll, options));
+            hub.do_send(Publish::new(
+                Topic::Server(server_id),
+                GatewayEvent::PostCreate(post.clone()),
+            ));
+            let mut view = PostView::new(post, quoted_post);
+            view.poll = poll;
+            HttpResponse::Ok().json(view)
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{middleware::UserID, post::authorize_post},
+    storage::soft_delete_post,
+};
+
+#[tracing::instrument(
+    name = "Soft deleting post",
+    skip(post_id, user_id, db_pool, hub),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn soft_delete(
+    post_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let post_id = post_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let (post, permissions) =
+        match authorize_post(&db_pool, post_id, user_id, Permissions::empty()).await {
+            Ok(authorized) => authorized,
+            Err(e) => return e,
+        };
+    if post.author_id() != user_id && !permissions.contains(Permissions::MANAGE_MESSAGES) {
+        let err = format!(
+            "user {} may not delete post {} from another user",
+            user_id, post_id
+        );
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    match soft_delete_post(&db_pool, post_id, Utc::now()).await {
+        Ok(_) => {
+            tracing::info!("post {} successfully soft deleted", post_id);
+            hub.do_send(Publish::new(
+                Topic::Server(post.server_id()),
+                GatewayEvent::PostDelete {
+                    id: post_id,
+                    server_id: post.server_id(),
+                },
+            ));
+            HttpResponse::Ok().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to soft delete post {}: {:?}", post_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
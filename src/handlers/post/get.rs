@@ -0,0 +1,81 @@
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        pagination::{Page, PageParams},
+        permission::Permissions,
+        post::PostView,
+    },
+    handlers::{
+        middleware::UserID,
+        post::{authorize_post, build_post_views},
+        server::authorize_server,
+    },
+    storage::get_posts_by_server_id,
+};
+
+#[tracing::instrument(
+    name = "Getting post by ID",
+    skip(post_id, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn get_by_id(
+    post_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let post_id = post_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let post = match authorize_post(&db_pool, post_id, user_id, Permissions::empty()).await {
+        Ok((post, _)) => post,
+        Err(e) => return e,
+    };
+
+    match build_post_views(&db_pool, vec![post], user_id).await {
+        Ok(mut posts) => HttpResponse::Ok().json(posts.pop()),
+        Err(e) => e,
+    }
+}
+
+/// Returns a server's feed of posts, newest first.
+#[tracing::instrument(
+    name = "Getting posts by server ID",
+    skip(server_id, params, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn get_many_by_server(
+    server_id: Path<Uuid>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    if let Err(e) = authorize_server(&db_pool, server_id, user_id, Permissions::empty()).await {
+        return e;
+    }
+
+    let posts = match get_posts_by_server_id(&db_pool, server_id, user_id, &params).await {
+        Ok(posts) => posts,
+        Err(e) => return e.handle_http(),
+    };
+
+    let page = Page::from_rows(posts, &params, |p| p.id());
+    let next_cursor = page.next_cursor();
+    match build_post_views(&db_pool, page.into_items(), user_id).await {
+        Ok(posts) => HttpResponse::Ok().json(Page::<PostView>::new(posts, next_cursor)),
+        Err(e) => e,
+    }
+}
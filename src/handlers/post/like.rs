@@ -0,0 +1,161 @@
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    handlers::{
+        middleware::UserID,
+        post::{authorize_post, find_post_comment},
+    },
+    storage::{
+        delete_comment_like, delete_post_like, get_comment_like_summary, get_post_like_summary,
+        insert_comment_like, insert_post_like,
+    },
+};
+
+/// Likes are idempotent: liking twice or unliking something not liked
+/// succeeds without changing anything. Both return the updated count.
+#[tracing::instrument(
+    name = "Liking post",
+    skip(post_id, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn like_post(
+    post_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    set_post_like(post_id.into_inner(), user_id.into_inner(), db_pool, true).await
+}
+
+#[tracing::instrument(
+    name = "Unliking post",
+    skip(post_id, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn unlike_post(
+    post_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    set_post_like(post_id.into_inner(), user_id.into_inner(), db_pool, false).await
+}
+
+async fn set_post_like(
+    post_id: Uuid,
+    user_id: UserID,
+    db_pool: Data<PgPool>,
+    liked: bool,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id);
+
+    // Taking a like back needs no permission, in case it has been revoked.
+    let required = if liked {
+        Permissions::ADD_REACTIONS
+    } else {
+        Permissions::empty()
+    };
+    if let Err(e) = authorize_post(&db_pool, post_id, user_id, required).await {
+        return e;
+    }
+
+    let result = if liked {
+        insert_post_like(&db_pool, post_id, user_id).await
+    } else {
+        delete_post_like(&db_pool, post_id, user_id).await
+    };
+    if let Err(e) = result {
+        tracing::error!("failed to update like on post {}: {:?}", post_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    match get_post_like_summary(&db_pool, post_id, user_id).await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(e) => {
+            tracing::error!("failed to count likes on post {}: {:?}", post_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Liking comment",
+    skip(path, user_id, db_pool),
+    fields(
+        post_id = %path.0,
+        comment_id = %path.1,
+    )
+)]
+pub async fn like_comment(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (post_id, comment_id) = path.into_inner();
+    set_comment_like(post_id, comment_id, user_id.into_inner(), db_pool, true).await
+}
+
+#[tracing::instrument(
+    name = "Unliking comment",
+    skip(path, user_id, db_pool),
+    fields(
+        post_id = %path.0,
+        comment_id = %path.1,
+    )
+)]
+pub async fn unlike_comment(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let (post_id, comment_id) = path.into_inner();
+    set_comment_like(post_id, comment_id, user_id.into_inner(), db_pool, false).await
+}
+
+async fn set_comment_like(
+    post_id: Uuid,
+    comment_id: Uuid,
+    user_id: UserID,
+    db_pool: Data<PgPool>,
+    liked: bool,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id);
+
+    let required = if liked {
+        Permissions::ADD_REACTIONS
+    } else {
+        Permissions::empty()
+    };
+    if let Err(e) = authorize_post(&db_pool, post_id, user_id, required).await {
+        return e;
+    }
+    if let Err(e) = find_post_comment(&db_pool, post_id, comment_id).await {
+        return e;
+    }
+
+    let result = if liked {
+        insert_comment_like(&db_pool, comment_id, user_id).await
+    } else {
+        delete_comment_like(&db_pool, comment_id, user_id).await
+    };
+    if let Err(e) = result {
+        tracing::error!("failed to update like on comment {}: {:?}", comment_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    match get_comment_like_summary(&db_pool, comment_id, user_id).await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(e) => {
+            tracing::error!("failed to count likes on comment {}: {:?}", comment_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,19 @@
+mod authorize;
+mod comment;
+mod create;
+mod delete;
+mod get;
+mod like;
+mod update;
+
+pub use authorize::*;
+pub use comment::*;
+pub use create::*;
+pub use delete::*;
+pub use get::*;
+pub use like::*;
+pub use update::*;
+
+pub const BASE_PATH: &str = "/posts";
+pub const COMMENTS_PATH: &str = "/comments";
+pub const LIKES_PATH: &str = "/likes";
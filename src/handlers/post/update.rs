@@ -0,0 +1,78 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{permission::Permissions, post::PostContent},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        middleware::UserID,
+        post::{authorize_post, build_post_views},
+    },
+    storage::edit_post,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct EditPostRequestBody {
+    pub content: String,
+}
+
+#[tracing::instrument(
+    name = "Editing post",
+    skip(post_id, body, user_id, db_pool, hub),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn edit(
+    post_id: Path<Uuid>,
+    body: Json<EditPostRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let post_id = post_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let mut post = match authorize_post(&db_pool, post_id, user_id, Permissions::empty()).await {
+        Ok((post, _)) => post,
+        Err(e) => return e,
+    };
+    if post.author_id() != user_id {
+        let err = format!("post {} can only be edited by its author", post_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    let content = match PostContent::try_from(body.into_inner().content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid post content: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    let now = Utc::now();
+    if let Err(e) = edit_post(&db_pool, post_id, &content, now).await {
+        tracing::error!("failed to edit post {}: {:?}", post_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    post.set_content(content);
+    post.set_edited_at(Some(now));
+    post.set_updated_at(now);
+    hub.do_send(Publish::new(
+        Topic::Server(post.server_id()),
+        GatewayEvent::PostUpdate(post.clone()),
+    ));
+
+    match build_post_views(&db_pool, vec![post], user_id).await {
+        Ok(mut posts) => HttpResponse::Ok().json(posts.pop()),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,106 @@
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        pagination::{Page, PageParams},
+        permission::Permissions,
+        search::{DmSearchParams, MessageSearchParams},
+    },
+    handlers::{middleware::UserID, server::authorize_server},
+    storage::{get_channels_with_permissions, search_direct_messages, search_server_messages},
+};
+
+/// Searches a server's messages, newest first. Only channels the user can
+/// view and read the history of are searched, along with their threads.
+#[tracing::instrument(
+    name = "Searching server messages",
+    skip(server_id, search, params, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+        q = %search.q,
+    )
+)]
+pub async fn server_messages(
+    server_id: Path<Uuid>,
+    search: Query<MessageSearchParams>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    if let Err(e) = search.validate() {
+        tracing::error!("invalid search in server {}: {:?}", server_id, e);
+        return e.handle_http();
+    }
+
+    let (server, _) =
+        match authorize_server(&db_pool, server_id, user_id, Permissions::empty()).await {
+            Ok(authorized) => authorized,
+            Err(e) => return e,
+        };
+
+    let readable = match get_channels_with_permissions(
+        &db_pool,
+        &server,
+        user_id,
+        Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY,
+    )
+    .await
+    {
+        Ok(channels) => channels,
+        Err(e) => {
+            tracing::error!(
+                "failed to resolve readable channels in server {}: {:?}",
+                server_id,
+                e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let channel_ids: Vec<Uuid> = readable.iter().map(|c| c.id()).collect();
+
+    match search_server_messages(&db_pool, user_id, &channel_ids, &search, &params).await {
+        Ok(results) => {
+            HttpResponse::Ok().json(Page::from_rows(results, &params, |r| r.message().id()))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
+
+/// Searches every DM thread the user takes part in, newest first.
+#[tracing::instrument(
+    name = "Searching direct messages",
+    skip(search, params, user_id, db_pool),
+    fields(
+        q = %search.q,
+    )
+)]
+pub async fn direct_messages(
+    search: Query<DmSearchParams>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    if let Err(e) = search.validate() {
+        tracing::error!("invalid search of direct messages: {:?}", e);
+        return e.handle_http();
+    }
+
+    match search_direct_messages(&db_pool, user_id, &search, &params).await {
+        Ok(results) => {
+            HttpResponse::Ok().json(Page::from_rows(results, &params, |r| r.message().id()))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
@@ -0,0 +1,5 @@
+mod message;
+
+pub use message::*;
+
+pub const BASE_PATH: &str = "/search";
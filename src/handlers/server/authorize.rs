@@ -0,0 +1,60 @@
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{permission::Permissions, server::Server},
+    storage::{get_server_by_id, get_server_permissions},
+};
+
+/// Loads a server that has not been soft deleted and checks that the user
+/// holds the `required` permissions in it. On failure, returns the response
+/// the handler should send.
+pub async fn authorize_server(
+    db_pool: &PgPool,
+    server_id: Uuid,
+    user_id: Uuid,
+    required: Permissions,
+) -> Result<(Server, Permissions), HttpResponse> {
+    let server = match get_server_by_id(db_pool, server_id).await {
+        Ok(server) if server.deleted_at().is_none() => server,
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("server {} not found", server_id);
+            tracing::error!(err);
+            return Err(HttpResponse::NotFound().body(err));
+        }
+        Err(e) => {
+            tracing::error!("failed to get server {}: {:?}", server_id, e);
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    let permissions = match get_server_permissions(db_pool, &server, user_id).await {
+        Ok((permissions, _)) => permissions,
+        Err(e) => {
+            tracing::error!(
+                "failed to resolve permissions for user {} in server {}: {:?}",
+                user_id,
+                server_id,
+                e
+            );
+            return Err(HttpResponse::InternalServerError().finish());
+        }
+    };
+
+    if permissions.is_empty() {
+        let err = format!("user {} is not a member of server {}", user_id, server_id);
+        tracing::error!(err);
+        return Err(HttpResponse::Forbidden().body(err));
+    }
+    if !permissions.contains(required) {
+        let err = format!(
+            "user {} is missing permissions in server {}",
+            user_id, server_id
+        );
+        tracing::error!(err);
+        return Err(HttpResponse::Forbidden().body(err));
+    }
+
+    Ok((server, permissions))
+}
@@ -1,3 +1,4 @@
+use actix::Addr;
 use actix_web::{
     web::{Data, Path},
     HttpResponse,
@@ -6,16 +7,23 @@ use chrono::Utc;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::storage::{get_server_by_id, hard_delete_server, soft_delete_server};
+use crate::{
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    storage::{get_server_by_id, hard_delete_server, soft_delete_server},
+};
 
 #[tracing::instrument(
     name = "Soft Deleting Server",
-    skip(server_id, db_pool),
+    skip(server_id, db_pool, hub),
     fields(
         id = %server_id,
     )
 )]
-pub async fn soft_delete(server_id: Path<Uuid>, db_pool: Data<PgPool>) -> HttpResponse {
+pub async fn soft_delete(
+    server_id: Path<Uuid>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
     let now = Utc::now();
     let id = server_id.into_inner();
 
@@ -29,6 +37,10 @@ pub async fn soft_delete(server_id: Path<Uuid>, db_pool: Data<PgPool>) -> HttpRe
                 match soft_delete_server(&db_pool, id, now).await {
                     Ok(_) => {
                         tracing::info!("server {} successfully soft deleted", id);
+                        hub.do_send(Publish::new(
+                            Topic::Server(id),
+                            GatewayEvent::ServerDelete { id },
+                        ));
                         HttpResponse::Ok().finish()
                     }
                     Err(e) => {
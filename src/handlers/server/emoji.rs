@@ -0,0 +1,159 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{permission::Permissions, reaction::ServerEmoji},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{middleware::UserID, server::authorize_server},
+    storage::{
+        get_server_emoji_by_id, get_server_emojis_by_server_id, insert_server_emoji,
+        soft_delete_server_emoji,
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateEmojiRequestBody {
+    pub name: String,
+}
+
+#[tracing::instrument(
+    name = "Creating server emoji",
+    skip(server_id, body, user_id, db_pool, hub),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn create_emoji(
+    server_id: Path<Uuid>,
+    body: Json<CreateEmojiRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_server(&db_pool, server_id, user_id, Permissions::MANAGE_EMOJIS).await
+    {
+        return e;
+    }
+
+    let name = body.into_inner().name;
+    if let Err(e) = ServerEmoji::validate_name(&name) {
+        tracing::error!("400 - invalid emoji name: {:?}", e);
+        return e.handle_http();
+    }
+
+    let emoji = ServerEmoji::new(Uuid::new_v4(), server_id, name, user_id, Utc::now(), None);
+    match insert_server_emoji(&db_pool, &emoji).await {
+        Ok(_) => {
+            tracing::info!("Emoji {} successfully inserted to database", emoji.id());
+            hub.do_send(Publish::new(
+                Topic::Server(server_id),
+                GatewayEvent::EmojiCreate(emoji.clone()),
+            ));
+            HttpResponse::Ok().json(emoji)
+        }
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23505") => {
+            let err = format!(
+                "server {} already has an emoji named {}",
+                server_id,
+                emoji.name()
+            );
+            tracing::error!("409 - {}", err);
+            HttpResponse::Conflict().body(err)
+        }
+        Err(e) => {
+            tracing::error!("failed to insert emoji {}: {:?}", emoji.id(), e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting server emojis",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn get_emojis(
+    server_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_server(&db_pool, server_id, user_id, Permissions::empty()).await {
+        return e;
+    }
+
+    match get_server_emojis_by_server_id(&db_pool, server_id).await {
+        Ok(emojis) => HttpResponse::Ok().json(emojis),
+        Err(e) => {
+            tracing::error!("failed to get emojis of server {}: {:?}", server_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Deleting server emoji",
+    skip(path, user_id, db_pool, hub),
+    fields(
+        server_id = %path.0,
+        emoji_id = %path.1,
+    )
+)]
+pub async fn delete_emoji(
+    path: Path<(Uuid, Uuid)>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (server_id, emoji_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_server(&db_pool, server_id, user_id, Permissions::MANAGE_EMOJIS).await
+    {
+        return e;
+    }
+
+    match get_server_emoji_by_id(&db_pool, emoji_id).await {
+        Ok(emoji) if emoji.deleted_at().is_none() && emoji.server_id() == server_id => {}
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("emoji {} not found", emoji_id);
+            tracing::error!(err);
+            return HttpResponse::NotFound().body(err);
+        }
+        Err(e) => {
+            tracing::error!("failed to get emoji {}: {:?}", emoji_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    match soft_delete_server_emoji(&db_pool, emoji_id).await {
+        Ok(_) => {
+            hub.do_send(Publish::new(
+                Topic::Server(server_id),
+                GatewayEvent::EmojiDelete {
+                    id: emoji_id,
+                    server_id,
+                },
+            ));
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to delete emoji {}: {:?}", emoji_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
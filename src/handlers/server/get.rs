@@ -0,0 +1,123 @@
+use actix_web::{
+    web::{Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::pagination::{Page, PageParams},
+    handlers::{
+        dm::ensure_user_exists,
+        middleware::optional_user_id,
+        user::{ensure_not_blocked_by, ensure_visible, get_profile_viewer},
+    },
+    storage::{get_many_servers_by_member_id, get_server_by_id, get_user_settings, search_servers},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct ServerSearchQuery {
+    #[serde(default)]
+    pub q: String,
+}
+
+#[tracing::instrument(
+    name = "Getting server by ID",
+    skip(server_id, db_pool),
+    fields(
+        id = %server_id,
+    )
+)]
+pub async fn get_by_id(server_id: Path<Uuid>, db_pool: Data<PgPool>) -> HttpResponse {
+    let id = server_id.into_inner();
+
+    match get_server_by_id(&db_pool, id).await {
+        Ok(server) => {
+            if server.deleted_at().is_some() {
+                let err = format!("server {} not found", id);
+                tracing::error!(err);
+                HttpResponse::NotFound().body(err)
+            } else {
+                HttpResponse::Ok().json(server)
+            }
+        }
+        Err(e) => match e {
+            sqlx::Error::RowNotFound => {
+                let err = format!("server {} not found", id);
+                tracing::error!(err);
+                HttpResponse::NotFound().body(err)
+            }
+            e => {
+                let err = format!("failed to get server {}: {}", id, e);
+                tracing::error!(err);
+                HttpResponse::InternalServerError().finish()
+            }
+        },
+    }
+}
+
+/// Lists the servers a user is in, if their settings let the caller see
+/// them.
+#[tracing::instrument(
+    name = "Getting servers by member ID",
+    skip(user_id, params, req, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_many_by_user(
+    user_id: Path<Uuid>,
+    params: Query<PageParams>,
+    req: HttpRequest,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let id = user_id.into_inner();
+    let viewer_id = optional_user_id(&req).map(|viewer_id| Uuid::from(&viewer_id));
+
+    if let Err(e) = ensure_user_exists(&db_pool, id).await {
+        return e;
+    }
+    if let Some(viewer_id) = viewer_id {
+        if let Err(e) = ensure_not_blocked_by(&db_pool, viewer_id, id).await {
+            return e;
+        }
+    }
+    let settings = match get_user_settings(&db_pool, id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("failed to get settings of user {}: {:?}", id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let viewer = match get_profile_viewer(&db_pool, id, viewer_id).await {
+        Ok(viewer) => viewer,
+        Err(e) => return e,
+    };
+    if let Err(e) = ensure_visible(settings.servers_visibility(), viewer, id, "servers") {
+        return e;
+    }
+
+    match get_many_servers_by_member_id(&db_pool, id, &params).await {
+        Ok(servers) => HttpResponse::Ok().json(Page::from_rows(servers, &params, |s| s.id())),
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Searching servers",
+    skip(search, params, db_pool),
+    fields(
+        q = %search.q,
+    )
+)]
+pub async fn search(
+    search: Query<ServerSearchQuery>,
+    params: Query<PageParams>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    match search_servers(&db_pool, &search.q, &params).await {
+        Ok(servers) => HttpResponse::Ok().json(Page::from_rows(servers, &params, |s| s.id())),
+        Err(e) => e.handle_http(),
+    }
+}
@@ -0,0 +1,79 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    gateway::{server_topics, GatewayEvent, Hub, Publish, Subscribe, Topic},
+    handlers::middleware::UserID,
+    storage::{get_server_by_id, get_server_member, insert_server_member},
+};
+
+#[tracing::instrument(
+    name = "Joining server",
+    skip(server_id, user_id, db_pool, hub),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn join(
+    server_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let server = match get_server_by_id(&db_pool, server_id).await {
+        Ok(server) if server.deleted_at().is_none() => server,
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("server {} not found", server_id);
+            tracing::error!(err);
+            return HttpResponse::NotFound().body(err);
+        }
+        Err(e) => {
+            tracing::error!("failed to get server {}: {:?}", server_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    match get_server_member(&db_pool, server_id, user_id).await {
+        Ok(Some(member)) if member.is_banned() => {
+            let err = format!("user {} is banned from server {}", user_id, server_id);
+            tracing::error!("403 - {}", err);
+            return HttpResponse::Forbidden().body(err);
+        }
+        Ok(Some(_)) => return HttpResponse::Ok().finish(),
+        Ok(None) if server.owner_id() == user_id => return HttpResponse::Ok().finish(),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("failed to get membership of user {}: {:?}", user_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    if let Err(e) = insert_server_member(&db_pool, server_id, user_id, false).await {
+        tracing::error!("500 - Failed to execute query: {:?}", e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    tracing::info!("user {} joined server {}", user_id, server_id);
+
+    match server_topics(&db_pool, &server, user_id).await {
+        Ok(topics) => hub.do_send(Subscribe { user_id, topics }),
+        Err(e) => tracing::error!(
+            "failed to subscribe user {} to server {}: {:?}",
+            user_id,
+            server_id,
+            e
+        ),
+    }
+    hub.do_send(Publish::new(
+        Topic::Server(server_id),
+        GatewayEvent::MemberJoin { server_id, user_id },
+    ));
+    HttpResponse::Ok().finish()
+}
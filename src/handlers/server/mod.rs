@@ -1,9 +1,19 @@
+mod authorize;
 mod create;
 mod delete;
+mod emoji;
+mod get;
+mod member;
 mod update;
 
+pub use authorize::*;
 pub use create::*;
 pub use delete::*;
+pub use emoji::*;
+pub use get::*;
+pub use member::*;
 pub use update::*;
 
 pub const BASE_PATH: &str = "/servers";
+pub const MEMBERS_PATH: &str = "/members";
+pub const EMOJIS_PATH: &str = "/emojis";
@@ -1,3 +1,4 @@
+use actix::Addr;
 use actix_web::{
     web::{Data, Json, Path},
     HttpResponse,
@@ -5,11 +6,15 @@ use actix_web::{
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::{domain::server::Server, storage::upsert_server};
+use crate::{
+    domain::server::Server,
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    storage::upsert_server,
+};
 
 #[tracing::instrument(
     name = "Updating server details",
-    skip(server_id, server_details, db_pool),
+    skip(server_id, server_details, db_pool, hub),
     fields(
         id = %server_id,
         name = %server_details.clone().name(),
@@ -23,9 +28,16 @@ pub async fn update(
     server_id: Path<Uuid>,
     server_details: Json<Server>,
     db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
 ) -> HttpResponse {
     match upsert_server(db_pool.get_ref(), &server_details).await {
-        Ok(_) => HttpResponse::Ok().finish(),
+        Ok(_) => {
+            hub.do_send(Publish::new(
+                Topic::Server(server_details.id()),
+                GatewayEvent::ServerUpdate(server_details.into_inner()),
+            ));
+            HttpResponse::Ok().finish()
+        }
         Err(e) => match e {
             sqlx::Error::RowNotFound => HttpResponse::NotFound().body("Server not found"),
             _ => HttpResponse::InternalServerError().finish(),
@@ -0,0 +1,96 @@
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        pagination::{Page, PageParams},
+        permission::Permissions,
+        post::PostView,
+        tag::Tag,
+    },
+    handlers::{middleware::UserID, post::build_post_views, server::authorize_server},
+    storage::{get_posts_by_tag, get_trending_tags},
+};
+
+/// Narrows tag lookups to one server. When unset, tagged posts come from
+/// every server the viewer is in, and trending tags from every server.
+#[derive(Serialize, Deserialize, Default)]
+pub struct TagScopeQuery {
+    pub server_id: Option<Uuid>,
+}
+
+/// Returns the posts carrying a tag, newest first.
+#[tracing::instrument(
+    name = "Getting posts by tag",
+    skip(tag, query, params, user_id, db_pool),
+    fields(
+        tag = %tag,
+    )
+)]
+pub async fn get_posts(
+    tag: Path<String>,
+    query: Query<TagScopeQuery>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let server_id = query.into_inner().server_id;
+    let params = params.into_inner();
+
+    let tag = match Tag::try_from(tag.as_str()) {
+        Ok(tag) => tag,
+        Err(e) => {
+            tracing::error!("400 - invalid tag: {:?}", e);
+            return e.handle_http();
+        }
+    };
+    if let Some(server_id) = server_id {
+        if let Err(e) = authorize_server(&db_pool, server_id, user_id, Permissions::empty()).await {
+            return e;
+        }
+    }
+
+    let posts = match get_posts_by_tag(&db_pool, &tag, user_id, server_id, &params).await {
+        Ok(posts) => posts,
+        Err(e) => return e.handle_http(),
+    };
+
+    let page = Page::from_rows(posts, &params, |p| p.id());
+    let next_cursor = page.next_cursor();
+    match build_post_views(&db_pool, page.into_items(), user_id).await {
+        Ok(posts) => HttpResponse::Ok().json(Page::<PostView>::new(posts, next_cursor)),
+        Err(e) => e,
+    }
+}
+
+/// Returns the trending tags of a server, or of every server, as last
+/// computed by the trending job.
+#[tracing::instrument(name = "Getting trending tags", skip(query, user_id, db_pool))]
+pub async fn get_trending(
+    query: Query<TagScopeQuery>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let server_id = query.into_inner().server_id;
+
+    if let Some(server_id) = server_id {
+        if let Err(e) = authorize_server(&db_pool, server_id, user_id, Permissions::empty()).await {
+            return e;
+        }
+    }
+
+    match get_trending_tags(&db_pool, server_id).await {
+        Ok(tags) => HttpResponse::Ok().json(tags),
+        Err(e) => {
+            tracing::error!("failed to get trending tags: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
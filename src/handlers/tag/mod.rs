@@ -0,0 +1,7 @@
+mod get;
+
+pub use get::*;
+
+pub const BASE_PATH: &str = "/tags";
+pub const POSTS_PATH: &str = "/posts";
+pub const TRENDING_PATH: &str = "/trending";
@@ -0,0 +1,43 @@
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{permission::Permissions, thread::Thread},
+    handlers::channel::authorize_channel,
+    storage::get_thread_by_id,
+};
+
+/// Loads a live thread and checks that the user holds the `required`
+/// permissions in it, which are those of its parent channel.
+pub async fn authorize_thread(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    user_id: Uuid,
+    required: Permissions,
+) -> Result<(Thread, Permissions), HttpResponse> {
+    let (_, _, permissions) = authorize_channel(db_pool, thread_id, user_id, required).await?;
+    let thread = find_thread(db_pool, thread_id).await?;
+    Ok((thread, permissions))
+}
+
+pub async fn find_thread(db_pool: &PgPool, thread_id: Uuid) -> Result<Thread, HttpResponse> {
+    match get_thread_by_id(db_pool, thread_id).await {
+        Ok(thread) => Ok(thread),
+        Err(sqlx::Error::RowNotFound) => {
+            let err = format!("thread {} not found", thread_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get thread {}: {:?}", thread_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Thread owners may rename and archive their threads; anyone who can manage
+/// messages in the parent channel may manage any thread.
+pub fn can_manage_thread(thread: &Thread, user_id: Uuid, permissions: Permissions) -> bool {
+    thread.owner_id() == user_id || permissions.contains(Permissions::MANAGE_MESSAGES)
+}
@@ -0,0 +1,111 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        permission::Permissions,
+        thread::{Thread, ThreadValidationErr, DEFAULT_AUTO_ARCHIVE_MINUTES},
+    },
+    gateway::{GatewayEvent, Hub, Publish, Subscribe, Topic},
+    handlers::{channel::authorize_channel, message::find_channel_message, middleware::UserID},
+    storage::insert_thread,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateThreadRequestBody {
+    pub name: String,
+    pub auto_archive_minutes: Option<i32>,
+}
+
+/// Starts a thread from a message. Each message can start at most one thread.
+#[tracing::instrument(
+    name = "Starting thread",
+    skip(path, body, user_id, db_pool, hub),
+    fields(
+        channel_id = %path.0,
+        message_id = %path.1,
+    )
+)]
+pub async fn create(
+    path: Path<(Uuid, Uuid)>,
+    body: Json<CreateThreadRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let (channel_id, message_id) = path.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+
+    let (server, channel) = match authorize_channel(
+        &db_pool,
+        channel_id,
+        user_id,
+        Permissions::SEND_MESSAGES | Permissions::READ_MESSAGE_HISTORY,
+    )
+    .await
+    {
+        Ok((server, channel, _)) => (server, channel),
+        Err(e) => return e,
+    };
+    if !channel.kind().can_have_threads() {
+        let e = ThreadValidationErr::ParentCannotHaveThreads;
+        tracing::error!("400 - {:?}", e);
+        return e.handle_http();
+    }
+    if let Err(e) = find_channel_message(&db_pool, channel_id, message_id).await {
+        return e;
+    }
+
+    let auto_archive_minutes = body
+        .auto_archive_minutes
+        .unwrap_or(DEFAULT_AUTO_ARCHIVE_MINUTES);
+    if let Err(e) = Thread::validate_name(&body.name)
+        .and(Thread::validate_auto_archive_minutes(auto_archive_minutes))
+    {
+        tracing::error!("400 - invalid thread: {:?}", e);
+        return e.handle_http();
+    }
+
+    let thread = Thread::new(
+        Uuid::new_v4(),
+        server.id(),
+        channel_id,
+        Some(message_id),
+        user_id,
+        body.name,
+        auto_archive_minutes,
+        Utc::now(),
+    );
+
+    match insert_thread(&db_pool, &thread).await {
+        Ok(_) => {
+            tracing::info!("Thread {} successfully started", thread.id());
+            hub.do_send(Subscribe {
+                user_id,
+                topics: vec![Topic::Channel(thread.id())],
+            });
+            hub.do_send(Publish::new(
+                Topic::Channel(channel_id),
+                GatewayEvent::ThreadCreate(thread.clone()),
+            ));
+            HttpResponse::Ok().json(thread)
+        }
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23505") => {
+            let err = format!("message {} already has a thread", message_id);
+            tracing::error!("409 - {}", err);
+            HttpResponse::Conflict().body(err)
+        }
+        Err(e) => {
+            tracing::error!("500 - Failed to insert thread: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        pagination::{Page, PageParams},
+        permission::Permissions,
+    },
+    handlers::{channel::authorize_channel, middleware::UserID, thread::authorize_thread},
+    storage::get_threads_by_parent_id,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct GetThreadsQuery {
+    #[serde(default)]
+    pub archived: bool,
+    pub cursor: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+#[tracing::instrument(
+    name = "Getting thread by ID",
+    skip(thread_id, user_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn get_by_id(
+    thread_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match authorize_thread(&db_pool, thread_id, user_id, Permissions::VIEW_CHANNEL).await {
+        Ok((thread, _)) => HttpResponse::Ok().json(thread),
+        Err(e) => e,
+    }
+}
+
+/// Lists a channel's open threads, or its archived ones with
+/// `?archived=true`, most recently active first.
+#[tracing::instrument(
+    name = "Getting threads by channel ID",
+    skip(channel_id, query, user_id, db_pool),
+    fields(
+        channel_id = %channel_id,
+    )
+)]
+pub async fn get_many_by_channel(
+    channel_id: Path<Uuid>,
+    query: Query<GetThreadsQuery>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let channel_id = channel_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let query = query.into_inner();
+    let params = PageParams::new(query.cursor, query.limit);
+
+    if let Err(e) =
+        authorize_channel(&db_pool, channel_id, user_id, Permissions::VIEW_CHANNEL).await
+    {
+        return e;
+    }
+
+    match get_threads_by_parent_id(&db_pool, channel_id, query.archived, &params).await {
+        Ok(threads) => HttpResponse::Ok().json(Page::from_rows(threads, &params, |t| t.id())),
+        Err(e) => e.handle_http(),
+    }
+}
@@ -0,0 +1,144 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::permission::Permissions,
+    gateway::{GatewayEvent, Hub, Publish, Subscribe, Topic, Unsubscribe},
+    handlers::{middleware::UserID, thread::authorize_thread},
+    storage::{delete_thread_member, get_thread_members, insert_thread_members},
+};
+
+/// Adds users to a thread's member list and subscribes them to its messages.
+pub async fn add_thread_members(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    thread_id: Uuid,
+    user_ids: &[Uuid],
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let added = insert_thread_members(db_pool, thread_id, user_ids).await?;
+    for &user_id in &added {
+        hub.do_send(Subscribe {
+            user_id,
+            topics: vec![Topic::Channel(thread_id)],
+        });
+        hub.do_send(Publish::new(
+            Topic::Channel(thread_id),
+            GatewayEvent::ThreadMemberAdd { thread_id, user_id },
+        ));
+    }
+    Ok(added)
+}
+
+#[tracing::instrument(
+    name = "Getting thread members",
+    skip(thread_id, user_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn get_members(
+    thread_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_thread(&db_pool, thread_id, user_id, Permissions::VIEW_CHANNEL).await
+    {
+        return e;
+    }
+
+    match get_thread_members(&db_pool, thread_id).await {
+        Ok(members) => HttpResponse::Ok().json(members),
+        Err(e) => {
+            tracing::error!("failed to get members of thread {}: {:?}", thread_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Joins a thread, so the user hears about its messages and is notified by
+/// mass mentions in it. Locked threads cannot be joined.
+#[tracing::instrument(
+    name = "Joining thread",
+    skip(thread_id, user_id, db_pool, hub),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn join(
+    thread_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let thread =
+        match authorize_thread(&db_pool, thread_id, user_id, Permissions::VIEW_CHANNEL).await {
+            Ok((thread, _)) => thread,
+            Err(e) => return e,
+        };
+    if thread.locked() {
+        let err = format!("thread {} is locked", thread_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    match add_thread_members(&db_pool, &hub, thread_id, &[user_id]).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            tracing::error!("failed to join thread {}: {:?}", thread_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Leaving thread",
+    skip(thread_id, user_id, db_pool, hub),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn leave(
+    thread_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = authorize_thread(&db_pool, thread_id, user_id, Permissions::VIEW_CHANNEL).await
+    {
+        return e;
+    }
+
+    match delete_thread_member(&db_pool, thread_id, user_id).await {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::Channel(thread_id),
+                    GatewayEvent::ThreadMemberRemove { thread_id, user_id },
+                ));
+                hub.do_send(Unsubscribe {
+                    user_id,
+                    topics: vec![Topic::Channel(thread_id)],
+                });
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to leave thread {}: {:?}", thread_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use actix::Addr;
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        message::Message, notification::MentionKind, permission::Permissions, thread::Thread,
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::thread::add_thread_members,
+    storage::{get_thread_members, touch_thread},
+};
+
+/// Only members with `MANAGE_MESSAGES` may post in a locked thread.
+pub fn ensure_thread_open(thread: &Thread, permissions: Permissions) -> Result<(), HttpResponse> {
+    if thread.locked() && !permissions.contains(Permissions::MANAGE_MESSAGES) {
+        let err = format!("thread {} is locked", thread.id());
+        tracing::error!("403 - {}", err);
+        return Err(HttpResponse::Forbidden().body(err));
+    }
+    Ok(())
+}
+
+/// Bookkeeping after a message is posted in a thread: reopens it if it was
+/// archived, adds the author and directly mentioned users as members, and
+/// narrows the mention recipients so that role and mass mentions only reach
+/// thread members.
+pub async fn record_thread_message(
+    db_pool: &PgPool,
+    hub: &Addr<Hub>,
+    mut thread: Thread,
+    message: &Message,
+    recipients: Vec<(Uuid, MentionKind)>,
+) -> Result<Vec<(Uuid, MentionKind)>, sqlx::Error> {
+    touch_thread(db_pool, thread.id(), message.created_at()).await?;
+    if thread.is_archived() {
+        thread.set_archived_at(None);
+        thread.set_last_message_at(Some(message.created_at()));
+        hub.do_send(Publish::new(
+            Topic::Channel(thread.parent_id()),
+            GatewayEvent::ThreadUpdate(thread.clone()),
+        ));
+    }
+
+    let mut joining = vec![message.author_id()];
+    joining.extend(
+        recipients
+            .iter()
+            .filter(|(_, kind)| *kind == MentionKind::User)
+            .map(|(id, _)| *id),
+    );
+    add_thread_members(db_pool, hub, thread.id(), &joining).await?;
+
+    let member_ids: Vec<Uuid> = get_thread_members(db_pool, thread.id())
+        .await?
+        .iter()
+        .map(|m| m.user_id())
+        .collect();
+    Ok(recipients
+        .into_iter()
+        .filter(|(id, _)| member_ids.contains(id))
+        .collect())
+}
@@ -0,0 +1,16 @@
+mod authorize;
+mod create;
+mod get;
+mod member;
+mod message;
+mod update;
+
+pub use authorize::*;
+pub use create::*;
+pub use get::*;
+pub use member::*;
+pub use message::*;
+pub use update::*;
+
+pub const BASE_PATH: &str = "/threads";
+pub const MEMBERS_PATH: &str = "/members";
@@ -0,0 +1,102 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{permission::Permissions, thread::Thread},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        middleware::UserID,
+        thread::{authorize_thread, can_manage_thread},
+    },
+    storage::update_thread,
+};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PatchThreadRequestBody {
+    pub name: Option<String>,
+    pub auto_archive_minutes: Option<i32>,
+    pub archived: Option<bool>,
+    pub locked: Option<bool>,
+}
+
+/// Renames, archives, unarchives, locks or unlocks a thread. The owner may
+/// do all but lock and unlock; a locked thread can only be changed by
+/// someone with `MANAGE_MESSAGES`.
+#[tracing::instrument(
+    name = "Updating thread",
+    skip(thread_id, body, user_id, db_pool, hub),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn patch(
+    thread_id: Path<Uuid>,
+    body: Json<PatchThreadRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let thread_id = thread_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+
+    let (mut thread, permissions) =
+        match authorize_thread(&db_pool, thread_id, user_id, Permissions::VIEW_CHANNEL).await {
+            Ok(res) => res,
+            Err(e) => return e,
+        };
+    let is_manager = permissions.contains(Permissions::MANAGE_MESSAGES);
+    if !can_manage_thread(&thread, user_id, permissions)
+        || (body.locked.is_some() && !is_manager)
+        || (thread.locked() && !is_manager)
+    {
+        let err = format!("user {} may not update thread {}", user_id, thread_id);
+        tracing::error!("403 - {}", err);
+        return HttpResponse::Forbidden().body(err);
+    }
+
+    if let Some(name) = body.name {
+        if let Err(e) = Thread::validate_name(&name) {
+            tracing::error!("400 - invalid thread name: {:?}", e);
+            return e.handle_http();
+        }
+        thread.set_name(name);
+    }
+    if let Some(minutes) = body.auto_archive_minutes {
+        if let Err(e) = Thread::validate_auto_archive_minutes(minutes) {
+            tracing::error!("400 - invalid auto archive duration: {:?}", e);
+            return e.handle_http();
+        }
+        thread.set_auto_archive_minutes(minutes);
+    }
+    match body.archived {
+        Some(true) if !thread.is_archived() => thread.set_archived_at(Some(Utc::now())),
+        Some(false) => thread.set_archived_at(None),
+        _ => {}
+    }
+    if let Some(locked) = body.locked {
+        thread.set_locked(locked);
+    }
+
+    match update_thread(&db_pool, &thread).await {
+        Ok(_) => {
+            tracing::info!("Thread {} successfully updated", thread_id);
+            hub.do_send(Publish::new(
+                Topic::Channel(thread.parent_id()),
+                GatewayEvent::ThreadUpdate(thread.clone()),
+            ));
+            HttpResponse::Ok().json(thread)
+        }
+        Err(e) => {
+            tracing::error!("Failed to update thread {}: {:?}", thread_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
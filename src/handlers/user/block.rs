@@ -0,0 +1,284 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        block::{ServerMute, UserBlock, UserMute},
+        pagination::{Page, PageParams},
+    },
+    gateway::Hub,
+    handlers::{dm::ensure_user_exists, middleware::UserID, user::refresh_presence_audiences},
+    storage::{
+        delete_follows_between, delete_friends_between, delete_server_mute, delete_user_block,
+        delete_user_mute, get_server_by_id, get_server_mutes, get_user_blocks, get_user_mutes,
+        insert_server_mute, insert_user_block, insert_user_mute, is_blocked_by,
+    },
+};
+
+/// Hides a user's profile from the users they blocked, failing with the
+/// same 404 as a missing user so the block is not revealed.
+pub async fn ensure_not_blocked_by(
+    db_pool: &PgPool,
+    viewer_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), HttpResponse> {
+    match is_blocked_by(db_pool, viewer_id, user_id).await {
+        Ok(false) => Ok(()),
+        Ok(true) => {
+            let err = format!("user {} not found", user_id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to check blocks of user {}: {:?}", user_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Blocks a user, ending any follows and friendship between the two.
+#[tracing::instrument(
+    name = "Blocking user",
+    skip(target_id, user_id, db_pool, hub),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn block_user(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let block = match UserBlock::new(user_id, target_id, Utc::now()) {
+        Ok(block) => block,
+        Err(e) => return e.handle_http(),
+    };
+    if let Err(e) = ensure_user_exists(&db_pool, target_id).await {
+        return e;
+    }
+
+    if let Err(e) = insert_user_block(&db_pool, &block).await {
+        tracing::error!("failed to block user {}: {:?}", target_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    if let Err(e) = delete_follows_between(&db_pool, user_id, target_id).await {
+        tracing::error!("failed to remove follows with user {}: {:?}", target_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    if let Err(e) = delete_friends_between(&db_pool, user_id, target_id).await {
+        tracing::error!(
+            "failed to remove friendship with user {}: {:?}",
+            target_id,
+            e
+        );
+        return HttpResponse::InternalServerError().finish();
+    }
+    refresh_presence_audiences(&db_pool, &hub, &[user_id, target_id]).await;
+    HttpResponse::NoContent().finish()
+}
+
+#[tracing::instrument(
+    name = "Unblocking user",
+    skip(target_id, user_id, db_pool, hub),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn unblock_user(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_user_block(&db_pool, user_id, target_id).await {
+        Ok(_) => {
+            refresh_presence_audiences(&db_pool, &hub, &[user_id, target_id]).await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to unblock user {}: {:?}", target_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the users the user has blocked, most recent first.
+#[tracing::instrument(name = "Getting blocked users", skip(params, user_id, db_pool))]
+pub async fn get_blocks(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    match get_user_blocks(&db_pool, user_id, &params).await {
+        Ok(blocks) => HttpResponse::Ok().json(Page::from_rows(blocks, &params, |b| b.blocked_id())),
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Muting user",
+    skip(target_id, user_id, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn mute_user(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let mute = match UserMute::new(user_id, target_id, Utc::now()) {
+        Ok(mute) => mute,
+        Err(e) => return e.handle_http(),
+    };
+    if let Err(e) = ensure_user_exists(&db_pool, target_id).await {
+        return e;
+    }
+
+    match insert_user_mute(&db_pool, &mute).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            tracing::error!("failed to mute user {}: {:?}", target_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Unmuting user",
+    skip(target_id, user_id, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn unmute_user(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_user_mute(&db_pool, user_id, target_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            tracing::error!("failed to unmute user {}: {:?}", target_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the users the user has muted, most recent first.
+#[tracing::instrument(name = "Getting muted users", skip(params, user_id, db_pool))]
+pub async fn get_mutes(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    match get_user_mutes(&db_pool, user_id, &params).await {
+        Ok(mutes) => HttpResponse::Ok().json(Page::from_rows(mutes, &params, |m| m.muted_id())),
+        Err(e) => e.handle_http(),
+    }
+}
+
+/// Mutes a server, leaving its posts out of the user's home feed. Servers
+/// can be muted without joining them, since the feed also carries posts
+/// from followed users.
+#[tracing::instrument(
+    name = "Muting server",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn mute_server(
+    server_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match get_server_by_id(&db_pool, server_id).await {
+        Ok(server) if server.deleted_at().is_none() => {}
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("server {} not found", server_id);
+            tracing::error!(err);
+            return HttpResponse::NotFound().body(err);
+        }
+        Err(e) => {
+            tracing::error!("failed to get server {}: {:?}", server_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    match insert_server_mute(&db_pool, &ServerMute::new(user_id, server_id, Utc::now())).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            tracing::error!("failed to mute server {}: {:?}", server_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Unmuting server",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn unmute_server(
+    server_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let server_id = server_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_server_mute(&db_pool, user_id, server_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            tracing::error!("failed to unmute server {}: {:?}", server_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the servers the user has muted, most recent first.
+#[tracing::instrument(name = "Getting muted servers", skip(params, user_id, db_pool))]
+pub async fn get_muted_servers(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    match get_server_mutes(&db_pool, user_id, &params).await {
+        Ok(mutes) => HttpResponse::Ok().json(Page::from_rows(mutes, &params, |m| m.server_id())),
+        Err(e) => e.handle_http(),
+    }
+}
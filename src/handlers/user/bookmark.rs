@@ -0,0 +1,562 @@
+use std::collections::{HashMap, HashSet};
+
+use actix_web::{
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        bookmark::{Bookmark, BookmarkCollection, BookmarkStatus, BookmarkTarget, BookmarkView},
+        message::Message,
+        pagination::{Page, PageParams},
+        permission::Permissions,
+        post::Post,
+    },
+    handlers::{
+        channel::{authorize_channel, deserialize_nullable},
+        middleware::UserID,
+        post::{authorize_post, build_post_views},
+        server::authorize_server,
+    },
+    storage::{
+        delete_bookmark, delete_bookmark_collection, get_bookmark_by_id, get_bookmark_by_target,
+        get_bookmark_collection_by_id, get_bookmark_collections as get_collections,
+        get_bookmarks as get_many_bookmarks, get_message_by_id, get_messages_by_ids,
+        get_posts_by_ids, insert_bookmark, insert_bookmark_collection,
+        rename_bookmark_collection as rename_collection, update_bookmark,
+    },
+};
+
+/// What a user needs in a channel to save or read back its messages.
+const MESSAGE_ACCESS: Permissions =
+    Permissions::VIEW_CHANNEL.union(Permissions::READ_MESSAGE_HISTORY);
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateBookmarkRequestBody {
+    #[serde(default)]
+    pub post_id: Option<Uuid>,
+    #[serde(default)]
+    pub message_id: Option<Uuid>,
+    #[serde(default)]
+    pub collection_id: Option<Uuid>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PatchBookmarkRequestBody {
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub collection_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub note: Option<Option<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct GetBookmarksQuery {
+    #[serde(default)]
+    pub collection_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BookmarkCollectionRequestBody {
+    pub name: String,
+}
+
+/// Checks that the collection exists and belongs to the user.
+async fn find_collection(
+    db_pool: &PgPool,
+    collection_id: Uuid,
+    user_id: Uuid,
+) -> Result<BookmarkCollection, HttpResponse> {
+    match get_bookmark_collection_by_id(db_pool, collection_id, user_id).await {
+        Ok(Some(collection)) => Ok(collection),
+        Ok(None) => {
+            let err = format!("bookmark collection {} not found", collection_id);
+            tracing::error!("404 - {}", err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to get bookmark collection {}: {:?}",
+                collection_id,
+                e
+            );
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+async fn find_bookmark(
+    db_pool: &PgPool,
+    bookmark_id: Uuid,
+    user_id: Uuid,
+) -> Result<Bookmark, HttpResponse> {
+    match get_bookmark_by_id(db_pool, bookmark_id, user_id).await {
+        Ok(Some(bookmark)) => Ok(bookmark),
+        Ok(None) => {
+            let err = format!("bookmark {} not found", bookmark_id);
+            tracing::error!("404 - {}", err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            tracing::error!("failed to get bookmark {}: {:?}", bookmark_id, e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Checks that the user can currently see what they are about to bookmark.
+/// Only channel messages can be bookmarked, not direct messages.
+async fn authorize_bookmark_target(
+    db_pool: &PgPool,
+    target: BookmarkTarget,
+    user_id: Uuid,
+) -> Result<(), HttpResponse> {
+    match target {
+        BookmarkTarget::Post(post_id) => {
+            authorize_post(db_pool, post_id, user_id, Permissions::empty()).await?;
+        }
+        BookmarkTarget::Message(message_id) => {
+            let message = match get_message_by_id(db_pool, message_id).await {
+                Ok(message) if message.deleted_at().is_none() => message,
+                Ok(_) | Err(sqlx::Error::RowNotFound) => {
+                    let err = format!("message {} not found", message_id);
+                    tracing::error!(err);
+                    return Err(HttpResponse::NotFound().body(err));
+                }
+                Err(e) => {
+                    tracing::error!("failed to get message {}: {:?}", message_id, e);
+                    return Err(HttpResponse::InternalServerError().finish());
+                }
+            };
+            authorize_channel(db_pool, message.channel_id(), user_id, MESSAGE_ACCESS).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolves whether the user may still see a server or channel. Rejections
+/// mean access was lost; anything else is a failure of the check itself.
+fn still_accessible<T>(result: Result<T, HttpResponse>) -> Result<bool, HttpResponse> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(e) if e.status().is_client_error() => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Fills in what each bookmark points at. Deleted targets become tombstones,
+/// and targets in servers or channels the user can no longer see are marked
+/// unavailable without their content.
+async fn build_bookmark_views(
+    db_pool: &PgPool,
+    bookmarks: Vec<Bookmark>,
+    user_id: Uuid,
+) -> Result<Vec<BookmarkView>, HttpResponse> {
+    let mut post_ids = vec![];
+    let mut message_ids = vec![];
+    for bookmark in &bookmarks {
+        match bookmark.target() {
+            BookmarkTarget::Post(id) => post_ids.push(id),
+            BookmarkTarget::Message(id) => message_ids.push(id),
+        }
+    }
+
+    let posts: Vec<Post> = if post_ids.is_empty() {
+        vec![]
+    } else {
+        get_posts_by_ids(db_pool, &post_ids).await.map_err(|e| {
+            tracing::error!("failed to get bookmarked posts: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        })?
+    };
+    let messages: Vec<Message> = if message_ids.is_empty() {
+        vec![]
+    } else {
+        get_messages_by_ids(db_pool, &message_ids)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to get bookmarked messages: {:?}", e);
+                HttpResponse::InternalServerError().finish()
+            })?
+    };
+
+    let mut readable_servers = HashSet::new();
+    for server_id in posts.iter().map(Post::server_id).collect::<HashSet<_>>() {
+        let authorized = authorize_server(db_pool, server_id, user_id, Permissions::empty()).await;
+        if still_accessible(authorized)? {
+            readable_servers.insert(server_id);
+        }
+    }
+    let mut readable_channels = HashSet::new();
+    for channel_id in messages
+        .iter()
+        .map(Message::channel_id)
+        .collect::<HashSet<_>>()
+    {
+        let authorized = authorize_channel(db_pool, channel_id, user_id, MESSAGE_ACCESS).await;
+        if still_accessible(authorized)? {
+            readable_channels.insert(channel_id);
+        }
+    }
+
+    let posts: HashMap<Uuid, Option<Post>> = posts
+        .into_iter()
+        .map(|post| {
+            let readable = readable_servers.contains(&post.server_id());
+            (post.id(), readable.then_some(post))
+        })
+        .collect();
+    let visible_posts: Vec<Post> = posts.values().flatten().cloned().collect();
+    let post_views: HashMap<Uuid, _> = build_post_views(db_pool, visible_posts, user_id)
+        .await?
+        .into_iter()
+        .map(|view| (view.id(), view))
+        .collect();
+    let messages: HashMap<Uuid, Option<Message>> = messages
+        .into_iter()
+        .map(|message| {
+            let readable = readable_channels.contains(&message.channel_id());
+            (message.id(), readable.then_some(message))
+        })
+        .collect();
+
+    Ok(bookmarks
+        .into_iter()
+        .map(|bookmark| match bookmark.target() {
+            BookmarkTarget::Post(id) => match posts.get(&id) {
+                Some(Some(_)) => {
+                    let post = post_views.get(&id).cloned();
+                    let mut view = BookmarkView::new(bookmark, BookmarkStatus::Available);
+                    view.post = post;
+                    view
+                }
+                Some(None) => BookmarkView::new(bookmark, BookmarkStatus::Unavailable),
+                None => BookmarkView::new(bookmark, BookmarkStatus::Deleted),
+            },
+            BookmarkTarget::Message(id) => match messages.get(&id) {
+                Some(Some(message)) => {
+                    let mut view = BookmarkView::new(bookmark, BookmarkStatus::Available);
+                    view.message = Some(message.clone());
+                    view
+                }
+                Some(None) => BookmarkView::new(bookmark, BookmarkStatus::Unavailable),
+                None => BookmarkView::new(bookmark, BookmarkStatus::Deleted),
+            },
+        })
+        .collect())
+}
+
+/// Bookmarks a post or channel message. Bookmarking something already
+/// saved returns the existing bookmark unchanged.
+#[tracing::instrument(name = "Creating bookmark", skip(body, user_id, db_pool))]
+pub async fn create_bookmark(
+    body: Json<CreateBookmarkRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+
+    let target = match BookmarkTarget::from_ids(body.post_id, body.message_id) {
+        Ok(target) => target,
+        Err(e) => {
+            tracing::error!("400 - invalid bookmark target: {:?}", e);
+            return e.handle_http();
+        }
+    };
+    let bookmark = match Bookmark::new(
+        Uuid::new_v4(),
+        user_id,
+        target,
+        body.collection_id,
+        body.note,
+        Utc::now(),
+    ) {
+        Ok(bookmark) => bookmark,
+        Err(e) => {
+            tracing::error!("400 - invalid bookmark: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    if let Err(e) = authorize_bookmark_target(&db_pool, target, user_id).await {
+        return e;
+    }
+    if let Some(collection_id) = bookmark.collection_id() {
+        if let Err(e) = find_collection(&db_pool, collection_id, user_id).await {
+            return e;
+        }
+    }
+
+    match insert_bookmark(&db_pool, &bookmark).await {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::Ok().json(bookmark),
+        Ok(_) => match get_bookmark_by_target(&db_pool, user_id, target).await {
+            Ok(Some(existing)) => HttpResponse::Ok().json(existing),
+            Ok(None) => {
+                tracing::error!("bookmark of {:?} vanished after conflicting", target);
+                HttpResponse::InternalServerError().finish()
+            }
+            Err(e) => {
+                tracing::error!("failed to get bookmark of {:?}: {:?}", target, e);
+                HttpResponse::InternalServerError().finish()
+            }
+        },
+        Err(e) => {
+            tracing::error!("failed to insert bookmark {}: {:?}", bookmark.id(), e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the user's bookmarks, newest first, optionally only those in one
+/// collection.
+#[tracing::instrument(name = "Getting bookmarks", skip(query, params, user_id, db_pool))]
+pub async fn get_bookmarks(
+    query: Query<GetBookmarksQuery>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let collection_id = query.into_inner().collection_id;
+    let params = params.into_inner();
+
+    if let Some(collection_id) = collection_id {
+        if let Err(e) = find_collection(&db_pool, collection_id, user_id).await {
+            return e;
+        }
+    }
+
+    let bookmarks = match get_many_bookmarks(&db_pool, user_id, collection_id, &params).await {
+        Ok(bookmarks) => bookmarks,
+        Err(e) => return e.handle_http(),
+    };
+
+    let page = Page::from_rows(bookmarks, &params, |b| b.id());
+    let next_cursor = page.next_cursor();
+    match build_bookmark_views(&db_pool, page.into_items(), user_id).await {
+        Ok(views) => HttpResponse::Ok().json(Page::new(views, next_cursor)),
+        Err(e) => e,
+    }
+}
+
+/// Moves a bookmark between collections or changes its note. Either may be
+/// set to `null` to clear it.
+#[tracing::instrument(
+    name = "Patching bookmark",
+    skip(bookmark_id, body, user_id, db_pool),
+    fields(
+        bookmark_id = %bookmark_id,
+    )
+)]
+pub async fn patch_bookmark(
+    bookmark_id: Path<Uuid>,
+    body: Json<PatchBookmarkRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let bookmark_id = bookmark_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+    let now = Utc::now();
+
+    let mut bookmark = match find_bookmark(&db_pool, bookmark_id, user_id).await {
+        Ok(bookmark) => bookmark,
+        Err(e) => return e,
+    };
+
+    if let Some(note) = body.note {
+        if let Err(e) = bookmark.set_note(note, now) {
+            tracing::error!("400 - invalid bookmark note: {:?}", e);
+            return e.handle_http();
+        }
+    }
+    if let Some(collection_id) = body.collection_id {
+        if let Some(collection_id) = collection_id {
+            if let Err(e) = find_collection(&db_pool, collection_id, user_id).await {
+                return e;
+            }
+        }
+        bookmark.set_collection_id(collection_id, now);
+    }
+
+    match update_bookmark(&db_pool, &bookmark).await {
+        Ok(_) => HttpResponse::Ok().json(bookmark),
+        Err(e) => {
+            tracing::error!("failed to update bookmark {}: {:?}", bookmark_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Removing bookmark",
+    skip(bookmark_id, user_id, db_pool),
+    fields(
+        bookmark_id = %bookmark_id,
+    )
+)]
+pub async fn remove_bookmark(
+    bookmark_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let bookmark_id = bookmark_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_bookmark(&db_pool, bookmark_id, user_id).await {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => {
+            let err = format!("bookmark {} not found", bookmark_id);
+            tracing::error!("404 - {}", err);
+            HttpResponse::NotFound().body(err)
+        }
+        Err(e) => {
+            tracing::error!("failed to delete bookmark {}: {:?}", bookmark_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(name = "Creating bookmark collection", skip(body, user_id, db_pool))]
+pub async fn create_bookmark_collection(
+    body: Json<BookmarkCollectionRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let collection = match BookmarkCollection::new(
+        Uuid::new_v4(),
+        user_id,
+        body.into_inner().name,
+        Utc::now(),
+    ) {
+        Ok(collection) => collection,
+        Err(e) => {
+            tracing::error!("400 - invalid bookmark collection: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    match insert_bookmark_collection(&db_pool, &collection).await {
+        Ok(_) => HttpResponse::Ok().json(collection),
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23505") => {
+            let err = format!("a bookmark collection named {} exists", collection.name());
+            tracing::error!("409 - {}", err);
+            HttpResponse::Conflict().body(err)
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to insert bookmark collection {}: {:?}",
+                collection.id(),
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the user's bookmark collections in alphabetical order.
+#[tracing::instrument(name = "Getting bookmark collections", skip(user_id, db_pool))]
+pub async fn get_bookmark_collections(
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match get_collections(&db_pool, user_id).await {
+        Ok(collections) => HttpResponse::Ok().json(collections),
+        Err(e) => {
+            tracing::error!(
+                "failed to get bookmark collections of user {}: {:?}",
+                user_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Renaming bookmark collection",
+    skip(collection_id, body, user_id, db_pool),
+    fields(
+        collection_id = %collection_id,
+    )
+)]
+pub async fn rename_bookmark_collection(
+    collection_id: Path<Uuid>,
+    body: Json<BookmarkCollectionRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let collection_id = collection_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let mut collection = match find_collection(&db_pool, collection_id, user_id).await {
+        Ok(collection) => collection,
+        Err(e) => return e,
+    };
+    if let Err(e) = collection.rename(body.into_inner().name) {
+        tracing::error!("400 - invalid bookmark collection name: {:?}", e);
+        return e.handle_http();
+    }
+
+    match rename_collection(&db_pool, &collection).await {
+        Ok(_) => HttpResponse::Ok().json(collection),
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23505") => {
+            let err = format!("a bookmark collection named {} exists", collection.name());
+            tracing::error!("409 - {}", err);
+            HttpResponse::Conflict().body(err)
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to rename bookmark collection {}: {:?}",
+                collection_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Deletes a collection. The bookmarks in it are kept and become unsorted.
+#[tracing::instrument(
+    name = "Deleting bookmark collection",
+    skip(collection_id, user_id, db_pool),
+    fields(
+        collection_id = %collection_id,
+    )
+)]
+pub async fn remove_bookmark_collection(
+    collection_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let collection_id = collection_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_bookmark_collection(&db_pool, collection_id, user_id).await {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => {
+            let err = format!("bookmark collection {} not found", collection_id);
+            tracing::error!("404 - {}", err);
+            HttpResponse::NotFound().body(err)
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to delete bookmark collection {}: {:?}",
+                collection_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use actix_web::{
+    web::{Data, Query, ReqData},
+    HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        pagination::{Page, PageParams},
+        post::{FeedSort, HomeFeed},
+    },
+    handlers::{middleware::UserID, post::build_post_views},
+    storage::get_home_feed,
+};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct GetFeedQuery {
+    #[serde(default)]
+    pub sort: FeedSort,
+    /// The `as_of` returned with the first page. Defaults to now.
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Returns the user's home feed, merging posts from every server they
+/// belong to, including those of the users they follow there.
+#[tracing::instrument(name = "Getting home feed", skip(query, params, user_id, db_pool))]
+pub async fn get_feed(
+    query: Query<GetFeedQuery>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let query = query.into_inner();
+    let params = params.into_inner();
+    let now = Utc::now();
+    let as_of = query.as_of.map_or(now, |as_of| as_of.min(now));
+
+    let posts = match get_home_feed(&db_pool, user_id, query.sort, as_of, &params).await {
+        Ok(posts) => posts,
+        Err(e) => return e.handle_http(),
+    };
+
+    let page = Page::from_rows(posts, &params, |p| p.id());
+    let next_cursor = page.next_cursor();
+    match build_post_views(&db_pool, page.into_items(), user_id).await {
+        Ok(posts) => HttpResponse::Ok().json(HomeFeed::new(Page::new(posts, next_cursor), as_of)),
+        Err(e) => e,
+    }
+}
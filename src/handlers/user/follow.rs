@@ -0,0 +1,334 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        follow::{Follow, FollowRequest, FollowResponse, FollowStatus},
+        pagination::{Page, PageParams},
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        dm::{ensure_not_blocked, ensure_user_exists},
+        middleware::UserID,
+        user::{
+            ensure_not_blocked_by, ensure_visible, get_profile_viewer, refresh_presence_audiences,
+        },
+    },
+    storage::{
+        accept_follow_requests, delete_follow, delete_follow_request,
+        get_follow_requests_by_target_id, get_followers, get_following, get_user_settings,
+        insert_follow, insert_follow_request, is_following,
+    },
+};
+
+/// Tells both users about a new follow.
+pub fn announce_follow(hub: &Addr<Hub>, follow: &Follow) {
+    for user_id in [follow.follower_id(), follow.followee_id()] {
+        hub.do_send(Publish::new(
+            Topic::User(user_id),
+            GatewayEvent::FollowCreate(follow.clone()),
+        ));
+    }
+}
+
+/// Private accounts only show who they follow and who follows them to
+/// themselves and to their followers. Either way the lists are also subject
+/// to the user's followers visibility setting.
+async fn authorize_follow_list(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    viewer_id: Uuid,
+) -> Result<(), HttpResponse> {
+    ensure_user_exists(db_pool, user_id).await?;
+    if user_id == viewer_id {
+        return Ok(());
+    }
+    ensure_not_blocked_by(db_pool, viewer_id, user_id).await?;
+
+    let settings = get_user_settings(db_pool, user_id).await.map_err(|e| {
+        tracing::error!("failed to get settings of user {}: {:?}", user_id, e);
+        HttpResponse::InternalServerError().finish()
+    })?;
+    let viewer = get_profile_viewer(db_pool, user_id, Some(viewer_id)).await?;
+    ensure_visible(
+        settings.followers_visibility(),
+        viewer,
+        user_id,
+        "followers",
+    )?;
+    if !settings.is_private() || viewer.is_follower {
+        return Ok(());
+    }
+
+    let err = format!("user {} is private", user_id);
+    tracing::error!("403 - {}", err);
+    Err(HttpResponse::Forbidden().body(err))
+}
+
+/// Follows a user, or asks to when their account is private.
+#[tracing::instrument(
+    name = "Following user",
+    skip(target_id, user_id, db_pool, hub),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn follow(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let now = Utc::now();
+
+    let follow = match Follow::new(user_id, target_id, now) {
+        Ok(follow) => follow,
+        Err(e) => return e.handle_http(),
+    };
+    if let Err(e) = ensure_user_exists(&db_pool, target_id).await {
+        return e;
+    }
+    if let Err(e) = ensure_not_blocked(&db_pool, user_id, target_id).await {
+        return e;
+    }
+
+    match is_following(&db_pool, user_id, target_id).await {
+        Ok(true) => {
+            return HttpResponse::Ok().json(FollowResponse {
+                status: FollowStatus::Following,
+            })
+        }
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!("failed to check follow of user {}: {:?}", target_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    let settings = match get_user_settings(&db_pool, target_id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("failed to get settings of user {}: {:?}", target_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    if settings.is_private() {
+        let request = FollowRequest::new(user_id, target_id, now);
+        match insert_follow_request(&db_pool, &request).await {
+            Ok(result) => {
+                if result.rows_affected() > 0 {
+                    hub.do_send(Publish::new(
+                        Topic::User(target_id),
+                        GatewayEvent::FollowRequestCreate(request),
+                    ));
+                }
+                HttpResponse::Ok().json(FollowResponse {
+                    status: FollowStatus::Requested,
+                })
+            }
+            Err(e) => {
+                tracing::error!("failed to request to follow user {}: {:?}", target_id, e);
+                HttpResponse::InternalServerError().finish()
+            }
+        }
+    } else {
+        match insert_follow(&db_pool, &follow).await {
+            Ok(result) => {
+                if result.rows_affected() > 0 {
+                    announce_follow(&hub, &follow);
+                    refresh_presence_audiences(&db_pool, &hub, &[target_id]).await;
+                }
+                HttpResponse::Ok().json(FollowResponse {
+                    status: FollowStatus::Following,
+                })
+            }
+            Err(e) => {
+                tracing::error!("failed to follow user {}: {:?}", target_id, e);
+                HttpResponse::InternalServerError().finish()
+            }
+        }
+    }
+}
+
+/// Unfollows a user, also withdrawing a pending request to follow them.
+#[tracing::instrument(
+    name = "Unfollowing user",
+    skip(target_id, user_id, db_pool, hub),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn unfollow(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = delete_follow(&db_pool, user_id, target_id).await {
+        tracing::error!("failed to unfollow user {}: {:?}", target_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+    refresh_presence_audiences(&db_pool, &hub, &[target_id]).await;
+    match delete_follow_request(&db_pool, user_id, target_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            tracing::error!(
+                "failed to withdraw follow request to {}: {:?}",
+                target_id,
+                e
+            );
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting followers",
+    skip(target_id, params, user_id, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn get_many_followers(
+    target_id: Path<Uuid>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    if let Err(e) = authorize_follow_list(&db_pool, target_id, user_id).await {
+        return e;
+    }
+
+    match get_followers(&db_pool, target_id, &params).await {
+        Ok(follows) => {
+            HttpResponse::Ok().json(Page::from_rows(follows, &params, |f| f.follower_id()))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting followees",
+    skip(target_id, params, user_id, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn get_many_following(
+    target_id: Path<Uuid>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    if let Err(e) = authorize_follow_list(&db_pool, target_id, user_id).await {
+        return e;
+    }
+
+    match get_following(&db_pool, target_id, &params).await {
+        Ok(follows) => {
+            HttpResponse::Ok().json(Page::from_rows(follows, &params, |f| f.followee_id()))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
+
+/// Returns the pending requests to follow the user, newest first.
+#[tracing::instrument(name = "Getting follow requests", skip(params, user_id, db_pool))]
+pub async fn get_follow_requests(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    match get_follow_requests_by_target_id(&db_pool, user_id, &params).await {
+        Ok(requests) => {
+            HttpResponse::Ok().json(Page::from_rows(requests, &params, |r| r.requester_id()))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Accepting follow request",
+    skip(requester_id, user_id, db_pool, hub),
+    fields(
+        requester_id = %requester_id,
+    )
+)]
+pub async fn accept_follow_request(
+    requester_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let requester_id = requester_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match accept_follow_requests(&db_pool, user_id, Some(requester_id), Utc::now()).await {
+        Ok(mut follows) => match follows.pop() {
+            Some(follow) => {
+                announce_follow(&hub, &follow);
+                refresh_presence_audiences(&db_pool, &hub, &[user_id]).await;
+                HttpResponse::Ok().json(follow)
+            }
+            None => HttpResponse::NotFound().body(format!(
+                "no follow request from user {} found",
+                requester_id
+            )),
+        },
+        Err(e) => {
+            tracing::error!("failed to accept follow request {}: {:?}", requester_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Rejecting follow request",
+    skip(requester_id, user_id, db_pool),
+    fields(
+        requester_id = %requester_id,
+    )
+)]
+pub async fn reject_follow_request(
+    requester_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let requester_id = requester_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_follow_request(&db_pool, requester_id, user_id).await {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => HttpResponse::NotFound().body(format!(
+            "no follow request from user {} found",
+            requester_id
+        )),
+        Err(e) => {
+            tracing::error!("failed to reject follow request {}: {:?}", requester_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
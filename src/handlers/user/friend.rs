@@ -0,0 +1,328 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, Query, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        friend::{
+            FriendRequest, FriendRequestDirection, FriendRequestResponse, FriendStatus, Friendship,
+            Mutuals,
+        },
+        pagination::{Page, PageParams},
+    },
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{
+        dm::{ensure_not_blocked, ensure_user_exists},
+        middleware::UserID,
+        user::{ensure_not_blocked_by, refresh_presence_audiences},
+    },
+    storage::{
+        accept_friend_request as accept_request, are_friends, delete_friend_request,
+        delete_friends_between, get_friend_requests as get_requests, get_friends,
+        get_mutual_friend_ids, get_mutual_server_ids, insert_friend_request,
+    },
+};
+
+/// Tells each user about their side of a new friendship.
+fn announce_friendships(hub: &Addr<Hub>, friendships: Vec<Friendship>) {
+    for friendship in friendships {
+        hub.do_send(Publish::new(
+            Topic::User(friendship.user_id()),
+            GatewayEvent::FriendAdd(friendship),
+        ));
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetFriendRequestsQuery {
+    #[serde(default)]
+    pub direction: FriendRequestDirection,
+}
+
+/// Sends a friend request, or accepts the one the target already sent.
+#[tracing::instrument(
+    name = "Sending friend request",
+    skip(target_id, user_id, db_pool, hub),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn send_friend_request(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+    let now = Utc::now();
+
+    let request = match FriendRequest::new(user_id, target_id, now) {
+        Ok(request) => request,
+        Err(e) => return e.handle_http(),
+    };
+    if let Err(e) = ensure_user_exists(&db_pool, target_id).await {
+        return e;
+    }
+    if let Err(e) = ensure_not_blocked(&db_pool, user_id, target_id).await {
+        return e;
+    }
+
+    let friends = HttpResponse::Ok().json(FriendRequestResponse {
+        status: FriendStatus::Friends,
+    });
+    match are_friends(&db_pool, user_id, target_id).await {
+        Ok(true) => return friends,
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!(
+                "failed to check friendship with user {}: {:?}",
+                target_id,
+                e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    match accept_request(&db_pool, target_id, user_id, now).await {
+        Ok(friendships) if !friendships.is_empty() => {
+            announce_friendships(&hub, friendships);
+            refresh_presence_audiences(&db_pool, &hub, &[user_id, target_id]).await;
+            return friends;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("failed to accept friend request of {}: {:?}", target_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    match insert_friend_request(&db_pool, &request).await {
+        Ok(result) => {
+            if result.rows_affected() > 0 {
+                hub.do_send(Publish::new(
+                    Topic::User(target_id),
+                    GatewayEvent::FriendRequestCreate(request),
+                ));
+            }
+            HttpResponse::Ok().json(FriendRequestResponse {
+                status: FriendStatus::Requested,
+            })
+        }
+        Err(e) => {
+            tracing::error!("failed to send friend request to {}: {:?}", target_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Withdraws a pending friend request the user sent.
+#[tracing::instrument(
+    name = "Cancelling friend request",
+    skip(target_id, user_id, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn cancel_friend_request(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_friend_request(&db_pool, user_id, target_id).await {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => {
+            HttpResponse::NotFound().body(format!("no friend request to user {} found", target_id))
+        }
+        Err(e) => {
+            tracing::error!("failed to cancel friend request to {}: {:?}", target_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the user's pending incoming or outgoing friend requests, newest
+/// first.
+#[tracing::instrument(
+    name = "Getting friend requests",
+    skip(query, params, user_id, db_pool)
+)]
+pub async fn get_friend_requests(
+    query: Query<GetFriendRequestsQuery>,
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let direction = query.into_inner().direction;
+    let params = params.into_inner();
+
+    match get_requests(&db_pool, user_id, direction, &params).await {
+        Ok(requests) => {
+            HttpResponse::Ok().json(Page::from_rows(requests, &params, |r| match direction {
+                FriendRequestDirection::Incoming => r.sender_id(),
+                FriendRequestDirection::Outgoing => r.recipient_id(),
+            }))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Accepting friend request",
+    skip(sender_id, user_id, db_pool, hub),
+    fields(
+        sender_id = %sender_id,
+    )
+)]
+pub async fn accept_friend_request(
+    sender_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let sender_id = sender_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match accept_request(&db_pool, sender_id, user_id, Utc::now()).await {
+        Ok(friendships) => {
+            let own = friendships.iter().find(|f| f.user_id() == user_id).cloned();
+            match own {
+                Some(friendship) => {
+                    announce_friendships(&hub, friendships);
+                    refresh_presence_audiences(&db_pool, &hub, &[user_id, sender_id]).await;
+                    HttpResponse::Ok().json(friendship)
+                }
+                None => HttpResponse::NotFound()
+                    .body(format!("no friend request from user {} found", sender_id)),
+            }
+        }
+        Err(e) => {
+            tracing::error!("failed to accept friend request {}: {:?}", sender_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Declining friend request",
+    skip(sender_id, user_id, db_pool),
+    fields(
+        sender_id = %sender_id,
+    )
+)]
+pub async fn decline_friend_request(
+    sender_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let sender_id = sender_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_friend_request(&db_pool, sender_id, user_id).await {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => HttpResponse::NotFound()
+            .body(format!("no friend request from user {} found", sender_id)),
+        Err(e) => {
+            tracing::error!("failed to decline friend request {}: {:?}", sender_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the user's friends, most recently added first.
+#[tracing::instrument(name = "Getting friends", skip(params, user_id, db_pool))]
+pub async fn get_many_friends(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    match get_friends(&db_pool, user_id, &params).await {
+        Ok(friendships) => {
+            HttpResponse::Ok().json(Page::from_rows(friendships, &params, |f| f.friend_id()))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Removing friend",
+    skip(friend_id, user_id, db_pool, hub),
+    fields(
+        friend_id = %friend_id,
+    )
+)]
+pub async fn remove_friend(
+    friend_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let friend_id = friend_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_friends_between(&db_pool, user_id, friend_id).await {
+        Ok(_) => {
+            refresh_presence_audiences(&db_pool, &hub, &[user_id, friend_id]).await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => {
+            tracing::error!("failed to remove friend {}: {:?}", friend_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the friends and servers the user shares with another user.
+#[tracing::instrument(
+    name = "Getting mutuals",
+    skip(target_id, user_id, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn get_mutuals(
+    target_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let target_id = target_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    if let Err(e) = ensure_user_exists(&db_pool, target_id).await {
+        return e;
+    }
+    if let Err(e) = ensure_not_blocked_by(&db_pool, user_id, target_id).await {
+        return e;
+    }
+
+    let friend_ids = match get_mutual_friend_ids(&db_pool, user_id, target_id).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!("failed to get mutual friends with {}: {:?}", target_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    match get_mutual_server_ids(&db_pool, user_id, target_id).await {
+        Ok(server_ids) => HttpResponse::Ok().json(Mutuals {
+            friend_ids,
+            server_ids,
+        }),
+        Err(e) => {
+            tracing::error!("failed to get mutual servers with {}: {:?}", target_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
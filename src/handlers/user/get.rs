@@ -1,52 +1,155 @@
 use actix_web::{
-    web::{Data, Path},
-    HttpResponse,
+    web::{Data, Path, ReqData},
+    HttpRequest, HttpResponse,
 };
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::{domain::user::GetUserResponse, storage};
+use crate::{
+    domain::user::{GetUserResponse, ProfileViewer, ProfileVisibility, User},
+    handlers::{
+        middleware::{optional_user_id, UserID},
+        user::ensure_not_blocked_by,
+    },
+    storage,
+};
+
+/// Works out how `viewer_id` relates to the user whose profile they are
+/// viewing, for deciding which parts of it they may see.
+pub async fn get_profile_viewer(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    viewer_id: Option<Uuid>,
+) -> Result<ProfileViewer, HttpResponse> {
+    let viewer_id = match viewer_id {
+        Some(viewer_id) if viewer_id == user_id => return Ok(ProfileViewer::owner()),
+        Some(viewer_id) => viewer_id,
+        None => return Ok(ProfileViewer::default()),
+    };
+
+    let internal_err = |e: sqlx::Error| {
+        tracing::error!("failed to check relation to user {}: {:?}", user_id, e);
+        HttpResponse::InternalServerError().finish()
+    };
+    let is_follower = storage::is_following(db_pool, viewer_id, user_id)
+        .await
+        .map_err(internal_err)?;
+    let is_friend = storage::are_friends(db_pool, viewer_id, user_id)
+        .await
+        .map_err(internal_err)?;
+    Ok(ProfileViewer {
+        is_self: false,
+        is_follower,
+        is_friend,
+    })
+}
+
+/// Fails with a 403 unless `viewer` may see the part of the user's profile
+/// that `visibility` guards.
+pub fn ensure_visible(
+    visibility: ProfileVisibility,
+    viewer: ProfileViewer,
+    user_id: Uuid,
+    what: &str,
+) -> Result<(), HttpResponse> {
+    if visibility.allows(viewer) {
+        return Ok(());
+    }
+    let err = format!("user {} hides their {}", user_id, what);
+    tracing::error!("403 - {}", err);
+    Err(HttpResponse::Forbidden().body(err))
+}
+
+async fn get_active_user(db_pool: &PgPool, id: Uuid) -> Result<User, HttpResponse> {
+    match storage::get_user_by_id(db_pool, id).await {
+        Ok(user) if user.deleted_at().is_some() => {
+            let err = format!("user {} has been soft deleted", id);
+            tracing::error!(err);
+            Err(HttpResponse::BadRequest().body(err))
+        }
+        Ok(user) => Ok(user),
+        Err(sqlx::Error::RowNotFound) => {
+            let err = format!("user {} not found", id);
+            tracing::error!(err);
+            Err(HttpResponse::NotFound().body(err))
+        }
+        Err(e) => {
+            let err = format!("failed to get user {}: {:?}", id, e);
+            tracing::error!(err);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
 
 #[tracing::instrument(
     name = "Getting user by ID",
-    skip(user_id, db_pool),
+    skip(user_id, req, db_pool),
     fields(
         id = %user_id,
     ),
 )]
-pub async fn get_by_id(user_id: Path<Uuid>, db_pool: Data<PgPool>) -> HttpResponse {
+pub async fn get_by_id(
+    user_id: Path<Uuid>,
+    req: HttpRequest,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
     let id = user_id.into_inner();
+    let viewer_id = optional_user_id(&req).map(|viewer_id| Uuid::from(&viewer_id));
 
-    match storage::get_user_by_id(&db_pool, id).await {
-        Ok(user) => {
-            if user.deleted_at().is_some() {
-                let err = format!("user {} has been soft deleted", id);
-                tracing::error!(err);
-                HttpResponse::BadRequest().body(err)
-            } else {
-                let response: GetUserResponse = user.into();
-                let response_str = match serde_json::to_string(&response) {
-                    Ok(response_str) => response_str,
-                    Err(e) => {
-                        let err = format!("failed to parse user struct {} to json: {:?}", id, e);
-                        tracing::error!(err);
-                        return HttpResponse::InternalServerError().finish();
-                    }
-                };
-                HttpResponse::Ok().body(response_str)
-            }
-        }
-        Err(e) => match e {
-            sqlx::Error::RowNotFound => {
-                let err = format!("user {} not found", id);
-                tracing::error!(err);
-                HttpResponse::NotFound().body(err)
-            }
-            e => {
-                let err = format!("failed to get user {}: {:?}", id, e);
-                tracing::error!(err);
-                HttpResponse::InternalServerError().finish()
-            }
-        },
+    if let Some(viewer_id) = viewer_id {
+        if let Err(e) = ensure_not_blocked_by(&db_pool, viewer_id, id).await {
+            return e;
+        }
+    }
+
+    let user = match get_active_user(&db_pool, id).await {
+        Ok(user) => user,
+        Err(e) => return e,
+    };
+    let settings = match storage::get_user_settings(&db_pool, id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("failed to get settings of user {}: {:?}", id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let viewer = match get_profile_viewer(&db_pool, id, viewer_id).await {
+        Ok(viewer) => viewer,
+        Err(e) => return e,
+    };
+
+    let mut response = GetUserResponse::public(user, &settings, viewer);
+    match storage::get_follow_counts(&db_pool, id).await {
+        Ok(counts) => response.set_follow_counts(counts, &settings, viewer),
+        Err(e) => {
+            tracing::error!("failed to get follow counts of user {}: {:?}", id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+    HttpResponse::Ok().json(response)
+}
+
+/// Returns the caller's own profile, including their email and settings.
+#[tracing::instrument(name = "Getting own user", skip(user_id, db_pool))]
+pub async fn get_me(user_id: ReqData<UserID>, db_pool: Data<PgPool>) -> HttpResponse {
+    let id = Uuid::from(&user_id.into_inner());
+
+    let user = match get_active_user(&db_pool, id).await {
+        Ok(user) => user,
+        Err(e) => return e,
+    };
+    let settings = match storage::get_user_settings(&db_pool, id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("failed to get settings of user {}: {:?}", id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    match storage::get_follow_counts(&db_pool, id).await {
+        Ok(counts) => HttpResponse::Ok().json(GetUserResponse::own(user, settings, counts)),
+        Err(e) => {
+            tracing::error!("failed to get follow counts of user {}: {:?}", id, e);
+            HttpResponse::InternalServerError().finish()
+        }
     }
 }
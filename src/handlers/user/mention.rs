@@ -0,0 +1,30 @@
+use actix_web::{
+    web::{Data, Query, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::pagination::{Page, PageParams},
+    handlers::middleware::UserID,
+    storage::get_notifications_by_user_id,
+};
+
+/// Returns the user's mention notifications, newest first.
+#[tracing::instrument(name = "Getting mentions", skip(params, user_id, db_pool))]
+pub async fn get_mentions(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    match get_notifications_by_user_id(&db_pool, user_id, &params).await {
+        Ok(notifications) => {
+            HttpResponse::Ok().json(Page::from_rows(notifications, &params, |n| n.id()))
+        }
+        Err(e) => e.handle_http(),
+    }
+}
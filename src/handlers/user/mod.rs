@@ -1,15 +1,55 @@
+mod block;
+mod bookmark;
 mod confirm;
 mod delete;
+mod feed;
+mod follow;
+mod friend;
 mod get;
 mod login;
+mod mention;
+mod presence;
+mod schedule;
+mod settings;
 mod signup;
+mod unread;
 mod update;
 
+pub use block::*;
+pub use bookmark::*;
 pub use confirm::*;
 pub use delete::*;
+pub use feed::*;
+pub use follow::*;
+pub use friend::*;
 pub use get::*;
 pub use login::*;
+pub use mention::*;
+pub use presence::*;
+pub use schedule::*;
+pub use settings::*;
 pub use signup::*;
+pub use unread::*;
 pub use update::*;
 
 pub const BASE_PATH: &str = "/users";
+pub const ME_PATH: &str = "/@me";
+pub const UNREAD_PATH: &str = "/unread";
+pub const MENTIONS_PATH: &str = "/mentions";
+pub const PRESENCE_PATH: &str = "/presence";
+pub const SCHEDULED_MESSAGES_PATH: &str = "/scheduled-messages";
+pub const FEED_PATH: &str = "/feed";
+pub const SETTINGS_PATH: &str = "/settings";
+pub const FOLLOW_PATH: &str = "/follow";
+pub const FOLLOWERS_PATH: &str = "/followers";
+pub const FOLLOWING_PATH: &str = "/following";
+pub const FOLLOW_REQUESTS_PATH: &str = "/follow-requests";
+pub const BLOCKS_PATH: &str = "/blocks";
+pub const MUTES_PATH: &str = "/mutes";
+pub const MUTED_SERVERS_PATH: &str = "/muted-servers";
+pub const FRIENDS_PATH: &str = "/friends";
+pub const FRIEND_REQUESTS_PATH: &str = "/friend-requests";
+pub const FRIEND_REQUEST_PATH: &str = "/friend-request";
+pub const MUTUALS_PATH: &str = "/mutuals";
+pub const BOOKMARKS_PATH: &str = "/bookmarks";
+pub const BOOKMARK_COLLECTIONS_PATH: &str = "/bookmark-collections";
@@ -0,0 +1,98 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Path, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::presence::Presence,
+    gateway::{GetPresence, Hub, SetPresenceAudience},
+    handlers::{
+        middleware::UserID,
+        user::{ensure_not_blocked_by, get_profile_viewer},
+    },
+    storage,
+};
+
+/// Reloads who may see each user's presence into the gateway after their
+/// settings or relationships changed. A failure is logged and leaves the
+/// old audience in place until the user reconnects.
+pub async fn refresh_presence_audiences(db_pool: &PgPool, hub: &Addr<Hub>, user_ids: &[Uuid]) {
+    for &user_id in user_ids {
+        match storage::get_presence_audience(db_pool, user_id).await {
+            Ok(audience) => hub.do_send(SetPresenceAudience { user_id, audience }),
+            Err(e) => tracing::error!(
+                "failed to refresh presence audience of user {}: {:?}",
+                user_id,
+                e
+            ),
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Getting user presence",
+    skip(user_id, viewer_id, db_pool, hub),
+    fields(
+        id = %user_id,
+    ),
+)]
+pub async fn get_presence(
+    user_id: Path<Uuid>,
+    viewer_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let id = user_id.into_inner();
+    let viewer_id = Uuid::from(&viewer_id.into_inner());
+
+    match storage::get_user_by_id(&db_pool, id).await {
+        Ok(user) if user.deleted_at().is_none() => {}
+        Ok(_) | Err(sqlx::Error::RowNotFound) => {
+            let err = format!("user {} not found", id);
+            tracing::error!(err);
+            return HttpResponse::NotFound().body(err);
+        }
+        Err(e) => {
+            tracing::error!("failed to get user {}: {:?}", id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    if let Err(e) = ensure_not_blocked_by(&db_pool, viewer_id, id).await {
+        return e;
+    }
+
+    // Users hiding their status from the viewer look offline, as if they
+    // were invisible.
+    let settings = match storage::get_user_settings(&db_pool, id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("failed to get settings of user {}: {:?}", id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let viewer = match get_profile_viewer(&db_pool, id, Some(viewer_id)).await {
+        Ok(viewer) => viewer,
+        Err(e) => return e,
+    };
+    if !settings.presence_visibility().allows(viewer) {
+        return HttpResponse::Ok().json(Presence::offline(id));
+    }
+
+    match hub
+        .send(GetPresence {
+            user_id: id,
+            viewer_id,
+        })
+        .await
+    {
+        Ok(presence) => HttpResponse::Ok().json(presence),
+        Err(e) => {
+            tracing::error!("failed to get presence of user {}: {:?}", id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use actix_web::{
+    web::{Data, Json, Path, Query, ReqData},
+    HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        dm::DmThreadKind,
+        message::MessageContent,
+        pagination::{Page, PageParams},
+        permission::Permissions,
+        schedule::{ScheduleTarget, ScheduledMessage},
+    },
+    handlers::{
+        channel::authorize_channel,
+        dm::{authorize_dm_thread, ensure_not_blocked},
+        middleware::UserID,
+    },
+    storage::{
+        count_scheduled_messages, delete_scheduled_message, get_scheduled_messages_by_author_id,
+        insert_scheduled_message,
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct ScheduleMessageRequestBody {
+    #[serde(default)]
+    pub channel_id: Option<Uuid>,
+    #[serde(default)]
+    pub dm_thread_id: Option<Uuid>,
+    pub content: String,
+    pub send_at: DateTime<Utc>,
+}
+
+/// Checks that the user could send to `target` right now. Delivery runs
+/// the full send path again, so this only catches mistakes early.
+async fn authorize_schedule_target(
+    db_pool: &PgPool,
+    target: ScheduleTarget,
+    user_id: Uuid,
+) -> Result<(), HttpResponse> {
+    match target {
+        ScheduleTarget::Channel(channel_id) => {
+            let (_, channel, _) =
+                authorize_channel(db_pool, channel_id, user_id, Permissions::SEND_MESSAGES).await?;
+            if !channel.kind().is_messageable() {
+                let err = format!(
+                    "messages cannot be sent to {} channels",
+                    channel.kind().as_str()
+                );
+                tracing::error!("400 - {}", err);
+                return Err(HttpResponse::BadRequest().body(err));
+            }
+        }
+        ScheduleTarget::DmThread(thread_id) => {
+            let (thread, participant_ids) =
+                authorize_dm_thread(db_pool, thread_id, user_id).await?;
+            if thread.kind() == DmThreadKind::Direct {
+                for &other_id in participant_ids.iter().filter(|&&id| id != user_id) {
+                    ensure_not_blocked(db_pool, user_id, other_id).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "Scheduling message", skip(body, user_id, db_pool))]
+pub async fn schedule_message(
+    body: Json<ScheduleMessageRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+    let now = Utc::now();
+
+    let target = match ScheduleTarget::from_ids(body.channel_id, body.dm_thread_id) {
+        Ok(target) => target,
+        Err(e) => {
+            tracing::error!("400 - invalid schedule target: {:?}", e);
+            return e.handle_http();
+        }
+    };
+    if let Err(e) = ScheduledMessage::validate_send_at(now, body.send_at) {
+        tracing::error!("400 - invalid send time: {:?}", e);
+        return e.handle_http();
+    }
+    let content = match MessageContent::try_from(body.content) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!("400 - invalid message content: {:?}", e);
+            return e.handle_http();
+        }
+    };
+
+    if let Err(e) = authorize_schedule_target(&db_pool, target, user_id).await {
+        return e;
+    }
+
+    match count_scheduled_messages(&db_pool, user_id).await {
+        Ok(pending) => {
+            if let Err(e) = ScheduledMessage::validate_pending_count(pending) {
+                tracing::error!("400 - {:?}", e);
+                return e.handle_http();
+            }
+        }
+        Err(e) => {
+            tracing::error!(
+                "failed to count scheduled messages of user {}: {:?}",
+                user_id,
+                e
+            );
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    let message =
+        ScheduledMessage::new(Uuid::new_v4(), user_id, target, content, body.send_at, now);
+    match insert_scheduled_message(&db_pool, &message).await {
+        Ok(_) => HttpResponse::Ok().json(message),
+        Err(e) => {
+            tracing::error!("500 - Failed to execute query: {:?}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Returns the user's messages still waiting to be sent, soonest first.
+#[tracing::instrument(name = "Getting scheduled messages", skip(params, user_id, db_pool))]
+pub async fn get_scheduled_messages(
+    params: Query<PageParams>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let params = params.into_inner();
+
+    match get_scheduled_messages_by_author_id(&db_pool, user_id, &params).await {
+        Ok(messages) => HttpResponse::Ok().json(Page::from_rows(messages, &params, |m| m.id())),
+        Err(e) => e.handle_http(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Cancelling scheduled message",
+    skip(message_id, user_id, db_pool),
+    fields(
+        message_id = %message_id,
+    )
+)]
+pub async fn cancel_scheduled_message(
+    message_id: Path<Uuid>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+) -> HttpResponse {
+    let message_id = message_id.into_inner();
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match delete_scheduled_message(&db_pool, message_id, user_id).await {
+        Ok(result) if result.rows_affected() > 0 => HttpResponse::NoContent().finish(),
+        Ok(_) => {
+            let err = format!("scheduled message {} not found", message_id);
+            tracing::error!("404 - {}", err);
+            HttpResponse::NotFound().body(err)
+        }
+        Err(e) => {
+            tracing::error!("failed to cancel scheduled message {}: {:?}", message_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use actix::Addr;
+use actix_web::{
+    web::{Data, Json, ReqData},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    domain::user::{DmPrivacy, ProfileVisibility},
+    gateway::Hub,
+    handlers::{
+        middleware::UserID,
+        user::{announce_follow, refresh_presence_audiences},
+    },
+    storage::{accept_follow_requests, get_user_settings, upsert_user_settings},
+};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct UpdateUserSettingsRequestBody {
+    pub is_private: Option<bool>,
+    pub dm_privacy: Option<DmPrivacy>,
+    pub bio_visibility: Option<ProfileVisibility>,
+    pub servers_visibility: Option<ProfileVisibility>,
+    pub followers_visibility: Option<ProfileVisibility>,
+    pub presence_visibility: Option<ProfileVisibility>,
+}
+
+#[tracing::instrument(name = "Getting user settings", skip(user_id, db_pool))]
+pub async fn get_settings(user_id: ReqData<UserID>, db_pool: Data<PgPool>) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    match get_user_settings(&db_pool, user_id).await {
+        Ok(settings) => HttpResponse::Ok().json(settings),
+        Err(e) => {
+            tracing::error!("failed to get settings of user {}: {:?}", user_id, e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Updates the given settings. Making an account public accepts every
+/// pending follow request, since they would no longer need approval.
+#[tracing::instrument(name = "Updating user settings", skip(body, user_id, db_pool, hub))]
+pub async fn update_settings(
+    body: Json<UpdateUserSettingsRequestBody>,
+    user_id: ReqData<UserID>,
+    db_pool: Data<PgPool>,
+    hub: Data<Addr<Hub>>,
+) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+    let body = body.into_inner();
+    let now = Utc::now();
+
+    let mut settings = match get_user_settings(&db_pool, user_id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("failed to get settings of user {}: {:?}", user_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let was_private = settings.is_private();
+    if let Some(is_private) = body.is_private {
+        settings.set_is_private(is_private);
+    }
+    if let Some(dm_privacy) = body.dm_privacy {
+        settings.set_dm_privacy(dm_privacy);
+    }
+    if let Some(visibility) = body.bio_visibility {
+        settings.set_bio_visibility(visibility);
+    }
+    if let Some(visibility) = body.servers_visibility {
+        settings.set_servers_visibility(visibility);
+    }
+    if let Some(visibility) = body.followers_visibility {
+        settings.set_followers_visibility(visibility);
+    }
+    if let Some(visibility) = body.presence_visibility {
+        settings.set_presence_visibility(visibility);
+    }
+    settings.set_updated_at(now);
+
+    if let Err(e) = upsert_user_settings(&db_pool, &settings).await {
+        tracing::error!("failed to update settings of user {}: {:?}", user_id, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    if was_private && !settings.is_private() {
+        match accept_follow_requests(&db_pool, user_id, None, now).await {
+            Ok(follows) => {
+                for follow in &follows {
+                    announce_follow(&hub, follow);
+                }
+            }
+            Err(e) => {
+                tracing::error!("failed to accept follow requests of {}: {:?}", user_id, e);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+    }
+
+    refresh_presence_audiences(&db_pool, &hub, &[user_id]).await;
+
+    HttpResponse::Ok().json(settings)
+}
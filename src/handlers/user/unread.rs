@@ -0,0 +1,91 @@
+use actix_web::{
+    web::{Data, ReqData},
+    HttpResponse,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    handlers::middleware::UserID,
+    storage::{
+        get_all_servers_by_member_id, get_channel_unread_states, get_dm_thread_ids_by_user_id,
+        get_dm_unread_states, get_joined_thread_ids, get_viewable_channels,
+    },
+};
+
+/// Returns unread and mention counts for every messageable channel the user
+/// can view, the threads they have joined in those channels and every DM
+/// thread they are part of.
+#[tracing::instrument(name = "Getting unread counts", skip(user_id, db_pool))]
+pub async fn get_unread(user_id: ReqData<UserID>, db_pool: Data<PgPool>) -> HttpResponse {
+    let user_id = Uuid::from(&user_id.into_inner());
+
+    let servers = match get_all_servers_by_member_id(&db_pool, user_id).await {
+        Ok(servers) => servers,
+        Err(e) => {
+            tracing::error!("failed to get servers of user {}: {:?}", user_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    let mut channel_ids = vec![];
+    for server in servers {
+        let viewable_ids: Vec<Uuid> = match get_viewable_channels(&db_pool, &server, user_id).await
+        {
+            Ok(channels) => channels
+                .iter()
+                .filter(|c| c.kind().is_messageable())
+                .map(|c| c.id())
+                .collect(),
+            Err(e) => {
+                tracing::error!(
+                    "failed to get viewable channels of server {}: {:?}",
+                    server.id(),
+                    e
+                );
+                return HttpResponse::InternalServerError().finish();
+            }
+        };
+        match get_joined_thread_ids(&db_pool, server.id(), user_id).await {
+            Ok(threads) => channel_ids.extend(
+                threads
+                    .into_iter()
+                    .filter(|(_, parent_id)| viewable_ids.contains(parent_id))
+                    .map(|(thread_id, _)| thread_id),
+            ),
+            Err(e) => {
+                tracing::error!(
+                    "failed to get joined threads of server {}: {:?}",
+                    server.id(),
+                    e
+                );
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+        channel_ids.extend(viewable_ids);
+    }
+
+    let mut unread = match get_channel_unread_states(&db_pool, user_id, &channel_ids).await {
+        Ok(unread) => unread,
+        Err(e) => {
+            tracing::error!("failed to get channel unread states: {:?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    let thread_ids = match get_dm_thread_ids_by_user_id(&db_pool, user_id).await {
+        Ok(thread_ids) => thread_ids,
+        Err(e) => {
+            tracing::error!("failed to get DM threads of user {}: {:?}", user_id, e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+    match get_dm_unread_states(&db_pool, user_id, &thread_ids).await {
+        Ok(dm_unread) => unread.extend(dm_unread),
+        Err(e) => {
+            tracing::error!("failed to get DM unread states: {:?}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    }
+
+    HttpResponse::Ok().json(unread)
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use actix::{Actor, AsyncContext, Context, WrapFuture};
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::{domain::message::retention_cutoff, storage::purge_deleted_messages};
+
+/// How often content past retention is purged.
+pub const MESSAGE_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically hard deletes the content and revisions of messages deleted
+/// longer ago than the retention period, leaving tombstones. The edit
+/// history of messages that are still live is kept.
+pub struct MessagePurger {
+    db_pool: PgPool,
+}
+
+impl MessagePurger {
+    pub fn new(db_pool: PgPool) -> Self {
+        MessagePurger { db_pool }
+    }
+}
+
+impl Actor for MessagePurger {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(MESSAGE_PURGE_INTERVAL, |purger, ctx| {
+            let db_pool = purger.db_pool.clone();
+            ctx.spawn(
+                async move {
+                    let cutoff = retention_cutoff(Utc::now());
+                    match purge_deleted_messages(&db_pool, cutoff).await {
+                        Ok(0) => {}
+                        Ok(purged) => tracing::info!("Purged {} deleted messages", purged),
+                        Err(e) => tracing::error!("failed to purge deleted messages: {:?}", e),
+                    }
+                }
+                .into_actor(purger),
+            );
+        });
+    }
+}
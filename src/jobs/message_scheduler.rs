@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use actix::{Actor, Addr, AsyncContext, Context, WrapFuture};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    domain::schedule::ScheduleTarget,
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    handlers::{dm::send_direct_message, message::send_message},
+    storage::{claim_due_scheduled_messages, expire_direct_messages},
+};
+
+/// How often due messages are sent and expired DMs cleared.
+pub const MESSAGE_SCHEDULE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Periodically sends scheduled messages that have come due and clears
+/// disappearing DMs whose time to live has passed.
+pub struct MessageScheduler {
+    db_pool: PgPool,
+    hub: Addr<Hub>,
+}
+
+impl MessageScheduler {
+    pub fn new(db_pool: PgPool, hub: Addr<Hub>) -> Self {
+        MessageScheduler { db_pool, hub }
+    }
+}
+
+impl Actor for MessageScheduler {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(MESSAGE_SCHEDULE_INTERVAL, |scheduler, ctx| {
+            let db_pool = scheduler.db_pool.clone();
+            let hub = scheduler.hub.clone();
+            ctx.spawn(
+                async move { run_message_schedule(&db_pool, &hub, Utc::now()).await }
+                    .into_actor(scheduler),
+            );
+        });
+    }
+}
+
+/// One scheduler sweep. Due messages go through the same send path as
+/// messages posted directly, so a message whose author lost access in the
+/// meantime is dropped rather than sent.
+pub async fn run_message_schedule(db_pool: &PgPool, hub: &Addr<Hub>, now: DateTime<Utc>) {
+    match claim_due_scheduled_messages(db_pool, now).await {
+        Ok(messages) => {
+            for scheduled in messages {
+                let content = scheduled.content().as_ref().to_string();
+                let sent = match scheduled.target() {
+                    ScheduleTarget::Channel(channel_id) => send_message(
+                        db_pool,
+                        hub,
+                        channel_id,
+                        scheduled.author_id(),
+                        content,
+                        None,
+                        None,
+                    )
+                    .await
+                    .map(|(message, _)| message.id()),
+                    ScheduleTarget::DmThread(thread_id) => send_direct_message(
+                        db_pool,
+                        hub,
+                        thread_id,
+                        scheduled.author_id(),
+                        content,
+                        None,
+                    )
+                    .await
+                    .map(|message| message.id()),
+                };
+                match sent {
+                    Ok(message_id) => tracing::info!(
+                        "Scheduled message {} sent as {}",
+                        scheduled.id(),
+                        message_id
+                    ),
+                    Err(response) => tracing::error!(
+                        "failed to send scheduled message {}: {}",
+                        scheduled.id(),
+                        response.status()
+                    ),
+                }
+            }
+        }
+        Err(e) => tracing::error!("failed to claim due scheduled messages: {:?}", e),
+    }
+
+    match expire_direct_messages(db_pool, now).await {
+        Ok(expired) => {
+            for (id, thread_id) in expired {
+                hub.do_send(Publish::new(
+                    Topic::DmThread(thread_id),
+                    GatewayEvent::DirectMessageDelete { id, thread_id },
+                ));
+            }
+        }
+        Err(e) => tracing::error!("failed to expire direct messages: {:?}", e),
+    }
+}
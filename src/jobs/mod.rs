@@ -0,0 +1,11 @@
+mod message_purger;
+mod message_scheduler;
+mod poll_closer;
+mod thread_archiver;
+mod trending_tags;
+
+pub use message_purger::*;
+pub use message_scheduler::*;
+pub use poll_closer::*;
+pub use thread_archiver::*;
+pub use trending_tags::*;
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use actix::{Actor, ActorFutureExt, Addr, AsyncContext, Context, WrapFuture};
+use chrono::Utc;
+use sqlx::{Error, PgPool};
+
+use crate::{
+    domain::poll::{Poll, PollTally, PollTarget},
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    storage::{
+        close_due_polls, get_message_by_id, get_poll_options, get_poll_votes, get_post_by_id,
+    },
+};
+
+/// How often polls that reached their close time are closed.
+pub const POLL_CLOSE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Periodically closes polls that reached their close time and pushes their
+/// final tally, which reveals the counts of polls that hid them.
+pub struct PollCloser {
+    db_pool: PgPool,
+    hub: Addr<Hub>,
+}
+
+impl PollCloser {
+    pub fn new(db_pool: PgPool, hub: Addr<Hub>) -> Self {
+        PollCloser { db_pool, hub }
+    }
+}
+
+/// Closes the due polls and builds the final tally of each whose post or
+/// message is still there, along with the topic to publish it to.
+async fn close_polls(db_pool: &PgPool) -> Result<Vec<(Topic, PollTally)>, Error> {
+    let now = Utc::now();
+    let polls = close_due_polls(db_pool, now).await?;
+    let ids: Vec<_> = polls.iter().map(Poll::id).collect();
+    let options = get_poll_options(db_pool, &ids).await?;
+    let votes = get_poll_votes(db_pool, &ids).await?;
+
+    let mut tallies = Vec::with_capacity(polls.len());
+    for poll in polls {
+        let topic = match poll.target() {
+            PollTarget::Post(post_id) => {
+                let post = get_post_by_id(db_pool, post_id).await?;
+                post.deleted_at()
+                    .is_none()
+                    .then(|| Topic::Server(post.server_id()))
+            }
+            PollTarget::Message(message_id) => {
+                let message = get_message_by_id(db_pool, message_id).await?;
+                message
+                    .deleted_at()
+                    .is_none()
+                    .then(|| Topic::Channel(message.channel_id()))
+            }
+        };
+        if let Some(topic) = topic {
+            let options: Vec<_> = options
+                .iter()
+                .filter(|o| o.poll_id() == poll.id())
+                .cloned()
+                .collect();
+            let votes: Vec<_> = votes
+                .iter()
+                .filter(|v| v.poll_id == poll.id())
+                .cloned()
+                .collect();
+            tallies.push((topic, PollTally::new(&poll, &options, &votes, now)));
+        }
+    }
+    Ok(tallies)
+}
+
+impl Actor for PollCloser {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(POLL_CLOSE_INTERVAL, |closer, ctx| {
+            let db_pool = closer.db_pool.clone();
+            ctx.spawn(
+                async move { close_polls(&db_pool).await }
+                    .into_actor(closer)
+                    .map(|result, closer, _| match result {
+                        Ok(tallies) => {
+                            for (topic, tally) in tallies {
+                                tracing::info!("Poll {} closed at its close time", tally.poll_id);
+                                closer
+                                    .hub
+                                    .do_send(Publish::new(topic, GatewayEvent::PollUpdate(tally)));
+                            }
+                        }
+                        Err(e) => tracing::error!("failed to close due polls: {:?}", e),
+                    }),
+            );
+        });
+    }
+}
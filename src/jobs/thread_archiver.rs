@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use actix::{Actor, ActorFutureExt, Addr, AsyncContext, Context, WrapFuture};
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::{
+    gateway::{GatewayEvent, Hub, Publish, Topic},
+    storage::archive_inactive_threads,
+};
+
+/// How often inactive threads are swept into the archive.
+pub const THREAD_ARCHIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Periodically archives threads that have gone quiet for longer than their
+/// auto archive duration and tells their parent channels.
+pub struct ThreadArchiver {
+    db_pool: PgPool,
+    hub: Addr<Hub>,
+}
+
+impl ThreadArchiver {
+    pub fn new(db_pool: PgPool, hub: Addr<Hub>) -> Self {
+        ThreadArchiver { db_pool, hub }
+    }
+}
+
+impl Actor for ThreadArchiver {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(THREAD_ARCHIVE_INTERVAL, |archiver, ctx| {
+            let db_pool = archiver.db_pool.clone();
+            ctx.spawn(
+                async move { archive_inactive_threads(&db_pool, Utc::now()).await }
+                    .into_actor(archiver)
+                    .map(|result, archiver, _| match result {
+                        Ok(threads) => {
+                            for thread in threads {
+                                tracing::info!("Thread {} archived after inactivity", thread.id());
+                                archiver.hub.do_send(Publish::new(
+                                    Topic::Channel(thread.parent_id()),
+                                    GatewayEvent::ThreadUpdate(thread),
+                                ));
+                            }
+                        }
+                        Err(e) => tracing::error!("failed to archive inactive threads: {:?}", e),
+                    }),
+            );
+        });
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use actix::{Actor, AsyncContext, Context, WrapFuture};
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::storage::refresh_trending_tags;
+
+/// How often trending tags are recomputed.
+pub const TRENDING_TAGS_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Periodically recomputes the trending tags of every server and across all
+/// of them, so that reading them is a lookup. Runs once on start too, so a
+/// restart does not serve a stale list for a whole interval.
+pub struct TrendingTagsRefresher {
+    db_pool: PgPool,
+}
+
+impl TrendingTagsRefresher {
+    pub fn new(db_pool: PgPool) -> Self {
+        TrendingTagsRefresher { db_pool }
+    }
+
+    fn refresh(&self, ctx: &mut Context<Self>) {
+        let db_pool = self.db_pool.clone();
+        ctx.spawn(
+            async move {
+                if let Err(e) = refresh_trending_tags(&db_pool, Utc::now()).await {
+                    tracing::error!("failed to refresh trending tags: {:?}", e);
+                }
+            }
+            .into_actor(self),
+        );
+    }
+}
+
+impl Actor for TrendingTagsRefresher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.refresh(ctx);
+        ctx.run_interval(TRENDING_TAGS_INTERVAL, |refresher, ctx| {
+            refresher.refresh(ctx)
+        });
+    }
+}
@@ -3,7 +3,9 @@ extern crate argon2;
 pub mod config;
 pub mod consts;
 pub mod domain;
+pub mod gateway;
 pub mod handlers;
+pub mod jobs;
 pub mod startup;
 pub mod storage;
 pub mod utils;
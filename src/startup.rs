@@ -1,11 +1,17 @@
 use crate::{
     config::{Config, DatabaseConfig},
     domain::{email, user::Email},
+    gateway::Hub,
     handlers::{
+        channel, dm, gateway,
         health_check::{health_check, HEALTH_CHECK_PATH},
-        server, user,
+        message,
+        middleware::AuthMiddleware,
+        poll, post, search, server, tag, thread, user,
     },
+    jobs::{MessagePurger, MessageScheduler, PollCloser, ThreadArchiver, TrendingTagsRefresher},
 };
+use actix::{Actor, Addr};
 use actix_web::{
     dev::Server,
     web::{delete, get, patch, post, put, scope, Data},
@@ -55,10 +61,17 @@ impl App {
     ) -> Result<Server, std::io::Error> {
         let db_pool = Data::new(db_pool);
         let email_client = Data::new(email_client);
+        let hub: Data<Addr<Hub>> = Data::new(Hub::default().start());
+        ThreadArchiver::new(db_pool.get_ref().clone(), hub.get_ref().clone()).start();
+        MessagePurger::new(db_pool.get_ref().clone()).start();
+        MessageScheduler::new(db_pool.get_ref().clone(), hub.get_ref().clone()).start();
+        TrendingTagsRefresher::new(db_pool.get_ref().clone()).start();
+        PollCloser::new(db_pool.get_ref().clone(), hub.get_ref().clone()).start();
         let server = HttpServer::new(move || {
             actix_web::App::new()
                 .wrap(TracingLogger::default())
                 .route(HEALTH_CHECK_PATH, get().to(health_check))
+                .route(gateway::BASE_PATH, get().to(gateway::connect))
                 .service(
                     scope(user::BASE_PATH)
                         .route(user::SIGNUP_PATH, post().to(user::signup))
@@ -67,27 +80,359 @@ impl App {
                             &format!("{}/{{confirmation_token}}", user::CONFIRM_PATH),
                             post().to(user::confirm),
                         )
+                        .service(
+                            scope(user::ME_PATH)
+                                .wrap(AuthMiddleware)
+                                .route("", get().to(user::get_me))
+                                .route(user::UNREAD_PATH, get().to(user::get_unread))
+                                .route(user::MENTIONS_PATH, get().to(user::get_mentions))
+                                .route(user::FEED_PATH, get().to(user::get_feed))
+                                .route(user::SETTINGS_PATH, get().to(user::get_settings))
+                                .route(user::SETTINGS_PATH, patch().to(user::update_settings))
+                                .service(
+                                    scope(user::BLOCKS_PATH)
+                                        .route("", get().to(user::get_blocks))
+                                        .route("/{user_id}", put().to(user::block_user))
+                                        .route("/{user_id}", delete().to(user::unblock_user)),
+                                )
+                                .service(
+                                    scope(user::MUTES_PATH)
+                                        .route("", get().to(user::get_mutes))
+                                        .route("/{user_id}", put().to(user::mute_user))
+                                        .route("/{user_id}", delete().to(user::unmute_user)),
+                                )
+                                .service(
+                                    scope(user::MUTED_SERVERS_PATH)
+                                        .route("", get().to(user::get_muted_servers))
+                                        .route("/{server_id}", put().to(user::mute_server))
+                                        .route("/{server_id}", delete().to(user::unmute_server)),
+                                )
+                                .service(
+                                    scope(user::FOLLOW_REQUESTS_PATH)
+                                        .route("", get().to(user::get_follow_requests))
+                                        .route(
+                                            "/{requester_id}",
+                                            put().to(user::accept_follow_request),
+                                        )
+                                        .route(
+                                            "/{requester_id}",
+                                            delete().to(user::reject_follow_request),
+                                        ),
+                                )
+                                .service(
+                                    scope(user::FRIEND_REQUESTS_PATH)
+                                        .route("", get().to(user::get_friend_requests))
+                                        .route(
+                                            "/{sender_id}",
+                                            put().to(user::accept_friend_request),
+                                        )
+                                        .route(
+                                            "/{sender_id}",
+                                            delete().to(user::decline_friend_request),
+                                        ),
+                                )
+                                .service(
+                                    scope(user::FRIENDS_PATH)
+                                        .route("", get().to(user::get_many_friends))
+                                        .route("/{friend_id}", delete().to(user::remove_friend)),
+                                )
+                                .service(
+                                    scope(user::SCHEDULED_MESSAGES_PATH)
+                                        .route("", post().to(user::schedule_message))
+                                        .route("", get().to(user::get_scheduled_messages))
+                                        .route(
+                                            "/{message_id}",
+                                            delete().to(user::cancel_scheduled_message),
+                                        ),
+                                )
+                                .service(
+                                    scope(user::BOOKMARKS_PATH)
+                                        .route("", post().to(user::create_bookmark))
+                                        .route("", get().to(user::get_bookmarks))
+                                        .route("/{bookmark_id}", patch().to(user::patch_bookmark))
+                                        .route(
+                                            "/{bookmark_id}",
+                                            delete().to(user::remove_bookmark),
+                                        ),
+                                )
+                                .service(
+                                    scope(user::BOOKMARK_COLLECTIONS_PATH)
+                                        .route("", post().to(user::create_bookmark_collection))
+                                        .route("", get().to(user::get_bookmark_collections))
+                                        .route(
+                                            "/{collection_id}",
+                                            patch().to(user::rename_bookmark_collection),
+                                        )
+                                        .route(
+                                            "/{collection_id}",
+                                            delete().to(user::remove_bookmark_collection),
+                                        ),
+                                ),
+                        )
                         .service(
                             scope("/{user_id}")
                                 .route("", get().to(user::get_by_id))
                                 .route("", put().to(user::update))
                                 .route("", patch().to(user::patch))
                                 .route("", delete().to(user::soft_delete))
-                                .route("/hard", delete().to(user::hard_delete)),
+                                .route("/hard", delete().to(user::hard_delete))
+                                .route("/servers", get().to(server::get_many_by_user))
+                                .service(
+                                    scope(user::PRESENCE_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", get().to(user::get_presence)),
+                                )
+                                .service(
+                                    scope(user::FOLLOW_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", put().to(user::follow))
+                                        .route("", delete().to(user::unfollow)),
+                                )
+                                .service(
+                                    scope(user::FOLLOWERS_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", get().to(user::get_many_followers)),
+                                )
+                                .service(
+                                    scope(user::FOLLOWING_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", get().to(user::get_many_following)),
+                                )
+                                .service(
+                                    scope(user::FRIEND_REQUEST_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", put().to(user::send_friend_request))
+                                        .route("", delete().to(user::cancel_friend_request)),
+                                )
+                                .service(
+                                    scope(user::MUTUALS_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", get().to(user::get_mutuals)),
+                                ),
                         ),
                 )
+                .service(
+                    scope(tag::BASE_PATH)
+                        .wrap(AuthMiddleware)
+                        .route(tag::TRENDING_PATH, get().to(tag::get_trending))
+                        .route(
+                            &format!("/{{tag}}{}", tag::POSTS_PATH),
+                            get().to(tag::get_posts),
+                        ),
+                )
+                .service(
+                    scope(&format!("{}/{{poll_id}}", poll::BASE_PATH))
+                        .wrap(AuthMiddleware)
+                        .route("", get().to(poll::get_by_id))
+                        .route(poll::VOTES_PATH, put().to(poll::vote))
+                        .route(poll::VOTES_PATH, delete().to(poll::retract_vote))
+                        .route(poll::CLOSE_PATH, post().to(poll::close)),
+                )
                 .service(
                     scope(server::BASE_PATH)
+                        .route("", get().to(server::search))
                         .route("", post().to(server::create))
                         .service(
                             scope("/{server_id}")
+                                .route("", get().to(server::get_by_id))
                                 .route("", put().to(server::update))
                                 .route("", delete().to(server::soft_delete))
-                                .route("/hard", delete().to(server::hard_delete)),
+                                .route("/hard", delete().to(server::hard_delete))
+                                .service(
+                                    scope(server::EMOJIS_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", get().to(server::get_emojis))
+                                        .route("", post().to(server::create_emoji))
+                                        .route("/{emoji_id}", delete().to(server::delete_emoji)),
+                                )
+                                .service(
+                                    scope(server::MEMBERS_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", post().to(server::join)),
+                                )
+                                .service(
+                                    scope(post::BASE_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", get().to(post::get_many_by_server))
+                                        .route("", post().to(post::create)),
+                                )
+                                .service(
+                                    scope(message::BASE_PATH).wrap(AuthMiddleware).route(
+                                        search::BASE_PATH,
+                                        get().to(search::server_messages),
+                                    ),
+                                )
+                                .service(
+                                    scope(channel::BASE_PATH)
+                                        .wrap(AuthMiddleware)
+                                        .route("", get().to(channel::get_many_by_server))
+                                        .route("", post().to(channel::create))
+                                        .service(
+                                            scope("/{channel_id}")
+                                                .route("", get().to(channel::get_by_id))
+                                                .route("", patch().to(channel::patch))
+                                                .route("", delete().to(channel::soft_delete))
+                                                .route(
+                                                    channel::PERMISSIONS_PATH,
+                                                    get().to(channel::get_overwrites),
+                                                )
+                                                .route(
+                                                    &format!(
+                                                        "{}/{{target_id}}",
+                                                        channel::PERMISSIONS_PATH
+                                                    ),
+                                                    put().to(channel::put_overwrite),
+                                                )
+                                                .route(
+                                                    &format!(
+                                                        "{}/{{target_id}}",
+                                                        channel::PERMISSIONS_PATH
+                                                    ),
+                                                    delete().to(channel::delete_overwrite),
+                                                ),
+                                        ),
+                                ),
+                        ),
+                )
+                .service(
+                    scope(&format!("{}/{{channel_id}}", channel::BASE_PATH))
+                        .wrap(AuthMiddleware)
+                        .route(message::TYPING_PATH, post().to(message::start_typing))
+                        .route(thread::BASE_PATH, get().to(thread::get_many_by_channel))
+                        .route(message::PINS_PATH, get().to(message::get_pins))
+                        .route(
+                            &format!("{}/{{message_id}}", message::PINS_PATH),
+                            put().to(message::pin),
+                        )
+                        .route(
+                            &format!("{}/{{message_id}}", message::PINS_PATH),
+                            delete().to(message::unpin),
+                        )
+                        .service(
+                            scope(message::BASE_PATH)
+                                .route("", get().to(message::get_many_by_channel))
+                                .route("", post().to(message::create))
+                                .service(
+                                    scope("/{message_id}")
+                                        .route("", get().to(message::get_by_id))
+                                        .route("", patch().to(message::edit))
+                                        .route("", delete().to(message::soft_delete))
+                                        .route(message::ACK_PATH, post().to(message::ack))
+                                        .route(
+                                            message::HISTORY_PATH,
+                                            get().to(message::get_history),
+                                        )
+                                        .route(thread::BASE_PATH, post().to(thread::create))
+                                        .service(
+                                            scope(&format!(
+                                                "{}/{{emoji}}",
+                                                message::REACTIONS_PATH
+                                            ))
+                                            .route("", get().to(message::get_reactions))
+                                            .route("", put().to(message::add_reaction))
+                                            .route("", delete().to(message::remove_own_reaction))
+                                            .route(
+                                                "/{user_id}",
+                                                delete().to(message::remove_user_reaction),
+                                            ),
+                                        ),
+                                ),
+                        ),
+                )
+                .service(
+                    scope(&format!("{}/{{thread_id}}", thread::BASE_PATH))
+                        .wrap(AuthMiddleware)
+                        .route("", get().to(thread::get_by_id))
+                        .route("", patch().to(thread::patch))
+                        .route(thread::MEMBERS_PATH, get().to(thread::get_members))
+                        .route(
+                            &format!("{}{}", thread::MEMBERS_PATH, user::ME_PATH),
+                            put().to(thread::join),
+                        )
+                        .route(
+                            &format!("{}{}", thread::MEMBERS_PATH, user::ME_PATH),
+                            delete().to(thread::leave),
+                        ),
+                )
+                .service(
+                    scope(&format!("{}/{{post_id}}", post::BASE_PATH))
+                        .wrap(AuthMiddleware)
+                        .route("", get().to(post::get_by_id))
+                        .route("", patch().to(post::edit))
+                        .route("", delete().to(post::soft_delete))
+                        .route(
+                            &format!("{}{}", post::LIKES_PATH, user::ME_PATH),
+                            put().to(post::like_post),
+                        )
+                        .route(
+                            &format!("{}{}", post::LIKES_PATH, user::ME_PATH),
+                            delete().to(post::unlike_post),
+                        )
+                        .service(
+                            scope(post::COMMENTS_PATH)
+                                .route("", get().to(post::get_comments))
+                                .route("", post().to(post::create_comment))
+                                .service(
+                                    scope("/{comment_id}")
+                                        .route("", patch().to(post::update_comment))
+                                        .route("", delete().to(post::delete_comment))
+                                        .route(
+                                            &format!("{}{}", post::LIKES_PATH, user::ME_PATH),
+                                            put().to(post::like_comment),
+                                        )
+                                        .route(
+                                            &format!("{}{}", post::LIKES_PATH, user::ME_PATH),
+                                            delete().to(post::unlike_comment),
+                                        ),
+                                ),
+                        ),
+                )
+                .service(
+                    scope(search::BASE_PATH)
+                        .wrap(AuthMiddleware)
+                        .route("", get().to(search::direct_messages)),
+                )
+                .service(
+                    scope(dm::BASE_PATH)
+                        .wrap(AuthMiddleware)
+                        .route("", get().to(dm::get_many))
+                        .route("", post().to(dm::open_direct))
+                        .route(dm::GROUPS_PATH, post().to(dm::create_group))
+                        .service(
+                            scope("/{thread_id}")
+                                .route("", get().to(dm::get_by_id))
+                                .route(
+                                    &format!("{}/{{user_id}}", dm::PARTICIPANTS_PATH),
+                                    put().to(dm::add_participant),
+                                )
+                                .route(
+                                    &format!("{}/{{user_id}}", dm::PARTICIPANTS_PATH),
+                                    delete().to(dm::remove_participant),
+                                )
+                                .route(dm::MESSAGES_PATH, get().to(dm::get_messages))
+                                .route(dm::MESSAGES_PATH, post().to(dm::create_message))
+                                .route(dm::TYPING_PATH, post().to(dm::start_typing))
+                                .service(
+                                    scope(&format!("{}/{{message_id}}", dm::MESSAGES_PATH))
+                                        .route("", get().to(dm::get_message))
+                                        .route("", patch().to(dm::edit_message))
+                                        .route("", delete().to(dm::delete_message))
+                                        .route(message::ACK_PATH, post().to(dm::ack_message))
+                                        .service(
+                                            scope(&format!(
+                                                "{}/{{emoji}}",
+                                                message::REACTIONS_PATH
+                                            ))
+                                            .route("", get().to(dm::get_reactions))
+                                            .route("", put().to(dm::add_reaction))
+                                            .route("", delete().to(dm::remove_reaction)),
+                                        ),
+                                ),
                         ),
                 )
                 .app_data(db_pool.clone())
                 .app_data(email_client.clone())
+                .app_data(hub.clone())
         })
         .listen(listener)?
         .run();
@@ -0,0 +1,403 @@
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    block::{ServerMute, UserBlock, UserMute},
+    pagination::{PageErr, PageParams},
+};
+
+pub const USER_BLOCKS_TABLE_NAME: &str = "user_blocks";
+pub const USER_MUTES_TABLE_NAME: &str = "user_mutes";
+pub const SERVER_MUTES_TABLE_NAME: &str = "server_mutes";
+
+#[tracing::instrument(
+    name = "Inserting user block to database",
+    skip(block, db_pool),
+    fields(
+        blocker_id = %block.blocker_id(),
+        blocked_id = %block.blocked_id(),
+    )
+)]
+pub async fn insert_user_block(
+    db_pool: &PgPool,
+    block: &UserBlock,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO user_blocks (blocker_id, blocked_id, created_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (blocker_id, blocked_id) DO NOTHING;
+        "#,
+    )
+    .bind(block.blocker_id())
+    .bind(block.blocked_id())
+    .bind(block.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting user block from database",
+    skip(blocker_id, blocked_id, db_pool),
+    fields(
+        blocker_id = %blocker_id,
+        blocked_id = %blocked_id,
+    )
+)]
+pub async fn delete_user_block(
+    db_pool: &PgPool,
+    blocker_id: Uuid,
+    blocked_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM user_blocks WHERE blocker_id = $1 AND blocked_id = $2;
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Whether either user has blocked the other.
+#[tracing::instrument(
+    name = "Checking for block between users",
+    skip(first_user_id, second_user_id, db_pool),
+    fields(
+        first_user_id = %first_user_id,
+        second_user_id = %second_user_id,
+    )
+)]
+pub async fn is_blocked_between(
+    db_pool: &PgPool,
+    first_user_id: Uuid,
+    second_user_id: Uuid,
+) -> Result<bool, Error> {
+    query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM user_blocks
+            WHERE (blocker_id = $1 AND blocked_id = $2)
+                OR (blocker_id = $2 AND blocked_id = $1)
+        )
+        "#,
+    )
+    .bind(first_user_id)
+    .bind(second_user_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Whether `blocked_id` has been blocked by `blocker_id`.
+#[tracing::instrument(
+    name = "Checking for user block",
+    skip(blocker_id, blocked_id, db_pool),
+    fields(
+        blocker_id = %blocker_id,
+        blocked_id = %blocked_id,
+    )
+)]
+pub async fn is_blocked_by(
+    db_pool: &PgPool,
+    blocked_id: Uuid,
+    blocker_id: Uuid,
+) -> Result<bool, Error> {
+    query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM user_blocks WHERE blocker_id = $1 AND blocked_id = $2
+        )
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(blocked_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns the users among `user_ids` who have blocked `user_id` or been
+/// blocked by them.
+#[tracing::instrument(
+    name = "Getting blocked users among ids",
+    skip(user_id, user_ids, db_pool),
+    fields(
+        user_id = %user_id,
+        candidates = user_ids.len(),
+    )
+)]
+pub async fn get_blocked_user_ids_among(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    user_ids: &[Uuid],
+) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        SELECT blocked_id FROM user_blocks WHERE blocker_id = $1 AND blocked_id = ANY($2)
+        UNION
+        SELECT blocker_id FROM user_blocks WHERE blocked_id = $1 AND blocker_id = ANY($2)
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Returns a page of the users `blocker_id` has blocked, most recent first.
+/// The cursor is a blocked user's ID.
+#[tracing::instrument(
+    name = "Getting user blocks",
+    skip(blocker_id, params, db_pool),
+    fields(
+        blocker_id = %blocker_id,
+    )
+)]
+pub async fn get_user_blocks(
+    db_pool: &PgPool,
+    blocker_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<UserBlock>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM user_blocks WHERE blocked_id = $1 AND blocker_id = $2)",
+        params.cursor(),
+        Some(blocker_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT blocker_id, blocked_id, created_at
+        FROM user_blocks
+        WHERE blocker_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (created_at, blocked_id) < (
+                    SELECT created_at, blocked_id FROM user_blocks
+                    WHERE blocker_id = $1 AND blocked_id = $2
+                )
+            )
+        ORDER BY created_at DESC, blocked_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(blocker_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Inserting user mute to database",
+    skip(mute, db_pool),
+    fields(
+        muter_id = %mute.muter_id(),
+        muted_id = %mute.muted_id(),
+    )
+)]
+pub async fn insert_user_mute(db_pool: &PgPool, mute: &UserMute) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO user_mutes (muter_id, muted_id, created_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (muter_id, muted_id) DO NOTHING;
+        "#,
+    )
+    .bind(mute.muter_id())
+    .bind(mute.muted_id())
+    .bind(mute.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting user mute from database",
+    skip(muter_id, muted_id, db_pool),
+    fields(
+        muter_id = %muter_id,
+        muted_id = %muted_id,
+    )
+)]
+pub async fn delete_user_mute(
+    db_pool: &PgPool,
+    muter_id: Uuid,
+    muted_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM user_mutes WHERE muter_id = $1 AND muted_id = $2;
+        "#,
+    )
+    .bind(muter_id)
+    .bind(muted_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Returns the users among `user_ids` who have muted `muted_id`.
+#[tracing::instrument(
+    name = "Getting muters among ids",
+    skip(muted_id, user_ids, db_pool),
+    fields(
+        muted_id = %muted_id,
+        candidates = user_ids.len(),
+    )
+)]
+pub async fn get_muter_ids_among(
+    db_pool: &PgPool,
+    muted_id: Uuid,
+    user_ids: &[Uuid],
+) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        SELECT muter_id FROM user_mutes WHERE muted_id = $1 AND muter_id = ANY($2)
+        "#,
+    )
+    .bind(muted_id)
+    .bind(user_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Returns a page of the users `muter_id` has muted, most recent first. The
+/// cursor is a muted user's ID.
+#[tracing::instrument(
+    name = "Getting user mutes",
+    skip(muter_id, params, db_pool),
+    fields(
+        muter_id = %muter_id,
+    )
+)]
+pub async fn get_user_mutes(
+    db_pool: &PgPool,
+    muter_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<UserMute>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM user_mutes WHERE muted_id = $1 AND muter_id = $2)",
+        params.cursor(),
+        Some(muter_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT muter_id, muted_id, created_at
+        FROM user_mutes
+        WHERE muter_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (created_at, muted_id) < (
+                    SELECT created_at, muted_id FROM user_mutes
+                    WHERE muter_id = $1 AND muted_id = $2
+                )
+            )
+        ORDER BY created_at DESC, muted_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(muter_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Inserting server mute to database",
+    skip(mute, db_pool),
+    fields(
+        user_id = %mute.user_id(),
+        server_id = %mute.server_id(),
+    )
+)]
+pub async fn insert_server_mute(
+    db_pool: &PgPool,
+    mute: &ServerMute,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO server_mutes (user_id, server_id, created_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, server_id) DO NOTHING;
+        "#,
+    )
+    .bind(mute.user_id())
+    .bind(mute.server_id())
+    .bind(mute.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting server mute from database",
+    skip(user_id, server_id, db_pool),
+    fields(
+        user_id = %user_id,
+        server_id = %server_id,
+    )
+)]
+pub async fn delete_server_mute(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    server_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM server_mutes WHERE user_id = $1 AND server_id = $2;
+        "#,
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Returns a page of the servers `user_id` has muted, most recent first.
+/// The cursor is a server ID.
+#[tracing::instrument(
+    name = "Getting server mutes",
+    skip(user_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_server_mutes(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<ServerMute>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM server_mutes WHERE server_id = $1 AND user_id = $2)",
+        params.cursor(),
+        Some(user_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT user_id, server_id, created_at
+        FROM server_mutes
+        WHERE user_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (created_at, server_id) < (
+                    SELECT created_at, server_id FROM server_mutes
+                    WHERE user_id = $1 AND server_id = $2
+                )
+            )
+        ORDER BY created_at DESC, server_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
@@ -0,0 +1,329 @@
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    bookmark::{Bookmark, BookmarkCollection, BookmarkTarget},
+    pagination::{PageErr, PageParams},
+};
+
+pub const BOOKMARKS_TABLE_NAME: &str = "bookmarks";
+pub const BOOKMARK_COLLECTIONS_TABLE_NAME: &str = "bookmark_collections";
+
+/// Saves a bookmark. Affects no rows when the user already bookmarked the
+/// same post or message.
+#[tracing::instrument(
+    name = "Inserting bookmark to database",
+    skip(bookmark, db_pool),
+    fields(
+        bookmark_id = %bookmark.id(),
+        user_id = %bookmark.user_id(),
+    )
+)]
+pub async fn insert_bookmark(
+    db_pool: &PgPool,
+    bookmark: &Bookmark,
+) -> Result<PgQueryResult, Error> {
+    let (post_id, message_id) = match bookmark.target() {
+        BookmarkTarget::Post(id) => (Some(id), None),
+        BookmarkTarget::Message(id) => (None, Some(id)),
+    };
+    query(
+        r#"
+        INSERT INTO bookmarks (id, user_id, post_id, message_id, collection_id, note, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT DO NOTHING;
+        "#,
+    )
+    .bind(bookmark.id())
+    .bind(bookmark.user_id())
+    .bind(post_id)
+    .bind(message_id)
+    .bind(bookmark.collection_id())
+    .bind(bookmark.note())
+    .bind(bookmark.created_at())
+    .bind(bookmark.updated_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting bookmark by target",
+    skip(user_id, target, db_pool),
+    fields(
+        user_id = %user_id,
+        target = ?target,
+    )
+)]
+pub async fn get_bookmark_by_target(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    target: BookmarkTarget,
+) -> Result<Option<Bookmark>, Error> {
+    let (post_id, message_id) = match target {
+        BookmarkTarget::Post(id) => (Some(id), None),
+        BookmarkTarget::Message(id) => (None, Some(id)),
+    };
+    query_as(
+        r#"
+        SELECT id, user_id, post_id, message_id, collection_id, note, created_at, updated_at
+        FROM bookmarks
+        WHERE user_id = $1 AND (post_id = $2 OR message_id = $3)
+        "#,
+    )
+    .bind(user_id)
+    .bind(post_id)
+    .bind(message_id)
+    .fetch_optional(db_pool)
+    .await
+}
+
+/// Loads one of the user's bookmarks. Other users' bookmarks are not found.
+#[tracing::instrument(
+    name = "Getting bookmark by id",
+    skip(id, user_id, db_pool),
+    fields(
+        bookmark_id = %id,
+        user_id = %user_id,
+    )
+)]
+pub async fn get_bookmark_by_id(
+    db_pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<Bookmark>, Error> {
+    query_as(
+        r#"
+        SELECT id, user_id, post_id, message_id, collection_id, note, created_at, updated_at
+        FROM bookmarks
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await
+}
+
+/// Returns a page of the user's bookmarks, newest first, optionally only
+/// those filed into `collection_id`.
+#[tracing::instrument(
+    name = "Getting bookmarks",
+    skip(user_id, collection_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+        collection_id = ?collection_id,
+    )
+)]
+pub async fn get_bookmarks(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    collection_id: Option<Uuid>,
+    params: &PageParams,
+) -> Result<Vec<Bookmark>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM bookmarks WHERE id = $1 AND user_id = $2)",
+        params.cursor(),
+        Some(user_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT id, user_id, post_id, message_id, collection_id, note, created_at, updated_at
+        FROM bookmarks
+        WHERE user_id = $1
+            AND ($2::uuid IS NULL OR collection_id = $2)
+            AND (
+                $3::uuid IS NULL
+                OR (created_at, id) < (SELECT created_at, id FROM bookmarks WHERE id = $3)
+            )
+        ORDER BY created_at DESC, id DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(user_id)
+    .bind(collection_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Updating bookmark",
+    skip(bookmark, db_pool),
+    fields(
+        bookmark_id = %bookmark.id(),
+    )
+)]
+pub async fn update_bookmark(
+    db_pool: &PgPool,
+    bookmark: &Bookmark,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE bookmarks SET collection_id = $1, note = $2, updated_at = $3
+        WHERE id = $4 AND user_id = $5;
+        "#,
+    )
+    .bind(bookmark.collection_id())
+    .bind(bookmark.note())
+    .bind(bookmark.updated_at())
+    .bind(bookmark.id())
+    .bind(bookmark.user_id())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting bookmark from database",
+    skip(id, user_id, db_pool),
+    fields(
+        bookmark_id = %id,
+        user_id = %user_id,
+    )
+)]
+pub async fn delete_bookmark(
+    db_pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM bookmarks WHERE id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Inserting bookmark collection to database",
+    skip(collection, db_pool),
+    fields(
+        collection_id = %collection.id(),
+        user_id = %collection.user_id(),
+    )
+)]
+pub async fn insert_bookmark_collection(
+    db_pool: &PgPool,
+    collection: &BookmarkCollection,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO bookmark_collections (id, user_id, name, created_at)
+        VALUES ($1, $2, $3, $4);
+        "#,
+    )
+    .bind(collection.id())
+    .bind(collection.user_id())
+    .bind(collection.name())
+    .bind(collection.created_at())
+    .execute(db_pool)
+    .await
+}
+
+/// Loads one of the user's collections. Other users' collections are not
+/// found.
+#[tracing::instrument(
+    name = "Getting bookmark collection by id",
+    skip(id, user_id, db_pool),
+    fields(
+        collection_id = %id,
+        user_id = %user_id,
+    )
+)]
+pub async fn get_bookmark_collection_by_id(
+    db_pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<BookmarkCollection>, Error> {
+    query_as(
+        r#"
+        SELECT id, user_id, name, created_at
+        FROM bookmark_collections
+        WHERE id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await
+}
+
+/// The user's collections in alphabetical order.
+#[tracing::instrument(
+    name = "Getting bookmark collections",
+    skip(user_id, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_bookmark_collections(
+    db_pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<BookmarkCollection>, Error> {
+    query_as(
+        r#"
+        SELECT id, user_id, name, created_at
+        FROM bookmark_collections
+        WHERE user_id = $1
+        ORDER BY name ASC, id ASC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Renaming bookmark collection",
+    skip(collection, db_pool),
+    fields(
+        collection_id = %collection.id(),
+    )
+)]
+pub async fn rename_bookmark_collection(
+    db_pool: &PgPool,
+    collection: &BookmarkCollection,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE bookmark_collections SET name = $1 WHERE id = $2 AND user_id = $3;
+        "#,
+    )
+    .bind(collection.name())
+    .bind(collection.id())
+    .bind(collection.user_id())
+    .execute(db_pool)
+    .await
+}
+
+/// Deletes a collection. Its bookmarks are kept and become unsorted.
+#[tracing::instrument(
+    name = "Deleting bookmark collection from database",
+    skip(id, user_id, db_pool),
+    fields(
+        collection_id = %id,
+        user_id = %user_id,
+    )
+)]
+pub async fn delete_bookmark_collection(
+    db_pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM bookmark_collections WHERE id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
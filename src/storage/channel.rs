@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use crate::domain::channel::{Channel, PermissionOverwrite};
+
+pub const CHANNELS_TABLE_NAME: &str = "channels";
+
+#[tracing::instrument(
+    name = "Upserting channel details to database",
+    skip(channel, db_pool),
+    fields(
+        channel_data = %channel,
+    )
+)]
+pub async fn upsert_channel(db_pool: &PgPool, channel: &Channel) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO channels (id, server_id, parent_id, kind, name, topic, position, max_pins, created_at, updated_at, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (id)
+        DO
+            UPDATE SET
+                parent_id = EXCLUDED.parent_id,
+                name = EXCLUDED.name,
+                topic = EXCLUDED.topic,
+                position = EXCLUDED.position,
+                max_pins = EXCLUDED.max_pins,
+                updated_at = now(),
+                deleted_at = EXCLUDED.deleted_at
+        WHERE
+            (channels.parent_id, channels.name, channels.topic, channels.position, channels.max_pins, channels.deleted_at) IS DISTINCT FROM
+            (EXCLUDED.parent_id, EXCLUDED.name, EXCLUDED.topic, EXCLUDED.position, EXCLUDED.max_pins, EXCLUDED.deleted_at);
+        "#)
+        .bind(channel.id())
+        .bind(channel.server_id())
+        .bind(channel.parent_id())
+        .bind(channel.kind())
+        .bind(channel.name())
+        .bind(channel.topic())
+        .bind(channel.position())
+        .bind(channel.max_pins())
+        .bind(channel.created_at())
+        .bind(channel.updated_at())
+        .bind(channel.deleted_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting channel by id",
+    skip(id, db_pool),
+    fields(
+        channel_id = %id
+    )
+)]
+pub async fn get_channel_by_id(db_pool: &PgPool, id: Uuid) -> Result<Channel, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, parent_id, kind, name, topic, position, max_pins, created_at, updated_at, deleted_at
+        FROM channels
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Threads are left out; they are listed under their parent channel.
+#[tracing::instrument(
+    name = "Getting channels by server id",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id
+    )
+)]
+pub async fn get_channels_by_server_id(
+    db_pool: &PgPool,
+    server_id: Uuid,
+) -> Result<Vec<Channel>, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, parent_id, kind, name, topic, position, max_pins, created_at, updated_at, deleted_at
+        FROM channels
+        WHERE server_id = $1 AND deleted_at IS NULL AND kind <> 'thread'
+        ORDER BY position, created_at
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting next channel position for server",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id
+    )
+)]
+pub async fn get_next_channel_position(db_pool: &PgPool, server_id: Uuid) -> Result<i32, Error> {
+    query_scalar(
+        r#"
+        SELECT COALESCE(MAX(position) + 1, 0)
+        FROM channels
+        WHERE server_id = $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(server_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Soft deletes a channel along with the threads started in it. Channels in
+/// a deleted category are moved out of it instead.
+#[tracing::instrument(
+    name = "Soft Deleting Channel in Database",
+    skip(channel_id, deleted_at, db_pool),
+    fields(
+        channel_id = %channel_id,
+        deleted_at = %deleted_at,
+    )
+)]
+pub async fn soft_delete_channel(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+    deleted_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+            UPDATE channels SET parent_id = NULL, updated_at = now() WHERE parent_id = $1 AND kind <> 'thread';
+        "#,
+    )
+    .bind(channel_id)
+    .execute(&mut transaction)
+    .await?;
+    let result = query(
+        r#"
+            UPDATE channels SET deleted_at = $1
+            WHERE id = $2 OR (parent_id = $2 AND kind = 'thread' AND deleted_at IS NULL);
+        "#,
+    )
+    .bind(deleted_at)
+    .bind(channel_id)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+#[tracing::instrument(
+    name = "Getting permission overwrites for channel",
+    skip(channel_id, db_pool),
+    fields(
+        channel_id = %channel_id
+    )
+)]
+pub async fn get_channel_overwrites(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+) -> Result<Vec<PermissionOverwrite>, Error> {
+    query_as(
+        r#"
+        SELECT channel_id, target_id, target_type, allow, deny
+        FROM channel_permission_overwrites
+        WHERE channel_id = $1
+        "#,
+    )
+    .bind(channel_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Upserting channel permission overwrite to database",
+    skip(overwrite, db_pool),
+    fields(
+        channel_id = %overwrite.channel_id(),
+        target_id = %overwrite.target_id(),
+    )
+)]
+pub async fn upsert_channel_overwrite(
+    db_pool: &PgPool,
+    overwrite: &PermissionOverwrite,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO channel_permission_overwrites (channel_id, target_id, target_type, allow, deny)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (channel_id, target_id)
+        DO
+            UPDATE SET
+                target_type = EXCLUDED.target_type,
+                allow = EXCLUDED.allow,
+                deny = EXCLUDED.deny;
+        "#,
+    )
+    .bind(overwrite.channel_id())
+    .bind(overwrite.target_id())
+    .bind(overwrite.target_type())
+    .bind(overwrite.allow())
+    .bind(overwrite.deny())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting channel permission overwrite from database",
+    skip(channel_id, target_id, db_pool),
+    fields(
+        channel_id = %channel_id,
+        target_id = %target_id,
+    )
+)]
+pub async fn delete_channel_overwrite(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+    target_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+            DELETE FROM channel_permission_overwrites WHERE channel_id = $1 AND target_id = $2;
+        "#,
+    )
+    .bind(channel_id)
+    .bind(target_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting permission overwrites for server channels",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id
+    )
+)]
+pub async fn get_channel_overwrites_by_server_id(
+    db_pool: &PgPool,
+    server_id: Uuid,
+) -> Result<Vec<PermissionOverwrite>, Error> {
+    query_as(
+        r#"
+        SELECT o.channel_id, o.target_id, o.target_type, o.allow, o.deny
+        FROM channel_permission_overwrites o
+        JOIN channels c ON c.id = o.channel_id
+        WHERE c.server_id = $1 AND c.deleted_at IS NULL
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    message::MessageContent,
+    pagination::{PageErr, PageParams},
+    post::{Comment, CommentSort, CommentView, LikeSummary},
+};
+
+pub const COMMENTS_TABLE_NAME: &str = "comments";
+pub const COMMENT_LIKES_TABLE_NAME: &str = "comment_likes";
+
+/// Comment rows along with their like and reply counts, as seen by the
+/// user bound to `$1`.
+const COMMENT_VIEW_COLUMNS: &str = r#"
+    c.id, c.post_id, c.parent_id, c.author_id, c.content, c.created_at, c.updated_at,
+    c.edited_at, c.deleted_at,
+    (SELECT count(*) FROM comment_likes l WHERE l.comment_id = c.id) AS like_count,
+    EXISTS(SELECT 1 FROM comment_likes l WHERE l.comment_id = c.id AND l.user_id = $1) AS liked,
+    (
+        SELECT count(*) FROM comments r
+        WHERE r.parent_id = c.id AND r.deleted_at IS NULL
+    ) AS reply_count
+"#;
+
+#[tracing::instrument(
+    name = "Inserting comment to database",
+    skip(comment, db_pool),
+    fields(
+        comment_id = %comment.id(),
+        post_id = %comment.post_id(),
+    )
+)]
+pub async fn insert_comment(db_pool: &PgPool, comment: &Comment) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO comments (id, post_id, parent_id, author_id, content, created_at, updated_at, edited_at, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);
+        "#,
+    )
+    .bind(comment.id())
+    .bind(comment.post_id())
+    .bind(comment.parent_id())
+    .bind(comment.author_id())
+    .bind(comment.content())
+    .bind(comment.created_at())
+    .bind(comment.updated_at())
+    .bind(comment.edited_at())
+    .bind(comment.deleted_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting comment by id",
+    skip(id, db_pool),
+    fields(
+        comment_id = %id
+    )
+)]
+pub async fn get_comment_by_id(db_pool: &PgPool, id: Uuid) -> Result<Comment, Error> {
+    query_as(
+        r#"
+        SELECT id, post_id, parent_id, author_id, content, created_at, updated_at, edited_at, deleted_at
+        FROM comments
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting comment view by id",
+    skip(id, user_id, db_pool),
+    fields(
+        comment_id = %id
+    )
+)]
+pub async fn get_comment_view_by_id(
+    db_pool: &PgPool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<CommentView, Error> {
+    query_as(&format!(
+        r#"
+        SELECT {}
+        FROM comments c
+        WHERE c.id = $2
+        "#,
+        COMMENT_VIEW_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns a page of the comments on a post that reply to `parent_id`, or
+/// the top level comments when it is `None`.
+#[tracing::instrument(
+    name = "Getting comments by post id",
+    skip(post_id, parent_id, user_id, sort, params, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn get_comments_by_post_id(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    parent_id: Option<Uuid>,
+    user_id: Uuid,
+    sort: CommentSort,
+    params: &PageParams,
+) -> Result<Vec<CommentView>, PageErr> {
+    let keys = match sort {
+        CommentSort::Newest => "created_at, id",
+        CommentSort::Top => "like_count, created_at, id",
+    };
+    let order = match sort {
+        CommentSort::Newest => "created_at DESC, id DESC",
+        CommentSort::Top => "like_count DESC, created_at DESC, id DESC",
+    };
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM comments WHERE id = $1 AND post_id = $2 AND deleted_at IS NULL)",
+        params.cursor(),
+        Some(post_id),
+    )
+    .await?;
+    query_as(&format!(
+        r#"
+        WITH c AS (
+            SELECT {}
+            FROM comments c
+            WHERE c.post_id = $2
+                AND c.parent_id IS NOT DISTINCT FROM $3
+                AND c.deleted_at IS NULL
+        )
+        SELECT * FROM c
+        WHERE $4::uuid IS NULL OR ({keys}) < (SELECT {keys} FROM c WHERE id = $4)
+        ORDER BY {order}
+        LIMIT $5
+        "#,
+        COMMENT_VIEW_COLUMNS,
+        keys = keys,
+        order = order,
+    ))
+    .bind(user_id)
+    .bind(post_id)
+    .bind(parent_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Editing comment in database",
+    skip(comment_id, content, edited_at, db_pool),
+    fields(
+        comment_id = %comment_id,
+    )
+)]
+pub async fn edit_comment(
+    db_pool: &PgPool,
+    comment_id: Uuid,
+    content: &MessageContent,
+    edited_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE comments
+        SET content = $1, edited_at = $2, updated_at = $2
+        WHERE id = $3;
+        "#,
+    )
+    .bind(content)
+    .bind(edited_at)
+    .bind(comment_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Soft deleting comment in database",
+    skip(comment_id, deleted_at, db_pool),
+    fields(
+        comment_id = %comment_id,
+        deleted_at = %deleted_at,
+    )
+)]
+pub async fn soft_delete_comment(
+    db_pool: &PgPool,
+    comment_id: Uuid,
+    deleted_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE comments SET deleted_at = $1 WHERE id = $2;
+        "#,
+    )
+    .bind(deleted_at)
+    .bind(comment_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Likes a comment. Liking a comment twice is a no-op.
+#[tracing::instrument(
+    name = "Inserting comment like to database",
+    skip(comment_id, user_id, db_pool),
+    fields(
+        comment_id = %comment_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn insert_comment_like(
+    db_pool: &PgPool,
+    comment_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO comment_likes (comment_id, user_id, created_at)
+        VALUES ($1, $2, now())
+        ON CONFLICT (comment_id, user_id) DO NOTHING;
+        "#,
+    )
+    .bind(comment_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting comment like from database",
+    skip(comment_id, user_id, db_pool),
+    fields(
+        comment_id = %comment_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn delete_comment_like(
+    db_pool: &PgPool,
+    comment_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM comment_likes WHERE comment_id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(comment_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting comment like summary",
+    skip(comment_id, user_id, db_pool),
+    fields(
+        comment_id = %comment_id,
+    )
+)]
+pub async fn get_comment_like_summary(
+    db_pool: &PgPool,
+    comment_id: Uuid,
+    user_id: Uuid,
+) -> Result<LikeSummary, Error> {
+    query_as(
+        r#"
+        SELECT count(*) AS like_count, COALESCE(bool_or(user_id = $2), false) AS liked
+        FROM comment_likes
+        WHERE comment_id = $1
+        "#,
+    )
+    .bind(comment_id)
+    .bind(user_id)
+    .fetch_one(db_pool)
+    .await
+}
@@ -76,11 +76,10 @@ pub async fn delete(db_pool: &PgPool, token: &ConfirmationToken) -> Result<(), E
             token.user_id()
         );
     })
-    .map_err(|e| {
+    .inspect_err(|_| {
         tracing::error!(
             "DELETE confirmation token for user {} failed",
             token.user_id()
         );
-        e
     })
 }
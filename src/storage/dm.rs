@@ -0,0 +1,572 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    dm::{DirectMessage, DmThread},
+    message::{MessageContent, MessageCursor},
+    pagination::{PageErr, PageParams},
+};
+
+pub const DM_THREADS_TABLE_NAME: &str = "dm_threads";
+pub const DIRECT_MESSAGES_TABLE_NAME: &str = "direct_messages";
+
+#[tracing::instrument(
+    name = "Getting or inserting direct DM thread",
+    skip(thread, first_user_id, second_user_id, db_pool),
+    fields(
+        first_user_id = %first_user_id,
+        second_user_id = %second_user_id,
+    )
+)]
+pub async fn get_or_insert_direct_thread(
+    db_pool: &PgPool,
+    thread: &DmThread,
+    first_user_id: Uuid,
+    second_user_id: Uuid,
+) -> Result<DmThread, Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        INSERT INTO dm_threads (id, kind, first_user_id, second_user_id, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (first_user_id, second_user_id) WHERE kind = 'direct' DO NOTHING;
+        "#,
+    )
+    .bind(thread.id())
+    .bind(thread.kind())
+    .bind(first_user_id)
+    .bind(second_user_id)
+    .bind(thread.created_at())
+    .bind(thread.updated_at())
+    .execute(&mut transaction)
+    .await?;
+    let thread: DmThread = query_as(
+        r#"
+        SELECT id, kind, owner_id, name, last_message_at, created_at, updated_at, deleted_at
+        FROM dm_threads
+        WHERE kind = 'direct' AND first_user_id = $1 AND second_user_id = $2
+        "#,
+    )
+    .bind(first_user_id)
+    .bind(second_user_id)
+    .fetch_one(&mut transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO dm_participants (thread_id, user_id)
+        SELECT $1, unnest($2::uuid[])
+        ON CONFLICT (thread_id, user_id) DO NOTHING;
+        "#,
+    )
+    .bind(thread.id())
+    .bind(vec![first_user_id, second_user_id])
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(thread)
+}
+
+#[tracing::instrument(
+    name = "Inserting group DM thread to database",
+    skip(thread, participant_ids, db_pool),
+    fields(
+        thread_id = %thread.id(),
+    )
+)]
+pub async fn insert_group_thread(
+    db_pool: &PgPool,
+    thread: &DmThread,
+    participant_ids: &[Uuid],
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+        INSERT INTO dm_threads (id, kind, owner_id, name, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6);
+        "#,
+    )
+    .bind(thread.id())
+    .bind(thread.kind())
+    .bind(thread.owner_id())
+    .bind(thread.name())
+    .bind(thread.created_at())
+    .bind(thread.updated_at())
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO dm_participants (thread_id, user_id)
+        SELECT $1, unnest($2::uuid[])
+        ON CONFLICT (thread_id, user_id) DO NOTHING;
+        "#,
+    )
+    .bind(thread.id())
+    .bind(participant_ids)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+#[tracing::instrument(
+    name = "Getting DM thread by id",
+    skip(id, db_pool),
+    fields(
+        thread_id = %id
+    )
+)]
+pub async fn get_dm_thread_by_id(db_pool: &PgPool, id: Uuid) -> Result<DmThread, Error> {
+    query_as(
+        r#"
+        SELECT id, kind, owner_id, name, last_message_at, created_at, updated_at, deleted_at
+        FROM dm_threads
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting DM threads by user id",
+    skip(user_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_dm_threads_by_user_id(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<DmThread>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM dm_threads WHERE id = $1)",
+        params.cursor(),
+        None,
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT t.id, t.kind, t.owner_id, t.name, t.last_message_at, t.created_at, t.updated_at, t.deleted_at
+        FROM dm_threads t
+        JOIN dm_participants p ON p.thread_id = t.id
+        WHERE p.user_id = $1
+            AND t.deleted_at IS NULL
+            AND (
+                $2::uuid IS NULL
+                OR (COALESCE(t.last_message_at, t.created_at), t.id) <
+                    (SELECT COALESCE(last_message_at, created_at), id FROM dm_threads WHERE id = $2)
+            )
+        ORDER BY COALESCE(t.last_message_at, t.created_at) DESC, t.id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Getting DM thread ids by user id",
+    skip(user_id, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_dm_thread_ids_by_user_id(
+    db_pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        SELECT t.id
+        FROM dm_threads t
+        JOIN dm_participants p ON p.thread_id = t.id
+        WHERE p.user_id = $1 AND t.deleted_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting DM participant ids",
+    skip(thread_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn get_dm_participant_ids(db_pool: &PgPool, thread_id: Uuid) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        SELECT user_id FROM dm_participants
+        WHERE thread_id = $1
+        ORDER BY joined_at, user_id
+        "#,
+    )
+    .bind(thread_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting DM participants by thread ids",
+    skip(thread_ids, db_pool),
+    fields(
+        threads = thread_ids.len(),
+    )
+)]
+pub async fn get_dm_participants_by_thread_ids(
+    db_pool: &PgPool,
+    thread_ids: &[Uuid],
+) -> Result<Vec<(Uuid, Uuid)>, Error> {
+    query_as(
+        r#"
+        SELECT thread_id, user_id FROM dm_participants
+        WHERE thread_id = ANY($1)
+        ORDER BY joined_at, user_id
+        "#,
+    )
+    .bind(thread_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Inserting DM participant",
+    skip(thread_id, user_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn insert_dm_participant(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO dm_participants (thread_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT (thread_id, user_id) DO NOTHING;
+        "#,
+    )
+    .bind(thread_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Removes a participant from a group DM. If the owner leaves, ownership
+/// passes to the longest standing remaining participant, whose ID is
+/// returned. A group left empty is soft deleted instead and keeps its last
+/// owner.
+#[tracing::instrument(
+    name = "Removing DM participant",
+    skip(thread_id, user_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn remove_dm_participant(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<Uuid>, Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        DELETE FROM dm_participants WHERE thread_id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(thread_id)
+    .bind(user_id)
+    .execute(&mut transaction)
+    .await?;
+    let new_owner_id: Option<Option<Uuid>> = query_scalar(
+        r#"
+        UPDATE dm_threads
+        SET
+            owner_id = COALESCE(
+                (SELECT user_id FROM dm_participants WHERE thread_id = $1 ORDER BY joined_at, user_id LIMIT 1),
+                owner_id
+            ),
+            deleted_at = CASE
+                WHEN EXISTS(SELECT 1 FROM dm_participants WHERE thread_id = $1) THEN deleted_at
+                ELSE now()
+            END,
+            updated_at = now()
+        WHERE id = $1 AND owner_id = $2
+        RETURNING CASE WHEN deleted_at IS NULL THEN owner_id END;
+        "#,
+    )
+    .bind(thread_id)
+    .bind(user_id)
+    .fetch_optional(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(new_owner_id.flatten())
+}
+
+#[tracing::instrument(
+    name = "Inserting direct message to database",
+    skip(message, db_pool),
+    fields(
+        message_id = %message.id(),
+        thread_id = %message.thread_id(),
+    )
+)]
+pub async fn insert_direct_message(
+    db_pool: &PgPool,
+    message: &DirectMessage,
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+        INSERT INTO direct_messages (id, thread_id, author_id, content, created_at, updated_at, edited_at, deleted_at, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);
+        "#,
+    )
+    .bind(message.id())
+    .bind(message.thread_id())
+    .bind(message.author_id())
+    .bind(message.content())
+    .bind(message.created_at())
+    .bind(message.updated_at())
+    .bind(message.edited_at())
+    .bind(message.deleted_at())
+    .bind(message.expires_at())
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        UPDATE dm_threads
+        SET last_message_at = GREATEST(last_message_at, $1)
+        WHERE id = $2;
+        "#,
+    )
+    .bind(message.created_at())
+    .bind(message.thread_id())
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+/// Disappearing messages that have expired, or been wiped by the scheduler,
+/// are treated as missing.
+#[tracing::instrument(
+    name = "Getting direct message by id",
+    skip(id, db_pool),
+    fields(
+        message_id = %id
+    )
+)]
+pub async fn get_direct_message_by_id(db_pool: &PgPool, id: Uuid) -> Result<DirectMessage, Error> {
+    query_as(
+        r#"
+        SELECT id, thread_id, author_id, content, created_at, updated_at, edited_at, deleted_at,
+            expires_at
+        FROM direct_messages
+        WHERE id = $1
+            AND (expires_at IS NULL OR (expires_at > now() AND deleted_at IS NULL))
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+async fn get_direct_messages_relative_to(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    pivot_id: Uuid,
+    comparison: &str,
+    order: &str,
+    limit: i64,
+) -> Result<Vec<DirectMessage>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM direct_messages WHERE id = $1 AND thread_id = $2)",
+        Some(pivot_id),
+        Some(thread_id),
+    )
+    .await?;
+    query_as(&format!(
+        r#"
+        SELECT id, thread_id, author_id, content, created_at, updated_at, edited_at, deleted_at,
+            expires_at
+        FROM direct_messages
+        WHERE thread_id = $1
+            AND deleted_at IS NULL
+            AND (expires_at IS NULL OR expires_at > now())
+            AND (created_at, id) {} (SELECT created_at, id FROM direct_messages WHERE id = $2 AND thread_id = $1)
+        ORDER BY created_at {}, id {}
+        LIMIT $3
+        "#,
+        comparison, order, order,
+    ))
+    .bind(thread_id)
+    .bind(pivot_id)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Getting direct messages by thread id",
+    skip(thread_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn get_direct_messages_by_thread_id(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    cursor: MessageCursor,
+    limit: i64,
+) -> Result<Vec<DirectMessage>, PageErr> {
+    match cursor {
+        MessageCursor::Latest => {
+            query_as(
+                r#"
+                SELECT id, thread_id, author_id, content, created_at, updated_at, edited_at, deleted_at,
+                    expires_at
+                FROM direct_messages
+                WHERE thread_id = $1
+                    AND deleted_at IS NULL
+                    AND (expires_at IS NULL OR expires_at > now())
+                ORDER BY created_at DESC, id DESC
+                LIMIT $2
+                "#,
+            )
+            .bind(thread_id)
+            .bind(limit)
+            .fetch_all(db_pool)
+            .await
+            .map_err(PageErr::Query)
+        }
+        MessageCursor::Before(id) => {
+            get_direct_messages_relative_to(db_pool, thread_id, id, "<", "DESC", limit).await
+        }
+        MessageCursor::After(id) => {
+            let mut messages =
+                get_direct_messages_relative_to(db_pool, thread_id, id, ">", "ASC", limit).await?;
+            messages.reverse();
+            Ok(messages)
+        }
+        MessageCursor::Around(id) => {
+            let mut messages =
+                get_direct_messages_relative_to(db_pool, thread_id, id, ">", "ASC", limit / 2)
+                    .await?;
+            messages.reverse();
+            let older = get_direct_messages_relative_to(
+                db_pool,
+                thread_id,
+                id,
+                "<=",
+                "DESC",
+                limit - messages.len() as i64,
+            )
+            .await?;
+            messages.extend(older);
+            Ok(messages)
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Editing direct message in database",
+    skip(message_id, content, edited_at, db_pool),
+    fields(
+        message_id = %message_id,
+    )
+)]
+pub async fn edit_direct_message(
+    db_pool: &PgPool,
+    message_id: Uuid,
+    content: &MessageContent,
+    edited_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE direct_messages
+        SET content = $1, edited_at = $2, updated_at = now()
+        WHERE id = $3;
+        "#,
+    )
+    .bind(content)
+    .bind(edited_at)
+    .bind(message_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Soft Deleting Direct Message in Database",
+    skip(message_id, deleted_at, db_pool),
+    fields(
+        message_id = %message_id,
+        deleted_at = %deleted_at,
+    )
+)]
+pub async fn soft_delete_direct_message(
+    db_pool: &PgPool,
+    message_id: Uuid,
+    deleted_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE direct_messages SET deleted_at = $1 WHERE id = $2;
+        "#,
+    )
+    .bind(deleted_at)
+    .bind(message_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Wipes the content of direct messages whose time to live has passed and
+/// soft deletes them as of their expiry, returning `(id, thread_id)` for
+/// each. The rows stay behind as tombstones so read markers keep pointing at
+/// something.
+#[tracing::instrument(name = "Expiring direct messages", skip(now, db_pool))]
+pub async fn expire_direct_messages(
+    db_pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<(Uuid, Uuid)>, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let expired: Vec<(Uuid, Uuid)> = query_as(
+        r#"
+        UPDATE direct_messages
+        SET content = '', deleted_at = expires_at, updated_at = $1
+        WHERE expires_at <= $1 AND deleted_at IS NULL
+        RETURNING id, thread_id
+        "#,
+    )
+    .bind(now)
+    .fetch_all(&mut transaction)
+    .await?;
+    let ids: Vec<Uuid> = expired.iter().map(|(id, _)| *id).collect();
+    query(
+        r#"
+        DELETE FROM direct_message_reactions WHERE message_id = ANY($1);
+        "#,
+    )
+    .bind(&ids)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(expired)
+}
@@ -0,0 +1,99 @@
+use chrono::Utc;
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use crate::domain::reaction::ServerEmoji;
+
+pub const SERVER_EMOJIS_TABLE_NAME: &str = "server_emojis";
+
+#[tracing::instrument(
+    name = "Inserting server emoji to database",
+    skip(emoji, db_pool),
+    fields(
+        emoji_data = %emoji,
+    )
+)]
+pub async fn insert_server_emoji(
+    db_pool: &PgPool,
+    emoji: &ServerEmoji,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO server_emojis (id, server_id, name, creator_id, created_at, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(emoji.id())
+    .bind(emoji.server_id())
+    .bind(emoji.name())
+    .bind(emoji.creator_id())
+    .bind(emoji.created_at())
+    .bind(emoji.deleted_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting server emoji by id",
+    skip(id, db_pool),
+    fields(
+        emoji_id = %id,
+    )
+)]
+pub async fn get_server_emoji_by_id(db_pool: &PgPool, id: Uuid) -> Result<ServerEmoji, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, name, creator_id, created_at, deleted_at
+        FROM server_emojis
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting server emojis by server id",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn get_server_emojis_by_server_id(
+    db_pool: &PgPool,
+    server_id: Uuid,
+) -> Result<Vec<ServerEmoji>, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, name, creator_id, created_at, deleted_at
+        FROM server_emojis
+        WHERE server_id = $1 AND deleted_at IS NULL
+        ORDER BY name
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Soft deleting server emoji",
+    skip(id, db_pool),
+    fields(
+        emoji_id = %id,
+    )
+)]
+pub async fn soft_delete_server_emoji(db_pool: &PgPool, id: Uuid) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE server_emojis
+        SET deleted_at = $2
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .bind(Utc::now())
+    .execute(db_pool)
+    .await
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{query_as, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    pagination::{PageErr, PageParams},
+    post::{FeedSort, Post, COMMENT_WEIGHT, ENGAGEMENT_WINDOW_DAYS, LIKE_WEIGHT, SCORE_GRAVITY},
+};
+
+/// Returns a page of a user's home feed: posts from the servers they belong
+/// to, leaving out servers and users they have muted and users on either
+/// side of a block. Posts by users they follow only show up from servers
+/// they share, since posts elsewhere are closed to non-members. Posts, likes and
+/// comments made after `as_of` are ignored, so scores and order do not
+/// change between pages.
+#[tracing::instrument(
+    name = "Getting home feed",
+    skip(user_id, sort, as_of, params, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_home_feed(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    sort: FeedSort,
+    as_of: DateTime<Utc>,
+    params: &PageParams,
+) -> Result<Vec<Post>, PageErr> {
+    let (score, since) = match sort {
+        FeedSort::Recent => ("0::float8", None),
+        FeedSort::Engagement => (
+            r#"
+            (
+                $5 * (SELECT count(*) FROM post_likes l WHERE l.post_id = p.id AND l.created_at <= $2)
+                + $6 * (
+                    SELECT count(*) FROM comments c
+                    WHERE c.post_id = p.id AND c.deleted_at IS NULL AND c.created_at <= $2
+                )
+            ) / power(extract(epoch FROM $2 - p.created_at)::float8 / 3600 + 2, $7)
+            "#,
+            Some(as_of - Duration::days(ENGAGEMENT_WINDOW_DAYS)),
+        ),
+    };
+    let keys = match sort {
+        FeedSort::Recent => "created_at, id",
+        FeedSort::Engagement => "score, created_at, id",
+    };
+    // The cursor's keys are read from the post itself rather than the feed,
+    // so paging on from a post that has since left the feed still works.
+    let cursor_keys = match sort {
+        FeedSort::Recent => String::from("p.created_at, p.id"),
+        FeedSort::Engagement => format!("{}, p.created_at, p.id", score),
+    };
+    let order = match sort {
+        FeedSort::Recent => "created_at DESC, id DESC",
+        FeedSort::Engagement => "score DESC, created_at DESC, id DESC",
+    };
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM posts WHERE id = $1)",
+        params.cursor(),
+        None,
+    )
+    .await?;
+    query_as(&format!(
+        r#"
+        WITH feed AS (
+            SELECT p.id, p.server_id, p.author_id, p.content, p.quoted_post_id, p.created_at,
+                p.updated_at, p.edited_at, p.deleted_at, {score} AS score
+            FROM posts p
+            JOIN servers s ON s.id = p.server_id AND s.deleted_at IS NULL
+            WHERE p.deleted_at IS NULL
+                AND p.created_at <= $2
+                AND ($3::timestamptz IS NULL OR p.created_at > $3)
+                AND (
+                    s.owner_id = $1
+                    OR EXISTS(
+                        SELECT 1 FROM server_members m
+                        WHERE m.server_id = s.id AND m.user_id = $1 AND m.is_banned IS NOT TRUE
+                    )
+                )
+                AND NOT EXISTS(
+                    SELECT 1 FROM server_mutes sm WHERE sm.user_id = $1 AND sm.server_id = s.id
+                )
+                AND NOT EXISTS(
+                    SELECT 1 FROM user_mutes um WHERE um.muter_id = $1 AND um.muted_id = p.author_id
+                )
+                AND NOT EXISTS(
+                    SELECT 1 FROM user_blocks b
+                    WHERE (b.blocker_id = $1 AND b.blocked_id = p.author_id)
+                        OR (b.blocker_id = p.author_id AND b.blocked_id = $1)
+                )
+        )
+        SELECT id, server_id, author_id, content, quoted_post_id, created_at, updated_at, edited_at, deleted_at
+        FROM feed
+        WHERE $4::uuid IS NULL OR ({keys}) < (SELECT {cursor_keys} FROM posts p WHERE p.id = $4)
+        ORDER BY {order}
+        LIMIT $8
+        "#,
+        score = score,
+        keys = keys,
+        cursor_keys = cursor_keys,
+        order = order,
+    ))
+    .bind(user_id)
+    .bind(as_of)
+    .bind(since)
+    .bind(params.cursor())
+    .bind(LIKE_WEIGHT)
+    .bind(COMMENT_WEIGHT)
+    .bind(SCORE_GRAVITY)
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
@@ -0,0 +1,393 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    follow::{Follow, FollowCounts, FollowRequest},
+    pagination::{PageErr, PageParams},
+};
+
+pub const USER_FOLLOWS_TABLE_NAME: &str = "user_follows";
+pub const FOLLOW_REQUESTS_TABLE_NAME: &str = "follow_requests";
+
+#[tracing::instrument(
+    name = "Inserting follow to database",
+    skip(follow, db_pool),
+    fields(
+        follower_id = %follow.follower_id(),
+        followee_id = %follow.followee_id(),
+    )
+)]
+pub async fn insert_follow(db_pool: &PgPool, follow: &Follow) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO user_follows (follower_id, followee_id, created_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (follower_id, followee_id) DO NOTHING;
+        "#,
+    )
+    .bind(follow.follower_id())
+    .bind(follow.followee_id())
+    .bind(follow.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting follow from database",
+    skip(follower_id, followee_id, db_pool),
+    fields(
+        follower_id = %follower_id,
+        followee_id = %followee_id,
+    )
+)]
+pub async fn delete_follow(
+    db_pool: &PgPool,
+    follower_id: Uuid,
+    followee_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM user_follows WHERE follower_id = $1 AND followee_id = $2;
+        "#,
+    )
+    .bind(follower_id)
+    .bind(followee_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Checking for follow",
+    skip(follower_id, followee_id, db_pool),
+    fields(
+        follower_id = %follower_id,
+        followee_id = %followee_id,
+    )
+)]
+pub async fn is_following(
+    db_pool: &PgPool,
+    follower_id: Uuid,
+    followee_id: Uuid,
+) -> Result<bool, Error> {
+    query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM user_follows WHERE follower_id = $1 AND followee_id = $2
+        )
+        "#,
+    )
+    .bind(follower_id)
+    .bind(followee_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Counts a user's followers and followees, leaving out deleted users.
+#[tracing::instrument(
+    name = "Getting follow counts",
+    skip(user_id, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_follow_counts(db_pool: &PgPool, user_id: Uuid) -> Result<FollowCounts, Error> {
+    query_as(
+        r#"
+        SELECT
+            (
+                SELECT count(*) FROM user_follows f
+                JOIN users u ON u.id = f.follower_id AND u.deleted_at IS NULL
+                WHERE f.followee_id = $1
+            ) AS follower_count,
+            (
+                SELECT count(*) FROM user_follows f
+                JOIN users u ON u.id = f.followee_id AND u.deleted_at IS NULL
+                WHERE f.follower_id = $1
+            ) AS following_count
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns a page of the users following `user_id`, most recent first. The
+/// cursor is a follower's ID.
+#[tracing::instrument(
+    name = "Getting followers",
+    skip(user_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_followers(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<Follow>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM user_follows WHERE follower_id = $1 AND followee_id = $2)",
+        params.cursor(),
+        Some(user_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT f.follower_id, f.followee_id, f.created_at
+        FROM user_follows f
+        JOIN users u ON u.id = f.follower_id AND u.deleted_at IS NULL
+        WHERE f.followee_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (f.created_at, f.follower_id) < (
+                    SELECT created_at, follower_id FROM user_follows
+                    WHERE followee_id = $1 AND follower_id = $2
+                )
+            )
+        ORDER BY f.created_at DESC, f.follower_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+/// Returns a page of the users `user_id` follows, most recent first. The
+/// cursor is a followee's ID.
+#[tracing::instrument(
+    name = "Getting followees",
+    skip(user_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_following(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<Follow>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM user_follows WHERE followee_id = $1 AND follower_id = $2)",
+        params.cursor(),
+        Some(user_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT f.follower_id, f.followee_id, f.created_at
+        FROM user_follows f
+        JOIN users u ON u.id = f.followee_id AND u.deleted_at IS NULL
+        WHERE f.follower_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (f.created_at, f.followee_id) < (
+                    SELECT created_at, followee_id FROM user_follows
+                    WHERE follower_id = $1 AND followee_id = $2
+                )
+            )
+        ORDER BY f.created_at DESC, f.followee_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Inserting follow request to database",
+    skip(request, db_pool),
+    fields(
+        requester_id = %request.requester_id(),
+        target_id = %request.target_id(),
+    )
+)]
+pub async fn insert_follow_request(
+    db_pool: &PgPool,
+    request: &FollowRequest,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO follow_requests (requester_id, target_id, created_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (requester_id, target_id) DO NOTHING;
+        "#,
+    )
+    .bind(request.requester_id())
+    .bind(request.target_id())
+    .bind(request.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting follow request from database",
+    skip(requester_id, target_id, db_pool),
+    fields(
+        requester_id = %requester_id,
+        target_id = %target_id,
+    )
+)]
+pub async fn delete_follow_request(
+    db_pool: &PgPool,
+    requester_id: Uuid,
+    target_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM follow_requests WHERE requester_id = $1 AND target_id = $2;
+        "#,
+    )
+    .bind(requester_id)
+    .bind(target_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Returns a page of the pending requests to follow `target_id`, most
+/// recent first. The cursor is a requester's ID.
+#[tracing::instrument(
+    name = "Getting follow requests",
+    skip(target_id, params, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn get_follow_requests_by_target_id(
+    db_pool: &PgPool,
+    target_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<FollowRequest>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM follow_requests WHERE requester_id = $1 AND target_id = $2)",
+        params.cursor(),
+        Some(target_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT r.requester_id, r.target_id, r.created_at
+        FROM follow_requests r
+        JOIN users u ON u.id = r.requester_id AND u.deleted_at IS NULL
+        WHERE r.target_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (r.created_at, r.requester_id) < (
+                    SELECT created_at, requester_id FROM follow_requests
+                    WHERE target_id = $1 AND requester_id = $2
+                )
+            )
+        ORDER BY r.created_at DESC, r.requester_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(target_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+/// Turns pending follow requests into follows. Accepts the request from
+/// `requester_id` when given, or every pending request otherwise, as when
+/// an account stops being private. Returns the new follows.
+#[tracing::instrument(
+    name = "Accepting follow requests",
+    skip(target_id, requester_id, accepted_at, db_pool),
+    fields(
+        target_id = %target_id,
+    )
+)]
+pub async fn accept_follow_requests(
+    db_pool: &PgPool,
+    target_id: Uuid,
+    requester_id: Option<Uuid>,
+    accepted_at: DateTime<Utc>,
+) -> Result<Vec<Follow>, Error> {
+    let mut transaction = db_pool.begin().await?;
+
+    let requests: Vec<FollowRequest> = query_as(
+        r#"
+        DELETE FROM follow_requests
+        WHERE target_id = $1 AND ($2::uuid IS NULL OR requester_id = $2)
+        RETURNING requester_id, target_id, created_at
+        "#,
+    )
+    .bind(target_id)
+    .bind(requester_id)
+    .fetch_all(&mut transaction)
+    .await?;
+
+    let follows: Vec<Follow> = requests.iter().map(|r| r.accept(accepted_at)).collect();
+    for follow in &follows {
+        query(
+            r#"
+            INSERT INTO user_follows (follower_id, followee_id, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (follower_id, followee_id) DO NOTHING;
+            "#,
+        )
+        .bind(follow.follower_id())
+        .bind(follow.followee_id())
+        .bind(follow.created_at())
+        .execute(&mut transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+    Ok(follows)
+}
+
+/// Removes any follows and pending follow requests between two users, in
+/// either direction, as when one blocks the other.
+#[tracing::instrument(
+    name = "Deleting follows between users",
+    skip(first_user_id, second_user_id, db_pool),
+    fields(
+        first_user_id = %first_user_id,
+        second_user_id = %second_user_id,
+    )
+)]
+pub async fn delete_follows_between(
+    db_pool: &PgPool,
+    first_user_id: Uuid,
+    second_user_id: Uuid,
+) -> Result<(), Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        DELETE FROM user_follows
+        WHERE (follower_id = $1 AND followee_id = $2)
+            OR (follower_id = $2 AND followee_id = $1);
+        "#,
+    )
+    .bind(first_user_id)
+    .bind(second_user_id)
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        DELETE FROM follow_requests
+        WHERE (requester_id = $1 AND target_id = $2)
+            OR (requester_id = $2 AND target_id = $1);
+        "#,
+    )
+    .bind(first_user_id)
+    .bind(second_user_id)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await
+}
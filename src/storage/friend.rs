@@ -0,0 +1,360 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    friend::{FriendRequest, FriendRequestDirection, Friendship},
+    pagination::{PageErr, PageParams},
+};
+
+pub const FRIENDSHIPS_TABLE_NAME: &str = "friendships";
+pub const FRIEND_REQUESTS_TABLE_NAME: &str = "friend_requests";
+
+#[tracing::instrument(
+    name = "Inserting friend request to database",
+    skip(request, db_pool),
+    fields(
+        sender_id = %request.sender_id(),
+        recipient_id = %request.recipient_id(),
+    )
+)]
+pub async fn insert_friend_request(
+    db_pool: &PgPool,
+    request: &FriendRequest,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO friend_requests (sender_id, recipient_id, created_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (sender_id, recipient_id) DO NOTHING;
+        "#,
+    )
+    .bind(request.sender_id())
+    .bind(request.recipient_id())
+    .bind(request.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting friend request from database",
+    skip(sender_id, recipient_id, db_pool),
+    fields(
+        sender_id = %sender_id,
+        recipient_id = %recipient_id,
+    )
+)]
+pub async fn delete_friend_request(
+    db_pool: &PgPool,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM friend_requests WHERE sender_id = $1 AND recipient_id = $2;
+        "#,
+    )
+    .bind(sender_id)
+    .bind(recipient_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Returns a page of the user's pending friend requests, most recent first.
+/// The cursor is the other user's ID.
+#[tracing::instrument(
+    name = "Getting friend requests",
+    skip(user_id, direction, params, db_pool),
+    fields(
+        user_id = %user_id,
+        direction = ?direction,
+    )
+)]
+pub async fn get_friend_requests(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    direction: FriendRequestDirection,
+    params: &PageParams,
+) -> Result<Vec<FriendRequest>, PageErr> {
+    let (own, other) = match direction {
+        FriendRequestDirection::Incoming => ("recipient_id", "sender_id"),
+        FriendRequestDirection::Outgoing => ("sender_id", "recipient_id"),
+    };
+    ensure_cursor_exists(
+        db_pool,
+        &format!(
+            "SELECT EXISTS(SELECT 1 FROM friend_requests WHERE {other} = $1 AND {own} = $2)",
+            own = own,
+            other = other,
+        ),
+        params.cursor(),
+        Some(user_id),
+    )
+    .await?;
+    query_as(&format!(
+        r#"
+        SELECT r.sender_id, r.recipient_id, r.created_at
+        FROM friend_requests r
+        JOIN users u ON u.id = r.{other} AND u.deleted_at IS NULL
+        WHERE r.{own} = $1
+            AND (
+                $2::uuid IS NULL
+                OR (r.created_at, r.{other}) < (
+                    SELECT created_at, {other} FROM friend_requests
+                    WHERE {own} = $1 AND {other} = $2
+                )
+            )
+        ORDER BY r.created_at DESC, r.{other} DESC
+        LIMIT $3
+        "#,
+        own = own,
+        other = other,
+    ))
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+/// Turns the friend request from `sender_id` to `recipient_id` into a
+/// friendship. Returns both sides of it, or nothing when there was no such
+/// request.
+#[tracing::instrument(
+    name = "Accepting friend request",
+    skip(sender_id, recipient_id, accepted_at, db_pool),
+    fields(
+        sender_id = %sender_id,
+        recipient_id = %recipient_id,
+    )
+)]
+pub async fn accept_friend_request(
+    db_pool: &PgPool,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    accepted_at: DateTime<Utc>,
+) -> Result<Vec<Friendship>, Error> {
+    let mut transaction = db_pool.begin().await?;
+
+    let request: Option<FriendRequest> = query_as(
+        r#"
+        DELETE FROM friend_requests
+        WHERE sender_id = $1 AND recipient_id = $2
+        RETURNING sender_id, recipient_id, created_at
+        "#,
+    )
+    .bind(sender_id)
+    .bind(recipient_id)
+    .fetch_optional(&mut transaction)
+    .await?;
+    let friendships = match request {
+        Some(request) => request.accept(accepted_at).to_vec(),
+        None => return Ok(vec![]),
+    };
+
+    for friendship in &friendships {
+        query(
+            r#"
+            INSERT INTO friendships (user_id, friend_id, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, friend_id) DO NOTHING;
+            "#,
+        )
+        .bind(friendship.user_id())
+        .bind(friendship.friend_id())
+        .bind(friendship.created_at())
+        .execute(&mut transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+    Ok(friendships)
+}
+
+#[tracing::instrument(
+    name = "Checking for friendship",
+    skip(user_id, other_user_id, db_pool),
+    fields(
+        user_id = %user_id,
+        other_user_id = %other_user_id,
+    )
+)]
+pub async fn are_friends(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    other_user_id: Uuid,
+) -> Result<bool, Error> {
+    query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM friendships WHERE user_id = $1 AND friend_id = $2
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_user_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns a page of the user's friends, most recent first. The cursor is a
+/// friend's ID.
+#[tracing::instrument(
+    name = "Getting friends",
+    skip(user_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_friends(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<Friendship>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM friendships WHERE friend_id = $1 AND user_id = $2)",
+        params.cursor(),
+        Some(user_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT f.user_id, f.friend_id, f.created_at
+        FROM friendships f
+        JOIN users u ON u.id = f.friend_id AND u.deleted_at IS NULL
+        WHERE f.user_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (f.created_at, f.friend_id) < (
+                    SELECT created_at, friend_id FROM friendships
+                    WHERE user_id = $1 AND friend_id = $2
+                )
+            )
+        ORDER BY f.created_at DESC, f.friend_id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+/// Removes the friendship and any pending friend requests between two
+/// users, in either direction.
+#[tracing::instrument(
+    name = "Deleting friendship between users",
+    skip(first_user_id, second_user_id, db_pool),
+    fields(
+        first_user_id = %first_user_id,
+        second_user_id = %second_user_id,
+    )
+)]
+pub async fn delete_friends_between(
+    db_pool: &PgPool,
+    first_user_id: Uuid,
+    second_user_id: Uuid,
+) -> Result<(), Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        DELETE FROM friendships
+        WHERE (user_id = $1 AND friend_id = $2)
+            OR (user_id = $2 AND friend_id = $1);
+        "#,
+    )
+    .bind(first_user_id)
+    .bind(second_user_id)
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        DELETE FROM friend_requests
+        WHERE (sender_id = $1 AND recipient_id = $2)
+            OR (sender_id = $2 AND recipient_id = $1);
+        "#,
+    )
+    .bind(first_user_id)
+    .bind(second_user_id)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await
+}
+
+/// Returns the friends two users have in common.
+#[tracing::instrument(
+    name = "Getting mutual friends",
+    skip(user_id, other_user_id, db_pool),
+    fields(
+        user_id = %user_id,
+        other_user_id = %other_user_id,
+    )
+)]
+pub async fn get_mutual_friend_ids(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    other_user_id: Uuid,
+) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        SELECT a.friend_id
+        FROM friendships a
+        JOIN friendships b ON b.friend_id = a.friend_id AND b.user_id = $2
+        JOIN users u ON u.id = a.friend_id AND u.deleted_at IS NULL
+        WHERE a.user_id = $1
+        ORDER BY a.friend_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_user_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Returns the servers two users are both in, as owner or as a member who is
+/// not banned.
+#[tracing::instrument(
+    name = "Getting mutual servers",
+    skip(user_id, other_user_id, db_pool),
+    fields(
+        user_id = %user_id,
+        other_user_id = %other_user_id,
+    )
+)]
+pub async fn get_mutual_server_ids(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    other_user_id: Uuid,
+) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        SELECT s.id
+        FROM servers s
+        WHERE s.deleted_at IS NULL
+            AND (
+                s.owner_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM server_members m
+                    WHERE m.server_id = s.id AND m.user_id = $1 AND m.is_banned IS NOT TRUE
+                )
+            )
+            AND (
+                s.owner_id = $2
+                OR EXISTS (
+                    SELECT 1 FROM server_members m
+                    WHERE m.server_id = s.id AND m.user_id = $2 AND m.is_banned IS NOT TRUE
+                )
+            )
+        ORDER BY s.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_user_id)
+    .fetch_all(db_pool)
+    .await
+}
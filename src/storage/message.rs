@@ -0,0 +1,401 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use super::{ensure_cursor_exists, insert_poll};
+use crate::domain::{
+    message::{
+        Message, MessageContent, MessageCursor, MessageHistory, MessageMentions, MessageRevision,
+    },
+    pagination::PageErr,
+    poll::{Poll, PollOption},
+};
+
+pub const MESSAGES_TABLE_NAME: &str = "messages";
+
+#[tracing::instrument(
+    name = "Inserting message to database",
+    skip(message, poll, db_pool),
+    fields(
+        message_id = %message.id(),
+        channel_id = %message.channel_id(),
+    )
+)]
+pub async fn insert_message(
+    db_pool: &PgPool,
+    message: &Message,
+    poll: Option<(&Poll, &[PollOption])>,
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+        INSERT INTO messages (
+            id, channel_id, author_id, content, created_at, updated_at, edited_at, deleted_at, kind, reply_to_id,
+            mentions, mention_roles, mention_channels, mention_everyone
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14);
+        "#,
+    )
+    .bind(message.id())
+    .bind(message.channel_id())
+    .bind(message.author_id())
+    .bind(message.content())
+    .bind(message.created_at())
+    .bind(message.updated_at())
+    .bind(message.edited_at())
+    .bind(message.deleted_at())
+    .bind(message.kind())
+    .bind(message.reply_to_id())
+    .bind(message.mentions().mentions())
+    .bind(message.mentions().mention_roles())
+    .bind(message.mentions().mention_channels())
+    .bind(message.mentions().mention_everyone())
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO message_revisions (id, message_id, editor_id, content, created_at)
+        VALUES ($1, $2, $3, $4, $5);
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(message.id())
+    .bind(message.author_id())
+    .bind(message.content())
+    .bind(message.created_at())
+    .execute(&mut transaction)
+    .await?;
+    if let Some((poll, options)) = poll {
+        insert_poll(&mut transaction, poll, options).await?;
+    }
+    transaction.commit().await?;
+    Ok(result)
+}
+
+#[tracing::instrument(
+    name = "Getting message by id",
+    skip(id, db_pool),
+    fields(
+        message_id = %id
+    )
+)]
+pub async fn get_message_by_id(db_pool: &PgPool, id: Uuid) -> Result<Message, Error> {
+    query_as(
+        r#"
+        SELECT id, channel_id, author_id, content, created_at, updated_at, edited_at, deleted_at, kind, reply_to_id,
+            mentions, mention_roles, mention_channels, mention_everyone
+        FROM messages
+        WHERE id = $1 AND purged_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns the messages among `ids` that have not been deleted, in no
+/// particular order.
+#[tracing::instrument(name = "Getting messages by ids", skip(ids, db_pool))]
+pub async fn get_messages_by_ids(db_pool: &PgPool, ids: &[Uuid]) -> Result<Vec<Message>, Error> {
+    query_as(
+        r#"
+        SELECT id, channel_id, author_id, content, created_at, updated_at, edited_at, deleted_at, kind, reply_to_id,
+            mentions, mention_roles, mention_channels, mention_everyone
+        FROM messages
+        WHERE id = ANY($1) AND deleted_at IS NULL
+        "#,
+    )
+    .bind(ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+async fn get_messages_relative_to(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+    pivot_id: Uuid,
+    comparison: &str,
+    order: &str,
+    limit: i64,
+) -> Result<Vec<Message>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM messages WHERE id = $1 AND channel_id = $2)",
+        Some(pivot_id),
+        Some(channel_id),
+    )
+    .await?;
+    query_as(&format!(
+        r#"
+        SELECT id, channel_id, author_id, content, created_at, updated_at, edited_at, deleted_at, kind, reply_to_id,
+            mentions, mention_roles, mention_channels, mention_everyone
+        FROM messages
+        WHERE channel_id = $1
+            AND deleted_at IS NULL
+            AND (created_at, id) {} (SELECT created_at, id FROM messages WHERE id = $2 AND channel_id = $1)
+        ORDER BY created_at {}, id {}
+        LIMIT $3
+        "#,
+        comparison, order, order,
+    ))
+    .bind(channel_id)
+    .bind(pivot_id)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Getting messages by channel id",
+    skip(channel_id, db_pool),
+    fields(
+        channel_id = %channel_id,
+    )
+)]
+pub async fn get_messages_by_channel_id(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+    cursor: MessageCursor,
+    limit: i64,
+) -> Result<Vec<Message>, PageErr> {
+    match cursor {
+        MessageCursor::Latest => {
+            query_as(
+                r#"
+                SELECT id, channel_id, author_id, content, created_at, updated_at, edited_at, deleted_at, kind, reply_to_id,
+                    mentions, mention_roles, mention_channels, mention_everyone
+                FROM messages
+                WHERE channel_id = $1 AND deleted_at IS NULL
+                ORDER BY created_at DESC, id DESC
+                LIMIT $2
+                "#,
+            )
+            .bind(channel_id)
+            .bind(limit)
+            .fetch_all(db_pool)
+            .await
+            .map_err(PageErr::Query)
+        }
+        MessageCursor::Before(id) => {
+            get_messages_relative_to(db_pool, channel_id, id, "<", "DESC", limit).await
+        }
+        MessageCursor::After(id) => {
+            let mut messages =
+                get_messages_relative_to(db_pool, channel_id, id, ">", "ASC", limit).await?;
+            messages.reverse();
+            Ok(messages)
+        }
+        MessageCursor::Around(id) => {
+            let mut messages =
+                get_messages_relative_to(db_pool, channel_id, id, ">", "ASC", limit / 2).await?;
+            messages.reverse();
+            let older = get_messages_relative_to(
+                db_pool,
+                channel_id,
+                id,
+                "<=",
+                "DESC",
+                limit - messages.len() as i64,
+            )
+            .await?;
+            messages.extend(older);
+            Ok(messages)
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "Editing message in database",
+    skip(message_id, editor_id, content, mentions, edited_at, db_pool),
+    fields(
+        message_id = %message_id,
+        editor_id = %editor_id,
+    )
+)]
+pub async fn edit_message(
+    db_pool: &PgPool,
+    message_id: Uuid,
+    editor_id: Uuid,
+    content: &MessageContent,
+    mentions: &MessageMentions,
+    edited_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+        UPDATE messages
+        SET content = $1, edited_at = $2, updated_at = now(),
+            mentions = $4, mention_roles = $5, mention_channels = $6, mention_everyone = $7
+        WHERE id = $3;
+        "#,
+    )
+    .bind(content)
+    .bind(edited_at)
+    .bind(message_id)
+    .bind(mentions.mentions())
+    .bind(mentions.mention_roles())
+    .bind(mentions.mention_channels())
+    .bind(mentions.mention_everyone())
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO message_revisions (id, message_id, editor_id, content, created_at)
+        VALUES ($1, $2, $3, $4, $5);
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(message_id)
+    .bind(editor_id)
+    .bind(content)
+    .bind(edited_at)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+#[tracing::instrument(
+    name = "Getting message revisions",
+    skip(message_id, db_pool),
+    fields(
+        message_id = %message_id
+    )
+)]
+pub async fn get_message_revisions(
+    db_pool: &PgPool,
+    message_id: Uuid,
+) -> Result<Vec<MessageRevision>, Error> {
+    query_as(
+        r#"
+        SELECT id, message_id, editor_id, content, created_at
+        FROM message_revisions
+        WHERE message_id = $1
+        ORDER BY created_at
+        "#,
+    )
+    .bind(message_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Soft Deleting Message in Database",
+    skip(message_id, deleted_by, deleted_at, db_pool),
+    fields(
+        message_id = %message_id,
+        deleted_by = %deleted_by,
+        deleted_at = %deleted_at,
+    )
+)]
+pub async fn soft_delete_message(
+    db_pool: &PgPool,
+    message_id: Uuid,
+    deleted_by: Uuid,
+    deleted_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+            UPDATE messages SET deleted_at = $1, deleted_by = $2 WHERE id = $3;
+        "#,
+    )
+    .bind(deleted_at)
+    .bind(deleted_by)
+    .bind(message_id)
+    .execute(&mut transaction)
+    .await?;
+    // Deleted messages free up their pin slot.
+    query(
+        r#"
+            DELETE FROM pins WHERE message_id = $1;
+        "#,
+    )
+    .bind(message_id)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+/// Loads the audit trail of a message, including one that was soft deleted.
+/// Purged messages are not found.
+#[tracing::instrument(
+    name = "Getting message history",
+    skip(message_id, db_pool),
+    fields(
+        message_id = %message_id
+    )
+)]
+pub async fn get_message_history(
+    db_pool: &PgPool,
+    message_id: Uuid,
+) -> Result<MessageHistory, Error> {
+    let (channel_id, author_id, created_at, deleted_at, deleted_by) = query_as::<
+        _,
+        (
+            Uuid,
+            Uuid,
+            DateTime<Utc>,
+            Option<DateTime<Utc>>,
+            Option<Uuid>,
+        ),
+    >(
+        r#"
+        SELECT channel_id, author_id, created_at, deleted_at, deleted_by
+        FROM messages
+        WHERE id = $1 AND purged_at IS NULL
+        "#,
+    )
+    .bind(message_id)
+    .fetch_one(db_pool)
+    .await?;
+    let revisions = get_message_revisions(db_pool, message_id).await?;
+    Ok(MessageHistory::new(
+        message_id, channel_id, author_id, created_at, deleted_at, deleted_by, revisions,
+    ))
+}
+
+/// Hard deletes the content and revisions of messages soft deleted before
+/// `cutoff`. The rows stay behind as empty tombstones. Returns how many
+/// messages were purged.
+#[tracing::instrument(
+    name = "Purging deleted message content",
+    skip(cutoff, db_pool),
+    fields(
+        cutoff = %cutoff,
+    )
+)]
+pub async fn purge_deleted_messages(db_pool: &PgPool, cutoff: DateTime<Utc>) -> Result<u64, Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        DELETE FROM message_revisions r
+        USING messages m
+        WHERE r.message_id = m.id
+            AND m.deleted_at < $1
+            AND m.purged_at IS NULL
+        "#,
+    )
+    .bind(cutoff)
+    .execute(&mut transaction)
+    .await?;
+    let result = query(
+        r#"
+        UPDATE messages
+        SET content = '',
+            mentions = '{}',
+            mention_roles = '{}',
+            mention_channels = '{}',
+            mention_everyone = FALSE,
+            purged_at = now()
+        WHERE deleted_at < $1 AND purged_at IS NULL
+        "#,
+    )
+    .bind(cutoff)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result.rows_affected())
+}
@@ -1,8 +1,54 @@
+mod block;
+mod bookmark;
+mod channel;
+mod comment;
 mod confirmation_token;
+mod dm;
+mod emoji;
+mod feed;
+mod follow;
+mod friend;
+mod message;
+mod notification;
+mod pagination;
+mod permission;
+mod pin;
+mod poll;
+mod post;
+mod reaction;
+mod read_state;
+mod role;
+mod schedule;
+mod search;
 mod server;
+mod tag;
+mod thread;
 mod types;
 mod user;
 
+pub use block::*;
+pub use bookmark::*;
+pub use channel::*;
+pub use comment::*;
 pub use confirmation_token::*;
+pub use dm::*;
+pub use emoji::*;
+pub use feed::*;
+pub use follow::*;
+pub use friend::*;
+pub use message::*;
+pub use notification::*;
+pub(crate) use pagination::*;
+pub use permission::*;
+pub use pin::*;
+pub use poll::*;
+pub use post::*;
+pub use reaction::*;
+pub use read_state::*;
+pub use role::*;
+pub use schedule::*;
+pub use search::*;
 pub use server::*;
+pub use tag::*;
+pub use thread::*;
 pub use user::*;
@@ -0,0 +1,112 @@
+use chrono::Utc;
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    message::Message,
+    notification::{MentionKind, Notification},
+    pagination::{PageErr, PageParams},
+};
+
+pub const NOTIFICATIONS_TABLE_NAME: &str = "notifications";
+
+/// Records a mention notification for each recipient and bumps their mention
+/// count in the message's channel, creating the read state if needed.
+#[tracing::instrument(
+    name = "Inserting mention notifications",
+    skip(message, recipients, db_pool),
+    fields(
+        message_id = %message.id(),
+        recipients = recipients.len(),
+    )
+)]
+pub async fn insert_mention_notifications(
+    db_pool: &PgPool,
+    message: &Message,
+    recipients: &[(Uuid, MentionKind)],
+) -> Result<PgQueryResult, Error> {
+    let ids: Vec<Uuid> = recipients.iter().map(|_| Uuid::new_v4()).collect();
+    let user_ids: Vec<Uuid> = recipients.iter().map(|(id, _)| *id).collect();
+    let kinds: Vec<&str> = recipients.iter().map(|(_, kind)| kind.as_str()).collect();
+
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+        INSERT INTO notifications (id, user_id, message_id, channel_id, author_id, kind, created_at)
+        SELECT r.id, r.user_id, $3, $4, $5, r.kind::mention_kind, $6
+        FROM UNNEST($1::uuid[], $2::uuid[], $7::text[]) AS r(id, user_id, kind)
+        ON CONFLICT (user_id, message_id) DO NOTHING
+        "#,
+    )
+    .bind(&ids)
+    .bind(&user_ids)
+    .bind(message.id())
+    .bind(message.channel_id())
+    .bind(message.author_id())
+    .bind(message.created_at())
+    .bind(&kinds)
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO read_states (user_id, channel_id, mention_count, updated_at)
+        SELECT user_id, $2, 1, $3 FROM UNNEST($1::uuid[]) AS r(user_id)
+        ON CONFLICT (user_id, channel_id) WHERE channel_id IS NOT NULL DO UPDATE
+        SET mention_count = read_states.mention_count + 1,
+            updated_at = EXCLUDED.updated_at
+        "#,
+    )
+    .bind(&user_ids)
+    .bind(message.channel_id())
+    .bind(Utc::now())
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+/// The user's mention notifications, newest first, skipping those whose
+/// message has since been deleted.
+#[tracing::instrument(
+    name = "Getting notifications by user id",
+    skip(user_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+        cursor = ?params.cursor(),
+    )
+)]
+pub async fn get_notifications_by_user_id(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<Notification>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM notifications WHERE id = $1 AND user_id = $2)",
+        params.cursor(),
+        Some(user_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT n.id, n.user_id, n.message_id, n.channel_id, n.author_id, n.kind, n.created_at
+        FROM notifications n
+        JOIN messages m ON m.id = n.message_id
+        WHERE n.user_id = $1
+            AND m.deleted_at IS NULL
+            AND (
+                $2::uuid IS NULL
+                OR (n.created_at, n.id) < (SELECT created_at, id FROM notifications WHERE id = $2)
+            )
+        ORDER BY n.created_at DESC, n.id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
@@ -0,0 +1,31 @@
+use sqlx::{query_scalar, PgPool};
+use uuid::Uuid;
+
+use crate::domain::pagination::PageErr;
+
+/// Keyset pages are read on from the sort key of the row a cursor points at,
+/// and comparing against a row that no longer exists quietly yields an
+/// empty page. Paged queries call this first so that a stale cursor fails
+/// with `PageErr::CursorNotFound` instead.
+///
+/// `exists` selects whether the cursor row (`$1`) exists, optionally within
+/// the list being paged (`$2`).
+pub(crate) async fn ensure_cursor_exists(
+    db_pool: &PgPool,
+    exists: &str,
+    cursor: Option<Uuid>,
+    scope_id: Option<Uuid>,
+) -> Result<(), PageErr> {
+    let Some(cursor) = cursor else {
+        return Ok(());
+    };
+    let mut check = query_scalar::<_, bool>(exists).bind(cursor);
+    if let Some(scope_id) = scope_id {
+        check = check.bind(scope_id);
+    }
+    if check.fetch_one(db_pool).await? {
+        Ok(())
+    } else {
+        Err(PageErr::CursorNotFound)
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        channel::{Channel, PermissionOverwrite},
+        permission::{apply_overwrites, compute_base_permissions, Permissions},
+        role::Role,
+        server::Server,
+    },
+    storage::{
+        get_channel_overwrites, get_channel_overwrites_by_server_id, get_channels_by_server_id,
+        get_member_role_ids_by_server_id, get_member_roles, get_roles_by_server_id,
+        get_server_member, get_server_members,
+    },
+};
+
+/// Resolves a user's server-wide permissions. Users who are neither the
+/// owner nor a member, and banned members, get no permissions.
+#[tracing::instrument(
+    name = "Resolving server permissions",
+    skip(server, user_id, db_pool),
+    fields(
+        server_id = %server.id(),
+        user_id = %user_id,
+    )
+)]
+pub async fn get_server_permissions(
+    db_pool: &PgPool,
+    server: &Server,
+    user_id: Uuid,
+) -> Result<(Permissions, Vec<Role>), Error> {
+    let is_owner = server.owner_id() == user_id;
+    let member = get_server_member(db_pool, server.id(), user_id).await?;
+    let is_admin = match &member {
+        Some(member) if member.is_banned() => return Ok((Permissions::empty(), vec![])),
+        Some(member) => member.is_admin(),
+        None if is_owner => false,
+        None => return Ok((Permissions::empty(), vec![])),
+    };
+    let roles = get_member_roles(db_pool, server.id(), user_id).await?;
+    let role_permissions: Vec<Permissions> = roles.iter().map(|r| r.permissions()).collect();
+    Ok((
+        compute_base_permissions(is_owner, is_admin, &role_permissions),
+        roles,
+    ))
+}
+
+/// Resolves a user's permissions in a channel by layering the channel's
+/// overwrites on top of their server-wide permissions.
+#[tracing::instrument(
+    name = "Resolving channel permissions",
+    skip(server, channel, user_id, db_pool),
+    fields(
+        channel_id = %channel.id(),
+        user_id = %user_id,
+    )
+)]
+pub async fn get_channel_permissions(
+    db_pool: &PgPool,
+    server: &Server,
+    channel: &Channel,
+    user_id: Uuid,
+) -> Result<Permissions, Error> {
+    let (base, roles) = get_server_permissions(db_pool, server, user_id).await?;
+    if base.is_empty() {
+        return Ok(base);
+    }
+    let overwrites = get_channel_overwrites(db_pool, channel.overwrites_channel_id()).await?;
+    let role_ids: Vec<Uuid> = roles.iter().map(|r| r.id()).collect();
+    Ok(apply_overwrites(
+        base,
+        server.id(),
+        user_id,
+        &role_ids,
+        &overwrites,
+    ))
+}
+
+/// Lists the server's channels that the user is allowed to view.
+pub async fn get_viewable_channels(
+    db_pool: &PgPool,
+    server: &Server,
+    user_id: Uuid,
+) -> Result<Vec<Channel>, Error> {
+    get_channels_with_permissions(db_pool, server, user_id, Permissions::VIEW_CHANNEL).await
+}
+
+/// Lists the server's channels in which the user holds all of `required`.
+#[tracing::instrument(
+    name = "Resolving channels with permissions",
+    skip(server, user_id, db_pool),
+    fields(
+        server_id = %server.id(),
+        user_id = %user_id,
+        required = ?required,
+    )
+)]
+pub async fn get_channels_with_permissions(
+    db_pool: &PgPool,
+    server: &Server,
+    user_id: Uuid,
+    required: Permissions,
+) -> Result<Vec<Channel>, Error> {
+    let (base, roles) = get_server_permissions(db_pool, server, user_id).await?;
+    if base.is_empty() {
+        return Ok(vec![]);
+    }
+    let channels = get_channels_by_server_id(db_pool, server.id()).await?;
+    let role_ids: Vec<Uuid> = roles.iter().map(|r| r.id()).collect();
+
+    let mut overwrites_by_channel: HashMap<Uuid, Vec<PermissionOverwrite>> = HashMap::new();
+    for overwrite in get_channel_overwrites_by_server_id(db_pool, server.id()).await? {
+        overwrites_by_channel
+            .entry(overwrite.channel_id())
+            .or_default()
+            .push(overwrite);
+    }
+
+    Ok(channels
+        .into_iter()
+        .filter(|channel| {
+            let overwrites = overwrites_by_channel
+                .get(&channel.id())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            apply_overwrites(base, server.id(), user_id, &role_ids, overwrites).contains(required)
+        })
+        .collect())
+}
+
+/// Everyone who can view the channel, each with the ids of the roles they
+/// hold. Resolves the whole server in a fixed number of queries, for fanning
+/// out mentions.
+#[tracing::instrument(
+    name = "Resolving channel viewers",
+    skip(server, channel, db_pool),
+    fields(
+        channel_id = %channel.id(),
+    )
+)]
+pub async fn get_channel_viewers(
+    db_pool: &PgPool,
+    server: &Server,
+    channel: &Channel,
+) -> Result<Vec<(Uuid, Vec<Uuid>)>, Error> {
+    let mut members: Vec<(Uuid, bool)> = get_server_members(db_pool, server.id())
+        .await?
+        .into_iter()
+        .map(|member| (member.user_id(), member.is_admin()))
+        .collect();
+    if !members.iter().any(|(id, _)| *id == server.owner_id()) {
+        members.push((server.owner_id(), false));
+    }
+
+    let role_permissions: HashMap<Uuid, Permissions> = get_roles_by_server_id(db_pool, server.id())
+        .await?
+        .into_iter()
+        .map(|role| (role.id(), role.permissions()))
+        .collect();
+    let mut roles_by_user: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (user_id, role_id) in get_member_role_ids_by_server_id(db_pool, server.id()).await? {
+        roles_by_user.entry(user_id).or_default().push(role_id);
+    }
+    let overwrites = get_channel_overwrites(db_pool, channel.overwrites_channel_id()).await?;
+
+    Ok(members
+        .into_iter()
+        .filter_map(|(user_id, is_admin)| {
+            let role_ids = roles_by_user.remove(&user_id).unwrap_or_default();
+            let permissions: Vec<Permissions> = role_ids
+                .iter()
+                .filter_map(|id| role_permissions.get(id).copied())
+                .collect();
+            let base =
+                compute_base_permissions(server.owner_id() == user_id, is_admin, &permissions);
+            apply_overwrites(base, server.id(), user_id, &role_ids, &overwrites)
+                .contains(Permissions::VIEW_CHANNEL)
+                .then_some((user_id, role_ids))
+        })
+        .collect())
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use crate::domain::message::PinnedMessage;
+
+pub const PINS_TABLE_NAME: &str = "pins";
+
+/// Pins a message unless the channel has already reached its pin limit.
+/// Affects no rows when the limit is reached or the message is already
+/// pinned.
+#[tracing::instrument(
+    name = "Inserting pin to database",
+    skip(channel_id, message_id, pinned_by, pinned_at, db_pool),
+    fields(
+        channel_id = %channel_id,
+        message_id = %message_id,
+    )
+)]
+pub async fn insert_pin(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+    message_id: Uuid,
+    pinned_by: Uuid,
+    pinned_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO pins (message_id, channel_id, pinned_by, pinned_at)
+        SELECT $1, $2, $3, $4
+        WHERE (SELECT count(*) FROM pins WHERE channel_id = $2)
+            < (SELECT max_pins FROM channels WHERE id = $2)
+        ON CONFLICT (message_id) DO NOTHING;
+        "#,
+    )
+    .bind(message_id)
+    .bind(channel_id)
+    .bind(pinned_by)
+    .bind(pinned_at)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting pin from database",
+    skip(message_id, db_pool),
+    fields(
+        message_id = %message_id,
+    )
+)]
+pub async fn delete_pin(db_pool: &PgPool, message_id: Uuid) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM pins WHERE message_id = $1;
+        "#,
+    )
+    .bind(message_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Checking whether message is pinned",
+    skip(message_id, db_pool),
+    fields(
+        message_id = %message_id,
+    )
+)]
+pub async fn is_message_pinned(db_pool: &PgPool, message_id: Uuid) -> Result<bool, Error> {
+    query_scalar(
+        r#"
+        SELECT EXISTS(SELECT 1 FROM pins WHERE message_id = $1)
+        "#,
+    )
+    .bind(message_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// The channel's pinned messages in the order they were pinned.
+#[tracing::instrument(
+    name = "Getting pins by channel id",
+    skip(channel_id, db_pool),
+    fields(
+        channel_id = %channel_id,
+    )
+)]
+pub async fn get_pins_by_channel_id(
+    db_pool: &PgPool,
+    channel_id: Uuid,
+) -> Result<Vec<PinnedMessage>, Error> {
+    query_as(
+        r#"
+        SELECT m.id, m.channel_id, m.author_id, m.content, m.created_at, m.updated_at, m.edited_at,
+            m.deleted_at, m.kind, m.reply_to_id, m.mentions, m.mention_roles, m.mention_channels,
+            m.mention_everyone, p.pinned_by, p.pinned_at
+        FROM pins p
+        JOIN messages m ON m.id = p.message_id
+        WHERE p.channel_id = $1 AND m.deleted_at IS NULL
+        ORDER BY p.pinned_at, m.id
+        "#,
+    )
+    .bind(channel_id)
+    .fetch_all(db_pool)
+    .await
+}
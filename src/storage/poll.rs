@@ -0,0 +1,244 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::poll::{Poll, PollOption, PollTarget, PollVote};
+
+pub const POLLS_TABLE_NAME: &str = "polls";
+pub const POLL_OPTIONS_TABLE_NAME: &str = "poll_options";
+pub const POLL_VOTES_TABLE_NAME: &str = "poll_votes";
+
+/// Inserts a poll inside the transaction that saves the post or message it is
+/// attached to, so neither is kept without the other.
+pub(super) async fn insert_poll(
+    transaction: &mut Transaction<'_, Postgres>,
+    poll: &Poll,
+    options: &[PollOption],
+) -> Result<(), Error> {
+    let (post_id, message_id) = match poll.target() {
+        PollTarget::Post(id) => (Some(id), None),
+        PollTarget::Message(id) => (None, Some(id)),
+    };
+    query(
+        r#"
+        INSERT INTO polls (id, post_id, message_id, author_id, question, multiple_choice, anonymous, hide_results, closes_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);
+        "#,
+    )
+    .bind(poll.id())
+    .bind(post_id)
+    .bind(message_id)
+    .bind(poll.author_id())
+    .bind(poll.question())
+    .bind(poll.multiple_choice())
+    .bind(poll.anonymous())
+    .bind(poll.hide_results())
+    .bind(poll.closes_at())
+    .bind(poll.created_at())
+    .execute(&mut *transaction)
+    .await?;
+    for option in options {
+        query(
+            r#"
+            INSERT INTO poll_options (id, poll_id, position, text)
+            VALUES ($1, $2, $3, $4);
+            "#,
+        )
+        .bind(option.id())
+        .bind(option.poll_id())
+        .bind(option.position())
+        .bind(option.text())
+        .execute(&mut *transaction)
+        .await?;
+    }
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "Getting poll by id",
+    skip(id, db_pool),
+    fields(
+        poll_id = %id
+    )
+)]
+pub async fn get_poll_by_id(db_pool: &PgPool, id: Uuid) -> Result<Poll, Error> {
+    query_as(
+        r#"
+        SELECT id, post_id, message_id, author_id, question, multiple_choice, anonymous, hide_results,
+            closes_at, closed_at, created_at
+        FROM polls
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns the polls attached to any of the given posts or messages, in no
+/// particular order.
+#[tracing::instrument(
+    name = "Getting polls by targets",
+    skip(post_ids, message_ids, db_pool)
+)]
+pub async fn get_polls_by_targets(
+    db_pool: &PgPool,
+    post_ids: &[Uuid],
+    message_ids: &[Uuid],
+) -> Result<Vec<Poll>, Error> {
+    query_as(
+        r#"
+        SELECT id, post_id, message_id, author_id, question, multiple_choice, anonymous, hide_results,
+            closes_at, closed_at, created_at
+        FROM polls
+        WHERE post_id = ANY($1) OR message_id = ANY($2)
+        "#,
+    )
+    .bind(post_ids)
+    .bind(message_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// The options of the given polls, each poll's in their original order.
+#[tracing::instrument(name = "Getting poll options", skip(poll_ids, db_pool))]
+pub async fn get_poll_options(
+    db_pool: &PgPool,
+    poll_ids: &[Uuid],
+) -> Result<Vec<PollOption>, Error> {
+    query_as(
+        r#"
+        SELECT id, poll_id, position, text
+        FROM poll_options
+        WHERE poll_id = ANY($1)
+        ORDER BY poll_id, position
+        "#,
+    )
+    .bind(poll_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Every vote cast in the given polls, oldest first.
+#[tracing::instrument(name = "Getting poll votes", skip(poll_ids, db_pool))]
+pub async fn get_poll_votes(db_pool: &PgPool, poll_ids: &[Uuid]) -> Result<Vec<PollVote>, Error> {
+    query_as(
+        r#"
+        SELECT poll_id, option_id, user_id
+        FROM poll_votes
+        WHERE poll_id = ANY($1)
+        ORDER BY created_at, user_id
+        "#,
+    )
+    .bind(poll_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Replaces whatever the user voted for in the poll with `option_ids`.
+#[tracing::instrument(
+    name = "Replacing poll votes",
+    skip(poll_id, user_id, option_ids, voted_at, db_pool),
+    fields(
+        poll_id = %poll_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn replace_poll_votes(
+    db_pool: &PgPool,
+    poll_id: Uuid,
+    user_id: Uuid,
+    option_ids: &[Uuid],
+    voted_at: DateTime<Utc>,
+) -> Result<(), Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        DELETE FROM poll_votes WHERE poll_id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(poll_id)
+    .bind(user_id)
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO poll_votes (poll_id, option_id, user_id, created_at)
+        SELECT $1, option_id, $2, $3 FROM unnest($4::uuid[]) AS option_id;
+        "#,
+    )
+    .bind(poll_id)
+    .bind(user_id)
+    .bind(voted_at)
+    .bind(option_ids)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await
+}
+
+#[tracing::instrument(
+    name = "Deleting poll votes",
+    skip(poll_id, user_id, db_pool),
+    fields(
+        poll_id = %poll_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn delete_poll_votes(
+    db_pool: &PgPool,
+    poll_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM poll_votes WHERE poll_id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(poll_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Closes a poll early. Affects no rows when it was already closed.
+#[tracing::instrument(
+    name = "Closing poll",
+    skip(poll_id, closed_at, db_pool),
+    fields(
+        poll_id = %poll_id,
+    )
+)]
+pub async fn close_poll(
+    db_pool: &PgPool,
+    poll_id: Uuid,
+    closed_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE polls SET closed_at = $2
+        WHERE id = $1 AND closed_at IS NULL AND (closes_at IS NULL OR closes_at > $2);
+        "#,
+    )
+    .bind(poll_id)
+    .bind(closed_at)
+    .execute(db_pool)
+    .await
+}
+
+/// Marks every poll that has reached its close time as closed, returning
+/// them so their final results can be announced. Each poll is returned by
+/// only one call.
+#[tracing::instrument(name = "Closing due polls", skip(now, db_pool))]
+pub async fn close_due_polls(db_pool: &PgPool, now: DateTime<Utc>) -> Result<Vec<Poll>, Error> {
+    query_as(
+        r#"
+        UPDATE polls SET closed_at = closes_at
+        WHERE closed_at IS NULL AND closes_at <= $1
+        RETURNING id, post_id, message_id, author_id, question, multiple_choice, anonymous,
+            hide_results, closes_at, closed_at, created_at
+        "#,
+    )
+    .bind(now)
+    .fetch_all(db_pool)
+    .await
+}
@@ -0,0 +1,301 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::{
+    domain::{
+        pagination::{PageErr, PageParams},
+        poll::{Poll, PollOption},
+        post::{LikeSummary, Post, PostContent},
+    },
+    storage::{insert_poll, replace_post_tags},
+};
+
+pub const POSTS_TABLE_NAME: &str = "posts";
+pub const POST_LIKES_TABLE_NAME: &str = "post_likes";
+
+#[tracing::instrument(
+    name = "Inserting post to database",
+    skip(post, poll, db_pool),
+    fields(
+        post_id = %post.id(),
+        server_id = %post.server_id(),
+    )
+)]
+pub async fn insert_post(
+    db_pool: &PgPool,
+    post: &Post,
+    poll: Option<(&Poll, &[PollOption])>,
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+        INSERT INTO posts (id, server_id, author_id, content, quoted_post_id, created_at, updated_at, edited_at, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);
+        "#,
+    )
+    .bind(post.id())
+    .bind(post.server_id())
+    .bind(post.author_id())
+    .bind(post.content())
+    .bind(post.quoted_post_id())
+    .bind(post.created_at())
+    .bind(post.updated_at())
+    .bind(post.edited_at())
+    .bind(post.deleted_at())
+    .execute(&mut transaction)
+    .await?;
+    replace_post_tags(&mut transaction, post.id(), &post.content()).await?;
+    if let Some((poll, options)) = poll {
+        insert_poll(&mut transaction, poll, options).await?;
+    }
+    transaction.commit().await?;
+    Ok(result)
+}
+
+#[tracing::instrument(
+    name = "Getting post by id",
+    skip(id, db_pool),
+    fields(
+        post_id = %id
+    )
+)]
+pub async fn get_post_by_id(db_pool: &PgPool, id: Uuid) -> Result<Post, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, author_id, content, quoted_post_id, created_at, updated_at, edited_at, deleted_at
+        FROM posts
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns the posts among `ids` that have not been deleted, in no
+/// particular order.
+#[tracing::instrument(name = "Getting posts by ids", skip(ids, db_pool))]
+pub async fn get_posts_by_ids(db_pool: &PgPool, ids: &[Uuid]) -> Result<Vec<Post>, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, author_id, content, quoted_post_id, created_at, updated_at, edited_at, deleted_at
+        FROM posts
+        WHERE id = ANY($1) AND deleted_at IS NULL
+        "#,
+    )
+    .bind(ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Returns a page of a server's posts, newest first, leaving out authors the
+/// viewer has muted and users on either side of a block with them.
+#[tracing::instrument(
+    name = "Getting posts by server id",
+    skip(server_id, viewer_id, params, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn get_posts_by_server_id(
+    db_pool: &PgPool,
+    server_id: Uuid,
+    viewer_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<Post>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM posts WHERE id = $1 AND server_id = $2)",
+        params.cursor(),
+        Some(server_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT p.id, p.server_id, p.author_id, p.content, p.quoted_post_id, p.created_at, p.updated_at, p.edited_at, p.deleted_at
+        FROM posts p
+        WHERE p.server_id = $1
+            AND p.deleted_at IS NULL
+            AND NOT EXISTS(
+                SELECT 1 FROM user_mutes um WHERE um.muter_id = $4 AND um.muted_id = p.author_id
+            )
+            AND NOT EXISTS(
+                SELECT 1 FROM user_blocks b
+                WHERE (b.blocker_id = $4 AND b.blocked_id = p.author_id)
+                    OR (b.blocker_id = p.author_id AND b.blocked_id = $4)
+            )
+            AND (
+                $2::uuid IS NULL
+                OR (p.created_at, p.id) < (SELECT created_at, id FROM posts WHERE id = $2)
+            )
+        ORDER BY p.created_at DESC, p.id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(server_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .bind(viewer_id)
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Editing post in database",
+    skip(post_id, content, edited_at, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn edit_post(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    content: &PostContent,
+    edited_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    let result = query(
+        r#"
+        UPDATE posts
+        SET content = $1, edited_at = $2, updated_at = $2
+        WHERE id = $3;
+        "#,
+    )
+    .bind(content)
+    .bind(edited_at)
+    .bind(post_id)
+    .execute(&mut transaction)
+    .await?;
+    replace_post_tags(&mut transaction, post_id, content).await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+#[tracing::instrument(
+    name = "Soft deleting post in database",
+    skip(post_id, deleted_at, db_pool),
+    fields(
+        post_id = %post_id,
+        deleted_at = %deleted_at,
+    )
+)]
+pub async fn soft_delete_post(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    deleted_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE posts SET deleted_at = $1 WHERE id = $2;
+        "#,
+    )
+    .bind(deleted_at)
+    .bind(post_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Returns `(post_id, like_count, liked, comment_count)` for each of `ids`,
+/// where `liked` says whether `user_id` likes the post.
+#[tracing::instrument(name = "Getting post stats", skip(ids, user_id, db_pool))]
+pub async fn get_post_stats(
+    db_pool: &PgPool,
+    ids: &[Uuid],
+    user_id: Uuid,
+) -> Result<Vec<(Uuid, i64, bool, i64)>, Error> {
+    query_as(
+        r#"
+        SELECT p.id,
+            (SELECT count(*) FROM post_likes l WHERE l.post_id = p.id) AS like_count,
+            EXISTS(SELECT 1 FROM post_likes l WHERE l.post_id = p.id AND l.user_id = $2) AS liked,
+            (
+                SELECT count(*) FROM comments c
+                WHERE c.post_id = p.id AND c.deleted_at IS NULL
+            ) AS comment_count
+        FROM UNNEST($1::uuid[]) AS p(id)
+        "#,
+    )
+    .bind(ids)
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Likes a post. Liking a post twice is a no-op.
+#[tracing::instrument(
+    name = "Inserting post like to database",
+    skip(post_id, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn insert_post_like(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO post_likes (post_id, user_id, created_at)
+        VALUES ($1, $2, now())
+        ON CONFLICT (post_id, user_id) DO NOTHING;
+        "#,
+    )
+    .bind(post_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting post like from database",
+    skip(post_id, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn delete_post_like(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM post_likes WHERE post_id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(post_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting post like summary",
+    skip(post_id, user_id, db_pool),
+    fields(
+        post_id = %post_id,
+    )
+)]
+pub async fn get_post_like_summary(
+    db_pool: &PgPool,
+    post_id: Uuid,
+    user_id: Uuid,
+) -> Result<LikeSummary, Error> {
+    query_as(
+        r#"
+        SELECT count(*) AS like_count, COALESCE(bool_or(user_id = $2), false) AS liked
+        FROM post_likes
+        WHERE post_id = $1
+        "#,
+    )
+    .bind(post_id)
+    .bind(user_id)
+    .fetch_one(db_pool)
+    .await
+}
@@ -0,0 +1,227 @@
+use chrono::Utc;
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    pagination::{PageErr, PageParams},
+    reaction::{ReactionCount, ReactionEmoji},
+    user::User,
+};
+
+pub const MESSAGE_REACTIONS_TABLE_NAME: &str = "message_reactions";
+pub const DIRECT_MESSAGE_REACTIONS_TABLE_NAME: &str = "direct_message_reactions";
+
+/// Which kind of message a reaction is attached to. Channel messages and
+/// direct messages keep their reactions in separate tables.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReactionTarget {
+    Message,
+    DirectMessage,
+}
+
+/// A grouped reaction row: message id, emoji columns, count and whether the
+/// viewer is among the reactors.
+type ReactionCountRow = (Uuid, Option<String>, Option<Uuid>, i64, bool);
+
+impl ReactionTarget {
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Message => MESSAGE_REACTIONS_TABLE_NAME,
+            Self::DirectMessage => DIRECT_MESSAGE_REACTIONS_TABLE_NAME,
+        }
+    }
+}
+
+/// Returns how many distinct emoji the message has been reacted with, and
+/// whether `emoji` is already one of them.
+#[tracing::instrument(
+    name = "Counting message reaction emoji",
+    skip(message_id, emoji, db_pool),
+    fields(
+        message_id = %message_id,
+    )
+)]
+pub async fn get_reaction_emoji_count(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    message_id: Uuid,
+    emoji: &ReactionEmoji,
+) -> Result<(i64, bool), Error> {
+    let (unicode, custom_emoji_id) = emoji.columns();
+    query_as(&format!(
+        r#"
+        SELECT
+            COUNT(DISTINCT (emoji, custom_emoji_id)),
+            COALESCE(BOOL_OR(emoji IS NOT DISTINCT FROM $2 AND custom_emoji_id IS NOT DISTINCT FROM $3), false)
+        FROM {}
+        WHERE message_id = $1
+        "#,
+        target.table(),
+    ))
+    .bind(message_id)
+    .bind(unicode)
+    .bind(custom_emoji_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Adds a reaction. Reacting twice with the same emoji is a no-op, so a
+/// result with no affected rows means the reaction already existed.
+#[tracing::instrument(
+    name = "Inserting reaction",
+    skip(message_id, user_id, emoji, db_pool),
+    fields(
+        message_id = %message_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn insert_reaction(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    message_id: Uuid,
+    user_id: Uuid,
+    emoji: &ReactionEmoji,
+) -> Result<PgQueryResult, Error> {
+    let (unicode, custom_emoji_id) = emoji.columns();
+    query(&format!(
+        r#"
+        INSERT INTO {} (message_id, user_id, emoji, custom_emoji_id, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT DO NOTHING
+        "#,
+        target.table(),
+    ))
+    .bind(message_id)
+    .bind(user_id)
+    .bind(unicode)
+    .bind(custom_emoji_id)
+    .bind(Utc::now())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Deleting reaction",
+    skip(message_id, user_id, emoji, db_pool),
+    fields(
+        message_id = %message_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn delete_reaction(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    message_id: Uuid,
+    user_id: Uuid,
+    emoji: &ReactionEmoji,
+) -> Result<PgQueryResult, Error> {
+    let (unicode, custom_emoji_id) = emoji.columns();
+    query(&format!(
+        r#"
+        DELETE FROM {}
+        WHERE message_id = $1
+            AND user_id = $2
+            AND emoji IS NOT DISTINCT FROM $3
+            AND custom_emoji_id IS NOT DISTINCT FROM $4
+        "#,
+        target.table(),
+    ))
+    .bind(message_id)
+    .bind(user_id)
+    .bind(unicode)
+    .bind(custom_emoji_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Returns the reaction counts of each message as seen by `viewer_id`, in
+/// the order each emoji was first used on the message.
+#[tracing::instrument(
+    name = "Getting reaction counts",
+    skip(message_ids, viewer_id, db_pool),
+    fields(
+        messages = message_ids.len(),
+    )
+)]
+pub async fn get_reaction_counts(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    message_ids: &[Uuid],
+    viewer_id: Uuid,
+) -> Result<Vec<(Uuid, ReactionCount)>, Error> {
+    let rows: Vec<ReactionCountRow> = query_as(&format!(
+        r#"
+        SELECT message_id, emoji, custom_emoji_id, COUNT(*), BOOL_OR(user_id = $2)
+        FROM {}
+        WHERE message_id = ANY($1)
+        GROUP BY message_id, emoji, custom_emoji_id
+        ORDER BY MIN(created_at)
+        "#,
+        target.table(),
+    ))
+    .bind(message_ids)
+    .bind(viewer_id)
+    .fetch_all(db_pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(message_id, unicode, custom_emoji_id, count, me)| {
+            ReactionEmoji::from_columns(unicode, custom_emoji_id)
+                .map(|emoji| (message_id, ReactionCount::new(emoji, count, me)))
+        })
+        .collect())
+}
+
+/// Lists the users who reacted to a message with an emoji, ordered by id.
+#[tracing::instrument(
+    name = "Getting reaction users",
+    skip(message_id, emoji, params, db_pool),
+    fields(
+        message_id = %message_id,
+        cursor = ?params.cursor(),
+    )
+)]
+pub async fn get_reaction_users(
+    db_pool: &PgPool,
+    target: ReactionTarget,
+    message_id: Uuid,
+    emoji: &ReactionEmoji,
+    params: &PageParams,
+) -> Result<Vec<User>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        &format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE user_id = $1 AND message_id = $2)",
+            target.table()
+        ),
+        params.cursor(),
+        Some(message_id),
+    )
+    .await?;
+    let (unicode, custom_emoji_id) = emoji.columns();
+    query_as(&format!(
+        r#"
+        SELECT u.id, u.email, u.handle, u.name, u.password, u.profile_photo, u.bio, u.email_confirmed, u.created_at, u.updated_at, u.deleted_at, u.failed_attempts
+        FROM {} r
+        JOIN users u ON u.id = r.user_id
+        WHERE r.message_id = $1
+            AND r.emoji IS NOT DISTINCT FROM $2
+            AND r.custom_emoji_id IS NOT DISTINCT FROM $3
+            AND u.deleted_at IS NULL
+            AND ($4::uuid IS NULL OR u.id > $4)
+        ORDER BY u.id
+        LIMIT $5
+        "#,
+        target.table(),
+    ))
+    .bind(message_id)
+    .bind(unicode)
+    .bind(custom_emoji_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
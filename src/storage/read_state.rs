@@ -0,0 +1,164 @@
+use chrono::Utc;
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use crate::domain::{dm::DirectMessage, message::Message, read_state::UnreadState};
+
+pub const READ_STATES_TABLE_NAME: &str = "read_states";
+
+/// Moves the user's read marker in the message's channel up to the message
+/// and clears their mentions there. Acknowledging a message older than the
+/// current marker does nothing.
+#[tracing::instrument(
+    name = "Acknowledging channel message",
+    skip(user_id, message, db_pool),
+    fields(
+        user_id = %user_id,
+        message_id = %message.id(),
+    )
+)]
+pub async fn ack_channel_message(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    message: &Message,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO read_states (user_id, channel_id, last_read_message_id, mention_count, updated_at)
+        VALUES ($1, $2, $3, 0, $4)
+        ON CONFLICT (user_id, channel_id) WHERE channel_id IS NOT NULL DO UPDATE
+        SET last_read_message_id = EXCLUDED.last_read_message_id,
+            mention_count = 0,
+            updated_at = EXCLUDED.updated_at
+        WHERE NOT EXISTS (
+            SELECT 1 FROM messages m
+            WHERE m.id = read_states.last_read_message_id AND (m.created_at, m.id) >= ($5, $3)
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(message.channel_id())
+    .bind(message.id())
+    .bind(Utc::now())
+    .bind(message.created_at())
+    .execute(db_pool)
+    .await
+}
+
+/// Moves the user's read marker in the message's DM thread up to the message
+/// and clears their mentions there. Acknowledging a message older than the
+/// current marker does nothing.
+#[tracing::instrument(
+    name = "Acknowledging direct message",
+    skip(user_id, message, db_pool),
+    fields(
+        user_id = %user_id,
+        message_id = %message.id(),
+    )
+)]
+pub async fn ack_direct_message(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    message: &DirectMessage,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO read_states (user_id, thread_id, last_read_message_id, mention_count, updated_at)
+        VALUES ($1, $2, $3, 0, $4)
+        ON CONFLICT (user_id, thread_id) WHERE thread_id IS NOT NULL DO UPDATE
+        SET last_read_message_id = EXCLUDED.last_read_message_id,
+            mention_count = 0,
+            updated_at = EXCLUDED.updated_at
+        WHERE NOT EXISTS (
+            SELECT 1 FROM direct_messages m
+            WHERE m.id = read_states.last_read_message_id AND (m.created_at, m.id) >= ($5, $3)
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(message.thread_id())
+    .bind(message.id())
+    .bind(Utc::now())
+    .bind(message.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting channel unread states",
+    skip(user_id, channel_ids, db_pool),
+    fields(
+        user_id = %user_id,
+        channels = channel_ids.len(),
+    )
+)]
+pub async fn get_channel_unread_states(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    channel_ids: &[Uuid],
+) -> Result<Vec<UnreadState>, Error> {
+    query_as(
+        r#"
+        SELECT
+            c.id AS channel_id,
+            NULL::uuid AS thread_id,
+            rs.last_read_message_id,
+            COALESCE(rs.mention_count, 0) AS mention_count,
+            (
+                SELECT COUNT(*) FROM messages m
+                WHERE m.channel_id = c.id
+                    AND m.deleted_at IS NULL
+                    AND m.author_id <> $1
+                    AND (lm.id IS NULL OR (m.created_at, m.id) > (lm.created_at, lm.id))
+            ) AS unread_count
+        FROM UNNEST($2::uuid[]) AS c(id)
+        LEFT JOIN read_states rs ON rs.user_id = $1 AND rs.channel_id = c.id
+        LEFT JOIN messages lm ON lm.id = rs.last_read_message_id
+        ORDER BY c.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(channel_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting DM unread states",
+    skip(user_id, thread_ids, db_pool),
+    fields(
+        user_id = %user_id,
+        threads = thread_ids.len(),
+    )
+)]
+pub async fn get_dm_unread_states(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    thread_ids: &[Uuid],
+) -> Result<Vec<UnreadState>, Error> {
+    query_as(
+        r#"
+        SELECT
+            NULL::uuid AS channel_id,
+            t.id AS thread_id,
+            rs.last_read_message_id,
+            COALESCE(rs.mention_count, 0) AS mention_count,
+            (
+                SELECT COUNT(*) FROM direct_messages m
+                WHERE m.thread_id = t.id
+                    AND m.deleted_at IS NULL
+                    AND (m.expires_at IS NULL OR m.expires_at > now())
+                    AND m.author_id <> $1
+                    AND (lm.id IS NULL OR (m.created_at, m.id) > (lm.created_at, lm.id))
+            ) AS unread_count
+        FROM UNNEST($2::uuid[]) AS t(id)
+        LEFT JOIN read_states rs ON rs.user_id = $1 AND rs.thread_id = t.id
+        LEFT JOIN direct_messages lm ON lm.id = rs.last_read_message_id
+        ORDER BY t.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(thread_ids)
+    .fetch_all(db_pool)
+    .await
+}
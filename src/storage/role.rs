@@ -0,0 +1,159 @@
+use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use uuid::Uuid;
+
+use crate::domain::role::Role;
+
+#[tracing::instrument(
+    name = "Upserting role details to database",
+    skip(role, db_pool),
+    fields(
+        role_data = %role,
+    )
+)]
+pub async fn upsert_role(db_pool: &PgPool, role: &Role) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO server_roles (id, server_id, name, permissions, position, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (id)
+        DO
+            UPDATE SET
+                name = EXCLUDED.name,
+                permissions = EXCLUDED.permissions,
+                position = EXCLUDED.position,
+                updated_at = now()
+        WHERE
+            (server_roles.name, server_roles.permissions, server_roles.position) IS DISTINCT FROM
+            (EXCLUDED.name, EXCLUDED.permissions, EXCLUDED.position);
+        "#,
+    )
+    .bind(role.id())
+    .bind(role.server_id())
+    .bind(role.name())
+    .bind(role.permissions())
+    .bind(role.position())
+    .bind(role.created_at())
+    .bind(role.updated_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting roles for server member",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn get_member_roles(
+    db_pool: &PgPool,
+    server_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<Role>, Error> {
+    query_as(
+        r#"
+        SELECT r.id, r.server_id, r.name, r.permissions, r.position, r.created_at, r.updated_at
+        FROM server_roles r
+        JOIN server_member_roles mr ON mr.role_id = r.id
+        WHERE r.server_id = $1 AND mr.user_id = $2
+        ORDER BY r.position
+        "#,
+    )
+    .bind(server_id)
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Assigning role to member",
+    skip(role_id, user_id, db_pool),
+    fields(
+        role_id = %role_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn insert_member_role(
+    db_pool: &PgPool,
+    role_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO server_member_roles (role_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING;
+        "#,
+    )
+    .bind(role_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting role by id",
+    skip(id, db_pool),
+    fields(
+        role_id = %id
+    )
+)]
+pub async fn get_role_by_id(db_pool: &PgPool, id: Uuid) -> Result<Role, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, name, permissions, position, created_at, updated_at
+        FROM server_roles
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting roles by server id",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id
+    )
+)]
+pub async fn get_roles_by_server_id(db_pool: &PgPool, server_id: Uuid) -> Result<Vec<Role>, Error> {
+    query_as(
+        r#"
+        SELECT id, server_id, name, permissions, position, created_at, updated_at
+        FROM server_roles
+        WHERE server_id = $1
+        ORDER BY position
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Every `(user_id, role_id)` assignment in the server.
+#[tracing::instrument(
+    name = "Getting member role ids by server id",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id
+    )
+)]
+pub async fn get_member_role_ids_by_server_id(
+    db_pool: &PgPool,
+    server_id: Uuid,
+) -> Result<Vec<(Uuid, Uuid)>, Error> {
+    query_as(
+        r#"
+        SELECT mr.user_id, mr.role_id
+        FROM server_member_roles mr
+        JOIN server_roles r ON r.id = mr.role_id
+        WHERE r.server_id = $1
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
@@ -0,0 +1,159 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    pagination::{PageErr, PageParams},
+    schedule::{ScheduleTarget, ScheduledMessage},
+};
+
+pub const SCHEDULED_MESSAGES_TABLE_NAME: &str = "scheduled_messages";
+
+/// The most due messages the scheduler claims in one sweep.
+const SCHEDULER_BATCH_SIZE: i64 = 100;
+
+#[tracing::instrument(
+    name = "Inserting scheduled message to database",
+    skip(message, db_pool),
+    fields(
+        message_id = %message.id(),
+        author_id = %message.author_id(),
+    )
+)]
+pub async fn insert_scheduled_message(
+    db_pool: &PgPool,
+    message: &ScheduledMessage,
+) -> Result<PgQueryResult, Error> {
+    let (channel_id, dm_thread_id) = match message.target() {
+        ScheduleTarget::Channel(id) => (Some(id), None),
+        ScheduleTarget::DmThread(id) => (None, Some(id)),
+    };
+    query(
+        r#"
+        INSERT INTO scheduled_messages (id, author_id, channel_id, dm_thread_id, content, send_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7);
+        "#,
+    )
+    .bind(message.id())
+    .bind(message.author_id())
+    .bind(channel_id)
+    .bind(dm_thread_id)
+    .bind(message.content())
+    .bind(message.send_at())
+    .bind(message.created_at())
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Counting pending scheduled messages",
+    skip(author_id, db_pool),
+    fields(
+        author_id = %author_id,
+    )
+)]
+pub async fn count_scheduled_messages(db_pool: &PgPool, author_id: Uuid) -> Result<i64, Error> {
+    query_scalar(
+        r#"
+        SELECT count(*) FROM scheduled_messages WHERE author_id = $1
+        "#,
+    )
+    .bind(author_id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Returns a user's pending scheduled messages, soonest first.
+#[tracing::instrument(
+    name = "Getting scheduled messages by author id",
+    skip(author_id, params, db_pool),
+    fields(
+        author_id = %author_id,
+    )
+)]
+pub async fn get_scheduled_messages_by_author_id(
+    db_pool: &PgPool,
+    author_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<ScheduledMessage>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM scheduled_messages WHERE id = $1 AND author_id = $2)",
+        params.cursor(),
+        Some(author_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT id, author_id, channel_id, dm_thread_id, content, send_at, created_at
+        FROM scheduled_messages
+        WHERE author_id = $1
+            AND (
+                $2::uuid IS NULL
+                OR (send_at, id) > (SELECT send_at, id FROM scheduled_messages WHERE id = $2)
+            )
+        ORDER BY send_at ASC, id ASC
+        LIMIT $3
+        "#,
+    )
+    .bind(author_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+/// Cancels a pending scheduled message. Affects no rows when the message
+/// belongs to someone else or has already been sent.
+#[tracing::instrument(
+    name = "Deleting scheduled message from database",
+    skip(id, author_id, db_pool),
+    fields(
+        message_id = %id,
+        author_id = %author_id,
+    )
+)]
+pub async fn delete_scheduled_message(
+    db_pool: &PgPool,
+    id: Uuid,
+    author_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM scheduled_messages WHERE id = $1 AND author_id = $2;
+        "#,
+    )
+    .bind(id)
+    .bind(author_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Removes and returns a batch of messages due by `now`. Claiming by delete
+/// means each message is handed to exactly one sweep, and a cancel racing the
+/// sweep either wins outright or finds nothing left to cancel.
+#[tracing::instrument(name = "Claiming due scheduled messages", skip(now, db_pool))]
+pub async fn claim_due_scheduled_messages(
+    db_pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<ScheduledMessage>, Error> {
+    query_as(
+        r#"
+        DELETE FROM scheduled_messages
+        WHERE id IN (
+            SELECT id FROM scheduled_messages
+            WHERE send_at <= $1
+            ORDER BY send_at ASC
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, author_id, channel_id, dm_thread_id, content, send_at, created_at
+        "#,
+    )
+    .bind(now)
+    .bind(SCHEDULER_BATCH_SIZE)
+    .fetch_all(db_pool)
+    .await
+}
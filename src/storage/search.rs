@@ -0,0 +1,160 @@
+use sqlx::{query_as, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    dm::DirectMessage,
+    message::Message,
+    pagination::{PageErr, PageParams},
+    search::{DmSearchParams, MessageSearchParams, SearchResult},
+};
+
+/// `ts_headline` options for result snippets: matched terms are wrapped in
+/// `<mark>` and long messages are cut down to the part around the matches.
+const HEADLINE_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, MaxWords=35, MinWords=15";
+
+/// Message content with HTML special characters escaped. Snippets are built
+/// from this so that the only markup in them is the `<mark>` highlighting.
+const ESCAPED_CONTENT: &str = r#"
+    replace(replace(replace(replace(replace(
+        m.content, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;')
+"#;
+
+/// Searches the messages of `channel_ids` and of the threads started in
+/// them, newest first. Callers pass only the channels the user can read.
+/// Messages from users on either side of a block with the user are left out.
+#[tracing::instrument(
+    name = "Searching server messages",
+    skip(user_id, channel_ids, search, params, db_pool),
+    fields(
+        q = %search.q,
+        cursor = ?params.cursor(),
+    )
+)]
+pub async fn search_server_messages(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    channel_ids: &[Uuid],
+    search: &MessageSearchParams,
+    params: &PageParams,
+) -> Result<Vec<SearchResult<Message>>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM messages WHERE id = $1)",
+        params.cursor(),
+        None,
+    )
+    .await?;
+    query_as(&format!(
+        r#"
+        SELECT m.id, m.channel_id, m.author_id, m.content, m.created_at, m.updated_at, m.edited_at,
+            m.deleted_at, m.kind, m.reply_to_id, m.mentions, m.mention_roles, m.mention_channels, m.mention_everyone,
+            CASE
+                WHEN $2::text IS NULL THEN {content}
+                ELSE ts_headline('simple', {content}, to_tsquery('simple', $2), '{options}')
+            END AS snippet
+        FROM messages m
+        JOIN channels c ON c.id = m.channel_id
+        WHERE (c.id = ANY($1) OR (c.kind = 'thread' AND c.parent_id = ANY($1)))
+            AND c.deleted_at IS NULL
+            AND m.deleted_at IS NULL
+            AND ($2::text IS NULL OR m.search_vector @@ to_tsquery('simple', $2))
+            AND ($3::uuid IS NULL OR m.author_id = $3)
+            AND ($4::uuid IS NULL OR m.channel_id = $4)
+            AND ($5::uuid IS NULL OR $5 = ANY(m.mentions))
+            AND ($6::timestamptz IS NULL OR m.created_at < $6)
+            AND ($7::timestamptz IS NULL OR m.created_at > $7)
+            AND NOT EXISTS(
+                SELECT 1 FROM user_blocks b
+                WHERE (b.blocker_id = $10 AND b.blocked_id = m.author_id)
+                    OR (b.blocker_id = m.author_id AND b.blocked_id = $10)
+            )
+            AND ($8::uuid IS NULL OR (m.created_at, m.id) < (SELECT created_at, id FROM messages WHERE id = $8))
+        ORDER BY m.created_at DESC, m.id DESC
+        LIMIT $9
+        "#,
+        content = ESCAPED_CONTENT,
+        options = HEADLINE_OPTIONS,
+    ))
+    .bind(channel_ids)
+    .bind(search.tsquery())
+    .bind(search.author_id)
+    .bind(search.channel_id)
+    .bind(search.mentions)
+    .bind(search.before)
+    .bind(search.after)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+/// Searches the DMs of every thread the user takes part in, newest first,
+/// leaving out messages from users on either side of a block with them.
+#[tracing::instrument(
+    name = "Searching direct messages",
+    skip(user_id, search, params, db_pool),
+    fields(
+        user_id = %user_id,
+        q = %search.q,
+        cursor = ?params.cursor(),
+    )
+)]
+pub async fn search_direct_messages(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    search: &DmSearchParams,
+    params: &PageParams,
+) -> Result<Vec<SearchResult<DirectMessage>>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM direct_messages WHERE id = $1)",
+        params.cursor(),
+        None,
+    )
+    .await?;
+    query_as(&format!(
+        r#"
+        SELECT m.id, m.thread_id, m.author_id, m.content, m.created_at, m.updated_at, m.edited_at,
+            m.deleted_at, m.expires_at,
+            CASE
+                WHEN $2::text IS NULL THEN {content}
+                ELSE ts_headline('simple', {content}, to_tsquery('simple', $2), '{options}')
+            END AS snippet
+        FROM direct_messages m
+        JOIN dm_participants p ON p.thread_id = m.thread_id AND p.user_id = $1
+        JOIN dm_threads t ON t.id = m.thread_id
+        WHERE t.deleted_at IS NULL
+            AND m.deleted_at IS NULL
+            AND (m.expires_at IS NULL OR m.expires_at > now())
+            AND ($2::text IS NULL OR m.search_vector @@ to_tsquery('simple', $2))
+            AND ($3::uuid IS NULL OR m.author_id = $3)
+            AND ($4::uuid IS NULL OR m.thread_id = $4)
+            AND ($5::timestamptz IS NULL OR m.created_at < $5)
+            AND ($6::timestamptz IS NULL OR m.created_at > $6)
+            AND NOT EXISTS(
+                SELECT 1 FROM user_blocks b
+                WHERE (b.blocker_id = $1 AND b.blocked_id = m.author_id)
+                    OR (b.blocker_id = m.author_id AND b.blocked_id = $1)
+            )
+            AND ($7::uuid IS NULL OR (m.created_at, m.id) < (SELECT created_at, id FROM direct_messages WHERE id = $7))
+        ORDER BY m.created_at DESC, m.id DESC
+        LIMIT $8
+        "#,
+        content = ESCAPED_CONTENT,
+        options = HEADLINE_OPTIONS,
+    ))
+    .bind(user_id)
+    .bind(search.tsquery())
+    .bind(search.author_id)
+    .bind(search.thread_id)
+    .bind(search.before)
+    .bind(search.after)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
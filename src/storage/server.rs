@@ -1,6 +1,11 @@
-use crate::domain::server::Server;
+use super::ensure_cursor_exists;
+use crate::domain::{
+    pagination::{PageErr, PageParams},
+    search::to_prefix_tsquery,
+    server::{Server, ServerMember},
+};
 use chrono::{DateTime, Utc};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
 use uuid::Uuid;
 
 pub const SERVERS_TABLE_NAME: &str = "servers";
@@ -129,3 +134,221 @@ pub async fn hard_delete_server(db_pool: &PgPool, server_id: Uuid) -> Result<PgQ
     .execute(db_pool)
     .await
 }
+
+#[tracing::instrument(
+    name = "Getting many servers by member id",
+    skip(user_id, params, db_pool),
+    fields(
+        user_id = %user_id,
+        cursor = ?params.cursor(),
+    )
+)]
+pub async fn get_many_servers_by_member_id(
+    db_pool: &PgPool,
+    user_id: Uuid,
+    params: &PageParams,
+) -> Result<Vec<Server>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM servers WHERE id = $1)",
+        params.cursor(),
+        None,
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT s.id, s.name, s.owner_id, s.description, s.photo, s.cover_photo, s.created_at, s.updated_at, s.deleted_at
+        FROM servers s
+        WHERE s.deleted_at IS NULL
+            AND (
+                s.owner_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM server_members m
+                    WHERE m.server_id = s.id AND m.user_id = $1 AND m.is_banned IS NOT TRUE
+                )
+            )
+            AND ($2::uuid IS NULL OR (s.created_at, s.id) < (SELECT created_at, id FROM servers WHERE id = $2))
+        ORDER BY s.created_at DESC, s.id DESC
+        LIMIT $3
+        "#
+    )
+    .bind(user_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Searching servers by name and description",
+    skip(search, params, db_pool),
+    fields(
+        search = %search,
+        cursor = ?params.cursor(),
+    )
+)]
+pub async fn search_servers(
+    db_pool: &PgPool,
+    search: &str,
+    params: &PageParams,
+) -> Result<Vec<Server>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM servers WHERE id = $1)",
+        params.cursor(),
+        None,
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT id, name, owner_id, description, photo, cover_photo, created_at, updated_at, deleted_at
+        FROM servers
+        WHERE deleted_at IS NULL
+            AND ($1::text IS NULL OR search_vector @@ to_tsquery('simple', $1))
+            AND ($2::uuid IS NULL OR (created_at, id) < (SELECT created_at, id FROM servers WHERE id = $2))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $3
+        "#
+    )
+    .bind(to_prefix_tsquery(search))
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Inserting server member to database",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn insert_server_member(
+    db_pool: &PgPool,
+    server_id: Uuid,
+    user_id: Uuid,
+    is_admin: bool,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO server_members (server_id, user_id, is_admin)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (server_id, user_id)
+        DO
+            UPDATE SET is_admin = EXCLUDED.is_admin;
+        "#,
+    )
+    .bind(server_id)
+    .bind(user_id)
+    .bind(is_admin)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting server member",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn get_server_member(
+    db_pool: &PgPool,
+    server_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<ServerMember>, Error> {
+    query_as(
+        r#"
+        SELECT server_id, user_id, COALESCE(is_admin, false) AS is_admin, COALESCE(is_banned, false) AS is_banned, joined_at
+        FROM server_members
+        WHERE server_id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(server_id)
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await
+}
+
+/// Every server the user can act in, unpaginated, for resolving gateway
+/// subscriptions.
+#[tracing::instrument(
+    name = "Getting all servers by member id",
+    skip(user_id, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_all_servers_by_member_id(
+    db_pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Server>, Error> {
+    query_as(
+        r#"
+        SELECT s.id, s.name, s.owner_id, s.description, s.photo, s.cover_photo, s.created_at, s.updated_at, s.deleted_at
+        FROM servers s
+        WHERE s.deleted_at IS NULL
+            AND (
+                s.owner_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM server_members m
+                    WHERE m.server_id = s.id AND m.user_id = $1 AND m.is_banned IS NOT TRUE
+                )
+            )
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// The ids of the owner and every member who is not banned.
+#[tracing::instrument(
+    name = "Getting server member ids",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn get_server_member_ids(db_pool: &PgPool, server_id: Uuid) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        SELECT owner_id FROM servers WHERE id = $1
+        UNION
+        SELECT user_id FROM server_members WHERE server_id = $1 AND is_banned IS NOT TRUE
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Every member who is not banned. The owner is only included if they also
+/// hold a membership row.
+#[tracing::instrument(
+    name = "Getting server members",
+    skip(server_id, db_pool),
+    fields(
+        server_id = %server_id,
+    )
+)]
+pub async fn get_server_members(
+    db_pool: &PgPool,
+    server_id: Uuid,
+) -> Result<Vec<ServerMember>, Error> {
+    query_as(
+        r#"
+        SELECT server_id, user_id, COALESCE(is_admin, false) AS is_admin, COALESCE(is_banned, false) AS is_banned, joined_at
+        FROM server_members
+        WHERE server_id = $1 AND is_banned IS NOT TRUE
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
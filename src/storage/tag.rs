@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, Error, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    pagination::{PageErr, PageParams},
+    post::{Post, PostContent},
+    tag::{Tag, TrendingTag, TRENDING_TAGS_LIMIT, TRENDING_WINDOW_HOURS},
+};
+
+pub const TAGS_TABLE_NAME: &str = "tags";
+pub const POST_TAGS_TABLE_NAME: &str = "post_tags";
+pub const TRENDING_TAGS_TABLE_NAME: &str = "trending_tags";
+
+/// Replaces a post's tags with the ones in `content`, creating tags seen for
+/// the first time. The post must already be written in the transaction.
+pub(super) async fn replace_post_tags(
+    transaction: &mut Transaction<'_, Postgres>,
+    post_id: Uuid,
+    content: &PostContent,
+) -> Result<(), Error> {
+    query("DELETE FROM post_tags WHERE post_id = $1;")
+        .bind(post_id)
+        .execute(&mut *transaction)
+        .await?;
+
+    let tags = Tag::parse_all(content.as_ref());
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<Uuid> = tags.iter().map(|_| Uuid::new_v4()).collect();
+    let names: Vec<String> = tags.iter().map(Tag::to_string).collect();
+
+    query(
+        r#"
+        INSERT INTO tags (id, name)
+        SELECT * FROM UNNEST($1::uuid[], $2::text[])
+        ON CONFLICT (name) DO NOTHING;
+        "#,
+    )
+    .bind(&ids)
+    .bind(&names)
+    .execute(&mut *transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO post_tags (post_id, tag_id, server_id, created_at)
+        SELECT p.id, t.id, p.server_id, p.created_at
+        FROM posts p
+        JOIN tags t ON t.name = ANY($2)
+        WHERE p.id = $1;
+        "#,
+    )
+    .bind(post_id)
+    .bind(&names)
+    .execute(&mut *transaction)
+    .await?;
+    Ok(())
+}
+
+/// Returns a page of posts carrying `tag`, newest first, from the servers
+/// the viewer is in, or only from `server_id` when given. Authors the viewer
+/// has muted and users on either side of a block with them are left out.
+#[tracing::instrument(
+    name = "Getting posts by tag",
+    skip(tag, viewer_id, server_id, params, db_pool),
+    fields(
+        tag = %tag,
+    )
+)]
+pub async fn get_posts_by_tag(
+    db_pool: &PgPool,
+    tag: &Tag,
+    viewer_id: Uuid,
+    server_id: Option<Uuid>,
+    params: &PageParams,
+) -> Result<Vec<Post>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM posts WHERE id = $1)",
+        params.cursor(),
+        None,
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT p.id, p.server_id, p.author_id, p.content, p.quoted_post_id, p.created_at, p.updated_at, p.edited_at, p.deleted_at
+        FROM post_tags pt
+        JOIN tags t ON t.id = pt.tag_id
+        JOIN posts p ON p.id = pt.post_id AND p.deleted_at IS NULL
+        JOIN servers s ON s.id = pt.server_id AND s.deleted_at IS NULL
+        WHERE t.name = $1
+            AND ($2::uuid IS NULL OR pt.server_id = $2)
+            AND (
+                s.owner_id = $3
+                OR EXISTS (
+                    SELECT 1 FROM server_members m
+                    WHERE m.server_id = s.id AND m.user_id = $3 AND m.is_banned IS NOT TRUE
+                )
+            )
+            AND NOT EXISTS(
+                SELECT 1 FROM user_mutes um WHERE um.muter_id = $3 AND um.muted_id = p.author_id
+            )
+            AND NOT EXISTS(
+                SELECT 1 FROM user_blocks b
+                WHERE (b.blocker_id = $3 AND b.blocked_id = p.author_id)
+                    OR (b.blocker_id = p.author_id AND b.blocked_id = $3)
+            )
+            AND (
+                $4::uuid IS NULL
+                OR (pt.created_at, pt.post_id) < (SELECT created_at, id FROM posts WHERE id = $4)
+            )
+        ORDER BY pt.created_at DESC, pt.post_id DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(tag.as_ref())
+    .bind(server_id)
+    .bind(viewer_id)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+/// Recomputes the trending tags of every server, and across all servers, as
+/// of `now`, replacing what was cached. Returns how many were cached.
+#[tracing::instrument(name = "Refreshing trending tags", skip(now, db_pool))]
+pub async fn refresh_trending_tags(db_pool: &PgPool, now: DateTime<Utc>) -> Result<u64, Error> {
+    let mut transaction = db_pool.begin().await?;
+    query("DELETE FROM trending_tags;")
+        .execute(&mut transaction)
+        .await?;
+    let result = query(
+        r#"
+        WITH uses AS (
+            SELECT pt.tag_id, pt.server_id,
+                1 - EXTRACT(EPOCH FROM ($1 - pt.created_at)) / ($2 * 3600.0) AS weight
+            FROM post_tags pt
+            JOIN posts p ON p.id = pt.post_id AND p.deleted_at IS NULL
+            JOIN servers s ON s.id = pt.server_id AND s.deleted_at IS NULL
+            WHERE pt.created_at > $1 - make_interval(hours => $2)
+                AND pt.created_at <= $1
+        ),
+        scored AS (
+            SELECT server_id, tag_id, SUM(weight) AS score, COUNT(*) AS use_count,
+                ROW_NUMBER() OVER (
+                    PARTITION BY server_id ORDER BY SUM(weight) DESC, COUNT(*) DESC, tag_id
+                ) AS rank
+            FROM uses
+            GROUP BY server_id, tag_id
+            UNION ALL
+            SELECT NULL, tag_id, SUM(weight), COUNT(*),
+                ROW_NUMBER() OVER (ORDER BY SUM(weight) DESC, COUNT(*) DESC, tag_id)
+            FROM uses
+            GROUP BY tag_id
+        )
+        INSERT INTO trending_tags (server_id, tag_id, score, use_count, computed_at)
+        SELECT server_id, tag_id, score, use_count, $1
+        FROM scored
+        WHERE rank <= $3;
+        "#,
+    )
+    .bind(now)
+    .bind(TRENDING_WINDOW_HOURS as i32)
+    .bind(TRENDING_TAGS_LIMIT)
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Returns the cached trending tags of a server, or across all servers when
+/// `server_id` is unset, highest scoring first.
+#[tracing::instrument(name = "Getting trending tags", skip(server_id, db_pool))]
+pub async fn get_trending_tags(
+    db_pool: &PgPool,
+    server_id: Option<Uuid>,
+) -> Result<Vec<TrendingTag>, Error> {
+    query_as(
+        r#"
+        SELECT t.name AS tag, tt.score, tt.use_count
+        FROM trending_tags tt
+        JOIN tags t ON t.id = tt.tag_id
+        WHERE tt.server_id IS NOT DISTINCT FROM $1
+        ORDER BY tt.score DESC, tt.use_count DESC, t.name
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
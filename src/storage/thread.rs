@@ -0,0 +1,340 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
+use uuid::Uuid;
+
+use super::ensure_cursor_exists;
+use crate::domain::{
+    channel::ChannelKind,
+    pagination::{PageErr, PageParams},
+    thread::{Thread, ThreadMember},
+};
+
+pub const THREADS_TABLE_NAME: &str = "threads";
+pub const THREAD_MEMBERS_TABLE_NAME: &str = "thread_members";
+
+/// Creates the thread's channel row and metadata, and adds its owner as the
+/// first member.
+#[tracing::instrument(
+    name = "Inserting thread to database",
+    skip(thread, db_pool),
+    fields(
+        thread_id = %thread.id(),
+        parent_id = %thread.parent_id(),
+    )
+)]
+pub async fn insert_thread(db_pool: &PgPool, thread: &Thread) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        INSERT INTO channels (id, server_id, parent_id, kind, name, topic, position, created_at, updated_at, deleted_at)
+        VALUES ($1, $2, $3, $4, $5, NULL, 0, $6, $6, NULL);
+        "#,
+    )
+    .bind(thread.id())
+    .bind(thread.server_id())
+    .bind(thread.parent_id())
+    .bind(ChannelKind::Thread)
+    .bind(thread.name())
+    .bind(thread.created_at())
+    .execute(&mut transaction)
+    .await?;
+    let result = query(
+        r#"
+        INSERT INTO threads (channel_id, starter_message_id, owner_id, auto_archive_minutes, last_message_at, archived_at, locked)
+        VALUES ($1, $2, $3, $4, $5, $6, $7);
+        "#,
+    )
+    .bind(thread.id())
+    .bind(thread.starter_message_id())
+    .bind(thread.owner_id())
+    .bind(thread.auto_archive_minutes())
+    .bind(thread.last_message_at())
+    .bind(thread.archived_at())
+    .bind(thread.locked())
+    .execute(&mut transaction)
+    .await?;
+    query(
+        r#"
+        INSERT INTO thread_members (thread_id, user_id, joined_at)
+        VALUES ($1, $2, $3);
+        "#,
+    )
+    .bind(thread.id())
+    .bind(thread.owner_id())
+    .bind(thread.created_at())
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+/// Gets a thread whose channel has not been soft deleted.
+#[tracing::instrument(
+    name = "Getting thread by id",
+    skip(id, db_pool),
+    fields(
+        thread_id = %id
+    )
+)]
+pub async fn get_thread_by_id(db_pool: &PgPool, id: Uuid) -> Result<Thread, Error> {
+    query_as(
+        r#"
+        SELECT c.id, c.server_id, c.parent_id, t.starter_message_id, t.owner_id, c.name,
+            t.auto_archive_minutes, t.last_message_at, t.archived_at, t.locked, c.created_at
+        FROM threads t
+        JOIN channels c ON c.id = t.channel_id
+        WHERE t.channel_id = $1 AND c.deleted_at IS NULL
+        "#,
+    )
+    .bind(id)
+    .fetch_one(db_pool)
+    .await
+}
+
+/// Lists a channel's open or archived threads, most recently active first.
+#[tracing::instrument(
+    name = "Getting threads by parent id",
+    skip(parent_id, params, db_pool),
+    fields(
+        parent_id = %parent_id,
+        archived = archived,
+        cursor = ?params.cursor(),
+    )
+)]
+pub async fn get_threads_by_parent_id(
+    db_pool: &PgPool,
+    parent_id: Uuid,
+    archived: bool,
+    params: &PageParams,
+) -> Result<Vec<Thread>, PageErr> {
+    ensure_cursor_exists(
+        db_pool,
+        "SELECT EXISTS(SELECT 1 FROM channels WHERE id = $1 AND parent_id = $2 AND kind = 'thread')",
+        params.cursor(),
+        Some(parent_id),
+    )
+    .await?;
+    query_as(
+        r#"
+        SELECT c.id, c.server_id, c.parent_id, t.starter_message_id, t.owner_id, c.name,
+            t.auto_archive_minutes, t.last_message_at, t.archived_at, t.locked, c.created_at
+        FROM threads t
+        JOIN channels c ON c.id = t.channel_id
+        WHERE c.parent_id = $1
+            AND c.deleted_at IS NULL
+            AND (t.archived_at IS NOT NULL) = $2
+            AND (
+                $3::uuid IS NULL
+                OR (COALESCE(t.last_message_at, c.created_at), c.id) < (
+                    SELECT COALESCE(t2.last_message_at, c2.created_at), c2.id
+                    FROM threads t2 JOIN channels c2 ON c2.id = t2.channel_id
+                    WHERE t2.channel_id = $3
+                )
+            )
+        ORDER BY COALESCE(t.last_message_at, c.created_at) DESC, c.id DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(parent_id)
+    .bind(archived)
+    .bind(params.cursor())
+    .bind(params.fetch_limit())
+    .fetch_all(db_pool)
+    .await
+    .map_err(PageErr::Query)
+}
+
+#[tracing::instrument(
+    name = "Updating thread in database",
+    skip(thread, db_pool),
+    fields(
+        thread_id = %thread.id(),
+    )
+)]
+pub async fn update_thread(db_pool: &PgPool, thread: &Thread) -> Result<PgQueryResult, Error> {
+    let mut transaction = db_pool.begin().await?;
+    query(
+        r#"
+        UPDATE channels
+        SET name = $1, updated_at = now()
+        WHERE id = $2;
+        "#,
+    )
+    .bind(thread.name())
+    .bind(thread.id())
+    .execute(&mut transaction)
+    .await?;
+    let result = query(
+        r#"
+        UPDATE threads
+        SET auto_archive_minutes = $1, archived_at = $2, locked = $3
+        WHERE channel_id = $4;
+        "#,
+    )
+    .bind(thread.auto_archive_minutes())
+    .bind(thread.archived_at())
+    .bind(thread.locked())
+    .bind(thread.id())
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(result)
+}
+
+/// Records a new message in the thread, reopening it if it was archived.
+#[tracing::instrument(
+    name = "Recording thread activity",
+    skip(thread_id, sent_at, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn touch_thread(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    sent_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        UPDATE threads
+        SET last_message_at = $1, archived_at = NULL
+        WHERE channel_id = $2;
+        "#,
+    )
+    .bind(sent_at)
+    .bind(thread_id)
+    .execute(db_pool)
+    .await
+}
+
+/// Archives every open thread that has been inactive for its auto archive
+/// duration as of `now`, returning the threads it archived.
+#[tracing::instrument(name = "Archiving inactive threads", skip(now, db_pool))]
+pub async fn archive_inactive_threads(
+    db_pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<Thread>, Error> {
+    query_as(
+        r#"
+        UPDATE threads t
+        SET archived_at = $1
+        FROM channels c
+        WHERE c.id = t.channel_id
+            AND c.deleted_at IS NULL
+            AND t.archived_at IS NULL
+            AND COALESCE(t.last_message_at, c.created_at) + t.auto_archive_minutes * INTERVAL '1 minute' <= $1
+        RETURNING c.id, c.server_id, c.parent_id, t.starter_message_id, t.owner_id, c.name,
+            t.auto_archive_minutes, t.last_message_at, t.archived_at, t.locked, c.created_at
+        "#,
+    )
+    .bind(now)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Adds users to a thread, returning the ids of those who were not already
+/// members.
+#[tracing::instrument(
+    name = "Adding thread members",
+    skip(thread_id, user_ids, db_pool),
+    fields(
+        thread_id = %thread_id,
+        users = user_ids.len(),
+    )
+)]
+pub async fn insert_thread_members(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    user_ids: &[Uuid],
+) -> Result<Vec<Uuid>, Error> {
+    query_scalar(
+        r#"
+        INSERT INTO thread_members (thread_id, user_id)
+        SELECT $1, user_id FROM UNNEST($2::uuid[]) AS m(user_id)
+        ON CONFLICT (thread_id, user_id) DO NOTHING
+        RETURNING user_id
+        "#,
+    )
+    .bind(thread_id)
+    .bind(user_ids)
+    .fetch_all(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Removing thread member",
+    skip(thread_id, user_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn delete_thread_member(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        DELETE FROM thread_members
+        WHERE thread_id = $1 AND user_id = $2;
+        "#,
+    )
+    .bind(thread_id)
+    .bind(user_id)
+    .execute(db_pool)
+    .await
+}
+
+#[tracing::instrument(
+    name = "Getting thread members",
+    skip(thread_id, db_pool),
+    fields(
+        thread_id = %thread_id,
+    )
+)]
+pub async fn get_thread_members(
+    db_pool: &PgPool,
+    thread_id: Uuid,
+) -> Result<Vec<ThreadMember>, Error> {
+    query_as(
+        r#"
+        SELECT thread_id, user_id, joined_at
+        FROM thread_members
+        WHERE thread_id = $1
+        ORDER BY joined_at, user_id
+        "#,
+    )
+    .bind(thread_id)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// The `(thread_id, parent_id)` of every live thread in the server that the
+/// user has joined, for resolving gateway subscriptions.
+#[tracing::instrument(
+    name = "Getting joined threads",
+    skip(server_id, user_id, db_pool),
+    fields(
+        server_id = %server_id,
+        user_id = %user_id,
+    )
+)]
+pub async fn get_joined_thread_ids(
+    db_pool: &PgPool,
+    server_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<(Uuid, Uuid)>, Error> {
+    query_as(
+        r#"
+        SELECT c.id, c.parent_id
+        FROM thread_members m
+        JOIN channels c ON c.id = m.thread_id
+        WHERE m.user_id = $1 AND c.server_id = $2 AND c.deleted_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(server_id)
+    .fetch_all(db_pool)
+    .await
+}
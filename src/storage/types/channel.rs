@@ -0,0 +1,51 @@
+use sqlx::{postgres::PgTypeInfo, Database, Decode, Encode, Postgres, Type};
+
+use crate::domain::channel::{ChannelKind, OverwriteTarget};
+
+impl<'r> Decode<'r, Postgres> for ChannelKind {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let kind = <&str as Decode<Postgres>>::decode(value)?;
+        Self::try_from(kind).map_err(sqlx::error::BoxDynError::from)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for ChannelKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+impl Type<Postgres> for ChannelKind {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("channel_kind")
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for OverwriteTarget {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let target = <&str as Decode<Postgres>>::decode(value)?;
+        Self::try_from(target).map_err(sqlx::error::BoxDynError::from)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for OverwriteTarget {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+impl Type<Postgres> for OverwriteTarget {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("overwrite_target")
+    }
+}
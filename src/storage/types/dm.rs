@@ -0,0 +1,27 @@
+use sqlx::{postgres::PgTypeInfo, Database, Decode, Encode, Postgres, Type};
+
+use crate::domain::dm::DmThreadKind;
+
+impl<'r> Decode<'r, Postgres> for DmThreadKind {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let kind = <&str as Decode<Postgres>>::decode(value)?;
+        Self::try_from(kind).map_err(sqlx::error::BoxDynError::from)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for DmThreadKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+impl Type<Postgres> for DmThreadKind {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("dm_thread_kind")
+    }
+}
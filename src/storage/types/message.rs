@@ -0,0 +1,53 @@
+use sqlx::{postgres::PgTypeInfo, Database, Decode, Encode, Postgres, Type};
+
+use crate::domain::message::{MessageContent, MessageKind};
+
+impl<'r> Decode<'r, Postgres> for MessageContent {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let content = String::decode(value)?;
+        Self::try_from(content).map_err(|e| {
+            sqlx::error::BoxDynError::from(format!("failed to decode message content: {:?}", e))
+        })
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for MessageContent {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        self.as_ref().encode_by_ref(buf)
+    }
+}
+
+impl Type<Postgres> for MessageContent {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("VARCHAR")
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for MessageKind {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let kind = <&str as Decode<Postgres>>::decode(value)?;
+        Self::try_from(kind).map_err(sqlx::error::BoxDynError::from)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for MessageKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+impl Type<Postgres> for MessageKind {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("message_kind")
+    }
+}
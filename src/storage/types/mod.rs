@@ -1,2 +1,8 @@
+mod channel;
 mod confirmation_token;
+mod dm;
+mod message;
+mod notification;
+mod permission;
+mod post;
 mod user;
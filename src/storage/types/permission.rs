@@ -0,0 +1,26 @@
+use sqlx::{Database, Decode, Encode, Postgres, Type};
+
+use crate::domain::permission::Permissions;
+
+impl<'r> Decode<'r, Postgres> for Permissions {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        Ok(Self::from_bits(i64::decode(value)?))
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for Permissions {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        self.bits().encode_by_ref(buf)
+    }
+}
+
+impl Type<Postgres> for Permissions {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        <i64 as Type<Postgres>>::type_info()
+    }
+}
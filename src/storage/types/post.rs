@@ -0,0 +1,29 @@
+use sqlx::{postgres::PgTypeInfo, Database, Decode, Encode, Postgres, Type};
+
+use crate::domain::post::PostContent;
+
+impl<'r> Decode<'r, Postgres> for PostContent {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let content = String::decode(value)?;
+        Self::try_from(content).map_err(|e| {
+            sqlx::error::BoxDynError::from(format!("failed to decode post content: {:?}", e))
+        })
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for PostContent {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        self.as_ref().encode_by_ref(buf)
+    }
+}
+
+impl Type<Postgres> for PostContent {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("VARCHAR")
+    }
+}
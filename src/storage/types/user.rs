@@ -3,7 +3,7 @@ use sqlx::{postgres::PgTypeInfo, Database, Decode, Encode, Postgres, Type};
 use uuid::Uuid;
 
 use crate::{
-    domain::user::{Email, Handle, Password},
+    domain::user::{DmPrivacy, Email, Handle, Password, ProfileVisibility},
     handlers::user::PatchUserRequestBody,
 };
 
@@ -142,3 +142,51 @@ impl PatchUserRequestBody {
         }
     }
 }
+
+impl<'r> Decode<'r, Postgres> for DmPrivacy {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let privacy = <&str as Decode<Postgres>>::decode(value)?;
+        Self::try_from(privacy).map_err(sqlx::error::BoxDynError::from)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for DmPrivacy {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+impl Type<Postgres> for DmPrivacy {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("dm_privacy")
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for ProfileVisibility {
+    fn decode(
+        value: <Postgres as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let visibility = <&str as Decode<Postgres>>::decode(value)?;
+        Self::try_from(visibility).map_err(sqlx::error::BoxDynError::from)
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for ProfileVisibility {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Postgres as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+impl Type<Postgres> for ProfileVisibility {
+    fn type_info() -> <Postgres as Database>::TypeInfo {
+        PgTypeInfo::with_name("profile_visibility")
+    }
+}
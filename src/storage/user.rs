@@ -1,10 +1,14 @@
 use chrono::{DateTime, Utc};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool};
+use sqlx::{postgres::PgQueryResult, query, query_as, query_scalar, Error, PgPool};
 use uuid::Uuid;
 
-use crate::domain::user::User;
+use crate::domain::{
+    presence::PresenceAudience,
+    user::{ProfileVisibility, User, UserSettings},
+};
 
 pub const USERS_TABLE_NAME: &str = "users";
+pub const USER_SETTINGS_TABLE_NAME: &str = "user_settings";
 
 #[tracing::instrument(name = "Upserting user details to database", skip(user, db_pool))]
 pub async fn upsert_user(db_pool: &PgPool, user: &User) -> Result<PgQueryResult, Error> {
@@ -46,7 +50,7 @@ pub async fn upsert_user(db_pool: &PgPool, user: &User) -> Result<PgQueryResult,
     .await
 }
 
-pub async fn patch_user<'a>(db_pool: &PgPool, q: String) -> Result<PgQueryResult, Error> {
+pub async fn patch_user(db_pool: &PgPool, q: String) -> Result<PgQueryResult, Error> {
     query(&q).execute(db_pool).await
 }
 
@@ -170,3 +174,143 @@ pub async fn hard_delete_user(db_pool: &PgPool, user_id: Uuid) -> Result<PgQuery
     .execute(db_pool)
     .await
 }
+
+/// Looks up every active user whose handle is one of `handles`, for
+/// resolving mentions in one query.
+#[tracing::instrument(
+    name = "Getting users by handles",
+    skip(handles, db_pool),
+    fields(
+        handles = handles.len(),
+    )
+)]
+pub async fn get_users_by_handles(
+    db_pool: &PgPool,
+    handles: &[String],
+) -> Result<Vec<User>, Error> {
+    query_as(
+        r#"
+            SELECT id, email, handle, name, password, profile_photo, bio, email_confirmed, created_at, updated_at, deleted_at, failed_attempts
+            FROM users
+            WHERE handle = ANY($1) AND deleted_at IS NULL
+        "#
+    )
+    .bind(handles)
+    .fetch_all(db_pool)
+    .await
+}
+
+/// Returns a user's settings, or the defaults when they never changed them.
+#[tracing::instrument(
+    name = "Getting user settings",
+    skip(user_id, db_pool),
+    fields(
+        user_id = %user_id
+    )
+)]
+pub async fn get_user_settings(db_pool: &PgPool, user_id: Uuid) -> Result<UserSettings, Error> {
+    let settings = query_as(
+        r#"
+        SELECT user_id, is_private, dm_privacy, bio_visibility, servers_visibility,
+            followers_visibility, presence_visibility, updated_at
+        FROM user_settings
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(db_pool)
+    .await?;
+    Ok(settings.unwrap_or_else(|| UserSettings::new(user_id, Utc::now())))
+}
+
+#[tracing::instrument(
+    name = "Upserting user settings to database",
+    skip(settings, db_pool),
+    fields(
+        user_id = %settings.user_id()
+    )
+)]
+pub async fn upsert_user_settings(
+    db_pool: &PgPool,
+    settings: &UserSettings,
+) -> Result<PgQueryResult, Error> {
+    query(
+        r#"
+        INSERT INTO user_settings (
+            user_id, is_private, dm_privacy, bio_visibility, servers_visibility,
+            followers_visibility, presence_visibility, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id)
+        DO
+            UPDATE SET
+                is_private = EXCLUDED.is_private,
+                dm_privacy = EXCLUDED.dm_privacy,
+                bio_visibility = EXCLUDED.bio_visibility,
+                servers_visibility = EXCLUDED.servers_visibility,
+                followers_visibility = EXCLUDED.followers_visibility,
+                presence_visibility = EXCLUDED.presence_visibility,
+                updated_at = EXCLUDED.updated_at
+        "#,
+    )
+    .bind(settings.user_id())
+    .bind(settings.is_private())
+    .bind(settings.dm_privacy())
+    .bind(settings.bio_visibility())
+    .bind(settings.servers_visibility())
+    .bind(settings.followers_visibility())
+    .bind(settings.presence_visibility())
+    .bind(settings.updated_at())
+    .execute(db_pool)
+    .await
+}
+
+/// Resolves who may see the user's presence from their presence visibility
+/// setting, their followers and friends, and blocks in either direction.
+#[tracing::instrument(
+    name = "Getting presence audience",
+    skip(user_id, db_pool),
+    fields(
+        user_id = %user_id,
+    )
+)]
+pub async fn get_presence_audience(
+    db_pool: &PgPool,
+    user_id: Uuid,
+) -> Result<PresenceAudience, Error> {
+    let visibility = get_user_settings(db_pool, user_id)
+        .await?
+        .presence_visibility();
+    let allowed = match visibility {
+        ProfileVisibility::Followers => {
+            query_scalar(
+                r#"
+                SELECT follower_id FROM user_follows WHERE followee_id = $1
+                UNION
+                SELECT friend_id FROM friendships WHERE user_id = $1
+                "#,
+            )
+            .bind(user_id)
+            .fetch_all(db_pool)
+            .await?
+        }
+        ProfileVisibility::Friends => {
+            query_scalar("SELECT friend_id FROM friendships WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_all(db_pool)
+                .await?
+        }
+        ProfileVisibility::Everyone | ProfileVisibility::Nobody => Vec::new(),
+    };
+    let blocked = query_scalar(
+        r#"
+        SELECT blocked_id FROM user_blocks WHERE blocker_id = $1
+        UNION
+        SELECT blocker_id FROM user_blocks WHERE blocked_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db_pool)
+    .await?;
+    Ok(PresenceAudience::new(visibility, allowed, blocked))
+}
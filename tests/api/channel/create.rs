@@ -0,0 +1,238 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::{
+        channel::{Channel, ChannelKind},
+        permission::Permissions,
+    },
+    handlers::{channel, server},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+#[actix::test]
+async fn test_create_channel_success() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(user.id()).expect("Failed to generate auth token for user");
+    let srv = app.database.insert_server(user.id()).await;
+    let path = format!("{}/{}{}", server::BASE_PATH, srv.id(), channel::BASE_PATH);
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            Some(json!({"kind": "category", "name": "Text Channels"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when creating a category"
+    );
+    let category = response
+        .json::<Channel>()
+        .await
+        .expect("failed to unmarshal json into Channel");
+
+    let test_cases = [
+        (
+            json!({"kind": "text", "name": "general", "topic": "Talk about anything"}),
+            ChannelKind::Text,
+            None,
+            "is a text channel with a topic",
+        ),
+        (
+            json!({"kind": "voice", "name": "Lounge", "parent_id": category.id()}),
+            ChannelKind::Voice,
+            Some(category.id()),
+            "is a voice channel in a category",
+        ),
+        (
+            json!({"kind": "announcement", "name": "news", "position": 10}),
+            ChannelKind::Announcement,
+            None,
+            "is an announcement channel with a position",
+        ),
+    ];
+
+    for (body, kind, parent_id, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::POST(&path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                Some(body.to_string()),
+            )
+            .await;
+
+        assert_eq!(
+            200,
+            response.status(),
+            "The API did not return 200 when creating channel that {}",
+            case,
+        );
+        let created = response
+            .json::<Channel>()
+            .await
+            .expect("failed to unmarshal json into Channel");
+        let stored = app
+            .database
+            .get_channel_by_id(created.id())
+            .await
+            .unwrap_or_else(|e| panic!("failed to retrieve channel from database: {}", e));
+
+        assert_eq!(created, stored, "stored channel did not match for {}", case);
+        assert_eq!(kind, stored.kind(), "wrong kind for channel that {}", case);
+        assert_eq!(
+            parent_id,
+            stored.parent_id(),
+            "wrong parent for channel that {}",
+            case
+        );
+    }
+}
+
+#[actix::test]
+async fn test_create_channel_failure() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let text = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let path = format!("{}/{}{}", server::BASE_PATH, srv.id(), channel::BASE_PATH);
+
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let outsider_token = generate_token(outsider.id()).unwrap();
+    let valid = json!({"kind": "text", "name": "general"}).to_string();
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[Header::ContentType(ContentType::Json)],
+            Some(valid.clone()),
+        )
+        .await;
+    assert_eq!(
+        401,
+        response.status(),
+        "The API did not return 401 when no token was provided"
+    );
+
+    let test_cases = [
+        (
+            outsider_token,
+            valid.clone(),
+            403,
+            "the user is not a member",
+        ),
+        (
+            member_token.clone(),
+            valid.clone(),
+            403,
+            "the member cannot manage channels",
+        ),
+        (
+            owner_token.clone(),
+            json!({"kind": "text", "name": "  "}).to_string(),
+            400,
+            "the name is empty",
+        ),
+        (
+            owner_token.clone(),
+            json!({"kind": "text", "name": "a".repeat(101)}).to_string(),
+            400,
+            "the name is too long",
+        ),
+        (
+            owner_token.clone(),
+            json!({"kind": "text", "name": "chat", "parent_id": text.id()}).to_string(),
+            400,
+            "the parent is not a category",
+        ),
+        (
+            owner_token.clone(),
+            json!({"kind": "forum", "name": "chat"}).to_string(),
+            400,
+            "the kind is not supported",
+        ),
+    ];
+
+    for (token, body, status, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::POST(&path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token),
+                ],
+                Some(body),
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status(),
+            "The API did not return {} when {}",
+            status,
+            case,
+        );
+    }
+
+    let role = app
+        .database
+        .insert_role(srv.id(), "Channel Manager", Permissions::MANAGE_CHANNELS)
+        .await;
+    app.database
+        .insert_member_role(role.id(), member.id())
+        .await;
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(member_token),
+            ],
+            Some(valid),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 once the member was given a role that can manage channels"
+    );
+}
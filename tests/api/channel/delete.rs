@@ -0,0 +1,216 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::Utc;
+use claim::{assert_none, assert_some};
+use muttr_server::{
+    domain::{channel::ChannelKind, thread::Thread},
+    handlers::{channel, message, server, thread},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+#[actix::test]
+async fn test_soft_delete_channel() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let category = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Category, "Text Channels")
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+    let channel_path = |id| {
+        format!(
+            "{}/{}{}/{}",
+            server::BASE_PATH,
+            srv.id(),
+            channel::BASE_PATH,
+            id
+        )
+    };
+
+    let response = app
+        .client
+        .request(
+            Path::PATCH(channel_path(general.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            Some(json!({"parent_id": category.id()}).to_string()),
+        )
+        .await;
+    assert_eq!(200, response.status(), "Failed to move channel to category");
+
+    let response = app
+        .client
+        .request(
+            Path::DELETE(channel_path(category.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 on valid channel soft delete"
+    );
+
+    let stored = app
+        .database
+        .get_channel_by_id(category.id())
+        .await
+        .expect("failed to retrieve channel from database");
+    assert_some!(stored.deleted_at(), "Channel deleted_at is None");
+    let child = app
+        .database
+        .get_channel_by_id(general.id())
+        .await
+        .expect("failed to retrieve channel from database");
+    assert_none!(
+        child.parent_id(),
+        "Child channel was not removed from the deleted category"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::DELETE(channel_path(category.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 when deleting an already deleted channel"
+    );
+}
+
+#[actix::test]
+async fn test_soft_delete_channel_deletes_its_threads() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "let's discuss", Utc::now())
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+
+    let started = app
+        .client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}/{}{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH,
+                msg.id(),
+                thread::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            Some(json!({"name": "a thread"}).to_string()),
+        )
+        .await
+        .json::<Thread>()
+        .await
+        .expect("failed to unmarshal json into Thread");
+
+    let response = app
+        .client
+        .request(
+            Path::DELETE(format!(
+                "{}/{}{}/{}",
+                server::BASE_PATH,
+                srv.id(),
+                channel::BASE_PATH,
+                general.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 on valid channel soft delete"
+    );
+
+    let stored = app
+        .database
+        .get_channel_by_id(started.id())
+        .await
+        .expect("failed to retrieve thread from database");
+    assert_some!(stored.deleted_at(), "Thread deleted_at is None");
+    assert_eq!(
+        Some(general.id()),
+        stored.parent_id(),
+        "The thread was moved out of its deleted channel"
+    );
+
+    let test_cases = [
+        (
+            format!("{}/{}", thread::BASE_PATH, started.id()),
+            "getting the thread",
+        ),
+        (
+            format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                started.id(),
+                message::BASE_PATH
+            ),
+            "reading its messages",
+        ),
+    ];
+    for (path, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::GET(path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            404,
+            response.status(),
+            "The API did not return 404 when {} of a deleted channel",
+            case
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::{
+        channel::{Channel, ChannelKind},
+        permission::Permissions,
+    },
+    handlers::{channel, server},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_get_channel_by_id() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let path = format!(
+        "{}/{}{}/{}",
+        server::BASE_PATH,
+        srv.id(),
+        channel::BASE_PATH,
+        general.id()
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(owner.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+    let channel_res = response
+        .json::<Channel>()
+        .await
+        .expect("failed to unmarshal json into Channel");
+    assert_eq!(general, channel_res, "The returned channel did not match");
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(outsider.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 when a non-member requested a channel"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}/{}",
+                server::BASE_PATH,
+                srv.id(),
+                channel::BASE_PATH,
+                Uuid::new_v4()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(owner.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 for a non-existant channel"
+    );
+}
+
+#[actix::test]
+async fn test_get_channels_respects_overwrites() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let staff = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "staff")
+        .await;
+    let role = app
+        .database
+        .insert_role(srv.id(), "Staff", Permissions::empty())
+        .await;
+
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let list_path = format!("{}/{}{}", server::BASE_PATH, srv.id(), channel::BASE_PATH);
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!(
+                "{}/{}{}/{}",
+                list_path,
+                staff.id(),
+                channel::PERMISSIONS_PATH,
+                srv.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(
+                json!({"target_type": "role", "deny": Permissions::VIEW_CHANNEL.bits()})
+                    .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when hiding a channel from @everyone"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!(
+                "{}/{}{}/{}",
+                list_path,
+                staff.id(),
+                channel::PERMISSIONS_PATH,
+                role.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(
+                json!({"target_type": "role", "allow": Permissions::VIEW_CHANNEL.bits()})
+                    .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when allowing a role to view a channel"
+    );
+
+    let list_ids = |token: String| {
+        let client = &app.client;
+        let path = list_path.clone();
+        async move {
+            let response = client
+                .request(
+                    Path::GET(path),
+                    &[
+                        Header::ContentType(ContentType::Json),
+                        Header::Authorization(token),
+                    ],
+                    None::<String>,
+                )
+                .await;
+            assert_eq!(200, response.status(), "The API did not return 200");
+            response
+                .json::<Vec<Channel>>()
+                .await
+                .expect("failed to unmarshal json into Vec<Channel>")
+                .iter()
+                .map(|c| c.id())
+                .collect::<Vec<Uuid>>()
+        }
+    };
+
+    assert_eq!(
+        vec![general.id(), staff.id()],
+        list_ids(owner_token.clone()).await,
+        "The owner should see every channel"
+    );
+    assert_eq!(
+        vec![general.id()],
+        list_ids(member_token.clone()).await,
+        "The member should not see the hidden channel"
+    );
+
+    app.database
+        .insert_member_role(role.id(), member.id())
+        .await;
+    assert_eq!(
+        vec![general.id(), staff.id()],
+        list_ids(member_token).await,
+        "The member should see the hidden channel once given the role"
+    );
+}
@@ -0,0 +1,5 @@
+mod create;
+mod delete;
+mod get;
+mod permission;
+mod update;
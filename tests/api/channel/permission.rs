@@ -0,0 +1,146 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::{
+        channel::{ChannelKind, OverwriteTarget, PermissionOverwrite},
+        permission::Permissions,
+    },
+    handlers::{channel, server},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_put_and_delete_overwrite() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+    let permissions_path = format!(
+        "{}/{}{}/{}{}",
+        server::BASE_PATH,
+        srv.id(),
+        channel::BASE_PATH,
+        general.id(),
+        channel::PERMISSIONS_PATH,
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!("{}/{}", permissions_path, member.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            Some(
+                json!({"target_type": "member", "deny": Permissions::SEND_MESSAGES.bits()})
+                    .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 on valid member overwrite"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&permissions_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+    let overwrites = response
+        .json::<Vec<PermissionOverwrite>>()
+        .await
+        .expect("failed to unmarshal json into Vec<PermissionOverwrite>");
+    assert_eq!(
+        vec![PermissionOverwrite::new(
+            general.id(),
+            member.id(),
+            OverwriteTarget::Member,
+            Permissions::empty(),
+            Permissions::SEND_MESSAGES,
+        )],
+        overwrites,
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!("{}/{}", permissions_path, Uuid::new_v4())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            Some(json!({"target_type": "role"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not return 400 for an overwrite on a non-existant role"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!("{}/{}", permissions_path, member.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(member.id()).unwrap()),
+            ],
+            Some(json!({"target_type": "member"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a member without manage roles set an overwrite"
+    );
+
+    for status in [200, 404] {
+        let response = app
+            .client
+            .request(
+                Path::DELETE(format!("{}/{}", permissions_path, member.id())),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status(),
+            "The API did not return {} when deleting the overwrite",
+            status
+        );
+    }
+}
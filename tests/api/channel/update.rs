@@ -0,0 +1,158 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::channel::ChannelKind,
+    handlers::{channel, server},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+#[actix::test]
+async fn test_patch_channel_success() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let category = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Category, "Text Channels")
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+    let path = format!(
+        "{}/{}{}/{}",
+        server::BASE_PATH,
+        srv.id(),
+        channel::BASE_PATH,
+        general.id()
+    );
+
+    let test_cases = [
+        (json!({"name": "chat"}), "name is updated"),
+        (json!({"topic": "New topic"}), "topic is updated"),
+        (json!({"position": 3}), "position is updated"),
+        (json!({"parent_id": category.id()}), "parent is set"),
+        (
+            json!({"topic": null, "parent_id": null}),
+            "topic and parent are cleared",
+        ),
+    ];
+
+    for (body, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::PATCH(&path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                Some(body.to_string()),
+            )
+            .await;
+        assert_eq!(
+            200,
+            response.status(),
+            "The API did not return 200 when {}",
+            case,
+        );
+    }
+
+    let stored = app
+        .database
+        .get_channel_by_id(general.id())
+        .await
+        .expect("failed to retrieve channel from database");
+    assert_eq!("chat", stored.name());
+    assert_eq!(None, stored.topic());
+    assert_eq!(3, stored.position());
+    assert_eq!(None, stored.parent_id());
+}
+
+#[actix::test]
+async fn test_patch_channel_failure() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let category = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Category, "Text Channels")
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let channel_path = |id| {
+        format!(
+            "{}/{}{}/{}",
+            server::BASE_PATH,
+            srv.id(),
+            channel::BASE_PATH,
+            id
+        )
+    };
+
+    let test_cases = [
+        (
+            member.id(),
+            channel_path(general.id()),
+            json!({"name": "chat"}),
+            403,
+            "the member cannot manage channels",
+        ),
+        (
+            owner.id(),
+            channel_path(general.id()),
+            json!({"topic": "a".repeat(1025)}),
+            400,
+            "the topic is too long",
+        ),
+        (
+            owner.id(),
+            channel_path(category.id()),
+            json!({"parent_id": category.id()}),
+            400,
+            "a category is given a parent",
+        ),
+    ];
+
+    for (user_id, path, body, status, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::PATCH(path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(user_id).unwrap()),
+                ],
+                Some(body.to_string()),
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status(),
+            "The API did not return {} when {}",
+            status,
+            case,
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::dm::{DmThreadKind, MAX_GROUP_DM_PARTICIPANTS},
+    handlers::dm::{self, DmThreadResponse},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_open_direct_thread() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+
+    let mut thread_ids = Vec::new();
+    for (sender, recipient) in [(&alice, &bob), (&bob, &alice)] {
+        let response = app
+            .client
+            .request(
+                Path::POST(dm::BASE_PATH),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(sender.id()).unwrap()),
+                ],
+                Some(json!({"recipient_id": recipient.id()}).to_string()),
+            )
+            .await;
+        assert_eq!(
+            200,
+            response.status(),
+            "The API did not return 200 when opening a DM thread"
+        );
+        let res = response
+            .json::<DmThreadResponse>()
+            .await
+            .expect("failed to unmarshal json into DmThreadResponse");
+        assert_eq!(
+            DmThreadKind::Direct,
+            res.thread.kind(),
+            "The thread kind did not match"
+        );
+        assert!(
+            res.participant_ids.contains(&alice.id()) && res.participant_ids.contains(&bob.id()),
+            "The thread participants did not match"
+        );
+        thread_ids.push(res.thread.id());
+    }
+    assert_eq!(
+        thread_ids[0], thread_ids[1],
+        "Opening a DM from either side did not return the same thread"
+    );
+}
+
+#[actix::test]
+async fn test_open_direct_thread_failure() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    app.database.insert_user_block(bob.id(), alice.id()).await;
+
+    let test_cases = [
+        (alice.id(), 400, "the recipient is the sender"),
+        (bob.id(), 403, "the recipient has blocked the sender"),
+        (Uuid::new_v4(), 404, "the recipient does not exist"),
+    ];
+
+    for (recipient_id, status, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::POST(dm::BASE_PATH),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(alice.id()).unwrap()),
+                ],
+                Some(json!({ "recipient_id": recipient_id }).to_string()),
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status().as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+}
+
+#[actix::test]
+async fn test_create_group_thread() {
+    let mut app = TestApp::spawn().await;
+
+    let mut users = Vec::new();
+    for i in 0..=MAX_GROUP_DM_PARTICIPANTS {
+        users.push(
+            app.database
+                .insert_user(
+                    &format!("testuser{}@email.com", i),
+                    &format!("test.user{}", i),
+                    true,
+                )
+                .await,
+        );
+    }
+    let owner = &users[0];
+    let path = format!("{}{}", dm::BASE_PATH, dm::GROUPS_PATH);
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(owner.id()).unwrap()),
+            ],
+            Some(
+                json!({"name": "Friends", "participant_ids": [users[1].id(), users[2].id()]})
+                    .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when creating a group DM"
+    );
+    let res = response
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse");
+    assert_eq!(
+        DmThreadKind::Group,
+        res.thread.kind(),
+        "The thread kind did not match"
+    );
+    assert_eq!(
+        Some(owner.id()),
+        res.thread.owner_id(),
+        "The owner did not match"
+    );
+    let mut expected = vec![owner.id(), users[1].id(), users[2].id()];
+    expected.sort();
+    let mut stored = app.database.get_dm_participant_ids(res.thread.id()).await;
+    stored.sort();
+    assert_eq!(expected, stored, "The stored participants did not match");
+
+    let everyone = users.iter().skip(1).map(|u| u.id()).collect::<Vec<Uuid>>();
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(owner.id()).unwrap()),
+            ],
+            Some(json!({ "participant_ids": everyone }).to_string()),
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not return 400 when a group DM was too large"
+    );
+}
@@ -0,0 +1,199 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::pagination::Page,
+    handlers::dm::{self, DmThreadResponse},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_get_dm_threads_ordered_by_activity() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(alice.id()).unwrap();
+    let mut thread_ids = Vec::new();
+    for i in 1..=3 {
+        let other = app
+            .database
+            .insert_user(
+                &format!("testuser{}@email.com", i + 1),
+                &format!("test.user{}", i + 1),
+                true,
+            )
+            .await;
+        let response = app
+            .client
+            .request(
+                Path::POST(dm::BASE_PATH),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                Some(json!({"recipient_id": other.id()}).to_string()),
+            )
+            .await;
+        let res = response
+            .json::<DmThreadResponse>()
+            .await
+            .expect("failed to unmarshal json into DmThreadResponse");
+        thread_ids.push(res.thread.id());
+    }
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&format!(
+                "{}/{}{}",
+                dm::BASE_PATH,
+                thread_ids[0],
+                dm::MESSAGES_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            Some(json!({"content": "bump"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when sending a DM"
+    );
+
+    let mut ids = Vec::new();
+    let mut cursor: Option<Uuid> = None;
+    loop {
+        let query = match cursor {
+            Some(c) => format!("?limit=2&cursor={}", c),
+            None => String::from("?limit=2"),
+        };
+        let response = app
+            .client
+            .request(
+                Path::GET(&format!("{}{}", dm::BASE_PATH, query)),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(200, response.status(), "The API did not return 200");
+        let page = response
+            .json::<Page<DmThreadResponse>>()
+            .await
+            .expect("failed to unmarshal json into Page<DmThreadResponse>");
+        ids.extend(page.items().iter().map(|t| t.thread.id()));
+        cursor = page.next_cursor();
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(
+        vec![thread_ids[0], thread_ids[2], thread_ids[1]],
+        ids,
+        "The threads were not ordered by last activity"
+    );
+
+    let stale_cursors = [
+        (
+            format!("{}?cursor={}", dm::BASE_PATH, Uuid::new_v4()),
+            "listing threads",
+        ),
+        (
+            format!(
+                "{}/{}{}?before={}",
+                dm::BASE_PATH,
+                thread_ids[0],
+                dm::MESSAGES_PATH,
+                Uuid::new_v4()
+            ),
+            "listing messages",
+        ),
+    ];
+    for (path, case) in stale_cursors {
+        let response = app
+            .client
+            .request(
+                Path::GET(&path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            400,
+            response.status(),
+            "The API did not reject a stale cursor when {}",
+            case
+        );
+    }
+}
+
+#[actix::test]
+async fn test_get_dm_thread_by_id_hidden_from_outsiders() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let eve = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let response = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(alice.id()).unwrap()),
+            ],
+            Some(json!({"recipient_id": bob.id()}).to_string()),
+        )
+        .await;
+    let thread = response
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse");
+
+    for (user_id, status, case) in [
+        (bob.id(), 200, "a participant reads the thread"),
+        (eve.id(), 404, "an outsider reads the thread"),
+    ] {
+        let response = app
+            .client
+            .request(
+                Path::GET(&format!("{}/{}", dm::BASE_PATH, thread.thread.id())),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(user_id).unwrap()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status().as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+}
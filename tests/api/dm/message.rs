@@ -0,0 +1,339 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::dm::{DirectMessage, MAX_DM_TTL_SECONDS},
+    handlers::dm::{self, DmThreadResponse},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+#[actix::test]
+async fn test_direct_message_lifecycle() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let alice_token = generate_token(alice.id()).unwrap();
+    let bob_token = generate_token(bob.id()).unwrap();
+    let thread = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"recipient_id": bob.id()}).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+    let path = format!("{}/{}{}", dm::BASE_PATH, thread.id(), dm::MESSAGES_PATH);
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"content": "hi bob"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when sending a DM"
+    );
+    let msg = response
+        .json::<DirectMessage>()
+        .await
+        .expect("failed to unmarshal json into DirectMessage");
+    let msg_path = format!("{}/{}", path, msg.id());
+
+    let response = app
+        .client
+        .request(
+            Path::PATCH(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(bob_token.clone()),
+            ],
+            Some(json!({"content": "hijacked"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-author edited a DM"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::PATCH(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"content": "hi bob!"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when editing a DM"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(bob_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when listing DMs"
+    );
+    let messages = response
+        .json::<Vec<DirectMessage>>()
+        .await
+        .expect("failed to unmarshal json into Vec<DirectMessage>");
+    assert_eq!(1, messages.len(), "The thread did not contain the message");
+    assert_eq!(
+        "hi bob!",
+        messages[0].content().as_ref(),
+        "The edit was not stored"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::DELETE(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when deleting a DM"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(bob_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 for a deleted DM"
+    );
+}
+
+#[actix::test]
+async fn test_send_direct_message_blocked() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let thread = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(alice.id()).unwrap()),
+            ],
+            Some(json!({"recipient_id": bob.id()}).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+    app.database.insert_user_block(alice.id(), bob.id()).await;
+
+    for user_id in [alice.id(), bob.id()] {
+        let response = app
+            .client
+            .request(
+                Path::POST(&format!(
+                    "{}/{}{}",
+                    dm::BASE_PATH,
+                    thread.id(),
+                    dm::MESSAGES_PATH
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(user_id).unwrap()),
+                ],
+                Some(json!({"content": "hello?"}).to_string()),
+            )
+            .await;
+        assert_eq!(
+            403,
+            response.status(),
+            "The API did not return 403 when sending a DM across a block"
+        );
+    }
+}
+
+#[actix::test]
+async fn test_ephemeral_direct_message_expires() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let alice_token = generate_token(alice.id()).unwrap();
+    let bob_token = generate_token(bob.id()).unwrap();
+    let thread = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"recipient_id": bob.id()}).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+    let path = format!("{}/{}{}", dm::BASE_PATH, thread.id(), dm::MESSAGES_PATH);
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"content": "too long", "ttl_seconds": MAX_DM_TTL_SECONDS + 1}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not return 400 for a TTL beyond the maximum"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"content": "this will self destruct", "ttl_seconds": 60}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when sending an ephemeral DM"
+    );
+    let msg = response
+        .json::<DirectMessage>()
+        .await
+        .expect("failed to unmarshal json into DirectMessage");
+    assert!(msg.expires_at().is_some(), "The DM was given no expiry");
+    let msg_path = format!("{}/{}", path, msg.id());
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(bob_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 for a DM before it expired"
+    );
+
+    app.database
+        .run_message_schedule(Utc::now() + Duration::minutes(2))
+        .await;
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(bob_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 for an expired DM"
+    );
+    let messages = app
+        .client
+        .request(
+            Path::GET(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(bob_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Vec<DirectMessage>>()
+        .await
+        .expect("failed to unmarshal json into Vec<DirectMessage>");
+    assert!(messages.is_empty(), "The expired DM was still listed");
+}
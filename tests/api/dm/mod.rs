@@ -0,0 +1,4 @@
+mod create;
+mod get;
+mod message;
+mod participant;
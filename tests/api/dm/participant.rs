@@ -0,0 +1,190 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    handlers::dm::{self, DmThreadResponse},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+#[actix::test]
+async fn test_group_dm_participants() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let newcomer = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let blocker = app
+        .database
+        .insert_user("testuser4@email.com", "test.user4", true)
+        .await;
+    let foe = app
+        .database
+        .insert_user("testuser5@email.com", "test.user5", true)
+        .await;
+    app.database
+        .insert_user_block(blocker.id(), owner.id())
+        .await;
+    app.database.insert_user_block(member.id(), foe.id()).await;
+
+    let thread = app
+        .client
+        .request(
+            Path::POST(&format!("{}{}", dm::BASE_PATH, dm::GROUPS_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(owner.id()).unwrap()),
+            ],
+            Some(json!({"participant_ids": [member.id()]}).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+    let path = |user_id| {
+        format!(
+            "{}/{}{}/{}",
+            dm::BASE_PATH,
+            thread.id(),
+            dm::PARTICIPANTS_PATH,
+            user_id
+        )
+    };
+
+    let test_cases = [
+        (
+            Path::PUT(path(newcomer.id())),
+            member.id(),
+            403,
+            "a non-owner adds someone",
+        ),
+        (
+            Path::PUT(path(blocker.id())),
+            owner.id(),
+            403,
+            "the owner adds someone who blocked them",
+        ),
+        (
+            Path::PUT(path(foe.id())),
+            owner.id(),
+            403,
+            "the owner adds someone a participant blocked",
+        ),
+        (
+            Path::PUT(path(newcomer.id())),
+            owner.id(),
+            200,
+            "the owner adds someone",
+        ),
+        (
+            Path::DELETE(path(newcomer.id())),
+            member.id(),
+            403,
+            "a non-owner removes someone else",
+        ),
+        (
+            Path::DELETE(path(member.id())),
+            member.id(),
+            200,
+            "a participant leaves",
+        ),
+        (
+            Path::DELETE(path(owner.id())),
+            owner.id(),
+            200,
+            "the owner leaves",
+        ),
+    ];
+
+    for (request_path, user_id, status, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                request_path,
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(user_id).unwrap()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status().as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&format!("{}/{}", dm::BASE_PATH, thread.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(newcomer.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    let res = response
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse");
+    assert_eq!(
+        vec![newcomer.id()],
+        res.participant_ids,
+        "The remaining participants did not match"
+    );
+    assert_eq!(
+        Some(newcomer.id()),
+        res.thread.owner_id(),
+        "Ownership did not pass to the remaining participant"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::DELETE(path(newcomer.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(newcomer.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when the last participant left"
+    );
+    let emptied = app.database.get_dm_thread(thread.id()).await;
+    assert!(
+        emptied.deleted_at().is_some(),
+        "The emptied group DM was not soft deleted"
+    );
+    assert_eq!(
+        Some(newcomer.id()),
+        emptied.owner_id(),
+        "The emptied group DM did not keep its last owner"
+    );
+    assert!(
+        app.database
+            .get_dm_participant_ids(thread.id())
+            .await
+            .is_empty(),
+        "The emptied group DM still has participants"
+    );
+}
@@ -0,0 +1,139 @@
+use crate::utils::{
+    app::TestApp,
+    gateway::GatewayClient,
+    http_client::{ContentType, Header, Path},
+};
+use awc::ws::CloseCode;
+use muttr_server::{
+    domain::channel::ChannelKind,
+    gateway::{GatewayEvent, ServerFrame},
+    handlers::{channel, message},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_gateway_heartbeat() {
+    let app = TestApp::spawn().await;
+
+    let mut client = GatewayClient::connect(&app).await;
+    client.send(json!({"op": "heartbeat"})).await;
+    assert_eq!(
+        ServerFrame::HeartbeatAck,
+        client.next_frame().await,
+        "The gateway did not acknowledge a heartbeat"
+    );
+}
+
+#[actix::test]
+async fn test_gateway_rejects_invalid_frames() {
+    let app = TestApp::spawn().await;
+
+    let test_cases = [
+        (
+            json!({"op": "identify", "token": "not.a.jwt"}),
+            CloseCode::Policy,
+            "the token is invalid",
+        ),
+        (
+            json!({"op": "subscribe"}),
+            CloseCode::Invalid,
+            "the op is unknown",
+        ),
+    ];
+
+    for (frame, code, case) in test_cases {
+        let mut client = GatewayClient::connect(&app).await;
+        client.send(frame).await;
+        let reason = client.expect_close().await;
+        assert_eq!(
+            Some(code),
+            reason.map(|r| r.code),
+            "The gateway did not close with {:?} when {}",
+            code,
+            case
+        );
+    }
+}
+
+async fn send_message(app: &TestApp, path: &str, token: &str, content: &str) {
+    let response = app
+        .client
+        .request(
+            Path::POST(path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({ "content": content }).to_string()),
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+}
+
+#[actix::test]
+async fn test_gateway_resume_replays_missed_events() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let path = format!(
+        "{}/{}{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::BASE_PATH
+    );
+
+    let (mut client, session_id) = GatewayClient::identify(&app, &token).await;
+    send_message(&app, &path, &token, "first").await;
+    let (seq, _) = client.next_event().await;
+    assert_eq!(1, seq, "The first event was not numbered 1");
+    client.close().await;
+
+    send_message(&app, &path, &token, "second").await;
+    send_message(&app, &path, &token, "third").await;
+
+    let mut client = GatewayClient::connect(&app).await;
+    client
+        .send(json!({"op": "resume", "token": token, "session_id": session_id, "seq": seq}))
+        .await;
+    let mut replayed = Vec::new();
+    for _ in 0..2 {
+        match client.next_event().await {
+            (s, GatewayEvent::MessageCreate(msg)) => replayed.push((s, msg.content().to_string())),
+            (_, event) => panic!("Unexpected event {:?}", event),
+        }
+    }
+    assert_eq!(
+        vec![(2, String::from("second")), (3, String::from("third"))],
+        replayed,
+        "The missed events were not replayed in order"
+    );
+    assert_eq!(
+        ServerFrame::Resumed {
+            session_id,
+            replayed: 2
+        },
+        client.next_frame().await,
+        "The gateway did not confirm the resume"
+    );
+
+    let mut client = GatewayClient::connect(&app).await;
+    client
+        .send(json!({"op": "resume", "token": token, "session_id": Uuid::new_v4(), "seq": 0}))
+        .await;
+    assert_eq!(
+        ServerFrame::InvalidSession,
+        client.next_frame().await,
+        "The gateway resumed an unknown session"
+    );
+}
@@ -0,0 +1,333 @@
+use crate::utils::{
+    app::TestApp,
+    gateway::GatewayClient,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::Utc;
+use muttr_server::{
+    domain::{
+        channel::{ChannelKind, OverwriteTarget},
+        permission::Permissions,
+        thread::Thread,
+    },
+    gateway::GatewayEvent,
+    handlers::{channel, dm, message, server, thread},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+#[actix::test]
+async fn test_gateway_delivers_only_visible_channel_messages() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let staff = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "staff")
+        .await;
+
+    let (mut client, _) =
+        GatewayClient::identify(&app, &generate_token(member.id()).unwrap()).await;
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(&format!(
+                "{}/{}{}/{}{}/{}",
+                server::BASE_PATH,
+                srv.id(),
+                channel::BASE_PATH,
+                staff.id(),
+                channel::PERMISSIONS_PATH,
+                srv.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(
+                json!({
+                    "target_type": OverwriteTarget::Role,
+                    "allow": Permissions::empty(),
+                    "deny": Permissions::VIEW_CHANNEL,
+                })
+                .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+
+    for (channel_id, content) in [(staff.id(), "secret"), (general.id(), "hello")] {
+        let response = app
+            .client
+            .request(
+                Path::POST(&format!(
+                    "{}/{}{}",
+                    channel::BASE_PATH,
+                    channel_id,
+                    message::BASE_PATH
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(owner_token.clone()),
+                ],
+                Some(json!({ "content": content }).to_string()),
+            )
+            .await;
+        assert_eq!(200, response.status(), "The API did not return 200");
+    }
+
+    match client.next_event().await {
+        (1, GatewayEvent::MessageCreate(msg)) => {
+            assert_eq!(
+                general.id(),
+                msg.channel_id(),
+                "The wrong channel was delivered"
+            );
+            assert_eq!(
+                "hello",
+                msg.content().as_ref(),
+                "The wrong message was delivered"
+            );
+        }
+        other => panic!("Expected MESSAGE_CREATE, got {:?}", other),
+    }
+}
+
+#[actix::test]
+async fn test_gateway_delivers_member_join_and_direct_messages() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let joiner = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let joiner_token = generate_token(joiner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+
+    let (mut client, _) = GatewayClient::identify(&app, &generate_token(owner.id()).unwrap()).await;
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&format!(
+                "{}/{}{}",
+                server::BASE_PATH,
+                srv.id(),
+                server::MEMBERS_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(joiner_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when joining"
+    );
+    assert_eq!(
+        (
+            1,
+            GatewayEvent::MemberJoin {
+                server_id: srv.id(),
+                user_id: joiner.id()
+            }
+        ),
+        client.next_event().await,
+        "The owner was not told about the new member"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(joiner_token.clone()),
+            ],
+            Some(json!({"recipient_id": owner.id()}).to_string()),
+        )
+        .await;
+    let thread_id = match client.next_event().await {
+        (2, GatewayEvent::DmThreadCreate { thread, .. }) => thread.id(),
+        other => panic!("Expected DM_THREAD_CREATE, got {:?}", other),
+    };
+    assert_eq!(200, response.status(), "The API did not return 200");
+
+    app.client
+        .request(
+            Path::POST(&format!(
+                "{}/{}{}",
+                dm::BASE_PATH,
+                thread_id,
+                dm::MESSAGES_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(joiner_token.clone()),
+            ],
+            Some(json!({"content": "hi"}).to_string()),
+        )
+        .await;
+    match client.next_event().await {
+        (3, GatewayEvent::DirectMessageCreate(msg)) => {
+            assert_eq!(thread_id, msg.thread_id(), "The wrong thread was delivered")
+        }
+        other => panic!("Expected DIRECT_MESSAGE_CREATE, got {:?}", other),
+    }
+}
+
+#[actix::test]
+async fn test_gateway_stops_thread_messages_when_parent_is_hidden() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let staff = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "staff")
+        .await;
+    let msg = app
+        .database
+        .insert_message(staff.id(), owner.id(), "let's plan", Utc::now())
+        .await;
+
+    let started = app
+        .client
+        .request(
+            Path::POST(&format!(
+                "{}/{}{}/{}{}",
+                channel::BASE_PATH,
+                staff.id(),
+                message::BASE_PATH,
+                msg.id(),
+                thread::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(json!({ "name": "plans" }).to_string()),
+        )
+        .await
+        .json::<Thread>()
+        .await
+        .expect("failed to unmarshal json into Thread");
+    let response = app
+        .client
+        .request(
+            Path::PUT(&format!(
+                "{}/{}{}/@me",
+                thread::BASE_PATH,
+                started.id(),
+                thread::MEMBERS_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(member_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(204, response.status(), "The API did not join the thread");
+
+    let (mut client, _) = GatewayClient::identify(&app, &member_token).await;
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(&format!(
+                "{}/{}{}/{}{}/{}",
+                server::BASE_PATH,
+                srv.id(),
+                channel::BASE_PATH,
+                staff.id(),
+                channel::PERMISSIONS_PATH,
+                srv.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(
+                json!({
+                    "target_type": OverwriteTarget::Role,
+                    "allow": Permissions::empty(),
+                    "deny": Permissions::VIEW_CHANNEL,
+                })
+                .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+
+    for (channel_id, content) in [(started.id(), "secret"), (general.id(), "hello")] {
+        let response = app
+            .client
+            .request(
+                Path::POST(&format!(
+                    "{}/{}{}",
+                    channel::BASE_PATH,
+                    channel_id,
+                    message::BASE_PATH
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(owner_token.clone()),
+                ],
+                Some(json!({ "content": content }).to_string()),
+            )
+            .await;
+        assert_eq!(200, response.status(), "The API did not return 200");
+    }
+
+    match client.next_event().await {
+        (1, GatewayEvent::MessageCreate(msg)) => {
+            assert_eq!(
+                general.id(),
+                msg.channel_id(),
+                "A message in a thread of a hidden channel was delivered"
+            );
+        }
+        other => panic!("Expected MESSAGE_CREATE, got {:?}", other),
+    }
+}
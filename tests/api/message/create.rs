@@ -0,0 +1,232 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::Utc;
+use muttr_server::{
+    domain::{channel::ChannelKind, message::Message},
+    handlers::{channel, message},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_create_message_success() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let path = format!(
+        "{}/{}{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::BASE_PATH
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(member.id()).unwrap()),
+            ],
+            Some(json!({"content": "hello world"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when a member sent a message"
+    );
+    let msg = response
+        .json::<Message>()
+        .await
+        .expect("failed to unmarshal json into Message");
+    assert_eq!(member.id(), msg.author_id(), "The author did not match");
+    assert_eq!(general.id(), msg.channel_id(), "The channel did not match");
+
+    let stored = app
+        .database
+        .get_message_by_id(msg.id())
+        .await
+        .expect("Failed to get message from database");
+    assert_eq!(msg, stored, "The stored message did not match the response");
+    let revisions = app.database.get_message_revisions(msg.id()).await;
+    assert_eq!(1, revisions.len(), "The initial revision was not recorded");
+}
+
+#[actix::test]
+async fn test_create_message_failure() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let lounge = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Voice, "Lounge")
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+
+    let test_cases = [
+        (
+            general.id(),
+            json!({"content": "   "}),
+            owner_token.clone(),
+            400,
+            "content is blank",
+        ),
+        (
+            general.id(),
+            json!({"content": "a".repeat(2001)}),
+            owner_token.clone(),
+            400,
+            "content is longer than 2000 characters",
+        ),
+        (
+            lounge.id(),
+            json!({"content": "hello"}),
+            owner_token.clone(),
+            400,
+            "channel is a voice channel",
+        ),
+        (
+            general.id(),
+            json!({"content": "hello"}),
+            generate_token(outsider.id()).unwrap(),
+            404,
+            "user is not a member of the server",
+        ),
+    ];
+
+    for (channel_id, body, token, status, case) in test_cases {
+        let path = format!(
+            "{}/{}{}",
+            channel::BASE_PATH,
+            channel_id,
+            message::BASE_PATH
+        );
+        let response = app
+            .client
+            .request(
+                Path::POST(&path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token),
+                ],
+                Some(body.to_string()),
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status().as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+}
+
+#[actix::test]
+async fn test_create_reply() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let random = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "random")
+        .await;
+    let original = app
+        .database
+        .insert_message(general.id(), owner.id(), "original", Utc::now())
+        .await;
+    let elsewhere = app
+        .database
+        .insert_message(random.id(), owner.id(), "elsewhere", Utc::now())
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+
+    let response = send_reply(&app, general.id(), original.id(), &token).await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+    let reply = response
+        .json::<Message>()
+        .await
+        .expect("failed to unmarshal json into Message");
+    assert_eq!(Some(original.id()), reply.reply_to_id());
+    let stored = app
+        .database
+        .get_message_by_id(reply.id())
+        .await
+        .expect("Failed to get message from database");
+    assert_eq!(Some(original.id()), stored.reply_to_id());
+
+    for (reply_to_id, case) in [
+        (elsewhere.id(), "the message is in another channel"),
+        (Uuid::new_v4(), "the message does not exist"),
+    ] {
+        let response = send_reply(&app, general.id(), reply_to_id, &token).await;
+        assert_eq!(
+            404,
+            response.status(),
+            "The API did not return 404 when {}",
+            case
+        );
+    }
+}
+
+async fn send_reply(
+    app: &TestApp,
+    channel_id: Uuid,
+    reply_to_id: Uuid,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({"content": "a reply", "reply_to_id": reply_to_id}).to_string()),
+        )
+        .await
+}
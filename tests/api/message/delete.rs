@@ -0,0 +1,106 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::Utc;
+use muttr_server::{
+    domain::{channel::ChannelKind, permission::Permissions},
+    handlers::{channel, message},
+    utils::jwt::generate_token,
+};
+
+#[actix::test]
+async fn test_soft_delete_message() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let author = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let bystander = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let moderator = app
+        .database
+        .insert_user("testuser4@email.com", "test.user4", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    for user_id in [author.id(), bystander.id(), moderator.id()] {
+        app.database.insert_server_member(srv.id(), user_id).await;
+    }
+    let role = app
+        .database
+        .insert_role(srv.id(), "Moderator", Permissions::MANAGE_MESSAGES)
+        .await;
+    app.database
+        .insert_member_role(role.id(), moderator.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let first = app
+        .database
+        .insert_message(general.id(), author.id(), "first", Utc::now())
+        .await;
+    let second = app
+        .database
+        .insert_message(general.id(), author.id(), "second", Utc::now())
+        .await;
+
+    let test_cases = [
+        (first.id(), bystander.id(), 403, "a bystander deletes"),
+        (first.id(), author.id(), 200, "the author deletes"),
+        (
+            first.id(),
+            author.id(),
+            404,
+            "the message is already deleted",
+        ),
+        (second.id(), moderator.id(), 200, "a moderator deletes"),
+    ];
+
+    for (message_id, user_id, status, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::DELETE(&format!(
+                    "{}/{}{}/{}",
+                    channel::BASE_PATH,
+                    general.id(),
+                    message::BASE_PATH,
+                    message_id
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(user_id).unwrap()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status().as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+
+    for id in [first.id(), second.id()] {
+        let stored = app
+            .database
+            .get_message_by_id(id)
+            .await
+            .expect("Failed to get message from database");
+        assert!(
+            stored.deleted_at().is_some(),
+            "The message was not soft deleted"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::{channel::ChannelKind, message::Message},
+    handlers::{channel, message},
+    utils::jwt::generate_token,
+};
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_get_messages_pagination() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let start = Utc::now() - Duration::minutes(10);
+    let mut messages = Vec::new();
+    for i in 0..5 {
+        let msg = app
+            .database
+            .insert_message(
+                general.id(),
+                owner.id(),
+                &format!("message {}", i),
+                start + Duration::seconds(i),
+            )
+            .await;
+        messages.push(msg.id());
+    }
+    let path = format!(
+        "{}/{}{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::BASE_PATH
+    );
+    let token = generate_token(owner.id()).unwrap();
+
+    let test_cases = [
+        (String::new(), vec![4, 3, 2, 1, 0], "no cursor is given"),
+        (
+            String::from("?limit=2"),
+            vec![4, 3],
+            "only a limit is given",
+        ),
+        (
+            format!("?before={}&limit=2", messages[3]),
+            vec![2, 1],
+            "a before cursor is given",
+        ),
+        (
+            format!("?after={}&limit=2", messages[1]),
+            vec![3, 2],
+            "an after cursor is given",
+        ),
+        (
+            format!("?around={}&limit=3", messages[2]),
+            vec![3, 2, 1],
+            "an around cursor is given",
+        ),
+    ];
+
+    for (query, expected, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::GET(&format!("{}{}", path, query)),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            200,
+            response.status(),
+            "The API did not return 200 when {}",
+            case
+        );
+        let ids = response
+            .json::<Vec<Message>>()
+            .await
+            .expect("failed to unmarshal json into Vec<Message>")
+            .iter()
+            .map(|m| m.id())
+            .collect::<Vec<Uuid>>();
+        let expected = expected.iter().map(|&i| messages[i]).collect::<Vec<Uuid>>();
+        assert_eq!(
+            expected, ids,
+            "The returned page did not match when {}",
+            case
+        );
+    }
+
+    let response = app
+        .client
+        .request(
+            Path::GET(&format!(
+                "{}?before={}&after={}",
+                path, messages[3], messages[1]
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not return 400 when multiple cursors were given"
+    );
+
+    let random = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "random")
+        .await;
+    let elsewhere = app
+        .database
+        .insert_message(random.id(), owner.id(), "elsewhere", start)
+        .await;
+    let stale_cursors = [
+        (format!("?before={}", Uuid::new_v4()), "matches no message"),
+        (
+            format!("?around={}", elsewhere.id()),
+            "is a message in another channel",
+        ),
+    ];
+    for (query, case) in stale_cursors {
+        let response = app
+            .client
+            .request(
+                Path::GET(&format!("{}{}", path, query)),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            400,
+            response.status(),
+            "The API did not return 400 when the cursor {}",
+            case
+        );
+    }
+}
+
+#[actix::test]
+async fn test_get_message_by_id() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let random = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "random")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "hello", Utc::now())
+        .await;
+
+    let test_cases = [
+        (general.id(), owner.id(), 200, "the owner reads the message"),
+        (
+            random.id(),
+            owner.id(),
+            404,
+            "the message belongs to another channel",
+        ),
+        (
+            general.id(),
+            outsider.id(),
+            404,
+            "the user is not a member of the server",
+        ),
+    ];
+
+    for (channel_id, user_id, status, case) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::GET(&format!(
+                    "{}/{}{}/{}",
+                    channel::BASE_PATH,
+                    channel_id,
+                    message::BASE_PATH,
+                    msg.id()
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(generate_token(user_id).unwrap()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            status,
+            response.status().as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+        if status == 200 {
+            let res = response
+                .json::<Message>()
+                .await
+                .expect("failed to unmarshal json into Message");
+            assert_eq!(msg, res, "The returned message did not match");
+        }
+    }
+}
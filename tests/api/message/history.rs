@@ -0,0 +1,255 @@
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::{
+        channel::ChannelKind,
+        message::{Message, MessageHistory},
+        permission::Permissions,
+    },
+    handlers::{channel, message},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn get_history(
+    app: &TestApp,
+    channel_id: Uuid,
+    message_id: Uuid,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::BASE_PATH,
+                message_id,
+                message::HISTORY_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+}
+
+#[actix::test]
+async fn test_message_history_and_purge() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let author = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let moderator = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let author_token = generate_token(author.id()).unwrap();
+    let moderator_token = generate_token(moderator.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    for user_id in [author.id(), moderator.id()] {
+        app.database.insert_server_member(srv.id(), user_id).await;
+    }
+    let role = app
+        .database
+        .insert_role(srv.id(), "Moderator", Permissions::MANAGE_MESSAGES)
+        .await;
+    app.database
+        .insert_member_role(role.id(), moderator.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let older = app
+        .database
+        .insert_message(
+            general.id(),
+            author.id(),
+            "older",
+            Utc::now() - Duration::minutes(1),
+        )
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), author.id(), "helo", Utc::now())
+        .await;
+    let msg_path = format!(
+        "{}/{}{}/{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::BASE_PATH,
+        msg.id()
+    );
+    let response = app
+        .client
+        .request(
+            Path::PATCH(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(author_token.clone()),
+            ],
+            Some(json!({"content": "hello"}).to_string()),
+        )
+        .await;
+    assert_eq!(200, response.status());
+
+    let response = get_history(&app, general.id(), msg.id(), &author_token).await;
+    assert_eq!(
+        403,
+        response.status(),
+        "A member without MANAGE_MESSAGES saw the history"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::DELETE(&msg_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(moderator_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(200, response.status());
+
+    let response = get_history(&app, general.id(), msg.id(), &moderator_token).await;
+    assert_eq!(
+        200,
+        response.status(),
+        "A moderator could not see the history of a deleted message"
+    );
+    let history = response
+        .json::<MessageHistory>()
+        .await
+        .expect("failed to unmarshal json into MessageHistory");
+    let contents: Vec<String> = history
+        .revisions()
+        .iter()
+        .map(|r| r.content().to_string())
+        .collect();
+    assert_eq!(vec!["helo", "hello"], contents);
+    assert!(history
+        .revisions()
+        .iter()
+        .all(|r| r.editor_id() == author.id()));
+    assert_eq!(Some(moderator.id()), history.deleted_by());
+    assert!(history.deleted_at().is_some());
+
+    assert_eq!(
+        0,
+        app.database
+            .purge_deleted_messages(Utc::now() - Duration::days(1))
+            .await,
+        "A message within retention was purged"
+    );
+    assert_eq!(
+        1,
+        app.database
+            .purge_deleted_messages(Utc::now() + Duration::seconds(1))
+            .await
+    );
+    let response = get_history(&app, general.id(), msg.id(), &moderator_token).await;
+    assert_eq!(404, response.status(), "Purged content was still visible");
+    assert!(app
+        .database
+        .get_message_revisions(msg.id())
+        .await
+        .is_empty());
+
+    // The tombstone keeps its place for cursors.
+    let page = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}?before={}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH,
+                msg.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(author_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Vec<Message>>()
+        .await
+        .expect("failed to unmarshal json into Vec<Message>");
+    assert_eq!(
+        vec![older.id()],
+        page.iter().map(|m| m.id()).collect::<Vec<Uuid>>()
+    );
+}
+
+#[actix::test]
+async fn test_purge_keeps_history_of_live_messages() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "one", Utc::now())
+        .await;
+    for content in ["two", "three"] {
+        let response = app
+            .client
+            .request(
+                Path::PATCH(format!(
+                    "{}/{}{}/{}",
+                    channel::BASE_PATH,
+                    general.id(),
+                    message::BASE_PATH,
+                    msg.id()
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(token.clone()),
+                ],
+                Some(json!({ "content": content }).to_string()),
+            )
+            .await;
+        assert_eq!(200, response.status());
+    }
+
+    assert_eq!(
+        0,
+        app.database
+            .purge_deleted_messages(Utc::now() + Duration::seconds(1))
+            .await
+    );
+    let revisions = app.database.get_message_revisions(msg.id()).await;
+    assert_eq!(
+        vec!["one", "two", "three"],
+        revisions
+            .iter()
+            .map(|r| r.content().as_ref().to_string())
+            .collect::<Vec<String>>(),
+        "The purge removed the history of a live message"
+    );
+}
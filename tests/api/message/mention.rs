@@ -0,0 +1,359 @@
+use chrono::Utc;
+use muttr_server::{
+    domain::{
+        channel::ChannelKind,
+        message::Message,
+        notification::{MentionKind, Notification},
+        pagination::Page,
+        permission::Permissions,
+        read_state::UnreadState,
+    },
+    handlers::{
+        channel, message,
+        user::{BASE_PATH, MENTIONS_PATH, ME_PATH, MUTES_PATH, UNREAD_PATH},
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn send_message(app: &TestApp, channel_id: Uuid, content: &str, token: &str) -> Message {
+    let response = app
+        .client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({ "content": content }).to_string()),
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+    response
+        .json::<Message>()
+        .await
+        .expect("Failed to parse message")
+}
+
+async fn get_mentions(app: &TestApp, token: &str) -> Vec<Notification> {
+    app.client
+        .request(
+            Path::GET(format!("{}{}{}", BASE_PATH, ME_PATH, MENTIONS_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Page<Notification>>()
+        .await
+        .expect("Failed to parse mentions")
+        .into_items()
+}
+
+async fn mention_count(app: &TestApp, channel_id: Uuid, token: &str) -> i32 {
+    app.client
+        .request(
+            Path::GET(format!("{}{}{}", BASE_PATH, ME_PATH, UNREAD_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Vec<UnreadState>>()
+        .await
+        .expect("Failed to parse unread response")
+        .iter()
+        .find(|u| u.channel_id() == Some(channel_id))
+        .map(|u| u.mention_count())
+        .unwrap_or_default()
+}
+
+#[actix::test]
+async fn test_user_and_channel_mentions_are_resolved() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("owner@email.com", "owner", true)
+        .await;
+    let ana = app.database.insert_user("ana@email.com", "ana", true).await;
+    let outsider = app
+        .database
+        .insert_user("carol@email.com", "carol", true)
+        .await;
+    let ana_token = generate_token(ana.id()).unwrap();
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database.insert_server_member(srv.id(), ana.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let random = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "Random")
+        .await;
+
+    let message = send_message(
+        &app,
+        general.id(),
+        "hey @ana, @carol and @nobody, see #random. and #missing",
+        &owner_token,
+    )
+    .await;
+    assert_eq!(&[ana.id()], message.mentions().mentions());
+    assert_eq!(&[random.id()], message.mentions().mention_channels());
+    assert!(!message.mentions().mention_everyone());
+
+    let stored = app
+        .database
+        .get_message_by_id(message.id())
+        .await
+        .expect("Failed to get message");
+    assert_eq!(message.mentions(), stored.mentions());
+
+    let mentions = get_mentions(&app, &ana_token).await;
+    assert_eq!(1, mentions.len(), "Ana was not notified");
+    assert_eq!(message.id(), mentions[0].message_id());
+    assert_eq!(MentionKind::User, mentions[0].kind());
+    assert_eq!(1, mention_count(&app, general.id(), &ana_token).await);
+    assert!(
+        get_mentions(&app, &generate_token(outsider.id()).unwrap())
+            .await
+            .is_empty(),
+        "A non-member was notified"
+    );
+
+    app.database
+        .insert_message(general.id(), owner.id(), "no mentions here", Utc::now())
+        .await;
+    send_message(&app, general.id(), "@ana again", &owner_token).await;
+    assert_eq!(2, mention_count(&app, general.id(), &ana_token).await);
+
+    let response = app
+        .client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}/{}{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH,
+                message.id(),
+                message::ACK_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(ana_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(204, response.status(), "The API did not return 204 on ack");
+    assert_eq!(0, mention_count(&app, general.id(), &ana_token).await);
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}{}{}?cursor={}",
+                BASE_PATH,
+                ME_PATH,
+                MENTIONS_PATH,
+                mentions[0].id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(outsider.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor from another user's mentions"
+    );
+}
+
+#[actix::test]
+async fn test_mass_mentions_require_permission() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("owner@email.com", "owner", true)
+        .await;
+    let ana = app.database.insert_user("ana@email.com", "ana", true).await;
+    let bob = app.database.insert_user("bob@email.com", "bob", true).await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let ana_token = generate_token(ana.id()).unwrap();
+    let bob_token = generate_token(bob.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database.insert_server_member(srv.id(), ana.id()).await;
+    app.database.insert_server_member(srv.id(), bob.id()).await;
+    let role = app
+        .database
+        .insert_role(srv.id(), "mods", Permissions::empty())
+        .await;
+    app.database.insert_member_role(role.id(), bob.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+
+    let content = format!("@everyone @here <@&{}>", role.id());
+    let message = send_message(&app, general.id(), &content, &ana_token).await;
+    assert!(
+        !message.mentions().mention_everyone(),
+        "A member without MENTION_EVERYONE mentioned everyone"
+    );
+    assert!(message.mentions().mention_roles().is_empty());
+    assert!(get_mentions(&app, &bob_token).await.is_empty());
+    assert!(get_mentions(&app, &owner_token).await.is_empty());
+
+    let message = send_message(&app, general.id(), &content, &owner_token).await;
+    assert!(message.mentions().mention_everyone());
+    assert_eq!(&[role.id()], message.mentions().mention_roles());
+    let bob_mentions = get_mentions(&app, &bob_token).await;
+    assert_eq!(1, bob_mentions.len());
+    assert_eq!(MentionKind::Role, bob_mentions[0].kind());
+    let ana_mentions = get_mentions(&app, &ana_token).await;
+    assert_eq!(1, ana_mentions.len());
+    assert_eq!(MentionKind::Everyone, ana_mentions[0].kind());
+    assert!(
+        get_mentions(&app, &owner_token).await.is_empty(),
+        "The author was notified of their own mention"
+    );
+}
+
+#[actix::test]
+async fn test_edits_refresh_mentions_without_notifying() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("owner@email.com", "owner", true)
+        .await;
+    let ana = app.database.insert_user("ana@email.com", "ana", true).await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database.insert_server_member(srv.id(), ana.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+
+    let message = send_message(&app, general.id(), "hello", &owner_token).await;
+    let edited = app
+        .client
+        .request(
+            Path::PATCH(format!(
+                "{}/{}{}/{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH,
+                message.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(json!({"content": "hello @ana"}).to_string()),
+        )
+        .await
+        .json::<Message>()
+        .await
+        .expect("Failed to parse edited message");
+
+    assert_eq!(&[ana.id()], edited.mentions().mentions());
+    assert!(
+        get_mentions(&app, &generate_token(ana.id()).unwrap())
+            .await
+            .is_empty(),
+        "An edit notified a newly mentioned user"
+    );
+}
+
+#[actix::test]
+async fn test_blocks_and_mutes_suppress_mentions() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("owner@email.com", "owner", true)
+        .await;
+    let ana = app.database.insert_user("ana@email.com", "ana", true).await;
+    let bob = app.database.insert_user("bob@email.com", "bob", true).await;
+    let carol = app
+        .database
+        .insert_user("carol@email.com", "carol", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let ana_token = generate_token(ana.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    for user_id in [ana.id(), bob.id(), carol.id()] {
+        app.database.insert_server_member(srv.id(), user_id).await;
+    }
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    app.database.insert_user_block(owner.id(), bob.id()).await;
+
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!(
+                "{}{}{}/{}",
+                BASE_PATH,
+                ME_PATH,
+                MUTES_PATH,
+                owner.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(ana_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(204, response.status(), "The API did not mute the user");
+
+    let message = send_message(&app, general.id(), "@ana @bob @carol", &owner_token).await;
+    assert_eq!(
+        &[ana.id(), carol.id()],
+        message.mentions().mentions(),
+        "A blocked user was mentioned"
+    );
+    assert!(
+        get_mentions(&app, &ana_token).await.is_empty(),
+        "A user was notified by someone they muted"
+    );
+    assert!(
+        get_mentions(&app, &generate_token(bob.id()).unwrap())
+            .await
+            .is_empty(),
+        "A blocked user was notified"
+    );
+    assert_eq!(
+        1,
+        get_mentions(&app, &generate_token(carol.id()).unwrap())
+            .await
+            .len()
+    );
+}
@@ -0,0 +1,9 @@
+mod create;
+mod delete;
+mod get;
+mod history;
+mod mention;
+mod pin;
+mod reaction;
+mod typing;
+mod update;
@@ -0,0 +1,297 @@
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::{
+        channel::ChannelKind,
+        message::{Message, MessageKind, PinnedMessage},
+    },
+    handlers::{channel, message, server},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn set_pinned(
+    app: &TestApp,
+    channel_id: Uuid,
+    message_id: Uuid,
+    pinned: bool,
+    token: &str,
+) -> reqwest::Response {
+    let path = format!(
+        "{}/{}{}/{}",
+        channel::BASE_PATH,
+        channel_id,
+        message::PINS_PATH,
+        message_id
+    );
+    app.client
+        .request(
+            if pinned {
+                Path::PUT(path)
+            } else {
+                Path::DELETE(path)
+            },
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+}
+
+async fn get_pins(app: &TestApp, channel_id: Uuid, token: &str) -> Vec<PinnedMessage> {
+    let response = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::PINS_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not return the pins");
+    response
+        .json::<Vec<PinnedMessage>>()
+        .await
+        .expect("failed to unmarshal json into Vec<PinnedMessage>")
+}
+
+async fn get_messages(app: &TestApp, channel_id: Uuid, token: &str) -> Vec<Message> {
+    app.client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Vec<Message>>()
+        .await
+        .expect("failed to unmarshal json into Vec<Message>")
+}
+
+async fn patch_max_pins(
+    app: &TestApp,
+    server_id: Uuid,
+    channel_id: Uuid,
+    max_pins: i32,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::PATCH(format!(
+                "{}/{}{}/{}",
+                server::BASE_PATH,
+                server_id,
+                channel::BASE_PATH,
+                channel_id
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({ "max_pins": max_pins }).to_string()),
+        )
+        .await
+}
+
+#[actix::test]
+async fn test_pin_lifecycle() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let first = app
+        .database
+        .insert_message(
+            general.id(),
+            member.id(),
+            "remember this",
+            Utc::now() - Duration::minutes(5),
+        )
+        .await;
+    let second = app
+        .database
+        .insert_message(
+            general.id(),
+            member.id(),
+            "and this",
+            Utc::now() - Duration::minutes(4),
+        )
+        .await;
+
+    let response = set_pinned(&app, general.id(), first.id(), true, &member_token).await;
+    assert_eq!(
+        403,
+        response.status(),
+        "A member without MANAGE_MESSAGES pinned a message"
+    );
+
+    for msg in [&second, &first] {
+        let response = set_pinned(&app, general.id(), msg.id(), true, &owner_token).await;
+        assert_eq!(204, response.status(), "The owner could not pin a message");
+    }
+    let response = set_pinned(&app, general.id(), first.id(), true, &owner_token).await;
+    assert_eq!(204, response.status(), "Pinning twice was not a no-op");
+
+    let pins = get_pins(&app, general.id(), &member_token).await;
+    let ids: Vec<Uuid> = pins.iter().map(|p| p.message().id()).collect();
+    assert_eq!(
+        vec![second.id(), first.id()],
+        ids,
+        "Pins were not in pin order"
+    );
+    assert!(pins.iter().all(|p| p.pinned_by() == owner.id()));
+
+    let system: Vec<Message> = get_messages(&app, general.id(), &member_token)
+        .await
+        .into_iter()
+        .filter(|m| m.kind() == MessageKind::MessagePinned)
+        .collect();
+    assert_eq!(2, system.len(), "A system message was not sent per pin");
+    assert!(system.iter().all(|m| m.author_id() == owner.id()));
+    assert!(system.iter().any(|m| m.reply_to_id() == Some(first.id())));
+
+    let response = app
+        .client
+        .request(
+            Path::PATCH(format!(
+                "{}/{}{}/{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH,
+                system[0].id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(json!({"content": "edited"}).to_string()),
+        )
+        .await;
+    assert_eq!(400, response.status(), "A system message was edited");
+
+    let response = set_pinned(&app, general.id(), second.id(), false, &owner_token).await;
+    assert_eq!(
+        204,
+        response.status(),
+        "The owner could not unpin a message"
+    );
+    let response = set_pinned(&app, general.id(), second.id(), false, &owner_token).await;
+    assert_eq!(404, response.status(), "Unpinning twice did not return 404");
+
+    let pins = get_pins(&app, general.id(), &member_token).await;
+    assert_eq!(1, pins.len());
+    assert_eq!(first.id(), pins[0].message().id());
+    assert!(get_messages(&app, general.id(), &member_token)
+        .await
+        .iter()
+        .any(|m| m.kind() == MessageKind::MessageUnpinned && m.reply_to_id() == Some(second.id())));
+}
+
+#[actix::test]
+async fn test_pin_limit() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let first = app
+        .database
+        .insert_message(general.id(), owner.id(), "first", Utc::now())
+        .await;
+    let second = app
+        .database
+        .insert_message(general.id(), owner.id(), "second", Utc::now())
+        .await;
+
+    assert_eq!(
+        400,
+        patch_max_pins(&app, srv.id(), general.id(), 0, &token)
+            .await
+            .status()
+    );
+    assert_eq!(
+        200,
+        patch_max_pins(&app, srv.id(), general.id(), 1, &token)
+            .await
+            .status()
+    );
+
+    let response = set_pinned(&app, general.id(), first.id(), true, &token).await;
+    assert_eq!(204, response.status());
+    let response = set_pinned(&app, general.id(), second.id(), true, &token).await;
+    assert_eq!(
+        400,
+        response.status(),
+        "A message was pinned past the channel's limit"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::DELETE(format!(
+                "{}/{}{}/{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH,
+                first.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(200, response.status());
+    let response = set_pinned(&app, general.id(), second.id(), true, &token).await;
+    assert_eq!(
+        204,
+        response.status(),
+        "Deleting a pinned message did not free its pin"
+    );
+}
@@ -0,0 +1,558 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::Utc;
+use muttr_server::{
+    domain::{
+        channel::ChannelKind,
+        dm::DirectMessage,
+        message::Message,
+        pagination::Page,
+        reaction::{ReactionCount, ReactionEmoji, ServerEmoji, MAX_REACTIONS_PER_MESSAGE},
+        user::GetUserResponse,
+    },
+    handlers::{
+        channel,
+        dm::{self, DmThreadResponse},
+        message::{self, MessageResponse},
+        server,
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn send(app: &TestApp, path: Path<String>, token: &str) -> reqwest::Response {
+    app.client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+}
+
+fn reactions_path(channel_id: Uuid, message_id: Uuid, emoji: &str) -> String {
+    format!(
+        "{}/{}{}/{}{}/{}",
+        channel::BASE_PATH,
+        channel_id,
+        message::BASE_PATH,
+        message_id,
+        message::REACTIONS_PATH,
+        emoji
+    )
+}
+
+async fn get_reactions(
+    app: &TestApp,
+    channel_id: Uuid,
+    message_id: Uuid,
+    token: &str,
+) -> Vec<ReactionCount> {
+    send(
+        app,
+        Path::GET(format!(
+            "{}/{}{}/{}",
+            channel::BASE_PATH,
+            channel_id,
+            message::BASE_PATH,
+            message_id
+        )),
+        token,
+    )
+    .await
+    .json::<MessageResponse<Message>>()
+    .await
+    .expect("Failed to parse message response")
+    .reactions
+}
+
+#[actix::test]
+async fn test_reaction_lifecycle() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "hello", Utc::now())
+        .await;
+
+    for (emoji, token) in [
+        ("👍", &member_token),
+        ("👍", &member_token),
+        ("❤", &member_token),
+        ("👍", &owner_token),
+        ("👩‍👩‍👧‍👦", &owner_token),
+    ] {
+        let response = send(
+            &app,
+            Path::PUT(reactions_path(general.id(), msg.id(), emoji)),
+            token,
+        )
+        .await;
+        assert_eq!(
+            204,
+            response.status(),
+            "The API did not return 204 when reacting with {}: {}",
+            emoji,
+            response.text().await.unwrap_or_default()
+        );
+    }
+
+    assert_eq!(
+        vec![
+            ReactionCount::new(ReactionEmoji::Unicode(String::from("👍")), 2, true),
+            ReactionCount::new(ReactionEmoji::Unicode(String::from("❤️")), 1, true),
+            ReactionCount::new(ReactionEmoji::Unicode(String::from("👩‍👩‍👧‍👦")), 1, false),
+        ],
+        get_reactions(&app, general.id(), msg.id(), &member_token).await,
+        "The message did not carry the expected reaction counts"
+    );
+
+    let users = send(
+        &app,
+        Path::GET(reactions_path(general.id(), msg.id(), "👍")),
+        &member_token,
+    )
+    .await
+    .json::<Page<GetUserResponse>>()
+    .await
+    .expect("Failed to parse reaction users");
+    let mut user_ids: Vec<Uuid> = users.items().iter().map(|u| u.id()).collect();
+    let mut expected = vec![owner.id(), member.id()];
+    user_ids.sort();
+    expected.sort();
+    assert_eq!(expected, user_ids, "The reaction users did not match");
+
+    let response = send(
+        &app,
+        Path::DELETE(reactions_path(general.id(), msg.id(), "👍")),
+        &member_token,
+    )
+    .await;
+    assert_eq!(204, response.status(), "The API did not return 204");
+    assert!(
+        get_reactions(&app, general.id(), msg.id(), &member_token)
+            .await
+            .contains(&ReactionCount::new(
+                ReactionEmoji::Unicode(String::from("👍")),
+                1,
+                false
+            )),
+        "The reaction was not removed"
+    );
+
+    let response = send(
+        &app,
+        Path::GET(format!(
+            "{}?cursor={}",
+            reactions_path(general.id(), msg.id(), "👍"),
+            Uuid::new_v4()
+        )),
+        &member_token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor that matches no reacting user"
+    );
+}
+
+#[actix::test]
+async fn test_removing_others_reactions_requires_manage_messages() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "hello", Utc::now())
+        .await;
+
+    for token in [&owner_token, &member_token] {
+        send(
+            &app,
+            Path::PUT(reactions_path(general.id(), msg.id(), "🎉")),
+            token,
+        )
+        .await;
+    }
+
+    let response = send(
+        &app,
+        Path::DELETE(format!(
+            "{}/{}",
+            reactions_path(general.id(), msg.id(), "🎉"),
+            owner.id()
+        )),
+        &member_token,
+    )
+    .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "A member removed someone else's reaction"
+    );
+
+    let response = send(
+        &app,
+        Path::DELETE(format!(
+            "{}/{}",
+            reactions_path(general.id(), msg.id(), "🎉"),
+            member.id()
+        )),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(204, response.status(), "The owner could not moderate");
+    assert_eq!(
+        1,
+        get_reactions(&app, general.id(), msg.id(), &owner_token).await[0].count(),
+        "The member's reaction was not removed"
+    );
+}
+
+#[actix::test]
+async fn test_invalid_reactions_are_rejected() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let other_srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "hello", Utc::now())
+        .await;
+
+    let other_emoji = app
+        .client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}",
+                server::BASE_PATH,
+                other_srv.id(),
+                server::EMOJIS_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(json!({"name": "party_parrot"}).to_string()),
+        )
+        .await
+        .json::<ServerEmoji>()
+        .await
+        .expect("Failed to parse server emoji");
+
+    let test_cases = [
+        (String::from("abc"), 400, "the emoji is not an emoji"),
+        (String::from("👍👍"), 400, "there are two emoji"),
+        (
+            other_emoji.id().to_string(),
+            400,
+            "the custom emoji belongs to another server",
+        ),
+        (
+            Uuid::new_v4().to_string(),
+            404,
+            "the custom emoji does not exist",
+        ),
+    ];
+
+    for (emoji, status, case) in test_cases {
+        let response = send(
+            &app,
+            Path::PUT(reactions_path(general.id(), msg.id(), &emoji)),
+            &owner_token,
+        )
+        .await;
+        assert_eq!(
+            status,
+            response.status(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+
+    for emoji in emojis::iter().take(MAX_REACTIONS_PER_MESSAGE as usize) {
+        let response = send(
+            &app,
+            Path::PUT(reactions_path(general.id(), msg.id(), emoji.as_str())),
+            &owner_token,
+        )
+        .await;
+        assert_eq!(204, response.status(), "Could not react with {}", emoji);
+    }
+    let response = send(
+        &app,
+        Path::PUT(reactions_path(general.id(), msg.id(), "🦀")),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not cap the number of distinct reactions"
+    );
+}
+
+#[actix::test]
+async fn test_custom_emoji_reactions() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "hello", Utc::now())
+        .await;
+    let emojis_path = format!("{}/{}{}", server::BASE_PATH, srv.id(), server::EMOJIS_PATH);
+
+    let create = |token: String, name: &'static str| {
+        let path = emojis_path.clone();
+        let app = &app;
+        async move {
+            app.client
+                .request(
+                    Path::POST(path),
+                    &[
+                        Header::ContentType(ContentType::Json),
+                        Header::Authorization(token),
+                    ],
+                    Some(json!({ "name": name }).to_string()),
+                )
+                .await
+        }
+    };
+
+    assert_eq!(
+        403,
+        create(member_token.clone(), "nope").await.status(),
+        "A member without MANAGE_EMOJIS created an emoji"
+    );
+    assert_eq!(
+        400,
+        create(owner_token.clone(), "not valid").await.status(),
+        "An invalid emoji name was accepted"
+    );
+    let response = create(owner_token.clone(), "party").await;
+    assert_eq!(200, response.status(), "The API did not return 200");
+    let emoji = response
+        .json::<ServerEmoji>()
+        .await
+        .expect("Failed to parse server emoji");
+    assert_eq!(
+        409,
+        create(owner_token.clone(), "PARTY").await.status(),
+        "A duplicate emoji name was accepted"
+    );
+
+    let listed = send(&app, Path::GET(emojis_path.clone()), &member_token)
+        .await
+        .json::<Vec<ServerEmoji>>()
+        .await
+        .expect("Failed to parse server emojis");
+    assert_eq!(vec![emoji.clone()], listed, "The emoji was not listed");
+
+    let response = send(
+        &app,
+        Path::PUT(reactions_path(
+            general.id(),
+            msg.id(),
+            &emoji.id().to_string(),
+        )),
+        &member_token,
+    )
+    .await;
+    assert_eq!(
+        204,
+        response.status(),
+        "Could not react with a custom emoji"
+    );
+    assert_eq!(
+        vec![ReactionCount::new(
+            ReactionEmoji::Custom(emoji.id()),
+            1,
+            false
+        )],
+        get_reactions(&app, general.id(), msg.id(), &owner_token).await
+    );
+
+    let response = send(
+        &app,
+        Path::DELETE(format!("{}/{}", emojis_path, emoji.id())),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(204, response.status(), "Could not delete the emoji");
+    let response = send(
+        &app,
+        Path::PUT(reactions_path(
+            general.id(),
+            msg.id(),
+            &emoji.id().to_string(),
+        )),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(404, response.status(), "A deleted emoji was still usable");
+}
+
+#[actix::test]
+async fn test_direct_message_reactions() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let alice_token = generate_token(alice.id()).unwrap();
+    let bob_token = generate_token(bob.id()).unwrap();
+    let thread = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"recipient_id": bob.id()}).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+    let messages_path = format!("{}/{}{}", dm::BASE_PATH, thread.id(), dm::MESSAGES_PATH);
+    let msg = app
+        .client
+        .request(
+            Path::POST(messages_path.clone()),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"content": "hi"}).to_string()),
+        )
+        .await
+        .json::<DirectMessage>()
+        .await
+        .expect("failed to unmarshal json into DirectMessage");
+    let path = |emoji: &str| {
+        format!(
+            "{}/{}{}/{}",
+            messages_path,
+            msg.id(),
+            message::REACTIONS_PATH,
+            emoji
+        )
+    };
+
+    let response = send(&app, Path::PUT(path("😂")), &bob_token).await;
+    assert_eq!(
+        204,
+        response.status(),
+        "Could not react to a direct message"
+    );
+    let response = send(
+        &app,
+        Path::PUT(path(&Uuid::new_v4().to_string())),
+        &bob_token,
+    )
+    .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "An unknown custom emoji was accepted in a DM"
+    );
+
+    let reactions = send(
+        &app,
+        Path::GET(format!("{}/{}", messages_path, msg.id())),
+        &alice_token,
+    )
+    .await
+    .json::<MessageResponse<serde_json::Value>>()
+    .await
+    .expect("Failed to parse direct message response")
+    .reactions;
+    assert_eq!(
+        vec![ReactionCount::new(
+            ReactionEmoji::Unicode(String::from("😂")),
+            1,
+            false
+        )],
+        reactions
+    );
+
+    let response = send(&app, Path::DELETE(path("😂")), &bob_token).await;
+    assert_eq!(204, response.status(), "Could not remove a DM reaction");
+}
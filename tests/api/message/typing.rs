@@ -0,0 +1,182 @@
+use crate::utils::{
+    app::TestApp,
+    gateway::GatewayClient,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::channel::ChannelKind,
+    gateway::{GatewayEvent, TYPING_TIMEOUT},
+    handlers::{
+        channel,
+        dm::{self, DmThreadResponse},
+        message,
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use std::time::Duration;
+
+#[actix::test]
+async fn test_typing_is_broadcast_rate_limited_and_expires() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let path = format!(
+        "{}/{}{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::TYPING_PATH
+    );
+
+    let (mut client, _) =
+        GatewayClient::identify(&app, &generate_token(member.id()).unwrap()).await;
+
+    for expected in [204, 429] {
+        let response = app
+            .client
+            .request(
+                Path::POST(&path),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(owner_token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(
+            expected,
+            response.status(),
+            "The API did not return {} on typing",
+            expected
+        );
+    }
+
+    let (_, event) = client.next_event().await;
+    assert_eq!(
+        GatewayEvent::TypingStart {
+            channel_id: general.id(),
+            user_id: owner.id(),
+        },
+        event,
+        "The member was not told the owner started typing"
+    );
+
+    actix_rt::time::sleep(TYPING_TIMEOUT - Duration::from_secs(2)).await;
+    let (_, event) = client.next_event().await;
+    assert_eq!(
+        GatewayEvent::TypingStop {
+            channel_id: general.id(),
+            user_id: owner.id(),
+        },
+        event,
+        "The typing indicator did not expire"
+    );
+}
+
+#[actix::test]
+async fn test_typing_in_unknown_channel_returns_404() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+
+    let response = app
+        .client
+        .request(
+            Path::POST(&format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::TYPING_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(outsider.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 when a non-member started typing"
+    );
+}
+
+#[actix::test]
+async fn test_dm_typing_over_gateway() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let alice_token = generate_token(alice.id()).unwrap();
+    let thread = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            Some(json!({"recipient_id": bob.id()}).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+
+    let (mut bob_client, _) =
+        GatewayClient::identify(&app, &generate_token(bob.id()).unwrap()).await;
+    let (mut alice_client, _) = GatewayClient::identify(&app, &alice_token).await;
+    match bob_client.next_event().await {
+        (_, GatewayEvent::PresenceUpdate(_)) => {}
+        (_, event) => panic!("Expected PRESENCE_UPDATE, got {:?}", event),
+    }
+
+    alice_client
+        .send(json!({"op": "dm_typing_start", "thread_id": thread.id()}))
+        .await;
+    let (_, event) = bob_client.next_event().await;
+    assert_eq!(
+        GatewayEvent::DmTypingStart {
+            thread_id: thread.id(),
+            user_id: alice.id(),
+        },
+        event,
+        "Bob was not told Alice started typing"
+    );
+}
@@ -0,0 +1,109 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use chrono::Utc;
+use muttr_server::{
+    domain::{channel::ChannelKind, message::Message},
+    handlers::{channel, message},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+#[actix::test]
+async fn test_edit_message() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), member.id(), "helo", Utc::now())
+        .await;
+    let path = format!(
+        "{}/{}{}/{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::BASE_PATH,
+        msg.id()
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::PATCH(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(owner.id()).unwrap()),
+            ],
+            Some(json!({"content": "owned"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-author edited a message"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::PATCH(&path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(member.id()).unwrap()),
+            ],
+            Some(json!({"content": "hello"}).to_string()),
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 when the author edited a message"
+    );
+    let edited = response
+        .json::<Message>()
+        .await
+        .expect("failed to unmarshal json into Message");
+    assert_eq!(
+        "hello",
+        edited.content().as_ref(),
+        "The content was not updated"
+    );
+    assert!(edited.edited_at().is_some(), "edited_at was not set");
+
+    let stored = app
+        .database
+        .get_message_by_id(msg.id())
+        .await
+        .expect("Failed to get message from database");
+    assert_eq!(
+        "hello",
+        stored.content().as_ref(),
+        "The edit was not stored"
+    );
+    let revisions = app.database.get_message_revisions(msg.id()).await;
+    let history = revisions
+        .iter()
+        .map(|r| r.content().to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(
+        vec!["helo", "hello"],
+        history,
+        "The edit history did not match"
+    );
+}
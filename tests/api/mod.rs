@@ -1,3 +1,11 @@
+mod channel;
+mod dm;
+mod gateway;
 mod health_check;
+mod message;
+mod poll;
+mod post;
+mod search;
 mod server;
+mod thread;
 mod user;
@@ -0,0 +1,630 @@
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::{
+        channel::ChannelKind,
+        message::Message,
+        poll::{PollTally, PollView},
+        post::PostView,
+    },
+    gateway::GatewayEvent,
+    handlers::{channel, message, message::MessageResponse, poll, post, server},
+    utils::jwt::generate_token,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    gateway::GatewayClient,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn request(
+    app: &TestApp,
+    path: Path<String>,
+    body: Option<Value>,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            body.map(|b| b.to_string()),
+        )
+        .await
+}
+
+fn poll_path(poll_id: Uuid, suffix: &str) -> String {
+    format!("{}/{}{}", poll::BASE_PATH, poll_id, suffix)
+}
+
+async fn vote_ok(app: &TestApp, poll_id: Uuid, option_ids: &[Uuid], token: &str) -> PollView {
+    let response = request(
+        app,
+        Path::PUT(poll_path(poll_id, poll::VOTES_PATH)),
+        Some(json!({ "option_ids": option_ids })),
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not accept the vote");
+    response
+        .json::<PollView>()
+        .await
+        .expect("failed to unmarshal json into PollView")
+}
+
+async fn get_poll_ok(app: &TestApp, poll_id: Uuid, token: &str) -> PollView {
+    let response = request(app, Path::GET(poll_path(poll_id, "")), None, token).await;
+    assert_eq!(200, response.status(), "The API did not return the poll");
+    response
+        .json::<PollView>()
+        .await
+        .expect("failed to unmarshal json into PollView")
+}
+
+/// Waits for the next live tally, skipping other events.
+async fn next_tally(client: &mut GatewayClient) -> PollTally {
+    loop {
+        if let (_, GatewayEvent::PollUpdate(tally)) = client.next_event().await {
+            return tally;
+        }
+    }
+}
+
+fn counts(view: &PollView) -> Vec<Option<i64>> {
+    view.options.iter().map(|o| o.vote_count).collect()
+}
+
+#[actix::test]
+async fn test_post_poll_voting_lifecycle() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+
+    let response = request(
+        &app,
+        Path::POST(format!(
+            "{}/{}{}",
+            server::BASE_PATH,
+            srv.id(),
+            post::BASE_PATH
+        )),
+        Some(json!({
+            "content": "where do we meet?",
+            "poll": {
+                "question": "Pick a place",
+                "options": ["park", "cafe", "library"],
+                "anonymous": true,
+                "hide_results": true,
+                "closes_at": Utc::now() + Duration::days(1),
+            }
+        })),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not create the post");
+    let created = response
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView");
+    let view = created.poll.expect("The post was created without its poll");
+    let poll_id = view.id();
+    let option_ids: Vec<Uuid> = view.options.iter().map(|o| o.option.id()).collect();
+    assert_eq!(
+        vec!["park", "cafe", "library"],
+        view.options
+            .iter()
+            .map(|o| o.option.text())
+            .collect::<Vec<_>>()
+    );
+
+    let unvoted = get_poll_ok(&app, poll_id, &member_token).await;
+    assert!(!unvoted.results_visible);
+    assert_eq!(vec![None, None, None], counts(&unvoted));
+
+    for (body, case) in [
+        (json!({ "option_ids": [] }), "no option is chosen"),
+        (
+            json!({ "option_ids": [option_ids[0], option_ids[1]] }),
+            "several options are chosen in a single choice poll",
+        ),
+        (
+            json!({ "option_ids": [Uuid::new_v4()] }),
+            "the option is not part of the poll",
+        ),
+    ] {
+        assert_eq!(
+            400,
+            request(
+                &app,
+                Path::PUT(poll_path(poll_id, poll::VOTES_PATH)),
+                Some(body),
+                &member_token,
+            )
+            .await
+            .status()
+            .as_u16(),
+            "The API did not return 400 when {}",
+            case
+        );
+    }
+
+    let voted = vote_ok(&app, poll_id, &option_ids[..1], &member_token).await;
+    assert!(voted.results_visible);
+    assert_eq!(vec![Some(1), Some(0), Some(0)], counts(&voted));
+    assert_eq!(vec![option_ids[0]], voted.own_votes);
+    assert!(
+        voted.options.iter().all(|o| o.voter_ids.is_none()),
+        "An anonymous poll revealed its voters"
+    );
+
+    let changed = vote_ok(&app, poll_id, &option_ids[2..], &member_token).await;
+    assert_eq!(vec![Some(0), Some(0), Some(1)], counts(&changed));
+    assert_eq!(Some(1), changed.voter_count);
+    let feed_view = request(
+        &app,
+        Path::GET(format!("{}/{}", post::BASE_PATH, created.post.id())),
+        None,
+        &member_token,
+    )
+    .await
+    .json::<PostView>()
+    .await
+    .expect("failed to unmarshal json into PostView");
+    assert_eq!(
+        Some(vec![option_ids[2]]),
+        feed_view.poll.map(|p| p.own_votes),
+        "The post did not show the member's vote"
+    );
+
+    for status in [204, 404] {
+        let response = request(
+            &app,
+            Path::DELETE(poll_path(poll_id, poll::VOTES_PATH)),
+            None,
+            &member_token,
+        )
+        .await;
+        assert_eq!(status, response.status().as_u16());
+    }
+    let retracted = get_poll_ok(&app, poll_id, &member_token).await;
+    assert!(!retracted.results_visible);
+    assert_eq!(
+        None, retracted.voter_count,
+        "The voter count was shown while results were hidden"
+    );
+
+    vote_ok(&app, poll_id, &option_ids[1..2], &member_token).await;
+    assert_eq!(
+        403,
+        request(
+            &app,
+            Path::POST(poll_path(poll_id, poll::CLOSE_PATH)),
+            None,
+            &member_token,
+        )
+        .await
+        .status()
+        .as_u16(),
+        "A voter closed a poll they did not create"
+    );
+    let response = request(
+        &app,
+        Path::POST(poll_path(poll_id, poll::CLOSE_PATH)),
+        None,
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status());
+    let closed = response
+        .json::<PollView>()
+        .await
+        .expect("failed to unmarshal json into PollView");
+    assert!(closed.closed);
+    assert!(
+        closed.results_visible,
+        "Closing the poll did not reveal its results"
+    );
+    assert_eq!(vec![Some(0), Some(1), Some(0)], counts(&closed));
+
+    for path in [
+        Path::PUT(poll_path(poll_id, poll::VOTES_PATH)),
+        Path::DELETE(poll_path(poll_id, poll::VOTES_PATH)),
+        Path::POST(poll_path(poll_id, poll::CLOSE_PATH)),
+    ] {
+        let token = match path {
+            Path::POST(_) => &owner_token,
+            _ => &member_token,
+        };
+        assert_eq!(
+            400,
+            request(
+                &app,
+                path,
+                Some(json!({ "option_ids": [option_ids[0]] })),
+                token
+            )
+            .await
+            .status()
+            .as_u16(),
+            "The API changed a closed poll"
+        );
+    }
+}
+
+#[actix::test]
+async fn test_message_poll_validation_and_access() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let outsider_token = generate_token(outsider.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let messages_path = format!(
+        "{}/{}{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::BASE_PATH
+    );
+
+    let test_cases = [
+        (
+            json!({ "question": " ", "options": ["a", "b"] }),
+            "the question is empty",
+        ),
+        (
+            json!({ "question": "q", "options": ["a"] }),
+            "there is one option",
+        ),
+        (
+            json!({ "question": "q", "options": (0..11).map(|i| i.to_string()).collect::<Vec<_>>() }),
+            "there are eleven options",
+        ),
+        (
+            json!({ "question": "q", "options": ["a", "A "] }),
+            "options repeat",
+        ),
+        (
+            json!({ "question": "q", "options": ["a", "b"], "closes_at": Utc::now() - Duration::hours(1) }),
+            "the poll closes in the past",
+        ),
+    ];
+    for (poll, case) in test_cases {
+        assert_eq!(
+            400,
+            request(
+                &app,
+                Path::POST(messages_path.clone()),
+                Some(json!({ "content": "vote!", "poll": poll })),
+                &owner_token,
+            )
+            .await
+            .status()
+            .as_u16(),
+            "The API did not return 400 when {}",
+            case
+        );
+    }
+
+    let response = request(
+        &app,
+        Path::POST(messages_path.clone()),
+        Some(json!({
+            "content": "vote!",
+            "poll": {
+                "question": "Which days work?",
+                "options": ["monday", "tuesday", "friday"],
+                "multiple_choice": true,
+            }
+        })),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not send the message");
+    let sent = response
+        .json::<MessageResponse<Message>>()
+        .await
+        .expect("failed to unmarshal json into MessageResponse<Message>");
+    let view = sent.poll.expect("The message was sent without its poll");
+    let option_ids: Vec<Uuid> = view.options.iter().map(|o| o.option.id()).collect();
+
+    let voted = vote_ok(
+        &app,
+        view.id(),
+        &[option_ids[0], option_ids[2], option_ids[0]],
+        &member_token,
+    )
+    .await;
+    assert_eq!(vec![option_ids[0], option_ids[2]], voted.own_votes);
+    vote_ok(&app, view.id(), &option_ids[..1], &owner_token).await;
+
+    let response = request(
+        &app,
+        Path::GET(format!("{}/{}", messages_path, sent.message.id())),
+        None,
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status());
+    let fetched = response
+        .json::<MessageResponse<Message>>()
+        .await
+        .expect("failed to unmarshal json into MessageResponse<Message>")
+        .poll
+        .expect("The message was returned without its poll");
+    assert_eq!(Some(2), fetched.voter_count);
+    assert_eq!(vec![Some(2), Some(0), Some(1)], counts(&fetched));
+    assert_eq!(
+        Some(vec![member.id(), owner.id()]),
+        fetched.options[0].voter_ids,
+        "A public poll did not list its voters"
+    );
+
+    for path in [
+        Path::GET(poll_path(view.id(), "")),
+        Path::PUT(poll_path(view.id(), poll::VOTES_PATH)),
+    ] {
+        assert_eq!(
+            404,
+            request(
+                &app,
+                path,
+                Some(json!({ "option_ids": [option_ids[1]] })),
+                &outsider_token,
+            )
+            .await
+            .status()
+            .as_u16(),
+            "A non-member reached a poll in a server they cannot see"
+        );
+    }
+}
+
+#[actix::test]
+async fn test_hidden_results_are_revealed_at_close_time() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let voter = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let onlooker = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let voter_token = generate_token(voter.id()).unwrap();
+    let onlooker_token = generate_token(onlooker.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    for member_id in [voter.id(), onlooker.id()] {
+        app.database.insert_server_member(srv.id(), member_id).await;
+    }
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+
+    let response = request(
+        &app,
+        Path::POST(format!(
+            "{}/{}{}",
+            channel::BASE_PATH,
+            general.id(),
+            message::BASE_PATH
+        )),
+        Some(json!({
+            "content": "vote!",
+            "poll": {
+                "question": "Tabs or spaces?",
+                "options": ["tabs", "spaces"],
+                "hide_results": true,
+                "closes_at": Utc::now() + Duration::hours(1),
+            }
+        })),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not send the message");
+    let view = response
+        .json::<MessageResponse<Message>>()
+        .await
+        .expect("failed to unmarshal json into MessageResponse<Message>")
+        .poll
+        .expect("The message was sent without its poll");
+    let option_ids: Vec<Uuid> = view.options.iter().map(|o| o.option.id()).collect();
+    vote_ok(&app, view.id(), &option_ids[1..], &voter_token).await;
+
+    let hidden = get_poll_ok(&app, view.id(), &onlooker_token).await;
+    assert!(!hidden.closed && !hidden.results_visible);
+    assert_eq!(vec![None, None], counts(&hidden));
+    assert_eq!(
+        None, hidden.voter_count,
+        "The voter count was shown while results were hidden"
+    );
+
+    assert!(
+        app.database.close_due_polls(Utc::now()).await.is_empty(),
+        "A poll was closed before its close time"
+    );
+    let after_close = Utc::now() + Duration::hours(2);
+    assert_eq!(
+        vec![view.id()],
+        app.database.close_due_polls(after_close).await
+    );
+    assert!(
+        app.database.close_due_polls(after_close).await.is_empty(),
+        "A poll was closed twice"
+    );
+
+    let revealed = get_poll_ok(&app, view.id(), &onlooker_token).await;
+    assert!(revealed.closed, "The poll was not closed at its close time");
+    assert!(
+        revealed.results_visible,
+        "Closing the poll did not reveal its results"
+    );
+    assert_eq!(vec![Some(0), Some(1)], counts(&revealed));
+    assert_eq!(Some(1), revealed.voter_count);
+    assert_eq!(
+        400,
+        request(
+            &app,
+            Path::PUT(poll_path(view.id(), poll::VOTES_PATH)),
+            Some(json!({ "option_ids": [option_ids[0]] })),
+            &onlooker_token,
+        )
+        .await
+        .status()
+        .as_u16(),
+        "The API accepted a vote after the close time"
+    );
+}
+
+#[actix::test]
+async fn test_multiple_choice_votes_change_live_tallies() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let voter = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let watcher = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let voter_token = generate_token(voter.id()).unwrap();
+    let watcher_token = generate_token(watcher.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    for member_id in [voter.id(), watcher.id()] {
+        app.database.insert_server_member(srv.id(), member_id).await;
+    }
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let (mut client, _) = GatewayClient::identify(&app, &watcher_token).await;
+
+    let response = request(
+        &app,
+        Path::POST(format!(
+            "{}/{}{}",
+            channel::BASE_PATH,
+            general.id(),
+            message::BASE_PATH
+        )),
+        Some(json!({
+            "content": "vote!",
+            "poll": {
+                "question": "What should we build?",
+                "options": ["bot", "game", "site"],
+                "multiple_choice": true,
+                "hide_results": true,
+            }
+        })),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not send the message");
+    let view = response
+        .json::<MessageResponse<Message>>()
+        .await
+        .expect("failed to unmarshal json into MessageResponse<Message>")
+        .poll
+        .expect("The message was sent without its poll");
+    let poll_id = view.id();
+    let option_ids: Vec<Uuid> = view.options.iter().map(|o| o.option.id()).collect();
+
+    let voted = vote_ok(&app, poll_id, &option_ids[..2], &voter_token).await;
+    assert_eq!(vec![Some(1), Some(1), Some(0)], counts(&voted));
+    assert_eq!(Some(1), voted.voter_count);
+    let tally = next_tally(&mut client).await;
+    assert_eq!(poll_id, tally.poll_id);
+    assert_eq!(
+        (None, None),
+        (tally.voter_count, tally.options),
+        "The live tally revealed hidden results"
+    );
+
+    let changed = vote_ok(&app, poll_id, &option_ids[1..], &voter_token).await;
+    assert_eq!(option_ids[1..].to_vec(), changed.own_votes);
+    assert_eq!(
+        vec![Some(0), Some(1), Some(1)],
+        counts(&changed),
+        "Changing the vote did not replace the earlier choices"
+    );
+    assert_eq!(Some(1), changed.voter_count, "A changed vote counted twice");
+    next_tally(&mut client).await;
+
+    let second = vote_ok(&app, poll_id, &[option_ids[2]], &owner_token).await;
+    assert_eq!(vec![Some(0), Some(1), Some(2)], counts(&second));
+    assert_eq!(Some(2), second.voter_count);
+    next_tally(&mut client).await;
+
+    let unvoted = get_poll_ok(&app, poll_id, &watcher_token).await;
+    assert_eq!(vec![None, None, None], counts(&unvoted));
+    assert_eq!(None, unvoted.voter_count);
+
+    let response = request(
+        &app,
+        Path::POST(poll_path(poll_id, poll::CLOSE_PATH)),
+        None,
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not close the poll");
+    let tally = next_tally(&mut client).await;
+    assert!(tally.closed, "The live tally did not report the close");
+    assert_eq!(Some(2), tally.voter_count);
+    assert_eq!(
+        Some(vec![(option_ids[1], 1), (option_ids[2], 2)]),
+        tally.options.map(|options| options
+            .iter()
+            .filter(|o| o.vote_count > 0)
+            .map(|o| (o.option_id, o.vote_count))
+            .collect::<Vec<_>>()),
+        "Closing the poll did not reveal its tally"
+    );
+}
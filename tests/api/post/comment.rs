@@ -0,0 +1,270 @@
+use muttr_server::{
+    domain::{
+        pagination::Page,
+        permission::Permissions,
+        post::{CommentView, PostView},
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{comments_path, create_post_ok, likes_path, post_request, request};
+use crate::utils::{app::TestApp, http_client::Path};
+
+async fn comment_ok(
+    app: &TestApp,
+    post_id: Uuid,
+    content: &str,
+    parent_id: Option<Uuid>,
+    token: &str,
+) -> CommentView {
+    let response = request(
+        app,
+        Path::POST(comments_path(post_id)),
+        Some(json!({"content": content, "parent_id": parent_id})),
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not create the comment");
+    response
+        .json::<CommentView>()
+        .await
+        .expect("failed to unmarshal json into CommentView")
+}
+
+async fn list_comments(
+    app: &TestApp,
+    post_id: Uuid,
+    query: &str,
+    token: &str,
+) -> Page<CommentView> {
+    let response = request(
+        app,
+        Path::GET(format!("{}{}", comments_path(post_id), query)),
+        None,
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not list the comments");
+    response
+        .json::<Page<CommentView>>()
+        .await
+        .expect("failed to unmarshal json into Page<CommentView>")
+}
+
+fn ids(page: &Page<CommentView>) -> Vec<Uuid> {
+    page.items().iter().map(CommentView::id).collect()
+}
+
+#[actix::test]
+async fn test_threaded_comments_sorted_by_newest_or_top() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+
+    let post = create_post_ok(&app, srv.id(), "discuss", &owner_token).await;
+    let older = comment_ok(&app, post.id(), "first!", None, &member_token).await;
+    let newer = comment_ok(&app, post.id(), "second", None, &owner_token).await;
+    let reply = comment_ok(&app, post.id(), "agreed", Some(older.id()), &owner_token).await;
+    assert_eq!(Some(older.id()), reply.comment.parent_id());
+
+    let other_post = create_post_ok(&app, srv.id(), "elsewhere", &owner_token).await;
+    let response = request(
+        &app,
+        Path::POST(comments_path(other_post.id())),
+        Some(json!({"content": "wrong thread", "parent_id": older.id()})),
+        &member_token,
+    )
+    .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 for a parent comment on another post"
+    );
+
+    assert_eq!(
+        200,
+        request(
+            &app,
+            Path::PUT(likes_path(post.id(), Some(older.id()))),
+            None,
+            &owner_token
+        )
+        .await
+        .status()
+    );
+
+    let newest = list_comments(&app, post.id(), "", &member_token).await;
+    assert_eq!(vec![newer.id(), older.id()], ids(&newest));
+    assert_eq!(1, newest.items()[1].reply_count);
+    assert_eq!(1, newest.items()[1].likes.like_count);
+
+    let top = list_comments(&app, post.id(), "?sort=top&limit=1", &member_token).await;
+    assert_eq!(vec![older.id()], ids(&top), "The most liked was not first");
+    let cursor = top.next_cursor().expect("The first page had no cursor");
+    let top = list_comments(
+        &app,
+        post.id(),
+        &format!("?sort=top&limit=1&cursor={}", cursor),
+        &member_token,
+    )
+    .await;
+    assert_eq!(vec![newer.id()], ids(&top));
+
+    let replies = list_comments(
+        &app,
+        post.id(),
+        &format!("?parent_id={}", older.id()),
+        &member_token,
+    )
+    .await;
+    assert_eq!(vec![reply.id()], ids(&replies));
+
+    let view = post_request(&app, post.id(), "GET", None, &member_token)
+        .await
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView");
+    assert_eq!(3, view.comment_count);
+
+    let response = request(
+        &app,
+        Path::GET(format!(
+            "{}?cursor={}",
+            comments_path(other_post.id()),
+            older.id()
+        )),
+        None,
+        &member_token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor from another post's comments"
+    );
+}
+
+#[actix::test]
+async fn test_edit_and_delete_comments() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let post_author = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let commenter = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let moderator = app
+        .database
+        .insert_user("testuser4@email.com", "test.user4", true)
+        .await;
+    let bystander = app
+        .database
+        .insert_user("testuser5@email.com", "test.user5", true)
+        .await;
+    let post_author_token = generate_token(post_author.id()).unwrap();
+    let commenter_token = generate_token(commenter.id()).unwrap();
+    let moderator_token = generate_token(moderator.id()).unwrap();
+    let bystander_token = generate_token(bystander.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    for user_id in [
+        post_author.id(),
+        commenter.id(),
+        moderator.id(),
+        bystander.id(),
+    ] {
+        app.database.insert_server_member(srv.id(), user_id).await;
+    }
+    let role = app
+        .database
+        .insert_role(srv.id(), "Moderator", Permissions::MANAGE_MESSAGES)
+        .await;
+    app.database
+        .insert_member_role(role.id(), moderator.id())
+        .await;
+
+    let post = create_post_ok(&app, srv.id(), "my post", &post_author_token).await;
+    let first = comment_ok(&app, post.id(), "hot take", None, &commenter_token).await;
+    let second = comment_ok(&app, post.id(), "hotter take", None, &commenter_token).await;
+    let comment_path = |id: Uuid| format!("{}/{}", comments_path(post.id()), id);
+
+    let response = request(
+        &app,
+        Path::PATCH(comment_path(first.id())),
+        Some(json!({"content": "not mine"})),
+        &post_author_token,
+    )
+    .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-author edited a comment"
+    );
+    let response = request(
+        &app,
+        Path::PATCH(comment_path(first.id())),
+        Some(json!({"content": "lukewarm take"})),
+        &commenter_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not edit the comment");
+    let edited = response
+        .json::<CommentView>()
+        .await
+        .expect("failed to unmarshal json into CommentView");
+    assert_eq!("lukewarm take", edited.comment.content().as_ref());
+    assert!(edited.comment.edited_at().is_some());
+
+    let response = request(
+        &app,
+        Path::DELETE(comment_path(first.id())),
+        None,
+        &bystander_token,
+    )
+    .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a bystander deleted a comment"
+    );
+    for (id, token) in [
+        (first.id(), &post_author_token),
+        (second.id(), &moderator_token),
+    ] {
+        let response = request(&app, Path::DELETE(comment_path(id)), None, token).await;
+        assert_eq!(
+            200,
+            response.status(),
+            "The API did not let the post author or a moderator delete a comment"
+        );
+    }
+
+    assert!(
+        list_comments(&app, post.id(), "", &commenter_token)
+            .await
+            .items()
+            .is_empty(),
+        "Deleted comments were still listed"
+    );
+}
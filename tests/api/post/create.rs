@@ -0,0 +1,125 @@
+use muttr_server::{domain::post::PostView, utils::jwt::generate_token};
+use serde_json::json;
+
+use super::{create_post, create_post_ok, post_request};
+use crate::utils::app::TestApp;
+
+#[actix::test]
+async fn test_create_and_quote_post() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let outsider_token = generate_token(outsider.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let other_srv = app.database.insert_server(outsider.id()).await;
+
+    let first = create_post_ok(&app, srv.id(), "the first post", &owner_token).await;
+    assert_eq!(None, first.post.quoted_post_id());
+    assert_eq!(owner.id(), first.post.author_id());
+
+    let test_cases = [
+        (json!({"content": "   "}), 400, "empty content"),
+        (
+            json!({"content": "a".repeat(4001)}),
+            400,
+            "content that is too long",
+        ),
+        (
+            json!({"content": "quoting nothing", "quoted_post_id": uuid::Uuid::new_v4()}),
+            404,
+            "a quote of a missing post",
+        ),
+    ];
+    for (body, expected, case) in test_cases {
+        let response = create_post(&app, srv.id(), body, &member_token).await;
+        assert_eq!(
+            expected,
+            response.status().as_u16(),
+            "The API did not return {} for {}",
+            expected,
+            case
+        );
+    }
+
+    let response = create_post(
+        &app,
+        srv.id(),
+        json!({"content": "let me in"}),
+        &outsider_token,
+    )
+    .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-member posted"
+    );
+
+    let foreign = create_post_ok(&app, other_srv.id(), "elsewhere", &outsider_token).await;
+    let response = create_post(
+        &app,
+        srv.id(),
+        json!({"content": "smuggled", "quoted_post_id": foreign.id()}),
+        &member_token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not return 400 for a quote of a post in another server"
+    );
+
+    let response = create_post(
+        &app,
+        srv.id(),
+        json!({"content": "so true", "quoted_post_id": first.id()}),
+        &member_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not create the quote");
+    let quote = response
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView");
+    assert_eq!(Some(first.id()), quote.post.quoted_post_id());
+    assert_eq!(Some(first.post.clone()), quote.quoted_post);
+
+    assert_eq!(
+        200,
+        post_request(&app, first.id(), "DELETE", None, &owner_token)
+            .await
+            .status()
+    );
+    let quote = post_request(&app, quote.id(), "GET", None, &member_token)
+        .await
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView");
+    assert_eq!(Some(first.id()), quote.post.quoted_post_id());
+    assert_eq!(
+        None, quote.quoted_post,
+        "A deleted quoted post was still inlined"
+    );
+
+    let response = post_request(&app, quote.id(), "GET", None, &outsider_token).await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-member read a post"
+    );
+}
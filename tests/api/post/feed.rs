@@ -0,0 +1,78 @@
+use muttr_server::utils::jwt::generate_token;
+
+use super::{create_post_ok, get_feed, get_feed_ok, post_request};
+use crate::utils::app::TestApp;
+
+#[actix::test]
+async fn test_server_feed_is_paged_newest_first() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let outsider_token = generate_token(outsider.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+
+    let mut posts = vec![];
+    for x in 0..4 {
+        posts.push(create_post_ok(&app, srv.id(), &format!("post {}", x), &owner_token).await);
+    }
+    assert_eq!(
+        200,
+        post_request(&app, posts[1].id(), "DELETE", None, &owner_token)
+            .await
+            .status()
+    );
+
+    let first_page = get_feed_ok(&app, srv.id(), "?limit=2", &owner_token).await;
+    assert_eq!(
+        vec![posts[3].clone(), posts[2].clone()],
+        first_page.items(),
+        "The first page was not the newest posts"
+    );
+    let cursor = first_page
+        .next_cursor()
+        .expect("The first page had no cursor");
+
+    let second_page = get_feed_ok(
+        &app,
+        srv.id(),
+        &format!("?limit=2&cursor={}", cursor),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(
+        vec![posts[0].clone()],
+        second_page.items(),
+        "The second page did not skip the deleted post"
+    );
+    assert_eq!(None, second_page.next_cursor());
+
+    let other = app.database.insert_server(owner.id()).await;
+    let elsewhere = create_post_ok(&app, other.id(), "elsewhere", &owner_token).await;
+    let response = get_feed(
+        &app,
+        srv.id(),
+        &format!("?cursor={}", elsewhere.id()),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor from another server's feed"
+    );
+
+    let response = get_feed(&app, srv.id(), "", &outsider_token).await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-member read the feed"
+    );
+}
@@ -0,0 +1,205 @@
+use chrono::SecondsFormat;
+use muttr_server::{
+    domain::post::{HomeFeed, PostView},
+    handlers::user::{BASE_PATH, FEED_PATH, ME_PATH},
+    utils::jwt::generate_token,
+};
+use uuid::Uuid;
+
+use super::{create_post_ok, get_feed_ok, likes_path, request};
+use crate::utils::{app::TestApp, http_client::Path};
+
+async fn get_home_feed(app: &TestApp, query: &str, token: &str) -> HomeFeed {
+    let response = request(
+        app,
+        Path::GET(format!("{}{}{}{}", BASE_PATH, ME_PATH, FEED_PATH, query)),
+        None,
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not return the feed");
+    response
+        .json::<HomeFeed>()
+        .await
+        .expect("failed to unmarshal json into HomeFeed")
+}
+
+fn ids(posts: &[PostView]) -> Vec<Uuid> {
+    posts.iter().map(|p| p.id()).collect()
+}
+
+#[actix::test]
+async fn test_home_feed_only_shows_joined_servers() {
+    let mut app = TestApp::spawn().await;
+
+    let viewer = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let friend = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let stranger = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let blocked = app
+        .database
+        .insert_user("testuser4@email.com", "test.user4", true)
+        .await;
+    let viewer_token = generate_token(viewer.id()).unwrap();
+    let friend_token = generate_token(friend.id()).unwrap();
+    let stranger_token = generate_token(stranger.id()).unwrap();
+    let blocked_token = generate_token(blocked.id()).unwrap();
+    let muted_author = app
+        .database
+        .insert_user("testuser5@email.com", "test.user5", true)
+        .await;
+    let muted_author_token = generate_token(muted_author.id()).unwrap();
+
+    let joined = app.database.insert_server(viewer.id()).await;
+    let muted = app.database.insert_server(stranger.id()).await;
+    let other = app.database.insert_server(stranger.id()).await;
+    for user_id in [stranger.id(), blocked.id(), muted_author.id()] {
+        app.database
+            .insert_server_member(joined.id(), user_id)
+            .await;
+    }
+    app.database
+        .insert_server_member(muted.id(), viewer.id())
+        .await;
+    for server_id in [joined.id(), other.id()] {
+        app.database
+            .insert_server_member(server_id, friend.id())
+            .await;
+    }
+    app.database
+        .insert_user_follow(viewer.id(), friend.id())
+        .await;
+    app.database
+        .insert_server_mute(viewer.id(), muted.id())
+        .await;
+    app.database
+        .insert_user_block(viewer.id(), blocked.id())
+        .await;
+    app.database
+        .insert_user_mute(viewer.id(), muted_author.id())
+        .await;
+
+    let own = create_post_ok(&app, joined.id(), "mine", &viewer_token).await;
+    create_post_ok(&app, joined.id(), "blocked", &blocked_token).await;
+    create_post_ok(&app, joined.id(), "muted author", &muted_author_token).await;
+    let member = create_post_ok(&app, joined.id(), "member", &stranger_token).await;
+    create_post_ok(&app, muted.id(), "muted", &stranger_token).await;
+    create_post_ok(&app, other.id(), "unfollowed", &stranger_token).await;
+    let elsewhere = create_post_ok(&app, other.id(), "followed elsewhere", &friend_token).await;
+    let followed = create_post_ok(&app, joined.id(), "followed", &friend_token).await;
+
+    let first_page = get_home_feed(&app, "?limit=2", &viewer_token).await;
+    assert_eq!(
+        vec![followed.id(), member.id()],
+        ids(first_page.page.items()),
+        "The first page was not the newest posts from joined servers"
+    );
+    let cursor = first_page
+        .page
+        .next_cursor()
+        .expect("The first page had no cursor");
+
+    create_post_ok(&app, joined.id(), "arrived later", &stranger_token).await;
+
+    let second_page = get_home_feed(
+        &app,
+        &format!(
+            "?limit=2&cursor={}&as_of={}",
+            cursor,
+            first_page
+                .as_of
+                .to_rfc3339_opts(SecondsFormat::Micros, true)
+        ),
+        &viewer_token,
+    )
+    .await;
+    assert_eq!(
+        vec![own.id()],
+        ids(second_page.page.items()),
+        "The second page shifted after a new post arrived"
+    );
+    assert_eq!(None, second_page.page.next_cursor());
+    assert!(
+        !ids(first_page.page.items())
+            .into_iter()
+            .chain(ids(second_page.page.items()))
+            .any(|id| id == elsewhere.id()),
+        "The feed showed a followed user's post from a server the viewer has not joined"
+    );
+
+    let server_feed = get_feed_ok(&app, joined.id(), "", &viewer_token).await;
+    assert_eq!(
+        4,
+        server_feed.items().len(),
+        "The server feed showed blocked or muted authors"
+    );
+
+    let response = request(
+        &app,
+        Path::GET(format!(
+            "{}{}{}?cursor={}",
+            BASE_PATH,
+            ME_PATH,
+            FEED_PATH,
+            Uuid::new_v4()
+        )),
+        None,
+        &viewer_token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor that matches no post"
+    );
+}
+
+#[actix::test]
+async fn test_home_feed_ranks_by_engagement() {
+    let mut app = TestApp::spawn().await;
+
+    let viewer = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let viewer_token = generate_token(viewer.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(viewer.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+
+    let popular = create_post_ok(&app, srv.id(), "popular", &viewer_token).await;
+    let quiet = create_post_ok(&app, srv.id(), "quiet", &viewer_token).await;
+    for token in [&viewer_token, &member_token] {
+        let response = request(&app, Path::PUT(likes_path(popular.id(), None)), None, token).await;
+        assert_eq!(200, response.status(), "The API did not like the post");
+    }
+
+    let recent = get_home_feed(&app, "", &viewer_token).await;
+    assert_eq!(
+        vec![quiet.id(), popular.id()],
+        ids(recent.page.items()),
+        "The feed was not newest first by default"
+    );
+
+    let ranked = get_home_feed(&app, "?sort=engagement", &viewer_token).await;
+    assert_eq!(
+        vec![popular.id(), quiet.id()],
+        ids(ranked.page.items()),
+        "The liked post was not ranked first"
+    );
+    assert_eq!(2, ranked.page.items()[0].likes.like_count);
+}
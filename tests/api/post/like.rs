@@ -0,0 +1,93 @@
+use muttr_server::{
+    domain::post::{LikeSummary, PostView},
+    utils::jwt::generate_token,
+};
+
+use super::{create_post_ok, likes_path, post_request, request};
+use crate::utils::{app::TestApp, http_client::Path};
+
+async fn set_like(app: &TestApp, path: &str, liked: bool, token: &str) -> LikeSummary {
+    let path = if liked {
+        Path::PUT(path.to_string())
+    } else {
+        Path::DELETE(path.to_string())
+    };
+    let response = request(app, path, None, token).await;
+    assert_eq!(200, response.status(), "The API did not update the like");
+    response
+        .json::<LikeSummary>()
+        .await
+        .expect("failed to unmarshal json into LikeSummary")
+}
+
+#[actix::test]
+async fn test_post_likes_are_idempotent() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let outsider_token = generate_token(outsider.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+
+    let post = create_post_ok(&app, srv.id(), "like this", &owner_token).await;
+    let path = likes_path(post.id(), None);
+
+    for _ in 0..2 {
+        assert_eq!(
+            LikeSummary {
+                like_count: 1,
+                liked: true
+            },
+            set_like(&app, &path, true, &member_token).await,
+            "Liking twice was not idempotent"
+        );
+    }
+    assert_eq!(
+        LikeSummary {
+            like_count: 2,
+            liked: true
+        },
+        set_like(&app, &path, true, &owner_token).await
+    );
+
+    let view = post_request(&app, post.id(), "GET", None, &member_token)
+        .await
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView");
+    assert_eq!(2, view.likes.like_count);
+    assert!(view.likes.liked, "The viewer's like was not reported");
+
+    for _ in 0..2 {
+        assert_eq!(
+            LikeSummary {
+                like_count: 1,
+                liked: false
+            },
+            set_like(&app, &path, false, &member_token).await,
+            "Unliking twice was not idempotent"
+        );
+    }
+
+    let response = request(&app, Path::PUT(path.clone()), None, &outsider_token).await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-member liked a post"
+    );
+}
@@ -0,0 +1,141 @@
+mod comment;
+mod create;
+mod feed;
+mod home_feed;
+mod like;
+mod tag;
+mod update;
+
+use muttr_server::{
+    domain::{pagination::Page, post::PostView},
+    handlers::{post, server, user},
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn create_post(
+    app: &TestApp,
+    server_id: Uuid,
+    body: Value,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}",
+                server::BASE_PATH,
+                server_id,
+                post::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(body.to_string()),
+        )
+        .await
+}
+
+async fn create_post_ok(app: &TestApp, server_id: Uuid, content: &str, token: &str) -> PostView {
+    let response = create_post(app, server_id, json!({ "content": content }), token).await;
+    assert_eq!(200, response.status(), "The API did not create the post");
+    response
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView")
+}
+
+async fn get_feed(app: &TestApp, server_id: Uuid, query: &str, token: &str) -> reqwest::Response {
+    app.client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}{}",
+                server::BASE_PATH,
+                server_id,
+                post::BASE_PATH,
+                query
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+}
+
+async fn get_feed_ok(app: &TestApp, server_id: Uuid, query: &str, token: &str) -> Page<PostView> {
+    let response = get_feed(app, server_id, query, token).await;
+    assert_eq!(200, response.status(), "The API did not return the feed");
+    response
+        .json::<Page<PostView>>()
+        .await
+        .expect("failed to unmarshal json into Page<PostView>")
+}
+
+async fn post_request(
+    app: &TestApp,
+    post_id: Uuid,
+    method: &str,
+    body: Option<Value>,
+    token: &str,
+) -> reqwest::Response {
+    let path = format!("{}/{}", post::BASE_PATH, post_id);
+    let path = match method {
+        "GET" => Path::GET(path),
+        "PATCH" => Path::PATCH(path),
+        "DELETE" => Path::DELETE(path),
+        other => panic!("unsupported method {}", other),
+    };
+    app.client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            body.map(|b| b.to_string()),
+        )
+        .await
+}
+
+async fn request(
+    app: &TestApp,
+    path: Path<String>,
+    body: Option<Value>,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            body.map(|b| b.to_string()),
+        )
+        .await
+}
+
+fn likes_path(post_id: Uuid, comment_id: Option<Uuid>) -> String {
+    let comment = comment_id
+        .map(|id| format!("{}/{}", post::COMMENTS_PATH, id))
+        .unwrap_or_default();
+    format!(
+        "{}/{}{}{}{}",
+        post::BASE_PATH,
+        post_id,
+        comment,
+        post::LIKES_PATH,
+        user::ME_PATH
+    )
+}
+
+fn comments_path(post_id: Uuid) -> String {
+    format!("{}/{}{}", post::BASE_PATH, post_id, post::COMMENTS_PATH)
+}
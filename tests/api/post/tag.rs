@@ -0,0 +1,311 @@
+use actix::Actor;
+use chrono::{Duration, SubsecRound, Utc};
+use muttr_server::{
+    domain::{pagination::Page, post::PostView, tag::TrendingTag},
+    handlers::tag,
+    jobs::TrendingTagsRefresher,
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{create_post_ok, post_request, request};
+use crate::utils::{app::TestApp, http_client::Path};
+
+async fn tagged_post_ids(
+    app: &TestApp,
+    name: &str,
+    query: &str,
+    token: &str,
+) -> Result<Vec<Uuid>, u16> {
+    let response = request(
+        app,
+        Path::GET(format!(
+            "{}/{}{}{}",
+            tag::BASE_PATH,
+            name,
+            tag::POSTS_PATH,
+            query
+        )),
+        None,
+        token,
+    )
+    .await;
+    if response.status() != 200 {
+        return Err(response.status().as_u16());
+    }
+    Ok(response
+        .json::<Page<PostView>>()
+        .await
+        .expect("failed to unmarshal json into Page<PostView>")
+        .items()
+        .iter()
+        .map(|p| p.id())
+        .collect())
+}
+
+async fn trending(app: &TestApp, query: &str, token: &str) -> Result<Vec<String>, u16> {
+    trending_tags(app, query, token)
+        .await
+        .map(|tags| tags.into_iter().map(|t| t.tag).collect())
+}
+
+async fn trending_tags(app: &TestApp, query: &str, token: &str) -> Result<Vec<TrendingTag>, u16> {
+    let response = request(
+        app,
+        Path::GET(format!("{}{}{}", tag::BASE_PATH, tag::TRENDING_PATH, query)),
+        None,
+        token,
+    )
+    .await;
+    if response.status() != 200 {
+        return Err(response.status().as_u16());
+    }
+    Ok(response
+        .json::<Vec<TrendingTag>>()
+        .await
+        .expect("failed to unmarshal json into Vec<TrendingTag>"))
+}
+
+#[actix::test]
+async fn test_posts_by_tag() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let other = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let other_token = generate_token(other.id()).unwrap();
+    let joined = app.database.insert_server(user.id()).await;
+    let unjoined = app.database.insert_server(other.id()).await;
+
+    let first = create_post_ok(&app, joined.id(), "#Rust is great", &token).await;
+    let edited = create_post_ok(&app, joined.id(), "more #rust, please", &token).await;
+    let deleted = create_post_ok(&app, joined.id(), "#rust #rust", &token).await;
+    let newest = create_post_ok(&app, joined.id(), "I like #RUST", &token).await;
+    create_post_ok(&app, unjoined.id(), "#rust elsewhere", &other_token).await;
+
+    let response = post_request(
+        &app,
+        edited.id(),
+        "PATCH",
+        Some(json!({ "content": "switched to #go" })),
+        &token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not edit the post");
+    let response = post_request(&app, deleted.id(), "DELETE", None, &token).await;
+    assert_eq!(200, response.status(), "The API did not delete the post");
+
+    assert_eq!(
+        Ok(vec![newest.id(), first.id()]),
+        tagged_post_ids(&app, "rust", "", &token).await,
+        "The tagged posts did not match"
+    );
+    assert_eq!(
+        Ok(vec![edited.id()]),
+        tagged_post_ids(&app, "Go", "", &token).await,
+        "Editing a post did not retag it"
+    );
+    assert_eq!(
+        Ok(vec![first.id()]),
+        tagged_post_ids(
+            &app,
+            "rust",
+            &format!("?limit=1&cursor={}", newest.id()),
+            &token
+        )
+        .await,
+        "The second page did not match"
+    );
+
+    let test_cases = [
+        (
+            "rust",
+            format!("?server_id={}", unjoined.id()),
+            403,
+            "another server",
+        ),
+        ("2024", String::new(), 400, "a tag with no letters"),
+        (
+            "rust",
+            format!("?cursor={}", Uuid::new_v4()),
+            400,
+            "a cursor that matches no post",
+        ),
+    ];
+    for (name, query, status, case) in test_cases {
+        assert_eq!(
+            Err(status),
+            tagged_post_ids(&app, name, &query, &token).await,
+            "The API did not return {} when getting posts for {}",
+            status,
+            case
+        );
+    }
+}
+
+#[actix::test]
+async fn test_trending_tags_favor_recent_use() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let other = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let other_token = generate_token(other.id()).unwrap();
+    let joined = app.database.insert_server(user.id()).await;
+    let unjoined = app.database.insert_server(other.id()).await;
+    let now = Utc::now();
+
+    for (content, age_hours) in [
+        ("#fresh", 0),
+        ("#fresh", 1),
+        ("#steady", 18),
+        ("#steady", 19),
+        ("#steady", 20),
+        ("#ancient", 30),
+    ] {
+        let post = create_post_ok(&app, joined.id(), content, &token).await;
+        app.database
+            .backdate_post(post.id(), now - Duration::hours(age_hours))
+            .await;
+    }
+    create_post_ok(&app, unjoined.id(), "#elsewhere", &other_token).await;
+    app.database.refresh_trending_tags(Utc::now()).await;
+
+    assert_eq!(
+        Ok(vec![
+            String::from("fresh"),
+            String::from("elsewhere"),
+            String::from("steady")
+        ]),
+        trending(&app, "", &token).await,
+        "The trending tags across servers did not match"
+    );
+    assert_eq!(
+        Ok(vec![String::from("fresh"), String::from("steady")]),
+        trending(&app, &format!("?server_id={}", joined.id()), &token).await,
+        "The trending tags of the server did not match"
+    );
+    assert_eq!(
+        Err(403),
+        trending(&app, &format!("?server_id={}", unjoined.id()), &token).await,
+        "A non-member saw a server's trending tags"
+    );
+}
+
+#[actix::test]
+async fn test_trending_scores_weigh_uses_within_the_window() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let srv = app.database.insert_server(user.id()).await;
+    let now = Utc::now().trunc_subsecs(0);
+
+    for (content, age_hours) in [
+        ("#alpha", 0),
+        ("#alpha", 12),
+        ("#beta", 6),
+        ("#gamma", 20),
+        ("#gamma", 20),
+        ("#gamma", 20),
+        ("#edge", 24),
+        ("#future", -1),
+    ] {
+        let post = create_post_ok(&app, srv.id(), content, &token).await;
+        app.database
+            .backdate_post(post.id(), now - Duration::hours(age_hours))
+            .await;
+    }
+    let deleted = create_post_ok(&app, srv.id(), "#deleted", &token).await;
+    assert_eq!(
+        200,
+        post_request(&app, deleted.id(), "DELETE", None, &token)
+            .await
+            .status()
+    );
+    app.database.refresh_trending_tags(now).await;
+
+    let tags = trending_tags(&app, "", &token)
+        .await
+        .expect("The API did not return trending tags");
+    assert_eq!(
+        vec!["alpha", "beta", "gamma"],
+        tags.iter().map(|t| t.tag.as_str()).collect::<Vec<_>>(),
+        "Uses outside the window or of deleted posts were counted"
+    );
+    for (tag, (score, use_count)) in tags.iter().zip([(1.5, 2), (0.75, 1), (0.5, 3)]) {
+        assert!(
+            (tag.score - score).abs() < 1e-6,
+            "#{} scored {} instead of {}",
+            tag.tag,
+            tag.score,
+            score
+        );
+        assert_eq!(use_count, tag.use_count, "#{} use count", tag.tag);
+    }
+
+    app.database
+        .refresh_trending_tags(now + Duration::hours(24))
+        .await;
+    assert_eq!(
+        Ok(vec![String::from("future")]),
+        trending(&app, "", &token).await,
+        "Tags that left the window were still trending"
+    );
+}
+
+#[actix::test]
+async fn test_trending_tags_refresher_refreshes_on_start() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let srv = app.database.insert_server(user.id()).await;
+    create_post_ok(&app, srv.id(), "#launch", &token).await;
+
+    let started_at = Utc::now();
+    TrendingTagsRefresher::new(app.database.db_pool.clone()).start();
+    let mut refreshed = false;
+    for _ in 0..50 {
+        refreshed = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM trending_tags WHERE computed_at >= $1)",
+        )
+        .bind(started_at)
+        .fetch_one(&app.database.db_pool)
+        .await
+        .expect("Failed to check trending tags");
+        if refreshed {
+            break;
+        }
+        actix::clock::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(refreshed, "The refresher did not run when it started");
+
+    for query in [String::new(), format!("?server_id={}", srv.id())] {
+        assert_eq!(
+            Ok(vec![String::from("launch")]),
+            trending(&app, &query, &token).await,
+            "The refreshed trending tags did not match for '{}'",
+            query
+        );
+    }
+}
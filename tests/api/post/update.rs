@@ -0,0 +1,100 @@
+use muttr_server::{
+    domain::{permission::Permissions, post::PostView},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+
+use super::{create_post_ok, post_request};
+use crate::utils::app::TestApp;
+
+#[actix::test]
+async fn test_edit_and_delete_post() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let author = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let moderator = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let bystander = app
+        .database
+        .insert_user("testuser4@email.com", "test.user4", true)
+        .await;
+    let author_token = generate_token(author.id()).unwrap();
+    let moderator_token = generate_token(moderator.id()).unwrap();
+    let bystander_token = generate_token(bystander.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    for user_id in [author.id(), moderator.id(), bystander.id()] {
+        app.database.insert_server_member(srv.id(), user_id).await;
+    }
+    let role = app
+        .database
+        .insert_role(srv.id(), "Moderator", Permissions::MANAGE_MESSAGES)
+        .await;
+    app.database
+        .insert_member_role(role.id(), moderator.id())
+        .await;
+
+    let post = create_post_ok(&app, srv.id(), "first draft", &author_token).await;
+
+    let response = post_request(
+        &app,
+        post.id(),
+        "PATCH",
+        Some(json!({"content": "vandalised"})),
+        &moderator_token,
+    )
+    .await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a non-author edited a post"
+    );
+
+    let response = post_request(
+        &app,
+        post.id(),
+        "PATCH",
+        Some(json!({"content": "final draft"})),
+        &author_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not edit the post");
+    let edited = response
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView");
+    assert_eq!("final draft", edited.post.content().as_ref());
+    assert!(
+        edited.post.edited_at().is_some(),
+        "The edit was not recorded"
+    );
+
+    let response = post_request(&app, post.id(), "DELETE", None, &bystander_token).await;
+    assert_eq!(
+        403,
+        response.status(),
+        "The API did not return 403 when a member deleted someone else's post"
+    );
+
+    let response = post_request(&app, post.id(), "DELETE", None, &moderator_token).await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not let a moderator delete the post"
+    );
+
+    let response = post_request(&app, post.id(), "GET", None, &author_token).await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 for a deleted post"
+    );
+}
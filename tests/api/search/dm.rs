@@ -0,0 +1,143 @@
+use muttr_server::{
+    domain::{dm::DirectMessage, pagination::Page, search::SearchResult},
+    handlers::{
+        dm::{self, DmThreadResponse},
+        search,
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn send_dm(app: &TestApp, recipient_id: Uuid, content: &str, token: &str) -> DirectMessage {
+    let thread = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({ "recipient_id": recipient_id }).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+    app.client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}",
+                dm::BASE_PATH,
+                thread.id(),
+                dm::MESSAGES_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({ "content": content }).to_string()),
+        )
+        .await
+        .json::<DirectMessage>()
+        .await
+        .expect("failed to unmarshal json into DirectMessage")
+}
+
+#[actix::test]
+async fn test_search_direct_messages() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let carol = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let alice_token = generate_token(alice.id()).unwrap();
+    let bob_token = generate_token(bob.id()).unwrap();
+    let carol_token = generate_token(carol.id()).unwrap();
+
+    let to_bob = send_dm(&app, bob.id(), "lunch on friday?", &alice_token).await;
+    let from_bob = send_dm(&app, alice.id(), "Friday works", &bob_token).await;
+    send_dm(&app, bob.id(), "friday plans without alice", &carol_token).await;
+
+    let search_ids = |query: &'static str, token: String| {
+        let client = &app.client;
+        async move {
+            let response = client
+                .request(
+                    Path::GET(format!("{}?{}", search::BASE_PATH, query)),
+                    &[
+                        Header::ContentType(ContentType::Json),
+                        Header::Authorization(token),
+                    ],
+                    None::<String>,
+                )
+                .await;
+            assert_eq!(200, response.status(), "The API did not search '{}'", query);
+            response
+                .json::<Page<SearchResult<DirectMessage>>>()
+                .await
+                .expect("failed to unmarshal json into Page<SearchResult<DirectMessage>>")
+                .items()
+                .iter()
+                .map(|r| r.message().id())
+                .collect::<Vec<Uuid>>()
+        }
+    };
+
+    assert_eq!(
+        vec![from_bob.id(), to_bob.id()],
+        search_ids("q=friday", alice_token.clone()).await,
+        "Alice saw the wrong DMs"
+    );
+    assert_eq!(3, search_ids("q=friday", bob_token.clone()).await.len());
+    assert!(search_ids("q=lunch", carol_token.clone()).await.is_empty());
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}?q=", search::BASE_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(400, response.status(), "An empty search was not rejected");
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}?q=friday&cursor={}",
+                search::BASE_PATH,
+                Uuid::new_v4()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(alice_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "A cursor that matches no DM was not rejected"
+    );
+}
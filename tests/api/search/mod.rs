@@ -0,0 +1,2 @@
+mod dm;
+mod server;
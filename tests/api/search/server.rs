@@ -0,0 +1,370 @@
+use chrono::{Duration, SecondsFormat, Utc};
+use muttr_server::{
+    domain::{
+        channel::ChannelKind, message::Message, pagination::Page, permission::Permissions,
+        search::SearchResult,
+    },
+    handlers::{channel, message, search, server},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn search_server(
+    app: &TestApp,
+    server_id: Uuid,
+    query: &str,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}{}?{}",
+                server::BASE_PATH,
+                server_id,
+                message::BASE_PATH,
+                search::BASE_PATH,
+                query
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+}
+
+async fn search_ids(app: &TestApp, server_id: Uuid, query: &str, token: &str) -> Vec<Uuid> {
+    let response = search_server(app, server_id, query, token).await;
+    assert_eq!(200, response.status(), "The API did not search '{}'", query);
+    response
+        .json::<Page<SearchResult<Message>>>()
+        .await
+        .expect("failed to unmarshal json into Page<SearchResult<Message>>")
+        .items()
+        .iter()
+        .map(|r| r.message().id())
+        .collect()
+}
+
+#[actix::test]
+async fn test_search_server_messages() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let staff = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "staff")
+        .await;
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!(
+                "{}/{}{}/{}{}/{}",
+                server::BASE_PATH,
+                srv.id(),
+                channel::BASE_PATH,
+                staff.id(),
+                channel::PERMISSIONS_PATH,
+                srv.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(
+                json!({"target_type": "role", "deny": Permissions::VIEW_CHANNEL.bits()})
+                    .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(200, response.status());
+
+    let now = Utc::now();
+    let oldest = app
+        .database
+        .insert_message(
+            general.id(),
+            owner.id(),
+            "Rust is great",
+            now - Duration::hours(3),
+        )
+        .await;
+    let linked = app
+        .database
+        .insert_message(
+            general.id(),
+            member.id(),
+            "Fellow rustaceans, read https://rust-lang.org",
+            now - Duration::hours(2),
+        )
+        .await;
+    app.database
+        .insert_message(general.id(), owner.id(), "Go is fine too", now)
+        .await;
+    let secret = app
+        .database
+        .insert_message(staff.id(), owner.id(), "rust secrets", now)
+        .await;
+
+    let response = search_server(&app, srv.id(), "q=rust", &member_token).await;
+    assert_eq!(200, response.status());
+    let page = response
+        .json::<Page<SearchResult<Message>>>()
+        .await
+        .expect("failed to unmarshal json into Page<SearchResult<Message>>");
+    let ids: Vec<Uuid> = page.items().iter().map(|r| r.message().id()).collect();
+    assert_eq!(
+        vec![linked.id(), oldest.id()],
+        ids,
+        "The member saw the wrong results"
+    );
+    assert!(
+        page.items()[0]
+            .snippet()
+            .contains("<mark>rustaceans</mark>"),
+        "The snippet was not highlighted: {}",
+        page.items()[0].snippet()
+    );
+    assert_eq!(
+        vec![secret.id(), linked.id(), oldest.id()],
+        search_ids(&app, srv.id(), "q=rust", &owner_token).await,
+        "The owner did not see the hidden channel"
+    );
+
+    let test_cases = [
+        (
+            format!("q=rust&author_id={}", member.id()),
+            vec![linked.id()],
+        ),
+        (format!("q=rust&channel_id={}", staff.id()), vec![]),
+        (
+            format!(
+                "q=rust&before={}",
+                (now - Duration::minutes(150)).to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+            vec![oldest.id()],
+        ),
+        (
+            format!(
+                "q=rust&after={}",
+                (now - Duration::minutes(150)).to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+            vec![linked.id()],
+        ),
+        (String::from("q=python"), vec![]),
+    ];
+    for (query, expected) in test_cases {
+        assert_eq!(
+            expected,
+            search_ids(&app, srv.id(), &query, &member_token).await,
+            "Wrong results for '{}'",
+            query
+        );
+    }
+
+    let first = search_server(&app, srv.id(), "q=rust&limit=1", &member_token)
+        .await
+        .json::<Page<SearchResult<Message>>>()
+        .await
+        .expect("failed to unmarshal json into Page<SearchResult<Message>>");
+    assert_eq!(Some(linked.id()), first.next_cursor());
+    assert_eq!(
+        vec![oldest.id()],
+        search_ids(
+            &app,
+            srv.id(),
+            &format!("q=rust&limit=1&cursor={}", linked.id()),
+            &member_token
+        )
+        .await,
+        "The second page was wrong"
+    );
+}
+
+#[actix::test]
+async fn test_search_server_messages_with_mentions() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    app.database
+        .insert_message(general.id(), owner.id(), "no mention", Utc::now())
+        .await;
+    let mentioned = app
+        .client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(json!({"content": "hey @test.user2"}).to_string()),
+        )
+        .await
+        .json::<Message>()
+        .await
+        .expect("failed to unmarshal json into Message");
+
+    assert_eq!(
+        vec![mentioned.id()],
+        search_ids(
+            &app,
+            srv.id(),
+            &format!("mentions={}", member.id()),
+            &owner_token
+        )
+        .await,
+        "Wrong results when filtering by mention"
+    );
+}
+
+#[actix::test]
+async fn test_search_snippets_escape_content() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    app.database
+        .insert_message(
+            general.id(),
+            owner.id(),
+            r#"<b onmouseover="alert(1)">rust</b> & more"#,
+            Utc::now(),
+        )
+        .await;
+
+    for query in [String::from("q=rust"), format!("author_id={}", owner.id())] {
+        let response = search_server(&app, srv.id(), &query, &owner_token).await;
+        assert_eq!(200, response.status());
+        let page = response
+            .json::<Page<SearchResult<Message>>>()
+            .await
+            .expect("failed to unmarshal json into Page<SearchResult<Message>>");
+        let snippet = page.items()[0].snippet();
+        assert!(
+            !snippet.contains("<b") && !snippet.contains('"'),
+            "The snippet for '{}' was not escaped: {}",
+            query,
+            snippet
+        );
+        assert!(snippet.contains("&lt;b"), "Unexpected snippet: {}", snippet);
+    }
+}
+
+#[actix::test]
+async fn test_search_server_messages_failure() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let outsider = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let test_cases = [
+        (
+            String::from("q=%20%3F"),
+            &owner_token,
+            400,
+            "there is nothing to search",
+        ),
+        (
+            format!("q=rust&before={}&after={}", now, now),
+            &owner_token,
+            400,
+            "the date range is empty",
+        ),
+        (
+            String::from("q=rust&has=attachment"),
+            &owner_token,
+            400,
+            "filtering on attachments, which messages cannot have",
+        ),
+        (
+            String::from("has=everything"),
+            &owner_token,
+            400,
+            "the filter is unknown",
+        ),
+        (
+            format!("q=rust&cursor={}", Uuid::new_v4()),
+            &owner_token,
+            400,
+            "the cursor matches no message",
+        ),
+        (
+            String::from("q=rust"),
+            &generate_token(outsider.id()).unwrap(),
+            403,
+            "the user is not a member",
+        ),
+    ];
+    for (query, token, status, case) in test_cases {
+        let response = search_server(&app, srv.id(), &query, token).await;
+        assert_eq!(
+            status,
+            response.status(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+use muttr_server::{
+    domain::{pagination::Page, server::Server},
+    handlers::{server::BASE_PATH, user},
+};
+use uuid::Uuid;
+
+#[actix::test]
+async fn test_get_server_by_id_success() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let server = app.database.insert_server(user.id()).await;
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}/{}", BASE_PATH, server.id())),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await;
+
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 on valid get server by id request",
+    );
+
+    let server_res = match response.json::<Server>().await {
+        Ok(json) => json,
+        Err(e) => panic!(
+            "failed to unmarshal json from api response into Server struct: {:?}",
+            e
+        ),
+    };
+
+    assert_eq!(server, server_res, "The returned server did not match");
+}
+
+#[actix::test]
+async fn test_get_server_by_id_failure() {
+    let mut app = TestApp::spawn().await;
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}/{}", BASE_PATH, Uuid::new_v4())),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await;
+
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 when trying to GET a non-existant server id",
+    );
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let server = app.database.insert_server(user.id()).await;
+
+    app.client
+        .request(
+            Path::DELETE(format!("{}/{}", BASE_PATH, server.id())),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await;
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}/{}", BASE_PATH, server.id())),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await;
+
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 when trying to GET a soft deleted server",
+    );
+}
+
+#[actix::test]
+async fn test_get_servers_by_user_paginates() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let other_user = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+
+    let owned = app.database.insert_server(user.id()).await;
+    let joined = app.database.insert_server(other_user.id()).await;
+    app.database
+        .insert_server_member(joined.id(), user.id())
+        .await;
+    let unrelated = app.database.insert_server(other_user.id()).await;
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}/{}/servers?limit=1", user::BASE_PATH, user.id())),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await;
+
+    assert_eq!(200, response.status(), "The API did not return 200");
+    let first_page = response
+        .json::<Page<Server>>()
+        .await
+        .expect("failed to unmarshal json into Page<Server>");
+    assert_eq!(1, first_page.items().len(), "first page had wrong length");
+    let cursor = first_page
+        .next_cursor()
+        .expect("first page did not return a next_cursor");
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}/servers?limit=1&cursor={}",
+                user::BASE_PATH,
+                user.id(),
+                cursor
+            )),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await;
+
+    assert_eq!(200, response.status(), "The API did not return 200");
+    let second_page = response
+        .json::<Page<Server>>()
+        .await
+        .expect("failed to unmarshal json into Page<Server>");
+    assert_eq!(1, second_page.items().len(), "second page had wrong length");
+    assert_eq!(None, second_page.next_cursor(), "second page was not last");
+
+    let ids: Vec<Uuid> = first_page
+        .items()
+        .iter()
+        .chain(second_page.items())
+        .map(|s| s.id())
+        .collect();
+    assert!(ids.contains(&owned.id()), "owned server was not returned");
+    assert!(ids.contains(&joined.id()), "joined server was not returned");
+    assert!(
+        !ids.contains(&unrelated.id()),
+        "server the user does not belong to was returned"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}/servers?limit=1&cursor={}",
+                user::BASE_PATH,
+                user.id(),
+                Uuid::new_v4()
+            )),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor that matches no server"
+    );
+}
+
+#[actix::test]
+async fn test_search_servers() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let by_name = app
+        .database
+        .insert_server_with_name(user.id(), "Rustaceans", None)
+        .await;
+    let by_description = app
+        .database
+        .insert_server_with_name(user.id(), "Crabs", Some("A place for rust fans"))
+        .await;
+    app.database
+        .insert_server_with_name(user.id(), "Gophers", Some("Go go go"))
+        .await;
+
+    let test_cases = [
+        ("rust", vec![by_description.id(), by_name.id()]),
+        ("RUSTACEAN", vec![by_name.id()]),
+        ("100%", vec![]),
+    ];
+
+    for (q, expected) in test_cases {
+        let response = app
+            .client
+            .request(
+                Path::GET(format!("{}?q={}", BASE_PATH, q.replace('%', "%25"))),
+                &[Header::ContentType(ContentType::Json)],
+                None::<String>,
+            )
+            .await;
+
+        assert_eq!(
+            200,
+            response.status(),
+            "The API did not return 200 when searching for '{}'",
+            q,
+        );
+        let page = response
+            .json::<Page<Server>>()
+            .await
+            .expect("failed to unmarshal json into Page<Server>");
+        let ids: Vec<Uuid> = page.items().iter().map(|s| s.id()).collect();
+        assert_eq!(expected, ids, "wrong servers returned for '{}'", q);
+    }
+}
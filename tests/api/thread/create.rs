@@ -0,0 +1,198 @@
+use chrono::Utc;
+use muttr_server::{
+    domain::{
+        channel::Channel, channel::ChannelKind, message::Message, pagination::Page, thread::Thread,
+    },
+    handlers::{channel, server, thread},
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{send_message, start_thread, start_thread_ok};
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+#[actix::test]
+async fn test_start_thread_success() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "let's discuss", Utc::now())
+        .await;
+
+    let started = start_thread_ok(&app, general.id(), msg.id(), &member_token).await;
+    assert_eq!(general.id(), started.parent_id());
+    assert_eq!(Some(msg.id()), started.starter_message_id());
+    assert_eq!(member.id(), started.owner_id());
+    assert!(!started.is_archived() && !started.locked());
+
+    let fetched = app
+        .client
+        .request(
+            Path::GET(format!("{}/{}", thread::BASE_PATH, started.id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Thread>()
+        .await
+        .expect("failed to unmarshal json into Thread");
+    assert_eq!(started, fetched, "The fetched thread did not match");
+
+    let response = send_message(&app, started.id(), "in the thread", &owner_token).await;
+    assert_eq!(200, response.status(), "Could not post in the thread");
+    let reply = response
+        .json::<Message>()
+        .await
+        .expect("failed to unmarshal json into Message");
+    assert_eq!(started.id(), reply.channel_id());
+
+    let listed = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                general.id(),
+                thread::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Page<Thread>>()
+        .await
+        .expect("failed to unmarshal json into Page<Thread>");
+    assert_eq!(1, listed.items().len(), "The thread was not listed");
+    assert!(listed.items()[0].last_message_at().is_some());
+
+    let channels = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}",
+                server::BASE_PATH,
+                srv.id(),
+                channel::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Vec<Channel>>()
+        .await
+        .expect("failed to unmarshal json into Vec<Channel>");
+    assert!(
+        channels.iter().all(|c| c.kind() != ChannelKind::Thread),
+        "The thread was listed as a server channel"
+    );
+}
+
+#[actix::test]
+async fn test_start_thread_failure() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "let's discuss", Utc::now())
+        .await;
+    let other = app
+        .database
+        .insert_message(general.id(), owner.id(), "another", Utc::now())
+        .await;
+    let started = start_thread_ok(&app, general.id(), msg.id(), &token).await;
+    let in_thread = app
+        .database
+        .insert_message(started.id(), owner.id(), "nested", Utc::now())
+        .await;
+
+    let test_cases = [
+        (
+            general.id(),
+            msg.id(),
+            json!({"name": "again"}),
+            409,
+            "the message already has a thread",
+        ),
+        (
+            general.id(),
+            Uuid::new_v4(),
+            json!({"name": "x"}),
+            404,
+            "the message does not exist",
+        ),
+        (
+            general.id(),
+            other.id(),
+            json!({"name": " "}),
+            400,
+            "the name is blank",
+        ),
+        (
+            general.id(),
+            other.id(),
+            json!({"name": "x", "auto_archive_minutes": 5}),
+            400,
+            "the auto archive duration is not offered",
+        ),
+        (
+            started.id(),
+            in_thread.id(),
+            json!({"name": "x"}),
+            400,
+            "the message is in a thread",
+        ),
+    ];
+
+    for (channel_id, message_id, body, status, case) in test_cases {
+        let response = start_thread(&app, channel_id, message_id, body, &token).await;
+        assert_eq!(
+            status,
+            response.status(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+}
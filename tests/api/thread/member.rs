@@ -0,0 +1,157 @@
+use chrono::Utc;
+use muttr_server::{
+    domain::{
+        channel::ChannelKind, notification::Notification, pagination::Page, thread::ThreadMember,
+    },
+    handlers::{thread, user},
+    utils::jwt::generate_token,
+};
+use uuid::Uuid;
+
+use super::{send_message, start_thread_ok};
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn get_members(app: &TestApp, thread_id: Uuid, token: &str) -> Vec<Uuid> {
+    app.client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}",
+                thread::BASE_PATH,
+                thread_id,
+                thread::MEMBERS_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Vec<ThreadMember>>()
+        .await
+        .expect("failed to unmarshal json into Vec<ThreadMember>")
+        .into_iter()
+        .map(|m| m.user_id())
+        .collect()
+}
+
+async fn set_membership(
+    app: &TestApp,
+    thread_id: Uuid,
+    join: bool,
+    token: &str,
+) -> reqwest::Response {
+    let path = format!(
+        "{}/{}{}/@me",
+        thread::BASE_PATH,
+        thread_id,
+        thread::MEMBERS_PATH
+    );
+    app.client
+        .request(
+            if join {
+                Path::PUT(path)
+            } else {
+                Path::DELETE(path)
+            },
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+}
+
+#[actix::test]
+async fn test_thread_membership() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "let's discuss", Utc::now())
+        .await;
+    let started = start_thread_ok(&app, general.id(), msg.id(), &owner_token).await;
+
+    assert_eq!(
+        vec![owner.id()],
+        get_members(&app, started.id(), &member_token).await
+    );
+
+    let response = send_message(&app, started.id(), "joining in", &member_token).await;
+    assert_eq!(200, response.status());
+    let members = get_members(&app, started.id(), &member_token).await;
+    assert!(members.contains(&member.id()), "The sender was not added");
+
+    let response = set_membership(&app, started.id(), false, &member_token).await;
+    assert_eq!(204, response.status(), "The API did not leave the thread");
+    assert_eq!(
+        vec![owner.id()],
+        get_members(&app, started.id(), &member_token).await
+    );
+
+    // Mass mentions in a thread only reach its members.
+    let response = send_message(&app, started.id(), "@everyone look", &owner_token).await;
+    assert_eq!(200, response.status());
+    let mentions = app
+        .client
+        .request(
+            Path::GET(format!("{}/@me{}", user::BASE_PATH, user::MENTIONS_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(member_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Page<Notification>>()
+        .await
+        .expect("failed to unmarshal json into Page<Notification>");
+    assert!(
+        mentions.items().is_empty(),
+        "A user outside the thread was notified"
+    );
+
+    let response = set_membership(&app, started.id(), true, &member_token).await;
+    assert_eq!(204, response.status(), "The API did not join the thread");
+    let members = get_members(&app, started.id(), &member_token).await;
+    assert!(members.contains(&member.id()), "The user did not rejoin");
+
+    let outsider = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let response = set_membership(
+        &app,
+        started.id(),
+        true,
+        &generate_token(outsider.id()).unwrap(),
+    )
+    .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "A user outside the server found the thread"
+    );
+}
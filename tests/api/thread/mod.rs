@@ -0,0 +1,80 @@
+mod create;
+mod member;
+mod update;
+
+use muttr_server::{
+    domain::thread::Thread,
+    handlers::{channel, message, thread},
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn start_thread(
+    app: &TestApp,
+    channel_id: Uuid,
+    message_id: Uuid,
+    body: Value,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::BASE_PATH,
+                message_id,
+                thread::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(body.to_string()),
+        )
+        .await
+}
+
+async fn start_thread_ok(app: &TestApp, channel_id: Uuid, message_id: Uuid, token: &str) -> Thread {
+    let response = start_thread(
+        app,
+        channel_id,
+        message_id,
+        json!({"name": "a thread"}),
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not start the thread");
+    response
+        .json::<Thread>()
+        .await
+        .expect("failed to unmarshal json into Thread")
+}
+
+async fn send_message(
+    app: &TestApp,
+    channel_id: Uuid,
+    content: &str,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({ "content": content }).to_string()),
+        )
+        .await
+}
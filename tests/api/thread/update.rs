@@ -0,0 +1,209 @@
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::{channel::ChannelKind, pagination::Page, thread::Thread},
+    handlers::{channel, thread},
+    utils::jwt::generate_token,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::{send_message, start_thread_ok};
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn patch_thread(
+    app: &TestApp,
+    thread_id: Uuid,
+    body: Value,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            Path::PATCH(format!("{}/{}", thread::BASE_PATH, thread_id)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(body.to_string()),
+        )
+        .await
+}
+
+async fn list_threads(app: &TestApp, channel_id: Uuid, archived: bool, token: &str) -> Vec<Thread> {
+    app.client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}?archived={}",
+                channel::BASE_PATH,
+                channel_id,
+                thread::BASE_PATH,
+                archived
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Page<Thread>>()
+        .await
+        .expect("failed to unmarshal json into Page<Thread>")
+        .into_items()
+}
+
+#[actix::test]
+async fn test_archive_and_reopen_thread() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "let's discuss", Utc::now())
+        .await;
+    let started = start_thread_ok(&app, general.id(), msg.id(), &member_token).await;
+
+    let response = patch_thread(&app, started.id(), json!({"archived": true}), &member_token).await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The owner could not archive the thread"
+    );
+    let archived = response
+        .json::<Thread>()
+        .await
+        .expect("failed to unmarshal json into Thread");
+    assert!(archived.is_archived());
+
+    let response = send_message(&app, started.id(), "back again", &member_token).await;
+    assert_eq!(200, response.status());
+    assert_eq!(
+        1,
+        list_threads(&app, general.id(), false, &owner_token)
+            .await
+            .len(),
+        "A new message did not reopen the thread"
+    );
+    assert!(list_threads(&app, general.id(), true, &owner_token)
+        .await
+        .is_empty());
+
+    let archived = app
+        .database
+        .archive_inactive_threads(Utc::now() + Duration::days(2))
+        .await;
+    assert_eq!(1, archived.len(), "The inactive thread was not archived");
+    assert!(list_threads(&app, general.id(), false, &owner_token)
+        .await
+        .is_empty());
+    assert_eq!(
+        1,
+        list_threads(&app, general.id(), true, &owner_token)
+            .await
+            .len(),
+        "The archived thread was not listed"
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}?archived=true&cursor={}",
+                channel::BASE_PATH,
+                general.id(),
+                thread::BASE_PATH,
+                msg.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor that is not a thread of the channel"
+    );
+}
+
+#[actix::test]
+async fn test_lock_thread() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let msg = app
+        .database
+        .insert_message(general.id(), owner.id(), "let's discuss", Utc::now())
+        .await;
+    let started = start_thread_ok(&app, general.id(), msg.id(), &member_token).await;
+
+    let response = patch_thread(&app, started.id(), json!({"locked": true}), &member_token).await;
+    assert_eq!(
+        403,
+        response.status(),
+        "A thread owner without MANAGE_MESSAGES locked the thread"
+    );
+
+    let response = patch_thread(&app, started.id(), json!({"locked": true}), &owner_token).await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The server owner could not lock the thread"
+    );
+
+    let response = send_message(&app, started.id(), "still here?", &member_token).await;
+    assert_eq!(403, response.status(), "A member posted in a locked thread");
+    let response = patch_thread(
+        &app,
+        started.id(),
+        json!({"name": "renamed"}),
+        &member_token,
+    )
+    .await;
+    assert_eq!(403, response.status(), "A member edited a locked thread");
+
+    let response = send_message(&app, started.id(), "moderating", &owner_token).await;
+    assert_eq!(
+        200,
+        response.status(),
+        "A moderator could not post in a locked thread"
+    );
+}
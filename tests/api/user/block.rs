@@ -0,0 +1,217 @@
+use muttr_server::{
+    domain::{
+        block::{ServerMute, UserBlock, UserMute},
+        follow::FollowCounts,
+        pagination::Page,
+        user::GetUserResponse,
+    },
+    handlers::user::{
+        BASE_PATH, BLOCKS_PATH, FOLLOW_PATH, ME_PATH, MUTED_SERVERS_PATH, MUTES_PATH,
+    },
+    utils::jwt::generate_token,
+};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn request(app: &TestApp, path: Path<String>, token: Option<&str>) -> reqwest::Response {
+    let mut headers = vec![Header::ContentType(ContentType::Json)];
+    if let Some(token) = token {
+        headers.push(Header::Authorization(token.to_string()));
+    }
+    app.client.request(path, &headers, None::<String>).await
+}
+
+fn list_path(list: &str, id: Option<Uuid>) -> String {
+    let id = id.map(|id| format!("/{}", id)).unwrap_or_default();
+    format!("{}{}{}{}", BASE_PATH, ME_PATH, list, id)
+}
+
+async fn get_user(app: &TestApp, user_id: Uuid, token: Option<&str>) -> reqwest::Response {
+    request(app, Path::GET(format!("{}/{}", BASE_PATH, user_id)), token).await
+}
+
+#[actix::test]
+async fn test_block_hides_profile_and_ends_follows() {
+    let mut app = TestApp::spawn().await;
+
+    let blocker = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let blocked = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let blocker_token = generate_token(blocker.id()).unwrap();
+    let blocked_token = generate_token(blocked.id()).unwrap();
+    app.database
+        .insert_user_follow(blocker.id(), blocked.id())
+        .await;
+    app.database
+        .insert_user_follow(blocked.id(), blocker.id())
+        .await;
+
+    let response = request(
+        &app,
+        Path::PUT(list_path(BLOCKS_PATH, Some(blocked.id()))),
+        Some(&blocker_token),
+    )
+    .await;
+    assert_eq!(204, response.status(), "The API did not block the user");
+    let response = request(
+        &app,
+        Path::PUT(list_path(BLOCKS_PATH, Some(blocker.id()))),
+        Some(&blocker_token),
+    )
+    .await;
+    assert_eq!(400, response.status(), "A user could block themselves");
+
+    let blocks = request(
+        &app,
+        Path::GET(list_path(BLOCKS_PATH, None)),
+        Some(&blocker_token),
+    )
+    .await
+    .json::<Page<UserBlock>>()
+    .await
+    .expect("failed to unmarshal json into Page<UserBlock>");
+    assert_eq!(
+        vec![blocked.id()],
+        blocks
+            .items()
+            .iter()
+            .map(|b| b.blocked_id())
+            .collect::<Vec<Uuid>>()
+    );
+
+    let profile = get_user(&app, blocker.id(), None)
+        .await
+        .json::<GetUserResponse>()
+        .await
+        .expect("failed to unmarshal json into GetUserResponse");
+    assert_eq!(
+        Some(FollowCounts::default()),
+        profile.follow_counts(),
+        "Blocking did not end the follows between the users"
+    );
+    assert_eq!(
+        404,
+        get_user(&app, blocker.id(), Some(&blocked_token))
+            .await
+            .status(),
+        "The blocked user could see the blocker's profile"
+    );
+    assert_eq!(
+        200,
+        get_user(&app, blocked.id(), Some(&blocker_token))
+            .await
+            .status()
+    );
+    let response = request(
+        &app,
+        Path::PUT(format!("{}/{}{}", BASE_PATH, blocker.id(), FOLLOW_PATH)),
+        Some(&blocked_token),
+    )
+    .await;
+    assert_eq!(403, response.status(), "The blocked user could follow");
+
+    let response = request(
+        &app,
+        Path::DELETE(list_path(BLOCKS_PATH, Some(blocked.id()))),
+        Some(&blocker_token),
+    )
+    .await;
+    assert_eq!(204, response.status(), "The API did not unblock the user");
+    assert_eq!(
+        200,
+        get_user(&app, blocker.id(), Some(&blocked_token))
+            .await
+            .status()
+    );
+}
+
+#[actix::test]
+async fn test_manage_mute_lists() {
+    let mut app = TestApp::spawn().await;
+
+    let muter = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let muted = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let token = generate_token(muter.id()).unwrap();
+    let srv = app.database.insert_server(muted.id()).await;
+
+    let test_cases = [
+        (list_path(MUTES_PATH, Some(muted.id())), 204),
+        (list_path(MUTES_PATH, Some(Uuid::new_v4())), 404),
+        (list_path(MUTED_SERVERS_PATH, Some(srv.id())), 204),
+        (list_path(MUTED_SERVERS_PATH, Some(Uuid::new_v4())), 404),
+    ];
+    for (path, status) in test_cases {
+        assert_eq!(
+            status,
+            request(&app, Path::PUT(path.clone()), Some(&token))
+                .await
+                .status()
+                .as_u16(),
+            "The API did not return {} for muting {}",
+            status,
+            path
+        );
+    }
+
+    let mutes = request(&app, Path::GET(list_path(MUTES_PATH, None)), Some(&token))
+        .await
+        .json::<Page<UserMute>>()
+        .await
+        .expect("failed to unmarshal json into Page<UserMute>");
+    assert_eq!(
+        vec![muted.id()],
+        mutes
+            .items()
+            .iter()
+            .map(|m| m.muted_id())
+            .collect::<Vec<Uuid>>()
+    );
+
+    for path in [
+        list_path(MUTES_PATH, Some(muted.id())),
+        list_path(MUTED_SERVERS_PATH, Some(srv.id())),
+    ] {
+        let response = request(&app, Path::DELETE(path), Some(&token)).await;
+        assert_eq!(204, response.status(), "The API did not unmute");
+    }
+    let response = request(
+        &app,
+        Path::GET(format!(
+            "{}?cursor={}",
+            list_path(MUTES_PATH, None),
+            muted.id()
+        )),
+        Some(&token),
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor pointing at a removed mute"
+    );
+    let servers = request(
+        &app,
+        Path::GET(list_path(MUTED_SERVERS_PATH, None)),
+        Some(&token),
+    )
+    .await
+    .json::<Page<ServerMute>>()
+    .await
+    .expect("failed to unmarshal json into Page<ServerMute>");
+    assert!(servers.items().is_empty(), "The server was still muted");
+}
@@ -0,0 +1,710 @@
+use chrono::Utc;
+use muttr_server::{
+    domain::{
+        bookmark::{
+            Bookmark, BookmarkCollection, BookmarkStatus, BookmarkTarget, BookmarkView,
+            MAX_BOOKMARK_NOTE_LENGTH,
+        },
+        channel::ChannelKind,
+        pagination::Page,
+        permission::Permissions,
+        post::PostView,
+    },
+    handlers::{
+        channel, post, server,
+        user::{BASE_PATH, BOOKMARKS_PATH, BOOKMARK_COLLECTIONS_PATH, ME_PATH},
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn request(
+    app: &TestApp,
+    path: Path<String>,
+    body: Option<Value>,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            body.map(|b| b.to_string()),
+        )
+        .await
+}
+
+fn bookmarks_path() -> String {
+    format!("{}{}{}", BASE_PATH, ME_PATH, BOOKMARKS_PATH)
+}
+
+fn collections_path() -> String {
+    format!("{}{}{}", BASE_PATH, ME_PATH, BOOKMARK_COLLECTIONS_PATH)
+}
+
+async fn create_post_ok(app: &TestApp, server_id: Uuid, content: &str, token: &str) -> PostView {
+    let response = request(
+        app,
+        Path::POST(format!(
+            "{}/{}{}",
+            server::BASE_PATH,
+            server_id,
+            post::BASE_PATH
+        )),
+        Some(json!({ "content": content })),
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not create the post");
+    response
+        .json::<PostView>()
+        .await
+        .expect("failed to unmarshal json into PostView")
+}
+
+async fn bookmark_ok(app: &TestApp, body: Value, token: &str) -> Bookmark {
+    let response = request(app, Path::POST(bookmarks_path()), Some(body), token).await;
+    assert_eq!(200, response.status(), "The API did not save the bookmark");
+    response
+        .json::<Bookmark>()
+        .await
+        .expect("failed to unmarshal json into Bookmark")
+}
+
+async fn create_collection_ok(app: &TestApp, name: &str, token: &str) -> BookmarkCollection {
+    let response = request(
+        app,
+        Path::POST(collections_path()),
+        Some(json!({ "name": name })),
+        token,
+    )
+    .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not create the collection"
+    );
+    response
+        .json::<BookmarkCollection>()
+        .await
+        .expect("failed to unmarshal json into BookmarkCollection")
+}
+
+async fn patch_bookmark(
+    app: &TestApp,
+    bookmark_id: Uuid,
+    body: Value,
+    token: &str,
+) -> Result<Bookmark, u16> {
+    let response = request(
+        app,
+        Path::PATCH(format!("{}/{}", bookmarks_path(), bookmark_id)),
+        Some(body),
+        token,
+    )
+    .await;
+    if response.status() != 200 {
+        return Err(response.status().as_u16());
+    }
+    Ok(response
+        .json::<Bookmark>()
+        .await
+        .expect("failed to unmarshal json into Bookmark"))
+}
+
+async fn get_bookmarks_ok(app: &TestApp, query: &str, token: &str) -> Vec<BookmarkView> {
+    let response = request(
+        app,
+        Path::GET(format!("{}{}", bookmarks_path(), query)),
+        None,
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not list bookmarks");
+    response
+        .json::<Page<BookmarkView>>()
+        .await
+        .expect("failed to unmarshal json into Page<BookmarkView>")
+        .into_items()
+}
+
+#[actix::test]
+async fn test_bookmark_and_collection_lifecycle() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let other = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let other_token = generate_token(other.id()).unwrap();
+    let srv = app.database.insert_server(user.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let message = app
+        .database
+        .insert_message(general.id(), user.id(), "worth keeping", Utc::now())
+        .await;
+    let saved_post = create_post_ok(&app, srv.id(), "a post to read later", &token).await;
+
+    let saved_message = bookmark_ok(
+        &app,
+        json!({ "message_id": message.id(), "note": "  reread this " }),
+        &token,
+    )
+    .await;
+    assert_eq!(
+        BookmarkTarget::Message(message.id()),
+        saved_message.target()
+    );
+    assert_eq!(Some("reread this"), saved_message.note());
+    assert_eq!(
+        saved_message.id(),
+        bookmark_ok(&app, json!({ "message_id": message.id() }), &token)
+            .await
+            .id(),
+        "Bookmarking a message twice did not return the existing bookmark"
+    );
+    let post_bookmark = bookmark_ok(&app, json!({ "post_id": saved_post.id() }), &token).await;
+
+    let test_cases = [
+        (json!({}), 400, "no target is given"),
+        (
+            json!({ "post_id": saved_post.id(), "message_id": message.id() }),
+            400,
+            "both targets are given",
+        ),
+        (
+            json!({ "message_id": Uuid::new_v4() }),
+            404,
+            "the message does not exist",
+        ),
+        (
+            json!({ "post_id": saved_post.id(), "collection_id": Uuid::new_v4() }),
+            404,
+            "the collection does not exist",
+        ),
+    ];
+    for (body, status, case) in test_cases {
+        assert_eq!(
+            status,
+            request(&app, Path::POST(bookmarks_path()), Some(body), &token)
+                .await
+                .status()
+                .as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+    assert_eq!(
+        404,
+        request(
+            &app,
+            Path::POST(bookmarks_path()),
+            Some(json!({ "message_id": message.id() })),
+            &other_token,
+        )
+        .await
+        .status()
+        .as_u16(),
+        "A non-member bookmarked a message in a server they cannot see"
+    );
+
+    let response = request(
+        &app,
+        Path::POST(collections_path()),
+        Some(json!({ "name": "reading list" })),
+        &token,
+    )
+    .await;
+    assert_eq!(200, response.status());
+    let collection = response
+        .json::<BookmarkCollection>()
+        .await
+        .expect("failed to unmarshal json into BookmarkCollection");
+    for (name, status) in [("reading list", 409), ("   ", 400)] {
+        assert_eq!(
+            status,
+            request(
+                &app,
+                Path::POST(collections_path()),
+                Some(json!({ "name": name })),
+                &token,
+            )
+            .await
+            .status()
+            .as_u16(),
+            "The API did not return {} for collection name '{}'",
+            status,
+            name
+        );
+    }
+
+    let response = request(
+        &app,
+        Path::PATCH(format!("{}/{}", bookmarks_path(), post_bookmark.id())),
+        Some(json!({ "collection_id": collection.id(), "note": "for the weekend" })),
+        &token,
+    )
+    .await;
+    assert_eq!(200, response.status());
+    assert_eq!(
+        404,
+        request(
+            &app,
+            Path::PATCH(format!("{}/{}", bookmarks_path(), post_bookmark.id())),
+            Some(json!({ "note": "mine now" })),
+            &other_token,
+        )
+        .await
+        .status()
+        .as_u16(),
+        "Another user edited the bookmark"
+    );
+
+    let all = get_bookmarks_ok(&app, "", &token).await;
+    assert_eq!(
+        vec![post_bookmark.id(), saved_message.id()],
+        all.iter().map(BookmarkView::id).collect::<Vec<_>>()
+    );
+    let filed =
+        get_bookmarks_ok(&app, &format!("?collection_id={}", collection.id()), &token).await;
+    assert_eq!(1, filed.len());
+    assert_eq!(Some(collection.id()), filed[0].bookmark.collection_id());
+    assert_eq!(Some("for the weekend"), filed[0].bookmark.note());
+    assert_eq!(
+        Some(saved_post.id()),
+        filed[0].post.as_ref().map(PostView::id)
+    );
+
+    let response = request(
+        &app,
+        Path::PATCH(format!("{}/{}", collections_path(), collection.id())),
+        Some(json!({ "name": "weekend" })),
+        &token,
+    )
+    .await;
+    assert_eq!(200, response.status());
+    let response = request(&app, Path::GET(collections_path()), None, &token).await;
+    let names: Vec<String> = response
+        .json::<Vec<BookmarkCollection>>()
+        .await
+        .expect("failed to unmarshal json into Vec<BookmarkCollection>")
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+    assert_eq!(vec![String::from("weekend")], names);
+
+    let response = request(
+        &app,
+        Path::DELETE(format!("{}/{}", collections_path(), collection.id())),
+        None,
+        &token,
+    )
+    .await;
+    assert_eq!(204, response.status());
+    let unsorted = get_bookmarks_ok(&app, "", &token).await;
+    assert!(
+        unsorted
+            .iter()
+            .all(|b| b.bookmark.collection_id().is_none()),
+        "Deleting a collection did not leave its bookmarks unsorted"
+    );
+
+    for status in [204, 404] {
+        let response = request(
+            &app,
+            Path::DELETE(format!("{}/{}", bookmarks_path(), saved_message.id())),
+            None,
+            &token,
+        )
+        .await;
+        assert_eq!(status, response.status().as_u16());
+    }
+
+    let response = request(
+        &app,
+        Path::GET(format!(
+            "{}?cursor={}",
+            bookmarks_path(),
+            saved_message.id()
+        )),
+        None,
+        &token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor at a removed bookmark"
+    );
+}
+
+#[actix::test]
+async fn test_bookmarks_show_tombstones_and_hide_lost_access() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let staff = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "staff")
+        .await;
+    let visible = app
+        .database
+        .insert_message(general.id(), owner.id(), "still here", Utc::now())
+        .await;
+    let hidden = app
+        .database
+        .insert_message(staff.id(), owner.id(), "soon hidden", Utc::now())
+        .await;
+    let deleted = create_post_ok(&app, srv.id(), "soon deleted", &owner_token).await;
+
+    let deleted_bookmark =
+        bookmark_ok(&app, json!({ "post_id": deleted.id() }), &member_token).await;
+    let hidden_bookmark =
+        bookmark_ok(&app, json!({ "message_id": hidden.id() }), &member_token).await;
+    let visible_bookmark =
+        bookmark_ok(&app, json!({ "message_id": visible.id() }), &member_token).await;
+
+    let response = request(
+        &app,
+        Path::PUT(format!(
+            "{}/{}{}/{}{}/{}",
+            server::BASE_PATH,
+            srv.id(),
+            channel::BASE_PATH,
+            staff.id(),
+            channel::PERMISSIONS_PATH,
+            srv.id()
+        )),
+        Some(json!({"target_type": "role", "deny": Permissions::VIEW_CHANNEL.bits()})),
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status());
+    let response = request(
+        &app,
+        Path::DELETE(format!("{}/{}", post::BASE_PATH, deleted.id())),
+        None,
+        &owner_token,
+    )
+    .await;
+    assert_eq!(200, response.status());
+
+    let views = get_bookmarks_ok(&app, "", &member_token).await;
+    assert_eq!(
+        vec![
+            (visible_bookmark.id(), BookmarkStatus::Available),
+            (hidden_bookmark.id(), BookmarkStatus::Unavailable),
+            (deleted_bookmark.id(), BookmarkStatus::Deleted),
+        ],
+        views.iter().map(|v| (v.id(), v.status)).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(visible.id()),
+        views[0].message.as_ref().map(|m| m.id())
+    );
+    assert!(
+        views[1..]
+            .iter()
+            .all(|v| v.message.is_none() && v.post.is_none()),
+        "A deleted or hidden item was still shown"
+    );
+
+    let owner_view = bookmark_ok(&app, json!({ "message_id": hidden.id() }), &owner_token).await;
+    let views = get_bookmarks_ok(&app, "", &owner_token).await;
+    assert_eq!(owner_view.id(), views[0].id());
+    assert_eq!(
+        BookmarkStatus::Available,
+        views[0].status,
+        "The owner lost access to a channel they can still see"
+    );
+}
+
+#[actix::test]
+async fn test_bookmark_collections_and_notes() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let other = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let other_token = generate_token(other.id()).unwrap();
+    let srv = app.database.insert_server(user.id()).await;
+    let first = create_post_ok(&app, srv.id(), "first", &token).await;
+    let second = create_post_ok(&app, srv.id(), "second", &token).await;
+
+    let reading = create_collection_ok(&app, "reading", &token).await;
+    let recipes = create_collection_ok(&app, "recipes", &token).await;
+    let foreign = create_collection_ok(&app, "reading", &other_token).await;
+
+    let first_bookmark = bookmark_ok(
+        &app,
+        json!({ "post_id": first.id(), "collection_id": reading.id(), "note": "  " }),
+        &token,
+    )
+    .await;
+    assert_eq!(None, first_bookmark.note(), "A blank note was kept");
+    let second_bookmark = bookmark_ok(
+        &app,
+        json!({ "post_id": second.id(), "collection_id": reading.id() }),
+        &token,
+    )
+    .await;
+    assert_eq!(
+        404,
+        request(
+            &app,
+            Path::POST(bookmarks_path()),
+            Some(json!({ "post_id": first.id(), "collection_id": foreign.id() })),
+            &token,
+        )
+        .await
+        .status()
+        .as_u16(),
+        "A bookmark was filed into another user's collection"
+    );
+
+    let moved = patch_bookmark(
+        &app,
+        second_bookmark.id(),
+        json!({ "collection_id": recipes.id(), "note": "try on sunday" }),
+        &token,
+    )
+    .await
+    .expect("The API did not move the bookmark");
+    assert_eq!(Some(recipes.id()), moved.collection_id());
+    for (collection, expected) in [
+        (reading.id(), first_bookmark.id()),
+        (recipes.id(), second_bookmark.id()),
+    ] {
+        assert_eq!(
+            vec![expected],
+            get_bookmarks_ok(&app, &format!("?collection_id={}", collection), &token)
+                .await
+                .iter()
+                .map(BookmarkView::id)
+                .collect::<Vec<_>>(),
+            "The collection held the wrong bookmarks"
+        );
+    }
+
+    let test_cases = [
+        (
+            json!({ "note": "a".repeat(MAX_BOOKMARK_NOTE_LENGTH + 1) }),
+            400,
+            "the note is too long",
+        ),
+        (
+            json!({ "collection_id": foreign.id() }),
+            404,
+            "the collection belongs to another user",
+        ),
+        (
+            json!({ "collection_id": Uuid::new_v4() }),
+            404,
+            "the collection does not exist",
+        ),
+    ];
+    for (body, status, case) in test_cases {
+        assert_eq!(
+            Err(status),
+            patch_bookmark(&app, second_bookmark.id(), body, &token).await,
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+
+    let unfiled = patch_bookmark(
+        &app,
+        second_bookmark.id(),
+        json!({ "collection_id": null }),
+        &token,
+    )
+    .await
+    .expect("The API did not unfile the bookmark");
+    assert_eq!(None, unfiled.collection_id());
+    assert_eq!(
+        Some("try on sunday"),
+        unfiled.note(),
+        "Unfiling the bookmark changed its note"
+    );
+    let cleared = patch_bookmark(&app, second_bookmark.id(), json!({ "note": null }), &token)
+        .await
+        .expect("The API did not clear the note");
+    assert_eq!(None, cleared.note());
+
+    for path in [
+        Path::PATCH(format!("{}/{}", collections_path(), foreign.id())),
+        Path::DELETE(format!("{}/{}", collections_path(), foreign.id())),
+        Path::GET(format!(
+            "{}?collection_id={}",
+            bookmarks_path(),
+            foreign.id()
+        )),
+    ] {
+        assert_eq!(
+            404,
+            request(&app, path, Some(json!({ "name": "mine" })), &token)
+                .await
+                .status()
+                .as_u16(),
+            "A user reached another user's collection"
+        );
+    }
+    let response = request(&app, Path::GET(collections_path()), None, &other_token).await;
+    assert_eq!(
+        vec![foreign.id()],
+        response
+            .json::<Vec<BookmarkCollection>>()
+            .await
+            .expect("failed to unmarshal json into Vec<BookmarkCollection>")
+            .iter()
+            .map(|c| c.id())
+            .collect::<Vec<_>>(),
+        "Another user's collections were listed"
+    );
+}
+
+#[actix::test]
+async fn test_bookmarks_follow_server_membership() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let message = app
+        .database
+        .insert_message(general.id(), owner.id(), "members only", Utc::now())
+        .await;
+    let saved_post = create_post_ok(&app, srv.id(), "members only too", &owner_token).await;
+
+    let message_bookmark =
+        bookmark_ok(&app, json!({ "message_id": message.id() }), &member_token).await;
+    let post_bookmark =
+        bookmark_ok(&app, json!({ "post_id": saved_post.id() }), &member_token).await;
+
+    app.database
+        .remove_server_member(srv.id(), member.id())
+        .await;
+    let views = get_bookmarks_ok(&app, "", &member_token).await;
+    assert_eq!(
+        vec![
+            (post_bookmark.id(), BookmarkStatus::Unavailable),
+            (message_bookmark.id(), BookmarkStatus::Unavailable),
+        ],
+        views.iter().map(|v| (v.id(), v.status)).collect::<Vec<_>>(),
+        "Bookmarks stayed available after leaving the server"
+    );
+    assert!(
+        views
+            .iter()
+            .all(|v| v.message.is_none() && v.post.is_none()),
+        "A bookmark showed content from a server the user left"
+    );
+    assert_eq!(
+        403,
+        request(
+            &app,
+            Path::POST(bookmarks_path()),
+            Some(json!({ "post_id": saved_post.id() })),
+            &member_token,
+        )
+        .await
+        .status()
+        .as_u16(),
+        "A post was bookmarked from a server the user left"
+    );
+    let noted = patch_bookmark(
+        &app,
+        post_bookmark.id(),
+        json!({ "note": "ask to rejoin" }),
+        &member_token,
+    )
+    .await
+    .expect("The API did not let the user annotate an unavailable bookmark");
+    assert_eq!(Some("ask to rejoin"), noted.note());
+
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let views = get_bookmarks_ok(&app, "", &member_token).await;
+    assert!(
+        views.iter().all(|v| v.status == BookmarkStatus::Available),
+        "Bookmarks stayed unavailable after rejoining the server"
+    );
+    assert_eq!(
+        Some(saved_post.id()),
+        views[0].post.as_ref().map(PostView::id)
+    );
+    assert_eq!(
+        Some(message.id()),
+        views[1].message.as_ref().map(|m| m.id())
+    );
+
+    assert_eq!(
+        204,
+        request(
+            &app,
+            Path::DELETE(format!("{}/{}", bookmarks_path(), message_bookmark.id())),
+            None,
+            &member_token,
+        )
+        .await
+        .status()
+        .as_u16()
+    );
+}
@@ -0,0 +1,315 @@
+use muttr_server::{
+    domain::{
+        follow::{Follow, FollowCounts, FollowRequest, FollowResponse, FollowStatus},
+        pagination::Page,
+        user::GetUserResponse,
+    },
+    handlers::user::{
+        BASE_PATH, FOLLOWERS_PATH, FOLLOWING_PATH, FOLLOW_PATH, FOLLOW_REQUESTS_PATH, ME_PATH,
+        SETTINGS_PATH,
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn request(
+    app: &TestApp,
+    path: Path<String>,
+    body: Option<Value>,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            body.map(|b| b.to_string()),
+        )
+        .await
+}
+
+async fn follow(app: &TestApp, target_id: Uuid, token: &str) -> reqwest::Response {
+    request(
+        app,
+        Path::PUT(format!("{}/{}{}", BASE_PATH, target_id, FOLLOW_PATH)),
+        None,
+        token,
+    )
+    .await
+}
+
+async fn follow_ok(app: &TestApp, target_id: Uuid, token: &str) -> FollowStatus {
+    let response = follow(app, target_id, token).await;
+    assert_eq!(200, response.status(), "The API did not follow the user");
+    response
+        .json::<FollowResponse>()
+        .await
+        .expect("failed to unmarshal json into FollowResponse")
+        .status
+}
+
+async fn list_ids(app: &TestApp, path: String, token: &str) -> Result<Vec<Uuid>, u16> {
+    let followers = path.ends_with(FOLLOWERS_PATH);
+    let response = request(app, Path::GET(path), None, token).await;
+    if response.status() != 200 {
+        return Err(response.status().as_u16());
+    }
+    let page = response
+        .json::<Page<Follow>>()
+        .await
+        .expect("failed to unmarshal json into Page<Follow>");
+    Ok(page
+        .items()
+        .iter()
+        .map(|f| {
+            if followers {
+                f.follower_id()
+            } else {
+                f.followee_id()
+            }
+        })
+        .collect())
+}
+
+async fn follow_counts(app: &TestApp, user_id: Uuid) -> FollowCounts {
+    app.client
+        .request(
+            Path::GET(format!("{}/{}", BASE_PATH, user_id)),
+            &[Header::ContentType(ContentType::Json)],
+            None::<String>,
+        )
+        .await
+        .json::<GetUserResponse>()
+        .await
+        .expect("failed to unmarshal json into GetUserResponse")
+        .follow_counts()
+        .expect("The follow counts were hidden")
+}
+
+async fn set_private(app: &TestApp, is_private: bool, token: &str) {
+    let response = request(
+        app,
+        Path::PATCH(format!("{}{}{}", BASE_PATH, ME_PATH, SETTINGS_PATH)),
+        Some(json!({ "is_private": is_private })),
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not update settings");
+}
+
+fn follow_request_path(requester_id: Option<Uuid>) -> String {
+    let requester = requester_id
+        .map(|id| format!("/{}", id))
+        .unwrap_or_default();
+    format!(
+        "{}{}{}{}",
+        BASE_PATH, ME_PATH, FOLLOW_REQUESTS_PATH, requester
+    )
+}
+
+#[actix::test]
+async fn test_follow_and_unfollow() {
+    let mut app = TestApp::spawn().await;
+
+    let follower = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let followee = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let blocker = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let token = generate_token(follower.id()).unwrap();
+    app.database
+        .insert_user_block(blocker.id(), follower.id())
+        .await;
+
+    for _ in 0..2 {
+        assert_eq!(
+            FollowStatus::Following,
+            follow_ok(&app, followee.id(), &token).await,
+            "Following twice was not idempotent"
+        );
+    }
+    assert_eq!(
+        FollowCounts {
+            follower_count: 1,
+            following_count: 0
+        },
+        follow_counts(&app, followee.id()).await
+    );
+    assert_eq!(
+        FollowCounts {
+            follower_count: 0,
+            following_count: 1
+        },
+        follow_counts(&app, follower.id()).await
+    );
+    assert_eq!(
+        Ok(vec![follower.id()]),
+        list_ids(
+            &app,
+            format!("{}/{}{}", BASE_PATH, followee.id(), FOLLOWERS_PATH),
+            &token
+        )
+        .await
+    );
+    assert_eq!(
+        Ok(vec![followee.id()]),
+        list_ids(
+            &app,
+            format!("{}/{}{}", BASE_PATH, follower.id(), FOLLOWING_PATH),
+            &token
+        )
+        .await
+    );
+
+    let test_cases = [
+        (follower.id(), 400, "following yourself"),
+        (Uuid::new_v4(), 404, "following a missing user"),
+        (blocker.id(), 403, "following a user who blocked you"),
+    ];
+    for (target_id, status, case) in test_cases {
+        assert_eq!(
+            status,
+            follow(&app, target_id, &token).await.status().as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+
+    let response = request(
+        &app,
+        Path::DELETE(format!("{}/{}{}", BASE_PATH, followee.id(), FOLLOW_PATH)),
+        None,
+        &token,
+    )
+    .await;
+    assert_eq!(204, response.status(), "The API did not unfollow the user");
+    assert_eq!(
+        FollowCounts::default(),
+        follow_counts(&app, followee.id()).await
+    );
+    assert_eq!(
+        Err(400),
+        list_ids(
+            &app,
+            format!(
+                "{}/{}{}?cursor={}",
+                BASE_PATH,
+                followee.id(),
+                FOLLOWERS_PATH,
+                follower.id()
+            ),
+            &token
+        )
+        .await,
+        "The API did not reject a cursor at a user who no longer follows"
+    );
+}
+
+#[actix::test]
+async fn test_private_accounts_approve_followers() {
+    let mut app = TestApp::spawn().await;
+
+    let target = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let accepted = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let rejected = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let late = app
+        .database
+        .insert_user("testuser4@email.com", "test.user4", true)
+        .await;
+    let target_token = generate_token(target.id()).unwrap();
+    let accepted_token = generate_token(accepted.id()).unwrap();
+    let rejected_token = generate_token(rejected.id()).unwrap();
+    let late_token = generate_token(late.id()).unwrap();
+    let followers_path = format!("{}/{}{}", BASE_PATH, target.id(), FOLLOWERS_PATH);
+
+    set_private(&app, true, &target_token).await;
+    for token in [&accepted_token, &rejected_token] {
+        assert_eq!(
+            FollowStatus::Requested,
+            follow_ok(&app, target.id(), token).await
+        );
+    }
+
+    let response = request(
+        &app,
+        Path::GET(follow_request_path(None)),
+        None,
+        &target_token,
+    )
+    .await;
+    let requests = response
+        .json::<Page<FollowRequest>>()
+        .await
+        .expect("failed to unmarshal json into Page<FollowRequest>");
+    assert_eq!(
+        vec![rejected.id(), accepted.id()],
+        requests
+            .items()
+            .iter()
+            .map(|r| r.requester_id())
+            .collect::<Vec<Uuid>>(),
+        "The pending requests were not listed newest first"
+    );
+    assert_eq!(
+        Err(403),
+        list_ids(&app, followers_path.clone(), &accepted_token).await,
+        "A pending follower could see a private account's followers"
+    );
+
+    let response = request(
+        &app,
+        Path::PUT(follow_request_path(Some(accepted.id()))),
+        None,
+        &target_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not accept the request");
+    assert_eq!(
+        Ok(vec![accepted.id()]),
+        list_ids(&app, followers_path.clone(), &accepted_token).await
+    );
+
+    for status in [204, 404] {
+        let response = request(
+            &app,
+            Path::DELETE(follow_request_path(Some(rejected.id()))),
+            None,
+            &target_token,
+        )
+        .await;
+        assert_eq!(status, response.status().as_u16());
+    }
+
+    follow_ok(&app, target.id(), &late_token).await;
+    set_private(&app, false, &target_token).await;
+    assert_eq!(
+        Ok(vec![late.id(), accepted.id()]),
+        list_ids(&app, followers_path, &target_token).await,
+        "Making the account public did not accept pending requests"
+    );
+}
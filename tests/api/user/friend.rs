@@ -0,0 +1,361 @@
+use muttr_server::{
+    domain::{
+        friend::{FriendRequest, FriendRequestResponse, FriendStatus, Friendship, Mutuals},
+        pagination::Page,
+    },
+    handlers::{
+        dm,
+        user::{
+            BASE_PATH, BLOCKS_PATH, FRIENDS_PATH, FRIEND_REQUESTS_PATH, FRIEND_REQUEST_PATH,
+            ME_PATH, MUTUALS_PATH, SETTINGS_PATH,
+        },
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn request(
+    app: &TestApp,
+    path: Path<String>,
+    body: Option<Value>,
+    token: &str,
+) -> reqwest::Response {
+    app.client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            body.map(|b| b.to_string()),
+        )
+        .await
+}
+
+fn friend_request_path(target_id: Uuid) -> String {
+    format!("{}/{}{}", BASE_PATH, target_id, FRIEND_REQUEST_PATH)
+}
+
+async fn send_friend_request(app: &TestApp, target_id: Uuid, token: &str) -> reqwest::Response {
+    request(app, Path::PUT(friend_request_path(target_id)), None, token).await
+}
+
+async fn send_friend_request_ok(app: &TestApp, target_id: Uuid, token: &str) -> FriendStatus {
+    let response = send_friend_request(app, target_id, token).await;
+    assert_eq!(200, response.status(), "The API did not send the request");
+    response
+        .json::<FriendRequestResponse>()
+        .await
+        .expect("failed to unmarshal json into FriendRequestResponse")
+        .status
+}
+
+async fn request_ids(app: &TestApp, direction: &str, token: &str) -> Vec<Uuid> {
+    let response = request(
+        app,
+        Path::GET(format!(
+            "{}{}{}?direction={}",
+            BASE_PATH, ME_PATH, FRIEND_REQUESTS_PATH, direction
+        )),
+        None,
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not list requests");
+    response
+        .json::<Page<FriendRequest>>()
+        .await
+        .expect("failed to unmarshal json into Page<FriendRequest>")
+        .items()
+        .iter()
+        .map(|r| {
+            if direction == "outgoing" {
+                r.recipient_id()
+            } else {
+                r.sender_id()
+            }
+        })
+        .collect()
+}
+
+async fn friend_ids(app: &TestApp, token: &str) -> Vec<Uuid> {
+    let response = request(
+        app,
+        Path::GET(format!("{}{}{}", BASE_PATH, ME_PATH, FRIENDS_PATH)),
+        None,
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not list friends");
+    response
+        .json::<Page<Friendship>>()
+        .await
+        .expect("failed to unmarshal json into Page<Friendship>")
+        .items()
+        .iter()
+        .map(|f| f.friend_id())
+        .collect()
+}
+
+async fn open_direct(app: &TestApp, recipient_id: Uuid, token: &str) -> u16 {
+    request(
+        app,
+        Path::POST(dm::BASE_PATH.to_string()),
+        Some(json!({ "recipient_id": recipient_id })),
+        token,
+    )
+    .await
+    .status()
+    .as_u16()
+}
+
+#[actix::test]
+async fn test_friend_request_lifecycle() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let carol = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let blocker = app
+        .database
+        .insert_user("testuser4@email.com", "test.user4", true)
+        .await;
+    let alice_token = generate_token(alice.id()).unwrap();
+    let bob_token = generate_token(bob.id()).unwrap();
+    let carol_token = generate_token(carol.id()).unwrap();
+    app.database
+        .insert_user_block(blocker.id(), alice.id())
+        .await;
+
+    for _ in 0..2 {
+        assert_eq!(
+            FriendStatus::Requested,
+            send_friend_request_ok(&app, bob.id(), &alice_token).await,
+            "Sending a friend request twice was not idempotent"
+        );
+    }
+    assert_eq!(
+        vec![bob.id()],
+        request_ids(&app, "outgoing", &alice_token).await
+    );
+    assert_eq!(
+        vec![alice.id()],
+        request_ids(&app, "incoming", &bob_token).await
+    );
+
+    assert_eq!(
+        FriendStatus::Friends,
+        send_friend_request_ok(&app, alice.id(), &bob_token).await,
+        "A request back to the sender did not accept theirs"
+    );
+    assert!(request_ids(&app, "incoming", &bob_token).await.is_empty());
+    assert_eq!(vec![bob.id()], friend_ids(&app, &alice_token).await);
+    assert_eq!(vec![alice.id()], friend_ids(&app, &bob_token).await);
+
+    let test_cases = [
+        (alice.id(), 400, "befriending yourself"),
+        (Uuid::new_v4(), 404, "befriending a missing user"),
+        (blocker.id(), 403, "befriending a user who blocked you"),
+    ];
+    for (target_id, status, case) in test_cases {
+        assert_eq!(
+            status,
+            send_friend_request(&app, target_id, &alice_token)
+                .await
+                .status()
+                .as_u16(),
+            "The API did not return {} when {}",
+            status,
+            case
+        );
+    }
+
+    send_friend_request_ok(&app, alice.id(), &carol_token).await;
+    for status in [204, 404] {
+        let response = request(
+            &app,
+            Path::DELETE(format!(
+                "{}{}{}/{}",
+                BASE_PATH,
+                ME_PATH,
+                FRIEND_REQUESTS_PATH,
+                carol.id()
+            )),
+            None,
+            &alice_token,
+        )
+        .await;
+        assert_eq!(status, response.status().as_u16(), "Declining failed");
+    }
+
+    send_friend_request_ok(&app, carol.id(), &bob_token).await;
+    let response = request(
+        &app,
+        Path::DELETE(friend_request_path(carol.id())),
+        None,
+        &bob_token,
+    )
+    .await;
+    assert_eq!(204, response.status(), "The API did not cancel the request");
+    assert!(request_ids(&app, "incoming", &carol_token).await.is_empty());
+
+    send_friend_request_ok(&app, bob.id(), &carol_token).await;
+    let response = request(
+        &app,
+        Path::PUT(format!(
+            "{}{}{}/{}",
+            BASE_PATH,
+            ME_PATH,
+            FRIEND_REQUESTS_PATH,
+            carol.id()
+        )),
+        None,
+        &bob_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not accept the request");
+    assert_eq!(
+        vec![carol.id(), alice.id()],
+        friend_ids(&app, &bob_token).await
+    );
+
+    let response = request(
+        &app,
+        Path::DELETE(format!(
+            "{}{}{}/{}",
+            BASE_PATH,
+            ME_PATH,
+            FRIENDS_PATH,
+            bob.id()
+        )),
+        None,
+        &alice_token,
+    )
+    .await;
+    assert_eq!(204, response.status(), "The API did not remove the friend");
+    assert!(friend_ids(&app, &alice_token).await.is_empty());
+    assert_eq!(vec![carol.id()], friend_ids(&app, &bob_token).await);
+
+    let response = request(
+        &app,
+        Path::GET(format!(
+            "{}{}{}?cursor={}",
+            BASE_PATH,
+            ME_PATH,
+            FRIENDS_PATH,
+            alice.id()
+        )),
+        None,
+        &bob_token,
+    )
+    .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor at a removed friend"
+    );
+}
+
+#[actix::test]
+async fn test_mutuals_and_friends_only_dms() {
+    let mut app = TestApp::spawn().await;
+
+    let alice = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let bob = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let carol = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let alice_token = generate_token(alice.id()).unwrap();
+    let bob_token = generate_token(bob.id()).unwrap();
+    let carol_token = generate_token(carol.id()).unwrap();
+
+    let shared = app.database.insert_server(alice.id()).await;
+    let unshared = app.database.insert_server(carol.id()).await;
+    app.database
+        .insert_server_member(shared.id(), bob.id())
+        .await;
+    app.database
+        .insert_server_member(unshared.id(), alice.id())
+        .await;
+    app.database.insert_friendship(alice.id(), carol.id()).await;
+    app.database.insert_friendship(carol.id(), bob.id()).await;
+
+    let response = request(
+        &app,
+        Path::GET(format!("{}/{}{}", BASE_PATH, bob.id(), MUTUALS_PATH)),
+        None,
+        &alice_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not return mutuals");
+    assert_eq!(
+        Mutuals {
+            friend_ids: vec![carol.id()],
+            server_ids: vec![shared.id()],
+        },
+        response
+            .json::<Mutuals>()
+            .await
+            .expect("failed to unmarshal json into Mutuals")
+    );
+
+    let response = request(
+        &app,
+        Path::PATCH(format!("{}{}{}", BASE_PATH, ME_PATH, SETTINGS_PATH)),
+        Some(json!({ "dm_privacy": "friends" })),
+        &bob_token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not update settings");
+    assert_eq!(
+        403,
+        open_direct(&app, bob.id(), &alice_token).await,
+        "A non-friend could DM a friends-only user"
+    );
+    assert_eq!(
+        200,
+        open_direct(&app, bob.id(), &carol_token).await,
+        "A friend could not DM a friends-only user"
+    );
+
+    let response = request(
+        &app,
+        Path::PUT(format!(
+            "{}{}{}/{}",
+            BASE_PATH,
+            ME_PATH,
+            BLOCKS_PATH,
+            carol.id()
+        )),
+        None,
+        &bob_token,
+    )
+    .await;
+    assert_eq!(204, response.status(), "The API did not block the user");
+    assert!(
+        friend_ids(&app, &bob_token).await.is_empty(),
+        "Blocking a friend did not end the friendship"
+    );
+}
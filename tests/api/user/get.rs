@@ -51,7 +51,7 @@ async fn test_get_by_id_success() {
         };
 
         assert_eq!(user.id(), user_res.id(), "id does not match",);
-        assert_eq!(user.email(), user_res.email(), "email does not match",);
+        assert_eq!(None, user_res.email(), "the public view exposed the email",);
         assert_eq!(user.handle(), user_res.handle(), "handle does not match",);
         assert_eq!(user.name(), user_res.name(), "name does not match",);
         assert_eq!(
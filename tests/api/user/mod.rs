@@ -1,7 +1,15 @@
+mod block;
+mod bookmark;
 mod confirm;
 mod delete;
+mod follow;
+mod friend;
 mod get;
 mod login;
 mod patch;
+mod presence;
+mod privacy;
+mod schedule;
 mod signup;
+mod unread;
 mod update;
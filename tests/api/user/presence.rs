@@ -0,0 +1,341 @@
+use awc::ws::CloseCode;
+use muttr_server::{
+    domain::presence::{Presence, PresenceStatus},
+    gateway::GatewayEvent,
+    handlers::user::{BASE_PATH, BLOCKS_PATH, ME_PATH, PRESENCE_PATH, SETTINGS_PATH},
+    utils::jwt::generate_token,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    gateway::GatewayClient,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn get_presence(app: &TestApp, user_id: Uuid, token: &str) -> Presence {
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}/{}{}", BASE_PATH, user_id, PRESENCE_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 on get presence: {}",
+        response.text().await.unwrap_or_default(),
+    );
+    response
+        .json::<Presence>()
+        .await
+        .expect("Failed to parse presence response")
+}
+
+async fn next_presence(client: &mut GatewayClient) -> Presence {
+    match client.next_event().await {
+        (_, GatewayEvent::PresenceUpdate(presence)) => presence,
+        (_, event) => panic!("Expected PRESENCE_UPDATE, got {:?}", event),
+    }
+}
+
+#[actix::test]
+async fn test_presence_follows_connections() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let observer = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(user.id()).await;
+    app.database
+        .insert_server_member(srv.id(), observer.id())
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let observer_token = generate_token(observer.id()).unwrap();
+
+    assert_eq!(
+        Presence::offline(user.id()),
+        get_presence(&app, user.id(), &observer_token).await,
+        "A user without connections was not offline"
+    );
+
+    let (mut watcher, _) = GatewayClient::identify(&app, &observer_token).await;
+    let (client, _) = GatewayClient::identify(&app, &token).await;
+
+    let online = Presence::new(user.id(), PresenceStatus::Online, None);
+    assert_eq!(
+        online,
+        next_presence(&mut watcher).await,
+        "A co-member was not told the user came online"
+    );
+    assert_eq!(
+        online,
+        get_presence(&app, user.id(), &observer_token).await,
+        "A connected user was not online"
+    );
+
+    client.close().await;
+    assert_eq!(
+        Presence::offline(user.id()),
+        next_presence(&mut watcher).await,
+        "A co-member was not told the user went offline"
+    );
+}
+
+#[actix::test]
+async fn test_invisible_users_appear_offline_to_others() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let observer = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let srv = app.database.insert_server(user.id()).await;
+    app.database
+        .insert_server_member(srv.id(), observer.id())
+        .await;
+    let token = generate_token(user.id()).unwrap();
+    let observer_token = generate_token(observer.id()).unwrap();
+
+    let (mut client, _) = GatewayClient::identify(&app, &token).await;
+    let (mut watcher, _) = GatewayClient::identify(&app, &observer_token).await;
+    assert_eq!(
+        Presence::new(observer.id(), PresenceStatus::Online, None),
+        next_presence(&mut client).await,
+        "The user was not told the co-member came online"
+    );
+
+    client
+        .send(json!({
+            "op": "presence_update",
+            "status": "dnd",
+            "custom_status": {"text": "focusing", "expires_at": null},
+        }))
+        .await;
+    let dnd = next_presence(&mut watcher).await;
+    assert_eq!(PresenceStatus::Dnd, dnd.status(), "The status was not dnd");
+    assert_eq!(
+        Some(String::from("focusing")),
+        dnd.custom_status().map(|c| c.text()),
+        "The custom status was not broadcast"
+    );
+    assert_eq!(
+        dnd,
+        next_presence(&mut client).await,
+        "The user's own session was not told about the change"
+    );
+
+    client
+        .send(json!({"op": "presence_update", "status": "invisible", "custom_status": null}))
+        .await;
+    assert_eq!(
+        Presence::offline(user.id()),
+        next_presence(&mut watcher).await,
+        "An invisible user did not appear offline to a co-member"
+    );
+    assert_eq!(
+        Presence::offline(user.id()),
+        get_presence(&app, user.id(), &observer_token).await,
+        "An invisible user did not appear offline over REST"
+    );
+    assert_eq!(
+        Presence::new(user.id(), PresenceStatus::Invisible, None),
+        get_presence(&app, user.id(), &token).await,
+        "An invisible user did not see their own status"
+    );
+}
+
+async fn send(app: &TestApp, path: Path<String>, body: Option<Value>, token: &str) {
+    let response = app
+        .client
+        .request(
+            path,
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            body.map(|b| b.to_string()),
+        )
+        .await;
+    assert!(
+        response.status().is_success(),
+        "The API did not accept the request: {}",
+        response.text().await.unwrap_or_default(),
+    );
+}
+
+#[actix::test]
+async fn test_presence_respects_visibility_and_blocks() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let friend = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let observer = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let srv = app.database.insert_server(user.id()).await;
+    for member_id in [friend.id(), observer.id()] {
+        app.database.insert_server_member(srv.id(), member_id).await;
+    }
+    app.database.insert_friendship(user.id(), friend.id()).await;
+    let token = generate_token(user.id()).unwrap();
+    let friend_token = generate_token(friend.id()).unwrap();
+    let observer_token = generate_token(observer.id()).unwrap();
+    let settings_path = format!("{}{}{}", BASE_PATH, ME_PATH, SETTINGS_PATH);
+    send(
+        &app,
+        Path::PATCH(settings_path.clone()),
+        Some(json!({"presence_visibility": "friends"})),
+        &token,
+    )
+    .await;
+
+    let (mut watcher, _) = GatewayClient::identify(&app, &observer_token).await;
+    let (mut friend_client, _) = GatewayClient::identify(&app, &friend_token).await;
+    assert_eq!(
+        Presence::new(friend.id(), PresenceStatus::Online, None),
+        next_presence(&mut watcher).await,
+        "The co-member was not told the friend came online"
+    );
+    let (_client, _) = GatewayClient::identify(&app, &token).await;
+    let online = Presence::new(user.id(), PresenceStatus::Online, None);
+    assert_eq!(
+        online,
+        next_presence(&mut friend_client).await,
+        "A friend was not told the user came online"
+    );
+    assert_eq!(
+        Presence::offline(user.id()),
+        get_presence(&app, user.id(), &observer_token).await,
+        "A user hiding their presence was online to a non-friend"
+    );
+
+    friend_client.close().await;
+    assert_eq!(
+        Presence::offline(friend.id()),
+        next_presence(&mut watcher).await,
+        "A non-friend was told the user came online"
+    );
+
+    send(
+        &app,
+        Path::PATCH(settings_path),
+        Some(json!({"presence_visibility": "everyone"})),
+        &token,
+    )
+    .await;
+    assert_eq!(
+        online,
+        next_presence(&mut watcher).await,
+        "The co-member was not shown the user once they could see them"
+    );
+
+    send(
+        &app,
+        Path::PUT(format!(
+            "{}{}{}/{}",
+            BASE_PATH,
+            ME_PATH,
+            BLOCKS_PATH,
+            observer.id()
+        )),
+        None,
+        &token,
+    )
+    .await;
+    assert_eq!(
+        Presence::offline(user.id()),
+        next_presence(&mut watcher).await,
+        "A blocked co-member still saw the user online"
+    );
+}
+
+#[actix::test]
+async fn test_presence_update_rejects_invalid_input() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+
+    let test_cases = [
+        (
+            json!({"op": "presence_update", "status": "offline", "custom_status": null}),
+            "the status is offline",
+        ),
+        (
+            json!({
+                "op": "presence_update",
+                "status": "online",
+                "custom_status": {"text": "", "expires_at": null},
+            }),
+            "the custom status is empty",
+        ),
+        (
+            json!({
+                "op": "presence_update",
+                "status": "online",
+                "custom_status": {"text": "brb", "expires_at": "2000-01-01T00:00:00Z"},
+            }),
+            "the custom status has expired",
+        ),
+    ];
+
+    for (frame, case) in test_cases {
+        let (mut client, _) = GatewayClient::identify(&app, &token).await;
+        client.send(frame).await;
+        assert_eq!(
+            Some(CloseCode::Invalid),
+            client.expect_close().await.map(|r| r.code),
+            "The gateway did not close the connection when {}",
+            case
+        );
+    }
+}
+
+#[actix::test]
+async fn test_get_presence_of_unknown_user_returns_404() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}/{}{}", BASE_PATH, Uuid::new_v4(), PRESENCE_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(generate_token(user.id()).unwrap()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(404, response.status(), "The API did not return 404");
+}
@@ -0,0 +1,224 @@
+use muttr_server::{
+    domain::{
+        presence::{Presence, PresenceStatus},
+        user::{GetUserResponse, UserSettings},
+    },
+    handlers::user::{
+        PatchUserRequestBody, BASE_PATH, FOLLOWERS_PATH, ME_PATH, PRESENCE_PATH, SETTINGS_PATH,
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    gateway::GatewayClient,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn request(
+    app: &TestApp,
+    path: Path<String>,
+    body: Option<Value>,
+    token: Option<&str>,
+) -> reqwest::Response {
+    let mut headers = vec![Header::ContentType(ContentType::Json)];
+    if let Some(token) = token {
+        headers.push(Header::Authorization(token.to_string()));
+    }
+    app.client
+        .request(path, &headers, body.map(|b| b.to_string()))
+        .await
+}
+
+async fn get_user(app: &TestApp, user_id: Uuid, token: Option<&str>) -> GetUserResponse {
+    let response = request(
+        app,
+        Path::GET(format!("{}/{}", BASE_PATH, user_id)),
+        None,
+        token,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not return the user");
+    response
+        .json::<GetUserResponse>()
+        .await
+        .expect("failed to unmarshal json into GetUserResponse")
+}
+
+async fn status(app: &TestApp, path: String, token: Option<&str>) -> u16 {
+    request(app, Path::GET(path), None, token)
+        .await
+        .status()
+        .as_u16()
+}
+
+#[actix::test]
+async fn test_get_me_includes_email_and_settings() {
+    let mut app = TestApp::spawn().await;
+
+    let user = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let token = generate_token(user.id()).unwrap();
+
+    let response = request(
+        &app,
+        Path::GET(format!("{}{}", BASE_PATH, ME_PATH)),
+        None,
+        Some(&token),
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not return the user");
+    let me = response
+        .json::<GetUserResponse>()
+        .await
+        .expect("failed to unmarshal json into GetUserResponse");
+    assert_eq!(Some(user.email()), me.email(), "The email was missing");
+    assert_eq!(
+        Some(UserSettings::new(user.id(), chrono::Utc::now())),
+        me.settings(),
+        "The settings were missing"
+    );
+    assert!(
+        me.follow_counts().is_some(),
+        "The follow counts were missing"
+    );
+
+    assert_eq!(
+        401,
+        status(&app, format!("{}{}", BASE_PATH, ME_PATH), None).await,
+        "The self view was served without a token"
+    );
+    let public = get_user(&app, user.id(), Some(&token)).await;
+    assert_eq!(None, public.email(), "The public view exposed the email");
+    assert_eq!(None, public.settings(), "The public view exposed settings");
+}
+
+#[actix::test]
+async fn test_privacy_settings_hide_profile_parts() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let friend = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let stranger = app
+        .database
+        .insert_user("testuser3@email.com", "test.user3", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let friend_token = generate_token(friend.id()).unwrap();
+    let stranger_token = generate_token(stranger.id()).unwrap();
+    app.database
+        .insert_friendship(owner.id(), friend.id())
+        .await;
+
+    let response = request(
+        &app,
+        Path::PATCH(format!("{}/{}", BASE_PATH, owner.id())),
+        Some(json!(PatchUserRequestBody {
+            email: None,
+            handle: None,
+            password: None,
+            name: None,
+            profile_photo: None,
+            bio: Some(String::from("Hello there")),
+        })),
+        None,
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not update the bio");
+    let response = request(
+        &app,
+        Path::PATCH(format!("{}{}{}", BASE_PATH, ME_PATH, SETTINGS_PATH)),
+        Some(json!({
+            "bio_visibility": "friends",
+            "servers_visibility": "nobody",
+            "followers_visibility": "followers",
+            "presence_visibility": "friends",
+        })),
+        Some(&owner_token),
+    )
+    .await;
+    assert_eq!(200, response.status(), "The API did not update settings");
+
+    for (token, case) in [(None, "anonymous"), (Some(&stranger_token), "a stranger")] {
+        let profile = get_user(&app, owner.id(), token.map(|t| t.as_str())).await;
+        assert_eq!(None, profile.bio(), "The bio was shown to {}", case);
+        assert_eq!(
+            None,
+            profile.follow_counts(),
+            "The follow counts were shown to {}",
+            case
+        );
+    }
+    let profile = get_user(&app, owner.id(), Some(&friend_token)).await;
+    assert_eq!(Some(String::from("Hello there")), profile.bio());
+    assert!(profile.follow_counts().is_some());
+
+    let followers_path = format!("{}/{}{}", BASE_PATH, owner.id(), FOLLOWERS_PATH);
+    let servers_path = format!("{}/{}/servers", BASE_PATH, owner.id());
+    let test_cases = [
+        (
+            &followers_path,
+            Some(&stranger_token),
+            403,
+            "a stranger's followers",
+        ),
+        (
+            &followers_path,
+            Some(&friend_token),
+            200,
+            "a friend's followers",
+        ),
+        (&servers_path, None, 403, "hidden servers anonymously"),
+        (
+            &servers_path,
+            Some(&friend_token),
+            403,
+            "hidden servers as a friend",
+        ),
+        (
+            &servers_path,
+            Some(&owner_token),
+            200,
+            "your own hidden servers",
+        ),
+    ];
+    for (path, token, expected, case) in test_cases {
+        assert_eq!(
+            expected,
+            status(&app, path.clone(), token.map(|t| t.as_str())).await,
+            "The API did not return {} when listing {}",
+            expected,
+            case
+        );
+    }
+
+    let (_client, _) = GatewayClient::identify(&app, &owner_token).await;
+    let presence_path = format!("{}/{}{}", BASE_PATH, owner.id(), PRESENCE_PATH);
+    for (token, expected) in [
+        (
+            &friend_token,
+            Presence::new(owner.id(), PresenceStatus::Online, None),
+        ),
+        (&stranger_token, Presence::offline(owner.id())),
+    ] {
+        let presence = request(&app, Path::GET(presence_path.clone()), None, Some(token))
+            .await
+            .json::<Presence>()
+            .await
+            .expect("failed to unmarshal json into Presence");
+        assert_eq!(
+            expected, presence,
+            "The presence visibility was not applied"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::{
+        channel::ChannelKind, message::Message, pagination::Page, schedule::ScheduledMessage,
+    },
+    handlers::{
+        channel, message,
+        user::{BASE_PATH, ME_PATH, SCHEDULED_MESSAGES_PATH},
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+fn scheduled_path() -> String {
+    format!("{}{}{}", BASE_PATH, ME_PATH, SCHEDULED_MESSAGES_PATH)
+}
+
+async fn schedule(app: &TestApp, body: serde_json::Value, token: &str) -> reqwest::Response {
+    app.client
+        .request(
+            Path::POST(scheduled_path()),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(body.to_string()),
+        )
+        .await
+}
+
+async fn get_scheduled(app: &TestApp, token: &str) -> Page<ScheduledMessage> {
+    app.client
+        .request(
+            Path::GET(scheduled_path()),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Page<ScheduledMessage>>()
+        .await
+        .expect("failed to unmarshal json into Page<ScheduledMessage>")
+}
+
+async fn cancel(app: &TestApp, id: Uuid, token: &str) -> u16 {
+    app.client
+        .request(
+            Path::DELETE(format!("{}/{}", scheduled_path(), id)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .status()
+        .as_u16()
+}
+
+#[actix::test]
+async fn test_schedule_list_and_cancel() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let other = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let other_token = generate_token(other.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let now = Utc::now();
+
+    let test_cases = [
+        (
+            json!({"channel_id": general.id(), "content": "late", "send_at": now - Duration::minutes(1)}),
+            400,
+            "a send time in the past",
+        ),
+        (
+            json!({"channel_id": general.id(), "content": "later", "send_at": now + Duration::days(31)}),
+            400,
+            "a send time too far ahead",
+        ),
+        (
+            json!({"content": "nowhere", "send_at": now + Duration::hours(1)}),
+            400,
+            "no target",
+        ),
+        (
+            json!({"channel_id": general.id(), "dm_thread_id": Uuid::new_v4(), "content": "everywhere", "send_at": now + Duration::hours(1)}),
+            400,
+            "two targets",
+        ),
+    ];
+    for (body, expected, case) in test_cases {
+        let response = schedule(&app, body, &owner_token).await;
+        assert_eq!(
+            expected,
+            response.status().as_u16(),
+            "The API did not return {} for {}",
+            expected,
+            case
+        );
+    }
+
+    let response = schedule(
+        &app,
+        json!({"channel_id": general.id(), "content": "sneaky", "send_at": now + Duration::hours(1)}),
+        &other_token,
+    )
+    .await;
+    assert_eq!(
+        404,
+        response.status(),
+        "The API did not return 404 when scheduling to a server the user is not in"
+    );
+
+    let mut scheduled = vec![];
+    for hours in [2, 1] {
+        let response = schedule(
+            &app,
+            json!({"channel_id": general.id(), "content": format!("in {} hours", hours), "send_at": now + Duration::hours(hours)}),
+            &owner_token,
+        )
+        .await;
+        assert_eq!(
+            200,
+            response.status(),
+            "The API did not return 200 when scheduling a message"
+        );
+        scheduled.push(
+            response
+                .json::<ScheduledMessage>()
+                .await
+                .expect("failed to unmarshal json into ScheduledMessage"),
+        );
+    }
+
+    let page = get_scheduled(&app, &owner_token).await;
+    assert_eq!(
+        vec![scheduled[1].clone(), scheduled[0].clone()],
+        page.items(),
+        "Scheduled messages were not listed soonest first"
+    );
+    assert!(
+        get_scheduled(&app, &other_token).await.items().is_empty(),
+        "Another user's scheduled messages were listed"
+    );
+
+    assert_eq!(
+        404,
+        cancel(&app, scheduled[0].id(), &other_token).await,
+        "Another user cancelled the message"
+    );
+    assert_eq!(204, cancel(&app, scheduled[0].id(), &owner_token).await);
+    assert_eq!(
+        404,
+        cancel(&app, scheduled[0].id(), &owner_token).await,
+        "A cancelled message was cancelled twice"
+    );
+    assert_eq!(
+        vec![scheduled[1].clone()],
+        get_scheduled(&app, &owner_token).await.items()
+    );
+
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}?cursor={}", scheduled_path(), scheduled[0].id())),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        400,
+        response.status(),
+        "The API did not reject a cursor at a cancelled message"
+    );
+}
+
+#[actix::test]
+async fn test_scheduled_message_is_delivered() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+
+    let send_at = Utc::now() + Duration::minutes(5);
+    let scheduled = schedule(
+        &app,
+        json!({"channel_id": general.id(), "content": "good morning", "send_at": send_at}),
+        &owner_token,
+    )
+    .await
+    .json::<ScheduledMessage>()
+    .await
+    .expect("failed to unmarshal json into ScheduledMessage");
+
+    app.database
+        .run_message_schedule(send_at - Duration::minutes(1))
+        .await;
+    assert_eq!(
+        1,
+        get_scheduled(&app, &owner_token).await.items().len(),
+        "A message was sent before it was due"
+    );
+
+    app.database.run_message_schedule(send_at).await;
+    assert!(
+        get_scheduled(&app, &owner_token).await.items().is_empty(),
+        "A sent message was still pending"
+    );
+    assert_eq!(
+        404,
+        cancel(&app, scheduled.id(), &owner_token).await,
+        "A sent message could still be cancelled"
+    );
+
+    let messages = app
+        .client
+        .request(
+            Path::GET(format!(
+                "{}/{}{}",
+                channel::BASE_PATH,
+                general.id(),
+                message::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            None::<String>,
+        )
+        .await
+        .json::<Vec<Message>>()
+        .await
+        .expect("failed to unmarshal json into Vec<Message>");
+    assert_eq!(1, messages.len(), "The scheduled message was not delivered");
+    assert_eq!("good morning", messages[0].content().as_ref());
+    assert_eq!(owner.id(), messages[0].author_id());
+}
@@ -0,0 +1,380 @@
+use chrono::{Duration, Utc};
+use muttr_server::{
+    domain::{
+        channel::{ChannelKind, OverwriteTarget},
+        dm::DirectMessage,
+        permission::Permissions,
+        read_state::UnreadState,
+        thread::Thread,
+    },
+    handlers::{
+        channel,
+        dm::{self, DmThreadResponse},
+        message, server, thread,
+        user::{BASE_PATH, ME_PATH, UNREAD_PATH},
+    },
+    utils::jwt::generate_token,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::utils::{
+    app::TestApp,
+    http_client::{ContentType, Header, Path},
+};
+
+async fn get_unread(app: &TestApp, token: &str) -> Vec<UnreadState> {
+    let response = app
+        .client
+        .request(
+            Path::GET(format!("{}{}{}", BASE_PATH, ME_PATH, UNREAD_PATH)),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await;
+    assert_eq!(
+        200,
+        response.status(),
+        "The API did not return 200 on get unread: {}",
+        response.text().await.unwrap_or_default(),
+    );
+    response
+        .json::<Vec<UnreadState>>()
+        .await
+        .expect("Failed to parse unread response")
+}
+
+async fn ack(app: &TestApp, path: &str, token: &str) -> u16 {
+    app.client
+        .request(
+            Path::POST(path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            None::<String>,
+        )
+        .await
+        .status()
+        .as_u16()
+}
+
+fn unread_count(unread: &[UnreadState], id: Uuid) -> i64 {
+    unread
+        .iter()
+        .find(|u| u.channel_id() == Some(id) || u.thread_id() == Some(id))
+        .unwrap_or_else(|| panic!("No unread state for {}", id))
+        .unread_count()
+}
+
+#[actix::test]
+async fn test_unread_counts_follow_acks() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let voice = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Voice, "voice")
+        .await;
+
+    let now = Utc::now();
+    let mut messages = vec![];
+    for x in 0..3 {
+        messages.push(
+            app.database
+                .insert_message(
+                    general.id(),
+                    owner.id(),
+                    &format!("message {}", x),
+                    now - Duration::minutes(3 - x),
+                )
+                .await,
+        );
+    }
+
+    let thread = app
+        .client
+        .request(
+            Path::POST(dm::BASE_PATH),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(json!({"recipient_id": member.id()}).to_string()),
+        )
+        .await
+        .json::<DmThreadResponse>()
+        .await
+        .expect("failed to unmarshal json into DmThreadResponse")
+        .thread;
+    let dm_path = format!("{}/{}{}", dm::BASE_PATH, thread.id(), dm::MESSAGES_PATH);
+    let direct_message = app
+        .client
+        .request(
+            Path::POST(&dm_path),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(json!({"content": "hi"}).to_string()),
+        )
+        .await
+        .json::<DirectMessage>()
+        .await
+        .expect("failed to unmarshal json into DirectMessage");
+
+    let unread = get_unread(&app, &member_token).await;
+    assert_eq!(2, unread.len(), "Unexpected unread states: {:?}", unread);
+    assert!(
+        unread.iter().all(|u| u.channel_id() != Some(voice.id())),
+        "A voice channel was included"
+    );
+    assert_eq!(3, unread_count(&unread, general.id()));
+    assert_eq!(1, unread_count(&unread, thread.id()));
+    assert!(
+        unread.iter().all(|u| u.mention_count() == 0),
+        "Mention counts were not zero"
+    );
+
+    let channel_ack = |id: Uuid| {
+        format!(
+            "{}/{}{}/{}{}",
+            channel::BASE_PATH,
+            general.id(),
+            message::BASE_PATH,
+            id,
+            message::ACK_PATH
+        )
+    };
+    assert_eq!(
+        204,
+        ack(&app, &channel_ack(messages[1].id()), &member_token).await
+    );
+    let unread = get_unread(&app, &member_token).await;
+    assert_eq!(1, unread_count(&unread, general.id()));
+    assert_eq!(
+        Some(messages[1].id()),
+        unread
+            .iter()
+            .find(|u| u.channel_id() == Some(general.id()))
+            .and_then(|u| u.last_read_message_id()),
+        "The read marker was not moved"
+    );
+
+    assert_eq!(
+        204,
+        ack(&app, &channel_ack(messages[0].id()), &member_token).await
+    );
+    assert_eq!(
+        1,
+        unread_count(&get_unread(&app, &member_token).await, general.id()),
+        "Acknowledging an older message moved the read marker back"
+    );
+
+    assert_eq!(
+        204,
+        ack(
+            &app,
+            &format!("{}/{}{}", dm_path, direct_message.id(), message::ACK_PATH),
+            &member_token
+        )
+        .await
+    );
+    assert_eq!(
+        0,
+        unread_count(&get_unread(&app, &member_token).await, thread.id())
+    );
+
+    let owner_unread = get_unread(&app, &owner_token).await;
+    assert_eq!(
+        0,
+        unread_count(&owner_unread, general.id()),
+        "The author's own messages counted as unread"
+    );
+}
+
+async fn start_thread(app: &TestApp, channel_id: Uuid, message_id: Uuid, token: &str) -> Thread {
+    app.client
+        .request(
+            Path::POST(format!(
+                "{}/{}{}/{}{}",
+                channel::BASE_PATH,
+                channel_id,
+                message::BASE_PATH,
+                message_id,
+                thread::BASE_PATH
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(token.to_string()),
+            ],
+            Some(json!({"name": "a thread"}).to_string()),
+        )
+        .await
+        .json::<Thread>()
+        .await
+        .expect("failed to unmarshal json into Thread")
+}
+
+#[actix::test]
+async fn test_unread_counts_include_joined_threads_of_viewable_channels() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let member = app
+        .database
+        .insert_user("testuser2@email.com", "test.user2", true)
+        .await;
+    let owner_token = generate_token(owner.id()).unwrap();
+    let member_token = generate_token(member.id()).unwrap();
+    let srv = app.database.insert_server(owner.id()).await;
+    app.database
+        .insert_server_member(srv.id(), member.id())
+        .await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+    let staff = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "staff")
+        .await;
+
+    let mut threads = vec![];
+    for channel_id in [general.id(), general.id(), staff.id()] {
+        let msg = app
+            .database
+            .insert_message(channel_id, owner.id(), "let's discuss", Utc::now())
+            .await;
+        threads.push(start_thread(&app, channel_id, msg.id(), &owner_token).await);
+    }
+    let (joined, unjoined, hidden) = (&threads[0], &threads[1], &threads[2]);
+    for thread_id in [joined.id(), hidden.id()] {
+        let response = app
+            .client
+            .request(
+                Path::PUT(format!(
+                    "{}/{}{}/@me",
+                    thread::BASE_PATH,
+                    thread_id,
+                    thread::MEMBERS_PATH
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(member_token.clone()),
+                ],
+                None::<String>,
+            )
+            .await;
+        assert_eq!(204, response.status(), "The API did not join the thread");
+    }
+    for thread in &threads {
+        let response = app
+            .client
+            .request(
+                Path::POST(format!(
+                    "{}/{}{}",
+                    channel::BASE_PATH,
+                    thread.id(),
+                    message::BASE_PATH
+                )),
+                &[
+                    Header::ContentType(ContentType::Json),
+                    Header::Authorization(owner_token.clone()),
+                ],
+                Some(json!({"content": "news"}).to_string()),
+            )
+            .await;
+        assert_eq!(200, response.status(), "The API did not send the message");
+    }
+    let response = app
+        .client
+        .request(
+            Path::PUT(format!(
+                "{}/{}{}/{}{}/{}",
+                server::BASE_PATH,
+                srv.id(),
+                channel::BASE_PATH,
+                staff.id(),
+                channel::PERMISSIONS_PATH,
+                srv.id()
+            )),
+            &[
+                Header::ContentType(ContentType::Json),
+                Header::Authorization(owner_token.clone()),
+            ],
+            Some(
+                json!({
+                    "target_type": OverwriteTarget::Role,
+                    "allow": Permissions::empty(),
+                    "deny": Permissions::VIEW_CHANNEL,
+                })
+                .to_string(),
+            ),
+        )
+        .await;
+    assert_eq!(200, response.status(), "The API did not hide the channel");
+
+    let unread = get_unread(&app, &member_token).await;
+    assert_eq!(1, unread_count(&unread, joined.id()));
+    assert!(
+        unread.iter().all(|u| u.channel_id() != Some(unjoined.id())),
+        "A thread the user has not joined was included"
+    );
+    assert!(
+        unread.iter().all(|u| u.channel_id() != Some(hidden.id())),
+        "A thread of a channel the user cannot view was included"
+    );
+}
+
+#[actix::test]
+async fn test_ack_unknown_message_returns_404() {
+    let mut app = TestApp::spawn().await;
+
+    let owner = app
+        .database
+        .insert_user("testuser@email.com", "test.user", true)
+        .await;
+    let srv = app.database.insert_server(owner.id()).await;
+    let general = app
+        .database
+        .insert_channel(srv.id(), ChannelKind::Text, "general")
+        .await;
+
+    let path = format!(
+        "{}/{}{}/{}{}",
+        channel::BASE_PATH,
+        general.id(),
+        message::BASE_PATH,
+        Uuid::new_v4(),
+        message::ACK_PATH
+    );
+    assert_eq!(
+        404,
+        ack(&app, &path, &generate_token(owner.id()).unwrap()).await,
+        "The API did not return 404 when acknowledging an unknown message"
+    );
+}
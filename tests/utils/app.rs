@@ -20,6 +20,7 @@ static TRACING: Lazy<()> = Lazy::new(|| {
     }
 });
 
+#[allow(dead_code)]
 pub struct TestApp {
     pub config: Config,
     pub address: String,
@@ -46,7 +47,7 @@ impl TestApp {
             .await
             .expect("Failed to build app");
         let address = format!("http://127.0.0.1:{}", app.port());
-        let _ = tokio::spawn(app.run_until_stopped());
+        tokio::spawn(app.run_until_stopped());
 
         let test_db = TestDB::new(&config.database).await;
         TestApp {
@@ -54,7 +55,7 @@ impl TestApp {
             address: address.clone(),
             database: test_db,
             client: Client::new(address.clone()),
-            email_server: email_server,
+            email_server,
         }
     }
 }
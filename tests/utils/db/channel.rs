@@ -0,0 +1,73 @@
+use chrono::Utc;
+use muttr_server::{
+    domain::{
+        channel::{Channel, ChannelKind},
+        permission::Permissions,
+        role::Role,
+    },
+    storage::{get_channel_by_id, insert_member_role, upsert_channel, upsert_role},
+};
+use uuid::Uuid;
+
+use super::TestDB;
+
+impl TestDB {
+    pub async fn insert_channel(
+        &mut self,
+        server_id: Uuid,
+        kind: ChannelKind,
+        name: &str,
+    ) -> Channel {
+        let now = Utc::now();
+        let channel = Channel::new(
+            Uuid::new_v4(),
+            server_id,
+            None,
+            kind,
+            name.to_string(),
+            None,
+            0,
+            now,
+            now,
+            None,
+        );
+
+        match upsert_channel(&self.db_pool, &channel).await {
+            Ok(_) => channel,
+            Err(e) => panic!("Failed to insert channel: {:?}", e),
+        }
+    }
+
+    pub async fn get_channel_by_id(&mut self, id: Uuid) -> Result<Channel, sqlx::Error> {
+        get_channel_by_id(&self.db_pool, id).await
+    }
+
+    pub async fn insert_role(
+        &mut self,
+        server_id: Uuid,
+        name: &str,
+        permissions: Permissions,
+    ) -> Role {
+        let now = Utc::now();
+        let role = Role::new(
+            Uuid::new_v4(),
+            server_id,
+            name.to_string(),
+            permissions,
+            0,
+            now,
+            now,
+        );
+
+        match upsert_role(&self.db_pool, &role).await {
+            Ok(_) => role,
+            Err(e) => panic!("Failed to insert role: {:?}", e),
+        }
+    }
+
+    pub async fn insert_member_role(&mut self, role_id: Uuid, user_id: Uuid) {
+        if let Err(e) = insert_member_role(&self.db_pool, role_id, user_id).await {
+            panic!("Failed to assign role: {:?}", e);
+        }
+    }
+}
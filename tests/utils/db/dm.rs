@@ -0,0 +1,29 @@
+use chrono::Utc;
+use muttr_server::{
+    domain::{block::UserBlock, dm::DmThread},
+    storage::{get_dm_participant_ids, get_dm_thread_by_id, insert_user_block},
+};
+use uuid::Uuid;
+
+use super::TestDB;
+
+impl TestDB {
+    pub async fn insert_user_block(&mut self, blocker_id: Uuid, blocked_id: Uuid) {
+        let block = UserBlock::new(blocker_id, blocked_id, Utc::now()).unwrap();
+        if let Err(e) = insert_user_block(&self.db_pool, &block).await {
+            panic!("Failed to insert user block: {:?}", e);
+        }
+    }
+
+    pub async fn get_dm_participant_ids(&mut self, thread_id: Uuid) -> Vec<Uuid> {
+        get_dm_participant_ids(&self.db_pool, thread_id)
+            .await
+            .expect("Failed to get DM participants")
+    }
+
+    pub async fn get_dm_thread(&mut self, thread_id: Uuid) -> DmThread {
+        get_dm_thread_by_id(&self.db_pool, thread_id)
+            .await
+            .expect("Failed to get DM thread")
+    }
+}
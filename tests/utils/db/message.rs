@@ -0,0 +1,59 @@
+use actix::Actor;
+use chrono::{DateTime, Utc};
+use muttr_server::{
+    domain::message::{Message, MessageContent, MessageRevision},
+    gateway::Hub,
+    jobs::run_message_schedule,
+    storage::{get_message_by_id, get_message_revisions, insert_message, purge_deleted_messages},
+};
+use uuid::Uuid;
+
+use super::TestDB;
+
+impl TestDB {
+    pub async fn insert_message(
+        &mut self,
+        channel_id: Uuid,
+        author_id: Uuid,
+        content: &str,
+        created_at: DateTime<Utc>,
+    ) -> Message {
+        let message = Message::new(
+            Uuid::new_v4(),
+            channel_id,
+            author_id,
+            MessageContent::try_from(content).expect("Invalid test message content"),
+            created_at,
+            created_at,
+            None,
+            None,
+        );
+
+        match insert_message(&self.db_pool, &message, None).await {
+            Ok(_) => message,
+            Err(e) => panic!("Failed to insert message: {:?}", e),
+        }
+    }
+
+    pub async fn get_message_by_id(&mut self, id: Uuid) -> Result<Message, sqlx::Error> {
+        get_message_by_id(&self.db_pool, id).await
+    }
+
+    pub async fn get_message_revisions(&mut self, message_id: Uuid) -> Vec<MessageRevision> {
+        get_message_revisions(&self.db_pool, message_id)
+            .await
+            .expect("Failed to get message revisions")
+    }
+
+    pub async fn purge_deleted_messages(&mut self, cutoff: DateTime<Utc>) -> u64 {
+        purge_deleted_messages(&self.db_pool, cutoff)
+            .await
+            .expect("Failed to purge deleted messages")
+    }
+
+    /// Runs one message scheduler sweep as if it were `now`.
+    pub async fn run_message_schedule(&mut self, now: DateTime<Utc>) {
+        let hub = Hub::default().start();
+        run_message_schedule(&self.db_pool, &hub, now).await;
+    }
+}
@@ -1,5 +1,11 @@
+pub mod channel;
 mod confirmation_token;
+pub mod dm;
+pub mod message;
+pub mod poll;
+pub mod post;
 pub mod server;
+pub mod thread;
 pub mod user;
 
 use muttr_server::{config::DatabaseConfig, startup::App};
@@ -40,6 +46,6 @@ impl TestDB {
         self.db_pool
             .execute(format!(r#"DELETE FROM {}"#, table_name).as_str())
             .await
-            .expect(format!("Failed to clear {} table in database", table_name).as_str());
+            .unwrap_or_else(|_| panic!("Failed to clear {} table in database", table_name));
     }
 }
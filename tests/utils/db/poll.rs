@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use muttr_server::storage::close_due_polls;
+use uuid::Uuid;
+
+use super::TestDB;
+
+impl TestDB {
+    /// Closes the polls due by `now`, returning their ids.
+    pub async fn close_due_polls(&mut self, now: DateTime<Utc>) -> Vec<Uuid> {
+        close_due_polls(&self.db_pool, now)
+            .await
+            .expect("Failed to close due polls")
+            .iter()
+            .map(|poll| poll.id())
+            .collect()
+    }
+}
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use muttr_server::storage::refresh_trending_tags;
+use uuid::Uuid;
+
+use super::TestDB;
+
+impl TestDB {
+    /// Moves a post, and its tag uses, back to `created_at`.
+    pub async fn backdate_post(&mut self, post_id: Uuid, created_at: DateTime<Utc>) {
+        for table in ["posts", "post_tags"] {
+            let column = if table == "posts" { "id" } else { "post_id" };
+            sqlx::query(&format!(
+                "UPDATE {} SET created_at = $1 WHERE {} = $2",
+                table, column
+            ))
+            .bind(created_at)
+            .bind(post_id)
+            .execute(&self.db_pool)
+            .await
+            .expect("Failed to backdate post");
+        }
+    }
+
+    pub async fn refresh_trending_tags(&mut self, now: DateTime<Utc>) {
+        refresh_trending_tags(&self.db_pool, now)
+            .await
+            .expect("Failed to refresh trending tags");
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::Utc;
 use muttr_server::{
-    domain::server::Server,
-    storage::{get_server_by_id, upsert_server},
+    domain::{block::ServerMute, server::Server},
+    storage::{get_server_by_id, insert_server_member, insert_server_mute, upsert_server},
 };
 use uuid::Uuid;
 
@@ -9,12 +9,22 @@ use super::TestDB;
 
 impl TestDB {
     pub async fn insert_server(&mut self, owner_id: Uuid) -> Server {
+        self.insert_server_with_name(owner_id, "Test Server", Some("Just a test server"))
+            .await
+    }
+
+    pub async fn insert_server_with_name(
+        &mut self,
+        owner_id: Uuid,
+        name: &str,
+        description: Option<&str>,
+    ) -> Server {
         let now = Utc::now();
         let server = Server::new(
             Uuid::new_v4(),
-            "Test Server".to_string(),
+            name.to_string(),
             owner_id,
-            Some("Just a test server".to_string()),
+            description.map(String::from),
             None,
             None,
             now,
@@ -28,6 +38,29 @@ impl TestDB {
         }
     }
 
+    pub async fn insert_server_member(&mut self, server_id: Uuid, user_id: Uuid) {
+        if let Err(e) = insert_server_member(&self.db_pool, server_id, user_id, false).await {
+            panic!("Failed to insert server member: {:?}", e);
+        }
+    }
+
+    /// Takes a user out of a server, as if they had left it.
+    pub async fn remove_server_member(&mut self, server_id: Uuid, user_id: Uuid) {
+        sqlx::query("DELETE FROM server_members WHERE server_id = $1 AND user_id = $2")
+            .bind(server_id)
+            .bind(user_id)
+            .execute(&self.db_pool)
+            .await
+            .expect("Failed to remove server member");
+    }
+
+    pub async fn insert_server_mute(&mut self, user_id: Uuid, server_id: Uuid) {
+        let mute = ServerMute::new(user_id, server_id, Utc::now());
+        if let Err(e) = insert_server_mute(&self.db_pool, &mute).await {
+            panic!("Failed to insert server mute: {:?}", e);
+        }
+    }
+
     pub async fn get_server_by_id(&mut self, id: Uuid) -> Result<Server, sqlx::Error> {
         get_server_by_id(&self.db_pool, id).await
     }
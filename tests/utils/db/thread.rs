@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use muttr_server::{domain::thread::Thread, storage::archive_inactive_threads};
+
+use super::TestDB;
+
+impl TestDB {
+    pub async fn archive_inactive_threads(&mut self, now: DateTime<Utc>) -> Vec<Thread> {
+        archive_inactive_threads(&self.db_pool, now)
+            .await
+            .expect("Failed to archive inactive threads")
+    }
+}
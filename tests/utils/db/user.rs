@@ -1,7 +1,15 @@
 use chrono::Utc;
 use muttr_server::{
-    domain::user::{Email, Handle, Password, User},
-    storage::{get_user_by_id, upsert_user},
+    domain::{
+        block::UserMute,
+        follow::Follow,
+        friend::FriendRequest,
+        user::{Email, Handle, Password, User},
+    },
+    storage::{
+        accept_friend_request, get_user_by_id, insert_follow, insert_friend_request,
+        insert_user_mute, upsert_user,
+    },
 };
 use secrecy::Secret;
 use uuid::Uuid;
@@ -15,8 +23,8 @@ impl TestDB {
         let now = Utc::now();
         let user = User::new(
             Uuid::new_v4(),
-            Email::try_from(email).expect(&format!("Email '{}' is invalid", email)),
-            Handle::try_from(handle).expect(&format!("Handle '{}' is invalid", handle)),
+            Email::try_from(email).unwrap_or_else(|_| panic!("Email '{}' is invalid", email)),
+            Handle::try_from(handle).unwrap_or_else(|_| panic!("Handle '{}' is invalid", handle)),
             Password::try_from(Secret::new(TEST_USER_PASSWORD.into())).unwrap(),
             None,
             None,
@@ -34,6 +42,30 @@ impl TestDB {
         }
     }
 
+    pub async fn insert_user_follow(&mut self, follower_id: Uuid, followee_id: Uuid) {
+        let follow = Follow::new(follower_id, followee_id, Utc::now()).unwrap();
+        if let Err(e) = insert_follow(&self.db_pool, &follow).await {
+            panic!("Failed to insert user follow: {:?}", e);
+        }
+    }
+
+    pub async fn insert_friendship(&mut self, user_id: Uuid, friend_id: Uuid) {
+        let request = FriendRequest::new(user_id, friend_id, Utc::now()).unwrap();
+        if let Err(e) = insert_friend_request(&self.db_pool, &request).await {
+            panic!("Failed to insert friend request: {:?}", e);
+        }
+        if let Err(e) = accept_friend_request(&self.db_pool, user_id, friend_id, Utc::now()).await {
+            panic!("Failed to accept friend request: {:?}", e);
+        }
+    }
+
+    pub async fn insert_user_mute(&mut self, muter_id: Uuid, muted_id: Uuid) {
+        let mute = UserMute::new(muter_id, muted_id, Utc::now()).unwrap();
+        if let Err(e) = insert_user_mute(&self.db_pool, &mute).await {
+            panic!("Failed to insert user mute: {:?}", e);
+        }
+    }
+
     pub async fn get_user_by_id(&mut self, id: Uuid) -> Result<User, sqlx::Error> {
         get_user_by_id(&self.db_pool, id).await
     }
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use actix_codec::Framed;
+use awc::{
+    ws::{Codec, Frame, Message},
+    BoxedSocket,
+};
+use futures::{SinkExt, StreamExt};
+use muttr_server::gateway::{GatewayEvent, ServerFrame};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::app::TestApp;
+
+const FRAME_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct GatewayClient {
+    framed: Framed<BoxedSocket, Codec>,
+}
+
+impl GatewayClient {
+    /// Opens a connection and consumes the HELLO frame.
+    pub async fn connect(app: &TestApp) -> Self {
+        let url = format!("{}/gateway", app.address.replacen("http", "ws", 1));
+        let (_, framed) = awc::Client::new()
+            .ws(url)
+            .connect()
+            .await
+            .expect("Failed to connect to gateway");
+        let mut client = GatewayClient { framed };
+        match client.next_frame().await {
+            ServerFrame::Hello { .. } => client,
+            frame => panic!("Expected HELLO, got {:?}", frame),
+        }
+    }
+
+    /// Opens a connection and identifies, returning the client and session id.
+    pub async fn identify(app: &TestApp, token: &str) -> (Self, Uuid) {
+        let mut client = Self::connect(app).await;
+        client.send(json!({"op": "identify", "token": token})).await;
+        match client.next_frame().await {
+            ServerFrame::Ready { session_id, .. } => (client, session_id),
+            frame => panic!("Expected READY, got {:?}", frame),
+        }
+    }
+
+    pub async fn send(&mut self, frame: Value) {
+        self.framed
+            .send(Message::Text(frame.to_string().into()))
+            .await
+            .expect("Failed to send gateway frame");
+    }
+
+    /// Waits for the next JSON frame, skipping control frames.
+    pub async fn next_frame(&mut self) -> ServerFrame {
+        loop {
+            let frame = actix_rt::time::timeout(FRAME_TIMEOUT, self.framed.next())
+                .await
+                .expect("Timed out waiting for gateway frame")
+                .expect("Gateway connection ended")
+                .expect("Failed to read gateway frame");
+            match frame {
+                Frame::Text(text) => {
+                    return serde_json::from_slice(&text)
+                        .unwrap_or_else(|_| panic!("Failed to parse gateway frame {:?}", text))
+                }
+                Frame::Close(reason) => panic!("Gateway connection closed: {:?}", reason),
+                _ => continue,
+            }
+        }
+    }
+
+    /// Waits for the next dispatched event and its sequence number.
+    pub async fn next_event(&mut self) -> (u64, GatewayEvent) {
+        match self.next_frame().await {
+            ServerFrame::Dispatch { s, event } => (s, *event),
+            frame => panic!("Expected DISPATCH, got {:?}", frame),
+        }
+    }
+
+    /// Waits for the server to close the connection and returns the reason.
+    pub async fn expect_close(&mut self) -> Option<awc::ws::CloseReason> {
+        loop {
+            let frame = actix_rt::time::timeout(FRAME_TIMEOUT, self.framed.next())
+                .await
+                .expect("Timed out waiting for gateway to close");
+            match frame {
+                Some(Ok(Frame::Close(reason))) => return reason,
+                Some(Ok(Frame::Text(text))) => panic!("Expected close, got {:?}", text),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => panic!("Failed to read gateway frame: {:?}", e),
+                None => return None,
+            }
+        }
+    }
+
+    pub async fn close(mut self) {
+        let _ = self.framed.send(Message::Close(None)).await;
+    }
+}
@@ -6,9 +6,9 @@ pub enum Header {
     ContentType(ContentType),
 }
 
-impl Into<(&'static str, String)> for Header {
-    fn into(self) -> (&'static str, String) {
-        match self {
+impl From<Header> for (&'static str, String) {
+    fn from(header: Header) -> Self {
+        match header {
             Header::Authorization(token) => (AUTHORIZATION, token),
             Header::ContentType(content_type) => (CONTENT_TYPE, content_type.to_string()),
         }
@@ -21,12 +21,12 @@ pub enum ContentType {
     Json,
 }
 
-impl ToString for ContentType {
-    fn to_string(&self) -> String {
-        match self {
+impl std::fmt::Display for ContentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let content_type = match self {
             ContentType::FormURLEncoded => FORM_URL_ENCODED,
             ContentType::Json => APP_JSON,
-        }
-        .into()
+        };
+        write!(f, "{}", content_type)
     }
 }
@@ -38,7 +38,7 @@ impl Client {
         request
             .send()
             .await
-            .expect(&format!("Failed to execute request {:?}", path))
+            .unwrap_or_else(|_| panic!("Failed to execute request {:?}", path))
     }
 
     fn parse_headers(headers: &[Header]) -> HeaderMap {
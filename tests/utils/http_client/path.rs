@@ -2,6 +2,7 @@ use reqwest::{IntoUrl, RequestBuilder};
 
 use super::Client;
 
+#[allow(clippy::upper_case_acronyms)]
 pub enum Path<U>
 where
     U: IntoUrl,
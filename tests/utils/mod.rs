@@ -1,4 +1,5 @@
 pub mod app;
 pub mod db;
+pub mod gateway;
 pub mod http_client;
 pub mod jwt;